@@ -0,0 +1,520 @@
+//! Conversation history compaction for long-running sessions.
+//!
+//! [`LlmAgent`](crate::LlmAgent) sends the whole session history to the model on every
+//! iteration. A [`CompactionStrategy`] set with
+//! [`LlmAgentBuilder::compaction_strategy`](crate::LlmAgentBuilder::compaction_strategy)
+//! shrinks that history before each model call:
+//!
+//! - [`KeepLastTurns`] keeps only the most recent user turns
+//! - [`TokenBudget`] drops the oldest contents until the history fits a token budget
+//! - [`SummarizeOlderTurns`] replaces older turns with a model-written summary
+//!
+//! The kept history always starts with a user turn: the cut point moves back until it
+//! does, so a function call and its response are never split and the model never sees
+//! a history opening with its own turn.
+//!
+//! Summaries are persisted as session events carrying a function response part named
+//! [`COMPACTION_SUMMARY_FUNCTION_NAME`], so later invocations start from the summary
+//! instead of summarizing the same turns again. Agents replace that part with a plain
+//! text summary before anything is sent to a model; that text does not count as a user
+//! turn. Use [`Event::is_compaction_summary`](adk_core::Event::is_compaction_summary)
+//! to tell these events apart from conversation messages.
+//!
+//! ```rust,ignore
+//! use adk_agent::{LlmAgentBuilder, compaction::SummarizeOlderTurns};
+//!
+//! let agent = LlmAgentBuilder::new("assistant")
+//!     .model(model.clone())
+//!     .compaction_strategy(Arc::new(SummarizeOlderTurns::new(model).max_tokens(50_000)))
+//!     .build()?;
+//! ```
+
+pub use adk_core::COMPACTION_SUMMARY_FUNCTION_NAME;
+
+use adk_core::{Content, FunctionResponseData, Llm, LlmRequest, Part, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+
+const SUMMARY_MESSAGE_PREFIX: &str = "Summary of the earlier conversation:\n";
+
+const DEFAULT_SUMMARY_INSTRUCTION: &str = "Summarize the conversation below so it can replace \
+the original messages. Keep facts, decisions, open questions, tool results and anything the \
+user asked to remember. Reply with the summary only.";
+
+/// How a strategy wants the history shrunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compaction {
+    /// Index of the first content to keep verbatim; everything before it is dropped.
+    pub keep_from: usize,
+    /// Summary of the dropped contents. When set, it is persisted to the session and
+    /// sent to the model in place of the dropped contents.
+    pub summary: Option<String>,
+}
+
+/// Decides how to shrink conversation history before a model call.
+#[async_trait]
+pub trait CompactionStrategy: Send + Sync {
+    /// Inspect `history` (the session contents, without instructions) and return how
+    /// to compact it, or `None` to send it unchanged.
+    async fn compact(&self, history: &[Content]) -> Result<Option<Compaction>>;
+}
+
+/// Keeps only the last `turns` user turns and everything that followed them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepLastTurns {
+    turns: usize,
+}
+
+impl KeepLastTurns {
+    /// Keep the last `turns` user turns (at least one).
+    pub fn new(turns: usize) -> Self {
+        Self { turns: turns.max(1) }
+    }
+}
+
+#[async_trait]
+impl CompactionStrategy for KeepLastTurns {
+    async fn compact(&self, history: &[Content]) -> Result<Option<Compaction>> {
+        Ok(nth_last_user_turn(history, self.turns)
+            .filter(|&keep_from| keep_from > 0)
+            .map(|keep_from| Compaction { keep_from, summary: None }))
+    }
+}
+
+/// Drops the oldest contents until the estimated token count fits `max_tokens`.
+///
+/// Tokens are estimated with [`estimate_tokens`]. The most recent content is always
+/// kept, even if it alone exceeds the budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBudget {
+    max_tokens: usize,
+}
+
+impl TokenBudget {
+    /// Trim history to roughly `max_tokens` tokens.
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+}
+
+#[async_trait]
+impl CompactionStrategy for TokenBudget {
+    async fn compact(&self, history: &[Content]) -> Result<Option<Compaction>> {
+        let mut total = 0;
+        for (index, content) in history.iter().enumerate().rev() {
+            total += estimate_content_tokens(content);
+            if total > self.max_tokens {
+                let keep_from = (index + 1).min(history.len() - 1);
+                return Ok((keep_from > 0).then_some(Compaction { keep_from, summary: None }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Summarizes older turns with a model once the history exceeds a token threshold.
+///
+/// The last `keep_last_turns` user turns (default: 2) are kept verbatim, so a long
+/// tool loop within the current turn is not summarized.
+pub struct SummarizeOlderTurns {
+    model: Arc<dyn Llm>,
+    max_tokens: usize,
+    keep_last_turns: usize,
+    instruction: String,
+}
+
+impl SummarizeOlderTurns {
+    /// Summarize with `model` once history exceeds 100,000 estimated tokens.
+    pub fn new(model: Arc<dyn Llm>) -> Self {
+        Self {
+            model,
+            max_tokens: 100_000,
+            keep_last_turns: 2,
+            instruction: DEFAULT_SUMMARY_INSTRUCTION.to_string(),
+        }
+    }
+
+    /// Set the estimated token count above which older turns are summarized.
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Set how many recent user turns are kept verbatim (at least one).
+    pub fn keep_last_turns(mut self, turns: usize) -> Self {
+        self.keep_last_turns = turns.max(1);
+        self
+    }
+
+    /// Replace the instruction sent to the summarizing model.
+    pub fn instruction(mut self, instruction: impl Into<String>) -> Self {
+        self.instruction = instruction.into();
+        self
+    }
+}
+
+#[async_trait]
+impl CompactionStrategy for SummarizeOlderTurns {
+    async fn compact(&self, history: &[Content]) -> Result<Option<Compaction>> {
+        if estimate_tokens(history) <= self.max_tokens {
+            return Ok(None);
+        }
+        let Some(keep_from) =
+            nth_last_user_turn(history, self.keep_last_turns).filter(|&keep_from| keep_from > 0)
+        else {
+            return Ok(None);
+        };
+        // Everything older is already a persisted summary, so there is nothing new to
+        // summarize
+        if history[..keep_from].iter().all(is_summary_message) {
+            return Ok(None);
+        }
+
+        let prompt = format!("{}\n\n{}", self.instruction, transcript(&history[..keep_from]));
+        let request =
+            LlmRequest::new(self.model.name(), vec![Content::new("user").with_text(prompt)]);
+        let mut responses = self.model.generate_content(request, false).await?;
+        let mut summary = String::new();
+        while let Some(response) = responses.next().await {
+            for part in response?.content.into_iter().flat_map(|c| c.parts) {
+                if let Part::Text { text } = part {
+                    summary.push_str(&text);
+                }
+            }
+        }
+
+        Ok(Some(Compaction { keep_from, summary: Some(summary.trim().to_string()) }))
+    }
+}
+
+/// Roughly estimate the tokens in `contents`, at about four characters per token.
+pub fn estimate_tokens(contents: &[Content]) -> usize {
+    contents.iter().map(estimate_content_tokens).sum()
+}
+
+fn estimate_content_tokens(content: &Content) -> usize {
+    let chars: usize = content
+        .parts
+        .iter()
+        .map(|part| match part {
+            Part::Text { text } => text.len(),
+            Part::Thinking { thinking, .. } => thinking.len(),
+            Part::FunctionCall { name, args, .. } => name.len() + args.to_string().len(),
+            Part::FunctionResponse { function_response, .. } => {
+                function_response.name.len() + function_response.response.to_string().len()
+            }
+            // Media is billed per item by most providers rather than by size
+            _ => 1024,
+        })
+        .sum();
+    chars.div_ceil(4)
+}
+
+/// A message typed by the user, as opposed to a function response, instruction or
+/// summary of earlier turns.
+fn is_user_turn(content: &Content) -> bool {
+    content.role == "user"
+        && !content.parts.iter().any(|p| matches!(p, Part::FunctionResponse { .. }))
+        && !is_summary_message(content)
+}
+
+fn is_summary_message(content: &Content) -> bool {
+    matches!(content.parts.as_slice(), [Part::Text { text }] if text.starts_with(SUMMARY_MESSAGE_PREFIX))
+}
+
+/// Index of the `n`-th user turn counting from the end.
+fn nth_last_user_turn(history: &[Content], n: usize) -> Option<usize> {
+    history.iter().enumerate().rev().filter(|(_, c)| is_user_turn(c)).nth(n - 1).map(|(i, _)| i)
+}
+
+fn summary_part(content: &Content) -> Option<(String, usize)> {
+    content.parts.iter().find_map(|part| match part {
+        Part::FunctionResponse { function_response, .. }
+            if function_response.name == COMPACTION_SUMMARY_FUNCTION_NAME =>
+        {
+            let response = &function_response.response;
+            Some((
+                response["summary"].as_str().unwrap_or_default().to_string(),
+                response["retained_turns"].as_u64().unwrap_or_default() as usize,
+            ))
+        }
+        _ => None,
+    })
+}
+
+/// The text the model sees in place of the summarized contents.
+fn summary_message(summary: &str) -> Content {
+    Content::new("user").with_text(format!("{}{}", SUMMARY_MESSAGE_PREFIX, summary))
+}
+
+fn transcript(contents: &[Content]) -> String {
+    let mut lines = Vec::new();
+    for content in contents {
+        for part in &content.parts {
+            match part {
+                Part::Text { text } => lines.push(format!("{}: {}", content.role, text)),
+                Part::FunctionCall { name, args, .. } => {
+                    lines.push(format!("{} called {}({})", content.role, name, args))
+                }
+                Part::FunctionResponse { function_response, .. } => lines.push(format!(
+                    "{} returned {}",
+                    function_response.name, function_response.response
+                )),
+                _ => {}
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Replace the contents covered by the latest persisted summary with the summary
+/// text, and drop any other summary markers.
+pub(crate) fn apply_persisted_summaries(history: Vec<Content>) -> Vec<Content> {
+    let Some((marker, (summary, retained_turns))) =
+        history.iter().enumerate().rev().find_map(|(i, c)| summary_part(c).map(|s| (i, s)))
+    else {
+        return history;
+    };
+
+    let start = match retained_turns {
+        0 => marker,
+        n => nth_last_user_turn(&history[..marker], n).unwrap_or(0),
+    };
+
+    let mut compacted = vec![summary_message(&summary)];
+    compacted.extend(
+        history
+            .into_iter()
+            .enumerate()
+            .filter(|(i, c)| *i >= start && summary_part(c).is_none())
+            .map(|(_, c)| c),
+    );
+    compacted
+}
+
+/// Apply a strategy's result to `history`. Returns the compacted history and, for
+/// summaries, the content to persist to the session.
+pub(crate) fn apply_compaction(
+    history: &[Content],
+    compaction: Compaction,
+) -> Option<(Vec<Content>, Option<Content>)> {
+    // Start at a user turn, so no function response loses its call and no model turn
+    // opens the history. Persisted summaries also record how many user turns they kept.
+    let mut keep_from = compaction.keep_from.min(history.len().saturating_sub(1));
+    while keep_from > 0 && !is_user_turn(&history[keep_from]) {
+        keep_from -= 1;
+    }
+    if keep_from == 0 {
+        return None;
+    }
+
+    let kept = &history[keep_from..];
+    match compaction.summary {
+        Some(summary) => {
+            let retained_turns = kept.iter().filter(|c| is_user_turn(c)).count();
+            let marker = Content {
+                role: "model".to_string(),
+                parts: vec![Part::FunctionResponse {
+                    function_response: FunctionResponseData {
+                        name: COMPACTION_SUMMARY_FUNCTION_NAME.to_string(),
+                        response: serde_json::json!({
+                            "summary": summary,
+                            "retained_turns": retained_turns,
+                        }),
+                    },
+                    id: None,
+                }],
+            };
+            let mut compacted = vec![summary_message(&summary)];
+            compacted.extend_from_slice(kept);
+            Some((compacted, Some(marker)))
+        }
+        None => Some((kept.to_vec(), None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user(text: &str) -> Content {
+        Content::new("user").with_text(text)
+    }
+
+    fn model(text: &str) -> Content {
+        Content::new("model").with_text(text)
+    }
+
+    fn call(name: &str) -> Content {
+        Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionCall { name: name.to_string(), args: json!({}), id: None }],
+        }
+    }
+
+    fn response(name: &str) -> Content {
+        Content {
+            role: "user".to_string(),
+            parts: vec![Part::FunctionResponse {
+                function_response: FunctionResponseData {
+                    name: name.to_string(),
+                    response: json!({ "ok": true }),
+                },
+                id: None,
+            }],
+        }
+    }
+
+    fn texts(history: &[Content]) -> Vec<String> {
+        history
+            .iter()
+            .flat_map(|c| c.parts.iter())
+            .map(|p| match p {
+                Part::Text { text } => text.clone(),
+                Part::FunctionCall { name, .. } => format!("call:{}", name),
+                Part::FunctionResponse { function_response, .. } => {
+                    format!("response:{}", function_response.name)
+                }
+                _ => String::new(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_keep_last_turns() {
+        let history = vec![user("a"), model("1"), user("b"), model("2"), user("c")];
+
+        let compaction = KeepLastTurns::new(2).compact(&history).await.unwrap().unwrap();
+        assert_eq!(compaction.keep_from, 2);
+        assert!(KeepLastTurns::new(3).compact(&history).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_summary_message_is_not_a_user_turn() {
+        let history = vec![summary_message("user said a"), user("b"), model("2"), user("c")];
+
+        assert!(!is_user_turn(&history[0]));
+        assert_eq!(nth_last_user_turn(&history, 2), Some(1));
+        assert_eq!(nth_last_user_turn(&history, 3), None);
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_never_splits_function_pairs() {
+        let history = vec![
+            user(&"x".repeat(400)),
+            model("1"),
+            user("b"),
+            call("lookup"),
+            response("lookup"),
+            model("done"),
+        ];
+
+        let compaction = TokenBudget::new(6).compact(&history).await.unwrap().unwrap();
+        assert_eq!(compaction.keep_from, 4);
+
+        let (compacted, marker) = apply_compaction(&history, compaction).unwrap();
+        assert!(marker.is_none());
+        assert_eq!(texts(&compacted), ["b", "call:lookup", "response:lookup", "done"]);
+    }
+
+    #[test]
+    fn test_truncation_starts_at_a_user_turn() {
+        let history =
+            vec![user("a"), model("1"), user("b"), call("lookup"), response("lookup"), user("c")];
+
+        // Neither a model turn nor a function response may open the kept history
+        for keep_from in [3, 4] {
+            let compaction = Compaction { keep_from, summary: None };
+            let (compacted, _) = apply_compaction(&history, compaction).unwrap();
+            assert_eq!(texts(&compacted), ["b", "call:lookup", "response:lookup", "c"]);
+        }
+        let compaction = Compaction { keep_from: 1, summary: None };
+        assert!(apply_compaction(&history, compaction).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_skips_turns_already_summarized() {
+        struct UnusedModel;
+
+        #[async_trait]
+        impl Llm for UnusedModel {
+            fn name(&self) -> &str {
+                "unused"
+            }
+
+            async fn generate_content(
+                &self,
+                _req: LlmRequest,
+                _stream: bool,
+            ) -> Result<adk_core::LlmResponseStream> {
+                panic!("nothing new to summarize")
+            }
+        }
+
+        // A persisted summary followed by one long turn, kept verbatim
+        let history = vec![summary_message("user said a"), user(&"x".repeat(400))];
+        let strategy =
+            SummarizeOlderTurns::new(Arc::new(UnusedModel)).max_tokens(10).keep_last_turns(1);
+
+        assert!(strategy.compact(&history).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_counts_thinking_as_text() {
+        let thought = |thinking: &str| Content {
+            role: "model".to_string(),
+            parts: vec![Part::Thinking { thinking: thinking.to_string(), signature: None }],
+        };
+        assert_eq!(estimate_tokens(&[thought("ok")]), 1);
+
+        let history = vec![user("a"), thought(&"x".repeat(40_000)), model("1"), user("b")];
+        let compaction = TokenBudget::new(1_000).compact(&history).await.unwrap().unwrap();
+        assert_eq!(compaction.keep_from, 2);
+    }
+
+    #[test]
+    fn test_summary_is_persisted_and_reapplied() {
+        let history =
+            vec![user("a"), model("1"), user("b"), call("lookup"), response("lookup"), user("c")];
+        let compaction = Compaction { keep_from: 4, summary: Some("user said a".to_string()) };
+
+        // The cut moves back to the start of a user turn
+        let (compacted, marker) = apply_compaction(&history, compaction).unwrap();
+        let expected = [
+            "Summary of the earlier conversation:\nuser said a",
+            "b",
+            "call:lookup",
+            "response:lookup",
+            "c",
+        ];
+        assert_eq!(texts(&compacted), expected);
+
+        // A later invocation sees the marker in the session and rebuilds the same view
+        let mut session = history.clone();
+        session.push(marker.unwrap());
+        assert_eq!(texts(&apply_persisted_summaries(session)), expected);
+    }
+
+    #[test]
+    fn test_latest_summary_wins() {
+        let marker = |summary: &str, retained_turns: usize| Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionResponse {
+                function_response: FunctionResponseData {
+                    name: COMPACTION_SUMMARY_FUNCTION_NAME.to_string(),
+                    response: json!({ "summary": summary, "retained_turns": retained_turns }),
+                },
+                id: None,
+            }],
+        };
+        let session =
+            vec![user("a"), user("b"), marker("old", 1), user("c"), marker("new", 2), user("d")];
+
+        assert_eq!(
+            texts(&apply_persisted_summaries(session)),
+            ["Summary of the earlier conversation:\nnew", "b", "c", "d"]
+        );
+    }
+}
@@ -41,6 +41,20 @@
 //! let loop_agent = LoopAgent::new("iterator", worker, 10);
 //! ```
 //!
+//! ## Long Conversations
+//!
+//! Keep long sessions within the model's context window with a
+//! [`compaction`] strategy:
+//!
+//! ```rust,ignore
+//! use adk_agent::compaction::TokenBudget;
+//!
+//! let agent = LlmAgentBuilder::new("assistant")
+//!     .model(model)
+//!     .compaction_strategy(Arc::new(TokenBudget::new(32_000)))
+//!     .build()?;
+//! ```
+//!
 //! ## Guardrails (optional)
 //!
 //! Enable the `guardrails` feature for input/output validation:
@@ -57,6 +71,7 @@
 //!     .build()?;
 //! ```
 
+pub mod compaction;
 mod custom_agent;
 pub mod guardrails;
 mod llm_agent;
@@ -66,7 +81,9 @@ mod workflow;
 pub use adk_core::Agent;
 pub use custom_agent::{CustomAgent, CustomAgentBuilder};
 pub use guardrails::GuardrailSet;
-pub use llm_agent::{DEFAULT_MAX_ITERATIONS, LlmAgent, LlmAgentBuilder};
+pub use llm_agent::{
+    DEFAULT_MAX_CONCURRENT_TOOL_CALLS, DEFAULT_MAX_ITERATIONS, LlmAgent, LlmAgentBuilder,
+};
 pub use tool_call_markup::{normalize_content, normalize_option_content};
 pub use workflow::{
     ConditionalAgent, LlmConditionalAgent, LlmConditionalAgentBuilder, LoopAgent, ParallelAgent,
@@ -1,12 +1,17 @@
+use crate::compaction::{self, CompactionStrategy};
 use adk_core::{
     AfterAgentCallback, AfterModelCallback, AfterToolCallback, Agent, BeforeAgentCallback,
-    BeforeModelCallback, BeforeModelResult, BeforeToolCallback, CallbackContext, Content, Event,
-    EventActions, FunctionResponseData, GlobalInstructionProvider, InstructionProvider,
-    InvocationContext, Llm, LlmRequest, LlmResponse, MemoryEntry, Part, ReadonlyContext, Result,
-    Tool, ToolContext,
+    BeforeModelCallback, BeforeModelResult, BeforeToolCallback, BeforeToolResult, CallbackContext,
+    Content, Event, EventActions, FunctionResponseData, GlobalInstructionProvider,
+    InstructionProvider, InvocationContext, Llm, LlmRequest, LlmResponse, MemoryEntry, Part,
+    REQUEST_CONFIRMATION_FUNCTION_NAME, ReadonlyContext, Result, Tool, ToolConfirmation,
+    ToolConfirmationPolicy, ToolConfirmationRequest, ToolContext, ToolExecution,
+    ToolExecutionPolicy,
 };
 use async_stream::stream;
 use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tracing::Instrument;
 
@@ -15,6 +20,10 @@ use crate::guardrails::GuardrailSet;
 /// Default maximum number of LLM round-trips (iterations) before the agent stops.
 pub const DEFAULT_MAX_ITERATIONS: u32 = 100;
 
+/// Default number of tool calls from a single model turn that may run at once.
+/// A value of 1 executes function calls sequentially.
+pub const DEFAULT_MAX_CONCURRENT_TOOL_CALLS: usize = 1;
+
 pub struct LlmAgent {
     name: String,
     description: String,
@@ -31,11 +40,20 @@ pub struct LlmAgent {
     #[allow(dead_code)] // Part of public API via builder
     disallow_transfer_to_peers: bool,
     include_contents: adk_core::IncludeContents,
+    include_thinking: adk_core::IncludeThinking,
     tools: Vec<Arc<dyn Tool>>,
     sub_agents: Vec<Arc<dyn Agent>>,
     output_key: Option<String>,
     /// Maximum number of LLM round-trips before stopping
     max_iterations: u32,
+    /// Maximum number of function calls from one model turn executed concurrently
+    max_concurrent_tool_calls: usize,
+    /// Timeout/retry policy for tools that do not declare their own
+    tool_execution_policy: ToolExecutionPolicy,
+    /// Which tool calls must be approved by a human before they execute
+    tool_confirmation_policy: ToolConfirmationPolicy,
+    /// Shrinks conversation history before each model call
+    compaction_strategy: Option<Arc<dyn CompactionStrategy>>,
     before_callbacks: Arc<Vec<BeforeAgentCallback>>,
     after_callbacks: Arc<Vec<AfterAgentCallback>>,
     before_model_callbacks: Arc<Vec<BeforeModelCallback>>,
@@ -74,10 +92,15 @@ pub struct LlmAgentBuilder {
     disallow_transfer_to_parent: bool,
     disallow_transfer_to_peers: bool,
     include_contents: adk_core::IncludeContents,
+    include_thinking: adk_core::IncludeThinking,
     tools: Vec<Arc<dyn Tool>>,
     sub_agents: Vec<Arc<dyn Agent>>,
     output_key: Option<String>,
     max_iterations: u32,
+    max_concurrent_tool_calls: usize,
+    tool_execution_policy: ToolExecutionPolicy,
+    tool_confirmation_policy: ToolConfirmationPolicy,
+    compaction_strategy: Option<Arc<dyn CompactionStrategy>>,
     before_callbacks: Vec<BeforeAgentCallback>,
     after_callbacks: Vec<AfterAgentCallback>,
     before_model_callbacks: Vec<BeforeModelCallback>,
@@ -103,10 +126,15 @@ impl LlmAgentBuilder {
             disallow_transfer_to_parent: false,
             disallow_transfer_to_peers: false,
             include_contents: adk_core::IncludeContents::Default,
+            include_thinking: adk_core::IncludeThinking::default(),
             tools: Vec::new(),
             sub_agents: Vec::new(),
             output_key: None,
             max_iterations: DEFAULT_MAX_ITERATIONS,
+            max_concurrent_tool_calls: DEFAULT_MAX_CONCURRENT_TOOL_CALLS,
+            tool_execution_policy: ToolExecutionPolicy::default(),
+            tool_confirmation_policy: ToolConfirmationPolicy::default(),
+            compaction_strategy: None,
             before_callbacks: Vec::new(),
             after_callbacks: Vec::new(),
             before_model_callbacks: Vec::new(),
@@ -173,6 +201,13 @@ impl LlmAgentBuilder {
         self
     }
 
+    /// Set which thinking parts from session history are sent back to the model
+    /// (default: [`IncludeThinking::CurrentTurn`](adk_core::IncludeThinking::CurrentTurn)).
+    pub fn include_thinking(mut self, include: adk_core::IncludeThinking) -> Self {
+        self.include_thinking = include;
+        self
+    }
+
     pub fn output_key(mut self, key: impl Into<String>) -> Self {
         self.output_key = Some(key.into());
         self
@@ -185,6 +220,46 @@ impl LlmAgentBuilder {
         self
     }
 
+    /// Set how many function calls from a single model turn may execute concurrently.
+    /// Default is 1 (sequential); values below 1 are treated as 1.
+    ///
+    /// Function response events are always yielded in the order the model issued the
+    /// calls, so state deltas from each tool are applied in call order regardless of
+    /// which tool finishes first.
+    pub fn max_concurrent_tool_calls(mut self, limit: usize) -> Self {
+        self.max_concurrent_tool_calls = limit.max(1);
+        self
+    }
+
+    /// Set the default timeout and retry policy for tool execution.
+    /// Tools that return a policy from [`Tool::execution_policy`] override this default.
+    pub fn tool_execution_policy(mut self, policy: ToolExecutionPolicy) -> Self {
+        self.tool_execution_policy = policy;
+        self
+    }
+
+    /// Set which tool calls require human confirmation, in addition to tools that
+    /// declare it via [`Tool::requires_confirmation`].
+    ///
+    /// When a call requires confirmation the agent emits an `adk_request_confirmation`
+    /// function call event and pauses. A later run whose user content answers it with a
+    /// [`ToolConfirmation`] executes (or rejects) the original call and continues.
+    pub fn tool_confirmation_policy(mut self, policy: ToolConfirmationPolicy) -> Self {
+        self.tool_confirmation_policy = policy;
+        self
+    }
+
+    /// Set a strategy that shrinks conversation history before each model call, such
+    /// as [`KeepLastTurns`](crate::compaction::KeepLastTurns),
+    /// [`TokenBudget`](crate::compaction::TokenBudget) or
+    /// [`SummarizeOlderTurns`](crate::compaction::SummarizeOlderTurns).
+    ///
+    /// Summaries are persisted as session events and reused by later invocations.
+    pub fn compaction_strategy(mut self, strategy: Arc<dyn CompactionStrategy>) -> Self {
+        self.compaction_strategy = Some(strategy);
+        self
+    }
+
     pub fn tool(mut self, tool: Arc<dyn Tool>) -> Self {
         self.tools.push(tool);
         self
@@ -268,10 +343,15 @@ impl LlmAgentBuilder {
             disallow_transfer_to_parent: self.disallow_transfer_to_parent,
             disallow_transfer_to_peers: self.disallow_transfer_to_peers,
             include_contents: self.include_contents,
+            include_thinking: self.include_thinking,
             tools: self.tools,
             sub_agents: self.sub_agents,
             output_key: self.output_key,
             max_iterations: self.max_iterations,
+            max_concurrent_tool_calls: self.max_concurrent_tool_calls,
+            tool_execution_policy: self.tool_execution_policy,
+            tool_confirmation_policy: self.tool_confirmation_policy,
+            compaction_strategy: self.compaction_strategy,
             before_callbacks: Arc::new(self.before_callbacks),
             after_callbacks: Arc::new(self.after_callbacks),
             before_model_callbacks: Arc::new(self.before_model_callbacks),
@@ -362,6 +442,20 @@ impl ToolContext for AgentToolContext {
             Ok(vec![])
         }
     }
+
+    async fn save_memory(&self, entry: MemoryEntry) -> Result<String> {
+        match self.parent_ctx.memory() {
+            Some(memory) => memory.add(entry).await,
+            None => Err(adk_core::AdkError::Memory("memory is not available".into())),
+        }
+    }
+
+    async fn forget_memory(&self, id: &str) -> Result<bool> {
+        match self.parent_ctx.memory() {
+            Some(memory) => memory.delete(id).await,
+            None => Err(adk_core::AdkError::Memory("memory is not available".into())),
+        }
+    }
 }
 
 #[async_trait]
@@ -404,14 +498,20 @@ impl Agent for LlmAgent {
         let output_key = self.output_key.clone();
         let output_schema = self.output_schema.clone();
         let include_contents = self.include_contents;
+        let include_thinking = self.include_thinking;
         let max_iterations = self.max_iterations;
+        let max_concurrent_tool_calls = self.max_concurrent_tool_calls;
+        let tool_execution_policy = self.tool_execution_policy.clone();
+        let tool_confirmation_policy = self.tool_confirmation_policy.clone();
+        let compaction_strategy = self.compaction_strategy.clone();
         // Clone Arc references (cheap)
         let before_agent_callbacks = self.before_callbacks.clone();
         let after_agent_callbacks = self.after_callbacks.clone();
         let before_model_callbacks = self.before_model_callbacks.clone();
         let after_model_callbacks = self.after_model_callbacks.clone();
-        let _before_tool_callbacks = self.before_tool_callbacks.clone();
-        let _after_tool_callbacks = self.after_tool_callbacks.clone();
+        let before_tool_callbacks = self.before_tool_callbacks.clone();
+        let after_tool_callbacks = self.after_tool_callbacks.clone();
+        let cancellation_token = ctx.cancellation_token();
 
         let s = stream! {
             // ===== BEFORE AGENT CALLBACKS =====
@@ -508,7 +608,25 @@ impl Agent for LlmAgent {
             // Load previous conversation turns from the session
             // NOTE: Session history already includes the current user message (added by Runner before agent runs)
             let session_history = ctx.session().conversation_history();
-            conversation_history.extend(session_history);
+
+            // Decisions in the current user message answer confirmation requests from a
+            // paused run of this agent; the synthetic confirmation parts are never sent
+            // to the model
+            let requests = own_confirmation_requests(ctx.session(), &agent_name);
+            let confirmed_calls =
+                resolve_tool_confirmations(&session_history, &requests, ctx.user_content());
+            // A transfer requested in the same model turn as the confirmed calls was
+            // deferred when the run paused, and happens once they have run
+            let deferred_transfer = if confirmed_calls.is_empty() {
+                None
+            } else {
+                deferred_transfer(&session_history, &requests, ctx.user_content())
+            };
+            let history_start = conversation_history.len();
+            // Turns covered by a persisted compaction summary are replaced by the summary
+            conversation_history.extend(compaction::apply_persisted_summaries(
+                filter_thinking_parts(strip_confirmation_parts(session_history), include_thinking),
+            ));
 
             // ===== APPLY INCLUDE_CONTENTS FILTERING =====
             // Control what conversation history the agent sees
@@ -591,10 +709,75 @@ impl Agent for LlmAgent {
             }
 
 
+            // ===== RESUME CONFIRMED TOOL CALLS =====
+            // Execute (or reject) the calls a human decided on, then let the model continue
+            for (request, decision) in confirmed_calls {
+                if cancellation_token.is_cancelled() {
+                    return;
+                }
+                let (tool_result, tool_actions) = if decision.confirmed {
+                    execute_tool_call(
+                        tools.iter().find(|t| t.name() == request.tool_name).cloned(),
+                        tool_execution_policy.clone(),
+                        before_tool_callbacks.clone(),
+                        after_tool_callbacks.clone(),
+                        ctx.clone(),
+                        invocation_id.clone(),
+                        request.tool_name.clone(),
+                        request.args.clone(),
+                    ).await
+                } else {
+                    let reason = decision.reason.unwrap_or_else(|| "no reason given".to_string());
+                    (
+                        serde_json::json!({
+                            "error": format!("Tool {} was not approved: {}", request.tool_name, reason)
+                        }),
+                        EventActions::default(),
+                    )
+                };
+
+                let response_content = Content {
+                    role: "function".to_string(),
+                    parts: vec![Part::FunctionResponse {
+                        function_response: FunctionResponseData {
+                            name: request.tool_name.clone(),
+                            response: tool_result,
+                        },
+                        id: request.function_call_id.clone(),
+                    }],
+                };
+
+                let mut tool_event = Event::new(&invocation_id);
+                tool_event.author = agent_name.clone();
+                tool_event.actions = tool_actions.clone();
+                tool_event.llm_response.content = Some(response_content.clone());
+                yield Ok(tool_event);
+
+                if tool_actions.escalate || tool_actions.skip_summarization {
+                    return;
+                }
+
+                conversation_history.push(response_content);
+            }
+
+            if let Some(target_agent) = deferred_transfer {
+                let mut transfer_event = Event::new(&invocation_id);
+                transfer_event.author = agent_name.clone();
+                transfer_event.actions.transfer_to_agent = Some(target_agent);
+
+                yield Ok(transfer_event);
+                return;
+            }
+
             // Multi-turn loop with max iterations
             let mut iteration = 0;
 
             loop {
+                // A cancelled invocation stops before calling the model again
+                if cancellation_token.is_cancelled() {
+                    return;
+                }
+
                 iteration += 1;
                 if iteration > max_iterations {
                     yield Err(adk_core::AdkError::Agent(
@@ -603,6 +786,33 @@ impl Agent for LlmAgent {
                     return;
                 }
 
+                // ===== COMPACT CONVERSATION HISTORY =====
+                // Shrink the session part of the history (instructions are kept as-is)
+                if let Some(strategy) = &compaction_strategy {
+                    let history_start = history_start.min(conversation_history.len());
+                    let history = &conversation_history[history_start..];
+                    let compacted = match strategy.compact(history).await {
+                        Ok(Some(compaction)) => compaction::apply_compaction(history, compaction),
+                        Ok(None) => None,
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    };
+
+                    if let Some((compacted, summary)) = compacted {
+                        // Persist summaries so later invocations start from them
+                        if let Some(summary) = summary {
+                            let mut summary_event = Event::new(&invocation_id);
+                            summary_event.author = agent_name.clone();
+                            summary_event.llm_response.content = Some(summary);
+                            yield Ok(summary_event);
+                        }
+                        conversation_history.truncate(history_start);
+                        conversation_history.extend(compacted);
+                    }
+                }
+
                 // Build request with conversation history
                 let config = output_schema.as_ref().map(|schema| {
                     adk_core::GenerateContentConfig {
@@ -614,12 +824,15 @@ impl Agent for LlmAgent {
                     }
                 });
 
-                let request = LlmRequest {
+                let mut request = LlmRequest {
                     model: model.name().to_string(),
                     contents: conversation_history.clone(),
                     tools: tool_declarations.clone(),
                     config,
                 };
+                for tool in &tools {
+                    tool.process_llm_request(&mut request);
+                }
 
                 // ===== BEFORE MODEL CALLBACKS =====
                 // These can modify the request or skip the model call by returning a response
@@ -693,6 +906,7 @@ impl Agent for LlmAgent {
                         "gcp.vertex.agent.event_id" = %llm_event_id,
                         "gcp.vertex.agent.invocation_id" = %invocation_id,
                         "gcp.vertex.agent.session_id" = %ctx.session_id(),
+                        "gcp.vertex.agent.user_id" = %ctx.user_id(),
                         "gcp.vertex.agent.llm_request" = %request_json,
                         "gcp.vertex.agent.llm_response" = tracing::field::Empty  // Placeholder for later recording
                     );
@@ -706,13 +920,20 @@ impl Agent for LlmAgent {
                     // Always use streaming internally for LLM calls
                     let mut response_stream = model.generate_content(request, true).await?;
 
-                    use futures::StreamExt;
-
                     // Track last chunk for final event metadata (used in None mode)
                     let mut last_chunk: Option<LlmResponse> = None;
 
-                    // Stream and process chunks with AfterModel callbacks
-                    while let Some(chunk_result) = response_stream.next().await {
+                    // Stream and process chunks with AfterModel callbacks, dropping the
+                    // model's stream if the invocation is cancelled mid-response
+                    loop {
+                        let chunk_result = tokio::select! {
+                            biased;
+                            _ = cancellation_token.cancelled() => return,
+                            chunk_result = response_stream.next() => match chunk_result {
+                                Some(chunk_result) => chunk_result,
+                                None => break,
+                            },
+                        };
                         let mut chunk = match chunk_result {
                             Ok(c) => c,
                             Err(e) => {
@@ -742,6 +963,9 @@ impl Agent for LlmAgent {
                             }
                         }
 
+                        // Record which model answered so usage can be priced per model
+                        chunk.model_name.get_or_insert_with(|| model.name().to_string());
+
                         // Accumulate content for conversation history (always needed)
                         if let Some(chunk_content) = chunk.content.clone() {
                             if let Some(ref mut acc) = accumulated_content {
@@ -762,6 +986,7 @@ impl Agent for LlmAgent {
                             partial_event.llm_response.turn_complete = chunk.turn_complete;
                             partial_event.llm_response.finish_reason = chunk.finish_reason;
                             partial_event.llm_response.usage_metadata = chunk.usage_metadata.clone();
+                            partial_event.llm_response.model_name = chunk.model_name.clone();
                             partial_event.llm_response.content = chunk.content.clone();
 
                             // Populate long_running_tool_ids
@@ -807,6 +1032,7 @@ impl Agent for LlmAgent {
                         if let Some(ref last) = last_chunk {
                             final_event.llm_response.finish_reason = last.finish_reason;
                             final_event.llm_response.usage_metadata = last.usage_metadata.clone();
+                            final_event.llm_response.model_name = last.model_name.clone();
                             final_event.gcp_llm_response = Some(serde_json::to_string(last).unwrap_or_default());
                         }
 
@@ -903,97 +1129,135 @@ impl Agent for LlmAgent {
 
                 // Execute function calls and add responses to history
                 if let Some(content) = &accumulated_content {
-                    for part in &content.parts {
-                        if let Part::FunctionCall { name, args, id } = part {
-                            // Handle transfer_to_agent specially
-                            if name == "transfer_to_agent" {
-                                let target_agent = args.get("agent_name")
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or_default()
-                                    .to_string();
-
-                                let mut transfer_event = Event::new(&invocation_id);
-                                transfer_event.author = agent_name.clone();
-                                transfer_event.actions.transfer_to_agent = Some(target_agent);
-
-                                yield Ok(transfer_event);
-                                return;
-                            }
+                    let function_calls: Vec<(String, serde_json::Value, Option<String>)> = content.parts.iter()
+                        .filter_map(|p| match p {
+                            Part::FunctionCall { name, args, id } => Some((name.clone(), args.clone(), id.clone())),
+                            _ => None,
+                        })
+                        .collect();
+
+                    // transfer_to_agent ends the turn, so only the calls preceding it are executed
+                    let transfer_index = function_calls.iter().position(|(name, _, _)| name == "transfer_to_agent");
+                    let executable_calls = &function_calls[..transfer_index.unwrap_or(function_calls.len())];
+
+                    // Calls that need human approval are held back and paused on below
+                    let (calls_to_confirm, calls_to_run): (Vec<_>, Vec<_>) = executable_calls.iter()
+                        .enumerate()
+                        .partition(|(_, (name, _, _))| {
+                            tool_confirmation_policy.requires_confirmation(name)
+                                || tools.iter().any(|t| t.name() == name && t.requires_confirmation())
+                        });
+                    let calls_to_run: Vec<_> = calls_to_run.into_iter().map(|(_, call)| call).collect();
+
+                    // Up to max_concurrent_tool_calls tools run at once; `buffered` yields the
+                    // results in call order, so events and state deltas stay deterministic
+                    let pending_calls: Vec<_> = calls_to_run.iter()
+                        .map(|(name, args, _)| execute_tool_call(
+                            tools.iter().find(|t| t.name() == name).cloned(),
+                            tool_execution_policy.clone(),
+                            before_tool_callbacks.clone(),
+                            after_tool_callbacks.clone(),
+                            ctx.clone(),
+                            invocation_id.clone(),
+                            name.clone(),
+                            args.clone(),
+                        ))
+                        .collect();
+                    let mut tool_results = futures::stream::iter(pending_calls).buffered(max_concurrent_tool_calls);
+
+                    // Tools still running when the invocation is cancelled are dropped
+                    let mut call_index = 0;
+                    loop {
+                        let (tool_result, tool_actions) = tokio::select! {
+                            biased;
+                            _ = cancellation_token.cancelled() => return,
+                            result = tool_results.next() => match result {
+                                Some(result) => result,
+                                None => break,
+                            },
+                        };
+                        let (name, _, id) = calls_to_run[call_index];
+                        call_index += 1;
+
+                        // Yield tool execution event
+                        let mut tool_event = Event::new(&invocation_id);
+                        tool_event.author = agent_name.clone();
+                        tool_event.actions = tool_actions.clone();
+                        tool_event.llm_response.content = Some(Content {
+                            role: "function".to_string(),
+                            parts: vec![Part::FunctionResponse {
+                                function_response: FunctionResponseData {
+                                    name: name.clone(),
+                                    response: tool_result.clone(),
+                                },
+                                id: id.clone(),
+                            }],
+                        });
+                        yield Ok(tool_event);
+
+                        // Check if tool requested escalation or skip_summarization
+                        if tool_actions.escalate || tool_actions.skip_summarization {
+                            // Tool wants to terminate agent loop
+                            return;
+                        }
 
+                        // Add function response to history
+                        conversation_history.push(Content {
+                            role: "function".to_string(),
+                            parts: vec![Part::FunctionResponse {
+                                function_response: FunctionResponseData {
+                                    name: name.clone(),
+                                    response: tool_result,
+                                },
+                                id: id.clone(),
+                            }],
+                        });
+                    }
 
-                            // Find and execute tool
-                            let (tool_result, tool_actions) = if let Some(tool) = tools.iter().find(|t| t.name() == name) {
-                                // ✅ Use AgentToolContext that preserves parent context
-                                let tool_ctx: Arc<dyn ToolContext> = Arc::new(AgentToolContext::new(
-                                    ctx.clone(),
-                                    format!("{}_{}", invocation_id, name),
-                                ));
-
-                                // Create span name following adk-go pattern: "execute_tool {name}"
-                                let span_name = format!("execute_tool {}", name);
-                                let tool_span = tracing::info_span!(
-                                    "",
-                                    otel.name = %span_name,
-                                    tool.name = %name,
-                                    "gcp.vertex.agent.event_id" = %format!("{}_{}", invocation_id, name),
-                                    "gcp.vertex.agent.invocation_id" = %invocation_id,
-                                    "gcp.vertex.agent.session_id" = %ctx.session_id()
-                                );
+                    // Pause the invocation until a human answers the confirmation requests.
+                    // The requests are persisted with the event, and marked long-running so
+                    // the event is treated as this run's final response. A transfer_to_agent
+                    // call from this turn is deferred until the confirmed calls have run.
+                    if !calls_to_confirm.is_empty() {
+                        let mut confirmation_event = Event::new(&invocation_id);
+                        confirmation_event.author = agent_name.clone();
+                        let mut parts = Vec::new();
+                        for (index, (name, args, id)) in calls_to_confirm {
+                            let confirmation_id = format!(
+                                "confirm_{}",
+                                id.clone().unwrap_or_else(|| format!("{}_{}_{}", invocation_id, name, index))
+                            );
+                            let request = ToolConfirmationRequest {
+                                tool_name: name.clone(),
+                                args: args.clone(),
+                                function_call_id: id.clone(),
+                                hint: None,
+                            };
+                            parts.push(request.to_part(&confirmation_id));
+                            confirmation_event.long_running_tool_ids.push(confirmation_id.clone());
+                            confirmation_event.actions.requested_tool_confirmations.insert(confirmation_id, request);
+                        }
+                        confirmation_event.llm_response.content = Some(Content { role: "model".to_string(), parts });
 
-                                // Use instrument() for proper async span handling
-                                let result = async {
-                                    tracing::info!(tool.name = %name, tool.args = %args, "tool_call");
-                                    match tool.execute(tool_ctx.clone(), args.clone()).await {
-                                        Ok(result) => {
-                                            tracing::info!(tool.name = %name, tool.result = %result, "tool_result");
-                                            result
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!(tool.name = %name, error = %e, "tool_error");
-                                            serde_json::json!({ "error": e.to_string() })
-                                        }
-                                    }
-                                }.instrument(tool_span).await;
+                        tracing::info!(agent.name = %agent_name, "Paused for tool confirmation");
+                        yield Ok(confirmation_event);
+                        return;
+                    }
 
-                                (result, tool_ctx.actions())
-                            } else {
-                                (serde_json::json!({ "error": format!("Tool {} not found", name) }), EventActions::default())
-                            };
+                    // Handle transfer_to_agent specially
+                    if let Some(index) = transfer_index {
+                        let (_, args, _) = &function_calls[index];
+                        let target_agent = args.get("agent_name")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string();
 
-                            // Yield tool execution event
-                            let mut tool_event = Event::new(&invocation_id);
-                            tool_event.author = agent_name.clone();
-                            tool_event.actions = tool_actions.clone();
-                            tool_event.llm_response.content = Some(Content {
-                                role: "function".to_string(),
-                                parts: vec![Part::FunctionResponse {
-                                    function_response: FunctionResponseData {
-                                        name: name.clone(),
-                                        response: tool_result.clone(),
-                                    },
-                                    id: id.clone(),
-                                }],
-                            });
-                            yield Ok(tool_event);
-
-                            // Check if tool requested escalation or skip_summarization
-                            if tool_actions.escalate || tool_actions.skip_summarization {
-                                // Tool wants to terminate agent loop
-                                return;
-                            }
+                        let mut transfer_event = Event::new(&invocation_id);
+                        transfer_event.author = agent_name.clone();
+                        transfer_event.actions.transfer_to_agent = Some(target_agent);
 
-                            // Add function response to history
-                            conversation_history.push(Content {
-                                role: "function".to_string(),
-                                parts: vec![Part::FunctionResponse {
-                                    function_response: FunctionResponseData {
-                                        name: name.clone(),
-                                        response: tool_result,
-                                    },
-                                    id: id.clone(),
-                                }],
-                            });
-                        }
+                        yield Ok(transfer_event);
+                        return;
                     }
                 }
 
@@ -1035,3 +1299,257 @@ impl Agent for LlmAgent {
         Ok(Box::pin(s))
     }
 }
+
+/// The tool confirmations that events of `agent_name` requested in `session`, keyed by
+/// confirmation id.
+fn own_confirmation_requests(
+    session: &dyn adk_core::Session,
+    agent_name: &str,
+) -> HashMap<String, ToolConfirmationRequest> {
+    session
+        .confirmation_request_events()
+        .into_iter()
+        .filter(|event| event.author == agent_name)
+        .flat_map(|event| event.actions.requested_tool_confirmations)
+        .collect()
+}
+
+/// Pairs each confirmation decision in `user_content` with the request it answers,
+/// looked up from the session history.
+///
+/// Requests whose call already has a response after them in the history were decided
+/// before, so a replayed decision does not run the tool again.
+fn resolve_tool_confirmations(
+    history: &[Content],
+    requests: &HashMap<String, ToolConfirmationRequest>,
+    user_content: &Content,
+) -> Vec<(ToolConfirmationRequest, ToolConfirmation)> {
+    user_content
+        .parts
+        .iter()
+        .filter_map(ToolConfirmation::from_part)
+        .filter_map(|(confirmation_id, decision)| {
+            let (position, request) =
+                find_confirmation_request(history, requests, &confirmation_id)?;
+            let answered = history[position + 1..]
+                .iter()
+                .flat_map(|content| content.parts.iter())
+                .any(|part| match part {
+                    Part::FunctionResponse { function_response, id } => {
+                        function_response.name == request.tool_name
+                            && *id == request.function_call_id
+                    }
+                    _ => false,
+                });
+            (!answered).then_some((request, decision))
+        })
+        .collect()
+}
+
+/// Finds the confirmation request with the given id, returning the index of the
+/// history content holding it.
+///
+/// Only ids in `requests`, recorded by this agent's own events, are accepted, so a
+/// request call placed anywhere else in the history is never resumed.
+fn find_confirmation_request(
+    history: &[Content],
+    requests: &HashMap<String, ToolConfirmationRequest>,
+    confirmation_id: &str,
+) -> Option<(usize, ToolConfirmationRequest)> {
+    let request = requests.get(confirmation_id)?;
+    let position = history.iter().position(|content| {
+        content.role != "user"
+            && content
+                .parts
+                .iter()
+                .filter_map(ToolConfirmationRequest::from_part)
+                .any(|(id, _)| id == confirmation_id)
+    })?;
+    Some((position, request.clone()))
+}
+
+/// Returns the target of a `transfer_to_agent` call made in the model turn that was
+/// paused for the confirmations answered in `user_content`.
+fn deferred_transfer(
+    history: &[Content],
+    requests: &HashMap<String, ToolConfirmationRequest>,
+    user_content: &Content,
+) -> Option<String> {
+    let (position, _) =
+        user_content.parts.iter().filter_map(ToolConfirmation::from_part).find_map(
+            |(confirmation_id, _)| find_confirmation_request(history, requests, &confirmation_id),
+        )?;
+
+    // The paused turn runs back to the user message that started it
+    history[..position]
+        .iter()
+        .rev()
+        .take_while(|content| content.role != "user")
+        .flat_map(|content| content.parts.iter())
+        .find_map(|part| match part {
+            Part::FunctionCall { name, args, .. } if name == "transfer_to_agent" => Some(
+                args.get("agent_name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            ),
+            _ => None,
+        })
+}
+
+/// Removes confirmation requests and decisions from the history, dropping contents
+/// that are left without parts.
+fn strip_confirmation_parts(history: Vec<Content>) -> Vec<Content> {
+    history
+        .into_iter()
+        .filter_map(|mut content| {
+            content.parts.retain(|part| match part {
+                Part::FunctionCall { name, .. } => name != REQUEST_CONFIRMATION_FUNCTION_NAME,
+                Part::FunctionResponse { function_response, .. } => {
+                    function_response.name != REQUEST_CONFIRMATION_FUNCTION_NAME
+                }
+                _ => true,
+            });
+            (!content.parts.is_empty()).then_some(content)
+        })
+        .collect()
+}
+
+/// Removes thinking parts the policy excludes from the history, dropping contents
+/// that are left without parts.
+///
+/// The current turn starts at the latest user message with content of its own, so
+/// replies that only answer tool confirmations stay within the turn they resume.
+fn filter_thinking_parts(
+    history: Vec<Content>,
+    include: adk_core::IncludeThinking,
+) -> Vec<Content> {
+    let keep_from = match include {
+        adk_core::IncludeThinking::All => return history,
+        adk_core::IncludeThinking::None => history.len(),
+        adk_core::IncludeThinking::CurrentTurn => history
+            .iter()
+            .rposition(|content| {
+                content.role == "user"
+                    && content.parts.iter().any(|p| !matches!(p, Part::FunctionResponse { .. }))
+            })
+            .unwrap_or(0),
+    };
+
+    history
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, mut content)| {
+            if i < keep_from {
+                content.parts.retain(|part| !part.is_thinking());
+            }
+            (!content.parts.is_empty()).then_some(content)
+        })
+        .collect()
+}
+
+/// Executes a single function call against its tool and returns the response payload
+/// together with the actions the tool recorded on its context.
+///
+/// The tool's own [`ToolExecutionPolicy`] takes precedence over `default_policy` and is
+/// applied through [`ToolExecutionPolicy::run`]. Tool errors, timeouts and unknown tools
+/// are reported back to the model as `{"error": ...}` responses rather than failing
+/// the agent. Before-tool callbacks run once ahead of all attempts and after-tool
+/// callbacks once on the final outcome.
+#[allow(clippy::too_many_arguments)]
+async fn execute_tool_call(
+    tool: Option<Arc<dyn Tool>>,
+    default_policy: ToolExecutionPolicy,
+    before_tool_callbacks: Arc<Vec<BeforeToolCallback>>,
+    after_tool_callbacks: Arc<Vec<AfterToolCallback>>,
+    ctx: Arc<dyn InvocationContext>,
+    invocation_id: String,
+    name: String,
+    mut args: serde_json::Value,
+) -> (serde_json::Value, EventActions) {
+    let Some(tool) = tool else {
+        return (
+            serde_json::json!({ "error": format!("Tool {} not found", name) }),
+            EventActions::default(),
+        );
+    };
+    let policy = tool.execution_policy().unwrap_or(default_policy);
+
+    // Create span name following adk-go pattern: "execute_tool {name}"
+    let span_name = format!("execute_tool {}", name);
+    let tool_span = tracing::info_span!(
+        "",
+        otel.name = %span_name,
+        tool.name = %name,
+        "gcp.vertex.agent.event_id" = %format!("{}_{}", invocation_id, name),
+        "gcp.vertex.agent.invocation_id" = %invocation_id,
+        "gcp.vertex.agent.session_id" = %ctx.session_id(),
+        "gcp.vertex.agent.user_id" = %ctx.user_id()
+    );
+
+    // Use instrument() for proper async span handling
+    async {
+        // ===== BEFORE TOOL CALLBACKS =====
+        // These can rewrite the arguments or skip the tool by returning a result
+        for callback in before_tool_callbacks.as_ref() {
+            match callback(ctx.clone() as Arc<dyn CallbackContext>, tool.clone(), args.clone())
+                .await
+            {
+                Ok(BeforeToolResult::Continue(modified_args)) => args = modified_args,
+                Ok(BeforeToolResult::Skip(result)) => return (result, EventActions::default()),
+                Err(e) => {
+                    return (
+                        serde_json::json!({ "error": e.to_string() }),
+                        EventActions::default(),
+                    );
+                }
+            }
+        }
+
+        tracing::info!(tool.name = %name, tool.args = %args, "tool_call");
+        // ✅ Use AgentToolContext that preserves parent context
+        let execution = policy
+            .run(
+                tool.as_ref(),
+                || {
+                    Arc::new(AgentToolContext::new(
+                        ctx.clone(),
+                        format!("{}_{}", invocation_id, name),
+                    )) as Arc<dyn ToolContext>
+                },
+                &args,
+            )
+            .await;
+        match &execution.result {
+            Ok(result) => tracing::info!(tool.name = %name, tool.result = %result, "tool_result"),
+            Err(_) if execution.timed_out => {
+                tracing::warn!(tool.name = %name, attempts = execution.attempts, "tool_timeout")
+            }
+            Err(e) => tracing::warn!(
+                tool.name = %name,
+                error = %e,
+                attempts = execution.attempts,
+                "tool_error"
+            ),
+        }
+        let ToolExecution { result, response, actions, .. } = execution;
+
+        // ===== AFTER TOOL CALLBACKS =====
+        // The first callback that returns a value replaces the result sent to the model
+        for callback in after_tool_callbacks.as_ref() {
+            match callback(
+                ctx.clone() as Arc<dyn CallbackContext>,
+                tool.clone(),
+                args.clone(),
+                result.clone(),
+            )
+            .await
+            {
+                Ok(Some(replacement)) => return (replacement, actions),
+                Ok(None) => continue,
+                Err(e) => return (serde_json::json!({ "error": e.to_string() }), actions),
+            }
+        }
+
+        (response, actions)
+    }
+    .instrument(tool_span)
+    .await
+}
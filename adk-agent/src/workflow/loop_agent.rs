@@ -65,6 +65,7 @@ impl Agent for LoopAgent {
     async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
         let sub_agents = self.sub_agents.clone();
         let max_iterations = self.max_iterations;
+        let cancellation_token = ctx.cancellation_token();
 
         let s = stream! {
             use futures::StreamExt;
@@ -75,9 +76,20 @@ impl Agent for LoopAgent {
                 let mut should_exit = false;
 
                 for agent in &sub_agents {
+                    if cancellation_token.is_cancelled() {
+                        return;
+                    }
                     let mut stream = agent.run(ctx.clone()).await?;
 
-                    while let Some(result) = stream.next().await {
+                    loop {
+                        let result = tokio::select! {
+                            biased;
+                            _ = cancellation_token.cancelled() => return,
+                            result = stream.next() => match result {
+                                Some(result) => result,
+                                None => break,
+                            },
+                        };
                         match result {
                             Ok(event) => {
                                 // Append content to session history for sequential agent support
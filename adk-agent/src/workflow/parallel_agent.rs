@@ -57,6 +57,7 @@ impl Agent for ParallelAgent {
 
     async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
         let sub_agents = self.sub_agents.clone();
+        let cancellation_token = ctx.cancellation_token();
 
         let s = stream! {
             use futures::stream::{FuturesUnordered, StreamExt};
@@ -73,7 +74,15 @@ impl Agent for ParallelAgent {
             while let Some(result) = futures.next().await {
                 match result {
                     Ok(mut stream) => {
-                        while let Some(event_result) = stream.next().await {
+                        loop {
+                            let event_result = tokio::select! {
+                                biased;
+                                _ = cancellation_token.cancelled() => return,
+                                event_result = stream.next() => match event_result {
+                                    Some(event_result) => event_result,
+                                    None => break,
+                                },
+                            };
                             yield event_result;
                         }
                     }
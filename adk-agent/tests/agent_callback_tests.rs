@@ -33,6 +33,7 @@ impl adk_core::Llm for MockLlm {
                 interrupted: false,
                 error_code: None,
                 error_message: None,
+                model_name: None,
             });
         };
         Ok(Box::pin(s))
@@ -0,0 +1,250 @@
+use adk_agent::{CustomAgent, LlmAgentBuilder, SequentialAgent};
+use adk_core::{
+    Agent, CancellationToken, Content, Event, InvocationContext, Llm, LlmRequest, LlmResponse,
+    LlmResponseStream, Part, ReadonlyContext, Result, RunConfig, ToolContext,
+};
+use adk_tool::FunctionTool;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn text_response(text: &str) -> LlmResponse {
+    LlmResponse {
+        content: Some(Content::new("model").with_text(text)),
+        usage_metadata: None,
+        finish_reason: None,
+        partial: true,
+        turn_complete: false,
+        interrupted: false,
+        error_code: None,
+        error_message: None,
+        model_name: None,
+    }
+}
+
+/// Streams one chunk and then stalls, like a slow model
+struct StallingModel;
+
+#[async_trait]
+impl Llm for StallingModel {
+    fn name(&self) -> &str {
+        "stalling-model"
+    }
+
+    async fn generate_content(&self, _req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        let first = futures::stream::iter([Ok(text_response("Let me think"))]);
+        Ok(Box::pin(first.chain(futures::stream::pending())))
+    }
+}
+
+/// Always asks for the `cancel` tool, counting its calls
+#[derive(Default)]
+struct ToolCallingModel {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Llm for ToolCallingModel {
+    fn name(&self) -> &str {
+        "tool-calling-model"
+    }
+
+    async fn generate_content(&self, _req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let mut response = text_response("");
+        response.content = Some(Content {
+            role: "model".to_string(),
+            parts: vec![Part::FunctionCall {
+                name: "cancel".to_string(),
+                args: serde_json::json!({}),
+                id: Some("call-1".to_string()),
+            }],
+        });
+        response.partial = false;
+        response.turn_complete = true;
+        Ok(Box::pin(futures::stream::iter([Ok(response)])))
+    }
+}
+
+struct CancellableContext {
+    content: Content,
+    config: RunConfig,
+    token: CancellationToken,
+}
+
+impl CancellableContext {
+    fn new(token: CancellationToken) -> Self {
+        Self { content: Content::new("user").with_text("Hi"), config: RunConfig::default(), token }
+    }
+}
+
+#[async_trait]
+impl ReadonlyContext for CancellableContext {
+    fn invocation_id(&self) -> &str {
+        "test-invocation"
+    }
+    fn agent_name(&self) -> &str {
+        "test-agent"
+    }
+    fn user_id(&self) -> &str {
+        "test-user"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn session_id(&self) -> &str {
+        "test-session"
+    }
+    fn branch(&self) -> &str {
+        ""
+    }
+    fn user_content(&self) -> &Content {
+        &self.content
+    }
+}
+
+#[async_trait]
+impl adk_core::CallbackContext for CancellableContext {
+    fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+        None
+    }
+}
+
+#[async_trait]
+impl InvocationContext for CancellableContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+        None
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+    fn session(&self) -> &dyn adk_core::Session {
+        &DummySession
+    }
+    fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+struct DummySession;
+
+impl adk_core::Session for DummySession {
+    fn id(&self) -> &str {
+        "test-session"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn user_id(&self) -> &str {
+        "test-user"
+    }
+    fn state(&self) -> &dyn adk_core::State {
+        &DummyState
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        Vec::new()
+    }
+}
+
+struct DummyState;
+
+impl adk_core::State for DummyState {
+    fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+    fn set(&mut self, _key: String, _value: Value) {}
+    fn all(&self) -> std::collections::HashMap<String, Value> {
+        std::collections::HashMap::new()
+    }
+}
+
+#[tokio::test]
+async fn test_llm_agent_stops_mid_response_when_cancelled() {
+    let agent = LlmAgentBuilder::new("test-agent").model(Arc::new(StallingModel)).build().unwrap();
+    let token = CancellationToken::new();
+    let mut stream = agent.run(Arc::new(CancellableContext::new(token.clone()))).await.unwrap();
+
+    let first = stream.next().await.unwrap().unwrap();
+    assert!(first.llm_response.partial);
+
+    token.cancel();
+    let next = tokio::time::timeout(Duration::from_secs(5), stream.next()).await.unwrap();
+    assert!(next.is_none());
+}
+
+#[tokio::test]
+async fn test_llm_agent_does_not_call_model_again_when_cancelled() {
+    let token = CancellationToken::new();
+    let tool_token = token.clone();
+    let cancel_tool = FunctionTool::new(
+        "cancel",
+        "Cancels the invocation",
+        move |_ctx: Arc<dyn ToolContext>, _args: Value| {
+            let token = tool_token.clone();
+            async move {
+                token.cancel();
+                Ok(serde_json::json!({"cancelled": true}))
+            }
+        },
+    );
+    let model = Arc::new(ToolCallingModel::default());
+    let agent = LlmAgentBuilder::new("test-agent")
+        .model(model.clone())
+        .tool(Arc::new(cancel_tool))
+        .build()
+        .unwrap();
+
+    let stream = agent.run(Arc::new(CancellableContext::new(token))).await.unwrap();
+    let events: Vec<Event> = stream.map(|event| event.unwrap()).collect().await;
+
+    // The model's call and the tool's response, then nothing more
+    assert_eq!(events.len(), 2);
+    assert_eq!(model.calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_sequential_agent_stops_between_sub_agents_when_cancelled() {
+    let token = CancellationToken::new();
+    let first_token = token.clone();
+    let first = CustomAgent::builder("first")
+        .handler(move |ctx| {
+            let token = first_token.clone();
+            async move {
+                let mut event = Event::new(ctx.invocation_id());
+                event.author = "first".to_string();
+                // Cancelled once its answer is out
+                let events = async_stream::stream! {
+                    yield Ok(event);
+                    token.cancel();
+                };
+                Ok(Box::pin(events) as adk_core::EventStream)
+            }
+        })
+        .build()
+        .unwrap();
+    let second = CustomAgent::builder("second")
+        .handler(|ctx| async move {
+            let mut event = Event::new(ctx.invocation_id());
+            event.author = "second".to_string();
+            Ok(Box::pin(futures::stream::iter([Ok(event)])) as adk_core::EventStream)
+        })
+        .build()
+        .unwrap();
+
+    let agent = SequentialAgent::new("pipeline", vec![Arc::new(first), Arc::new(second)]);
+    let stream = agent.run(Arc::new(CancellableContext::new(token))).await.unwrap();
+    let events: Vec<Event> = stream.map(|event| event.unwrap()).collect().await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].author, "first");
+}
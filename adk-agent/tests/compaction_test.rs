@@ -0,0 +1,310 @@
+use adk_agent::LlmAgentBuilder;
+use adk_agent::compaction::{COMPACTION_SUMMARY_FUNCTION_NAME, KeepLastTurns, SummarizeOlderTurns};
+use adk_core::{
+    Agent, Content, Event, FinishReason, InvocationContext, Llm, LlmRequest, LlmResponse,
+    LlmResponseStream, Part, Result, RunConfig, Session, State,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// --- Mocks ---
+
+/// Answers every request with fixed text and records the requests it receives.
+struct RecordingModel {
+    name: String,
+    reply: String,
+    requests: Mutex<Vec<LlmRequest>>,
+}
+
+impl RecordingModel {
+    fn new(name: &str, reply: &str) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            reply: reply.to_string(),
+            requests: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    fn last_request_texts(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        texts(&requests.last().unwrap().contents)
+    }
+}
+
+#[async_trait]
+impl Llm for RecordingModel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn generate_content(&self, req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        self.requests.lock().unwrap().push(req);
+        let response = LlmResponse {
+            content: Some(Content::new("model").with_text(&self.reply)),
+            usage_metadata: None,
+            finish_reason: Some(FinishReason::Stop),
+            partial: false,
+            turn_complete: true,
+            interrupted: false,
+            error_code: None,
+            error_message: None,
+            model_name: None,
+        };
+        let s = async_stream::stream! {
+            yield Ok(response);
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+struct MockSession {
+    history: Vec<Content>,
+}
+
+impl Session for MockSession {
+    fn id(&self) -> &str {
+        "session-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn state(&self) -> &dyn State {
+        &MockState
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        self.history.clone()
+    }
+}
+
+struct MockState;
+impl State for MockState {
+    fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+    fn set(&mut self, _key: String, _value: Value) {}
+    fn all(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+}
+
+/// Context whose session history is the given contents followed by the user message,
+/// mirroring how the runner appends the user message before running the agent.
+struct MockContext {
+    session: MockSession,
+    user_content: Content,
+    config: RunConfig,
+}
+
+impl MockContext {
+    fn new(mut history: Vec<Content>, user_content: Content) -> Self {
+        history.push(user_content.clone());
+        Self { session: MockSession { history }, user_content, config: RunConfig::default() }
+    }
+}
+
+#[async_trait]
+impl adk_core::ReadonlyContext for MockContext {
+    fn invocation_id(&self) -> &str {
+        "inv-1"
+    }
+    fn agent_name(&self) -> &str {
+        "test-agent"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn session_id(&self) -> &str {
+        "session-1"
+    }
+    fn branch(&self) -> &str {
+        ""
+    }
+    fn user_content(&self) -> &Content {
+        &self.user_content
+    }
+}
+
+#[async_trait]
+impl adk_core::CallbackContext for MockContext {
+    fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+        None
+    }
+}
+
+#[async_trait]
+impl InvocationContext for MockContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+        None
+    }
+    fn session(&self) -> &dyn Session {
+        &self.session
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+}
+
+// --- Helpers ---
+
+fn texts(contents: &[Content]) -> Vec<String> {
+    contents
+        .iter()
+        .flat_map(|c| c.parts.iter())
+        .filter_map(|p| match p {
+            Part::Text { text } => Some(text.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Three earlier exchanges, each long enough to push the history over a small budget.
+fn long_history() -> Vec<Content> {
+    let mut history = Vec::new();
+    for turn in ["first", "second", "third"] {
+        history.push(Content::new("user").with_text(format!(
+            "{} question {}",
+            turn,
+            "x".repeat(200)
+        )));
+        history.push(Content::new("model").with_text(format!("{} answer", turn)));
+    }
+    history
+}
+
+async fn run(agent: &dyn Agent, ctx: MockContext) -> Vec<Event> {
+    let mut stream = agent.run(Arc::new(ctx)).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+    events
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_keep_last_turns_trims_request_history() {
+    let model = RecordingModel::new("main", "ok");
+    let agent = LlmAgentBuilder::new("assistant")
+        .instruction("Be brief.")
+        .model(model.clone())
+        .compaction_strategy(Arc::new(KeepLastTurns::new(2)))
+        .build()
+        .unwrap();
+
+    let question = Content::new("user").with_text("fourth question");
+    let events = run(&agent, MockContext::new(long_history(), question)).await;
+
+    // Instructions are kept, only the last two user turns reach the model
+    let sent = model.last_request_texts();
+    assert_eq!(sent[0], "Be brief.");
+    assert!(sent[1].starts_with("third question"));
+    assert_eq!(&sent[2..], ["third answer", "fourth question"]);
+    // Truncation is not persisted
+    assert_eq!(events.len(), 1);
+}
+
+#[tokio::test]
+async fn test_summary_is_persisted_and_reused() {
+    let model = RecordingModel::new("main", "ok");
+    let summarizer = RecordingModel::new("summarizer", "they asked three questions");
+    let agent = LlmAgentBuilder::new("assistant")
+        .model(model.clone())
+        .compaction_strategy(Arc::new(
+            SummarizeOlderTurns::new(summarizer.clone()).max_tokens(100).keep_last_turns(1),
+        ))
+        .build()
+        .unwrap();
+
+    let question = Content::new("user").with_text("fourth question");
+    let events = run(&agent, MockContext::new(long_history(), question.clone())).await;
+
+    assert_eq!(summarizer.request_count(), 1);
+    assert!(summarizer.last_request_texts()[0].contains("first question"));
+    assert_eq!(
+        model.last_request_texts(),
+        ["Summary of the earlier conversation:\nthey asked three questions", "fourth question"]
+    );
+
+    // The first event carries the persisted summary
+    let summary_content = events[0].llm_response.content.clone().unwrap();
+    assert!(matches!(
+        &summary_content.parts[0],
+        Part::FunctionResponse { function_response, .. }
+            if function_response.name == COMPACTION_SUMMARY_FUNCTION_NAME
+    ));
+
+    // The next invocation starts from the persisted summary without summarizing again
+    let mut history = long_history();
+    history.push(question);
+    history.extend(events.iter().filter_map(|e| e.llm_response.content.clone()));
+    let follow_up = Content::new("user").with_text("fifth question");
+    run(&agent, MockContext::new(history, follow_up)).await;
+
+    assert_eq!(summarizer.request_count(), 1);
+    assert_eq!(
+        model.last_request_texts(),
+        [
+            "Summary of the earlier conversation:\nthey asked three questions",
+            "fourth question",
+            "ok",
+            "fifth question"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_compacting_twice_summarizes_the_earlier_summary() {
+    let model = RecordingModel::new("main", "ok");
+    let summarizer = RecordingModel::new("summarizer", "summary");
+    let agent = LlmAgentBuilder::new("assistant")
+        .model(model.clone())
+        .compaction_strategy(Arc::new(
+            SummarizeOlderTurns::new(summarizer.clone()).max_tokens(100).keep_last_turns(1),
+        ))
+        .build()
+        .unwrap();
+
+    let mut history = long_history();
+    let question = Content::new("user").with_text("fourth question");
+    let events = run(&agent, MockContext::new(history.clone(), question.clone())).await;
+    history.push(question);
+    history.extend(events.iter().filter_map(|e| e.llm_response.content.clone()));
+    assert!(events[0].is_compaction_summary());
+
+    // The new question alone exceeds the budget, so the history is compacted again
+    let question = Content::new("user").with_text(format!("fifth question {}", "y".repeat(400)));
+    let events = run(&agent, MockContext::new(history, question)).await;
+
+    // The second summary covers the first one instead of the turns it replaced
+    assert_eq!(summarizer.request_count(), 2);
+    let prompt = &summarizer.last_request_texts()[0];
+    assert!(prompt.contains("Summary of the earlier conversation:\nsummary"));
+    assert!(prompt.contains("fourth question"));
+    assert!(!prompt.contains("first question"));
+    assert!(events[0].is_compaction_summary());
+    let sent = model.last_request_texts();
+    assert_eq!(sent[0], "Summary of the earlier conversation:\nsummary");
+    assert!(sent[1].starts_with("fifth question"));
+    assert_eq!(sent.len(), 2);
+}
@@ -36,6 +36,7 @@ impl MockModel {
                 interrupted: false,
                 error_code: None,
                 error_message: None,
+                model_name: None,
             },
         }
     }
@@ -0,0 +1,280 @@
+use adk_agent::LlmAgentBuilder;
+use adk_core::{
+    Agent, Content, Event, FinishReason, InvocationContext, Llm, LlmRequest, LlmResponse,
+    LlmResponseStream, Part, Result, RunConfig, Session, State, Tool, ToolContext,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// --- Mocks ---
+
+/// Issues one function call per tool name on the first turn, then answers with text
+/// once the function responses are in the request.
+struct MultiCallModel {
+    tool_names: Vec<String>,
+}
+
+#[async_trait]
+impl Llm for MultiCallModel {
+    fn name(&self) -> &str {
+        "multi-call-model"
+    }
+
+    async fn generate_content(&self, req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        let answered = req
+            .contents
+            .iter()
+            .any(|c| c.parts.iter().any(|p| matches!(p, Part::FunctionResponse { .. })));
+
+        let parts = if answered {
+            vec![Part::Text { text: "done".to_string() }]
+        } else {
+            self.tool_names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| Part::FunctionCall {
+                    name: name.clone(),
+                    args: json!({}),
+                    id: Some(format!("call_{}", i)),
+                })
+                .collect()
+        };
+
+        let response = LlmResponse {
+            content: Some(Content { role: "model".to_string(), parts }),
+            usage_metadata: None,
+            finish_reason: Some(FinishReason::Stop),
+            partial: false,
+            turn_complete: true,
+            interrupted: false,
+            error_code: None,
+            error_message: None,
+            model_name: None,
+        };
+        let s = async_stream::stream! {
+            yield Ok(response);
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+/// Sleeps for a fixed delay and records how many tools were running at the same time.
+struct SlowTool {
+    name: String,
+    delay: Duration,
+    in_flight: Arc<AtomicUsize>,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn description(&self) -> &str {
+        "Slow test tool"
+    }
+
+    async fn execute(&self, ctx: Arc<dyn ToolContext>, _args: Value) -> Result<Value> {
+        let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        let mut actions = ctx.actions();
+        actions.state_delta.insert("last_tool".to_string(), json!(self.name));
+        ctx.set_actions(actions);
+
+        Ok(json!({ "tool": self.name }))
+    }
+}
+
+struct MockSession;
+impl Session for MockSession {
+    fn id(&self) -> &str {
+        "session-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn state(&self) -> &dyn State {
+        &MockState
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        Vec::new()
+    }
+}
+
+struct MockState;
+impl State for MockState {
+    fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+    fn set(&mut self, _key: String, _value: Value) {}
+    fn all(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+}
+
+struct MockContext {
+    session: MockSession,
+    user_content: Content,
+    config: RunConfig,
+}
+
+impl MockContext {
+    fn new() -> Self {
+        Self {
+            session: MockSession,
+            user_content: Content::new("user").with_text("look everything up"),
+            config: RunConfig::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl adk_core::ReadonlyContext for MockContext {
+    fn invocation_id(&self) -> &str {
+        "inv-1"
+    }
+    fn agent_name(&self) -> &str {
+        "test-agent"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn session_id(&self) -> &str {
+        "session-1"
+    }
+    fn branch(&self) -> &str {
+        ""
+    }
+    fn user_content(&self) -> &Content {
+        &self.user_content
+    }
+}
+
+#[async_trait]
+impl adk_core::CallbackContext for MockContext {
+    fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+        None
+    }
+}
+
+#[async_trait]
+impl InvocationContext for MockContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+        None
+    }
+    fn session(&self) -> &dyn Session {
+        &self.session
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+}
+
+// --- Helpers ---
+
+/// Builds an agent with three tools whose delays decrease, so concurrent execution
+/// finishes them in reverse call order. Returns the collected events and the peak
+/// number of tools observed running at once.
+async fn run_with_limit(limit: Option<usize>) -> (Vec<Event>, usize) {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let names = ["lookup_a", "lookup_b", "lookup_c"];
+
+    let model =
+        Arc::new(MultiCallModel { tool_names: names.iter().map(|n| n.to_string()).collect() });
+    let mut builder = LlmAgentBuilder::new("lookup-agent").model(model);
+    for (i, name) in names.iter().enumerate() {
+        builder = builder.tool(Arc::new(SlowTool {
+            name: name.to_string(),
+            delay: Duration::from_millis(60 - 20 * i as u64),
+            in_flight: in_flight.clone(),
+            max_in_flight: max_in_flight.clone(),
+        }));
+    }
+    if let Some(limit) = limit {
+        builder = builder.max_concurrent_tool_calls(limit);
+    }
+    let agent = builder.build().unwrap();
+
+    let mut stream = agent.run(Arc::new(MockContext::new())).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+
+    (events, max_in_flight.load(Ordering::SeqCst))
+}
+
+fn function_responses(events: &[Event]) -> Vec<(String, Option<String>)> {
+    events
+        .iter()
+        .filter_map(|e| e.llm_response.content.as_ref())
+        .flat_map(|c| c.parts.iter())
+        .filter_map(|p| match p {
+            Part::FunctionResponse { function_response, id } => {
+                Some((function_response.name.clone(), id.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_tool_calls_are_sequential_by_default() {
+    let (events, max_in_flight) = run_with_limit(None).await;
+
+    assert_eq!(max_in_flight, 1);
+    assert_eq!(function_responses(&events).len(), 3);
+}
+
+#[tokio::test]
+async fn test_concurrent_tool_calls_preserve_call_order() {
+    let (events, max_in_flight) = run_with_limit(Some(3)).await;
+
+    assert_eq!(max_in_flight, 3);
+    assert_eq!(
+        function_responses(&events),
+        vec![
+            ("lookup_a".to_string(), Some("call_0".to_string())),
+            ("lookup_b".to_string(), Some("call_1".to_string())),
+            ("lookup_c".to_string(), Some("call_2".to_string())),
+        ]
+    );
+
+    // State deltas arrive in call order, so the last call's write wins when applied
+    let last_delta =
+        events.iter().filter_map(|e| e.actions.state_delta.get("last_tool")).next_back().cloned();
+    assert_eq!(last_delta, Some(json!("lookup_c")));
+}
+
+#[tokio::test]
+async fn test_concurrency_limit_is_respected() {
+    let (events, max_in_flight) = run_with_limit(Some(2)).await;
+
+    assert_eq!(max_in_flight, 2);
+    assert_eq!(function_responses(&events).len(), 3);
+}
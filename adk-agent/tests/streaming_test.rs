@@ -50,6 +50,7 @@ impl Llm for MockModel {
                     interrupted: false,
                     error_code: None,
                     error_message: None,
+                    model_name: None,
                 });
             }
         };
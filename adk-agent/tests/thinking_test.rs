@@ -0,0 +1,264 @@
+use adk_agent::LlmAgentBuilder;
+use adk_core::{
+    Agent, Content, Event, FinishReason, IncludeThinking, InvocationContext, Llm, LlmRequest,
+    LlmResponse, LlmResponseStream, Part, Result, RunConfig, Session, State,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// --- Mocks ---
+
+/// Streams a thinking chunk followed by an answer and records the requests it receives.
+struct ThinkingModel {
+    requests: Mutex<Vec<LlmRequest>>,
+}
+
+impl ThinkingModel {
+    fn new() -> Arc<Self> {
+        Arc::new(Self { requests: Mutex::new(Vec::new()) })
+    }
+
+    fn last_request_thinking(&self) -> Vec<String> {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .last()
+            .unwrap()
+            .contents
+            .iter()
+            .flat_map(|c| c.parts.iter())
+            .filter_map(|p| p.thinking().map(String::from))
+            .collect()
+    }
+}
+
+fn chunk(part: Part, last: bool) -> LlmResponse {
+    LlmResponse {
+        content: Some(Content { role: "model".to_string(), parts: vec![part] }),
+        usage_metadata: None,
+        finish_reason: if last { Some(FinishReason::Stop) } else { None },
+        partial: !last,
+        turn_complete: last,
+        interrupted: false,
+        error_code: None,
+        error_message: None,
+        model_name: None,
+    }
+}
+
+#[async_trait]
+impl Llm for ThinkingModel {
+    fn name(&self) -> &str {
+        "thinking-model"
+    }
+
+    async fn generate_content(&self, req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        self.requests.lock().unwrap().push(req);
+        let s = async_stream::stream! {
+            yield Ok(chunk(Part::thinking_part("Working it out", Some("sig-1".to_string())), false));
+            yield Ok(chunk(Part::Text { text: "42".to_string() }, true));
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+struct MockSession {
+    history: Vec<Content>,
+}
+
+impl Session for MockSession {
+    fn id(&self) -> &str {
+        "session-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn state(&self) -> &dyn State {
+        &MockState
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        self.history.clone()
+    }
+}
+
+struct MockState;
+impl State for MockState {
+    fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+    fn set(&mut self, _key: String, _value: Value) {}
+    fn all(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+}
+
+/// Context whose session history is the given contents followed by the user message,
+/// mirroring how the runner appends the user message before running the agent.
+struct MockContext {
+    session: MockSession,
+    user_content: Content,
+    config: RunConfig,
+}
+
+impl MockContext {
+    fn new(mut history: Vec<Content>, user_content: Content) -> Self {
+        history.push(user_content.clone());
+        Self { session: MockSession { history }, user_content, config: RunConfig::default() }
+    }
+}
+
+#[async_trait]
+impl adk_core::ReadonlyContext for MockContext {
+    fn invocation_id(&self) -> &str {
+        "inv-1"
+    }
+    fn agent_name(&self) -> &str {
+        "test-agent"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn session_id(&self) -> &str {
+        "session-1"
+    }
+    fn branch(&self) -> &str {
+        ""
+    }
+    fn user_content(&self) -> &Content {
+        &self.user_content
+    }
+}
+
+#[async_trait]
+impl adk_core::CallbackContext for MockContext {
+    fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+        None
+    }
+}
+
+#[async_trait]
+impl InvocationContext for MockContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+        None
+    }
+    fn session(&self) -> &dyn Session {
+        &self.session
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+}
+
+// --- Helpers ---
+
+fn thinking(text: &str) -> Part {
+    Part::thinking_part(text, None)
+}
+
+/// An earlier exchange with thinking, followed by a tool round trip in the current turn.
+fn history_with_thinking() -> Vec<Content> {
+    vec![
+        Content::new("user").with_text("first question"),
+        Content { role: "model".to_string(), parts: vec![thinking("old reasoning")] }
+            .with_text("first answer"),
+        Content::new("user").with_text("second question"),
+        Content {
+            role: "model".to_string(),
+            parts: vec![
+                thinking("plan the lookup"),
+                Part::FunctionCall {
+                    name: "lookup".to_string(),
+                    args: serde_json::json!({}),
+                    id: Some("call-1".to_string()),
+                },
+            ],
+        },
+    ]
+}
+
+async fn run(agent: &dyn Agent, ctx: MockContext) -> Vec<Event> {
+    let mut stream = agent.run(Arc::new(ctx)).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+    events
+}
+
+/// Runs `agent` on the history above, resuming with the tool's response.
+async fn run_with_history(agent: &dyn Agent) {
+    let response = Content {
+        role: "user".to_string(),
+        parts: vec![Part::FunctionResponse {
+            function_response: adk_core::FunctionResponseData {
+                name: "lookup".to_string(),
+                response: serde_json::json!({"result": 41}),
+            },
+            id: Some("call-1".to_string()),
+        }],
+    };
+    run(agent, MockContext::new(history_with_thinking(), response)).await;
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_thinking_streams_as_its_own_part() {
+    let model = ThinkingModel::new();
+    let agent = LlmAgentBuilder::new("assistant").model(model).build().unwrap();
+
+    let question = Content::new("user").with_text("what is six times seven?");
+    let events = run(&agent, MockContext::new(Vec::new(), question)).await;
+
+    assert_eq!(events.len(), 2);
+    assert!(events[0].llm_response.partial);
+    let first = &events[0].llm_response.content.as_ref().unwrap().parts[0];
+    assert_eq!(first.thinking(), Some("Working it out"));
+    assert!(matches!(first, Part::Thinking { signature: Some(s), .. } if s == "sig-1"));
+    assert!(events[1].is_final_response());
+}
+
+#[tokio::test]
+async fn test_current_turn_thinking_is_kept_by_default() {
+    let model = ThinkingModel::new();
+    let agent = LlmAgentBuilder::new("assistant").model(model.clone()).build().unwrap();
+
+    run_with_history(&agent).await;
+    assert_eq!(model.last_request_thinking(), ["plan the lookup"]);
+}
+
+#[tokio::test]
+async fn test_include_thinking_all_and_none() {
+    let model = ThinkingModel::new();
+    let agent = LlmAgentBuilder::new("assistant")
+        .model(model.clone())
+        .include_thinking(IncludeThinking::All)
+        .build()
+        .unwrap();
+    run_with_history(&agent).await;
+    assert_eq!(model.last_request_thinking(), ["old reasoning", "plan the lookup"]);
+
+    let model = ThinkingModel::new();
+    let agent = LlmAgentBuilder::new("assistant")
+        .model(model.clone())
+        .include_thinking(IncludeThinking::None)
+        .build()
+        .unwrap();
+    run_with_history(&agent).await;
+    assert!(model.last_request_thinking().is_empty());
+}
@@ -0,0 +1,286 @@
+use adk_agent::LlmAgentBuilder;
+use adk_core::{
+    AdkError, AfterToolCallback, Agent, BeforeToolCallback, BeforeToolResult, Content, Event,
+    FinishReason, InvocationContext, Llm, LlmRequest, LlmResponse, LlmResponseStream, Part, Result,
+    RunConfig, Session, State,
+};
+use adk_tool::FunctionTool;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+// --- Mocks ---
+
+/// Calls `tool_name` once with `{"city": "paris"}`, then answers with text after seeing the function response.
+struct SingleCallModel {
+    tool_name: String,
+}
+
+#[async_trait]
+impl Llm for SingleCallModel {
+    fn name(&self) -> &str {
+        "single-call-model"
+    }
+
+    async fn generate_content(&self, req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        let answered = req
+            .contents
+            .iter()
+            .any(|c| c.parts.iter().any(|p| matches!(p, Part::FunctionResponse { .. })));
+
+        let part = if answered {
+            Part::Text { text: "done".to_string() }
+        } else {
+            Part::FunctionCall {
+                name: self.tool_name.clone(),
+                args: json!({ "city": "paris" }),
+                id: Some("call_1".to_string()),
+            }
+        };
+
+        let response = LlmResponse {
+            content: Some(Content { role: "model".to_string(), parts: vec![part] }),
+            usage_metadata: None,
+            finish_reason: Some(FinishReason::Stop),
+            partial: false,
+            turn_complete: true,
+            interrupted: false,
+            error_code: None,
+            error_message: None,
+            model_name: None,
+        };
+        let s = async_stream::stream! {
+            yield Ok(response);
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+struct MockSession;
+impl Session for MockSession {
+    fn id(&self) -> &str {
+        "session-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn state(&self) -> &dyn State {
+        &MockState
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        Vec::new()
+    }
+}
+
+struct MockState;
+impl State for MockState {
+    fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+    fn set(&mut self, _key: String, _value: Value) {}
+    fn all(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+}
+
+struct MockContext {
+    session: MockSession,
+    user_content: Content,
+    config: RunConfig,
+}
+
+impl MockContext {
+    fn new() -> Self {
+        Self {
+            session: MockSession,
+            user_content: Content::new("user").with_text("call the tool"),
+            config: RunConfig::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl adk_core::ReadonlyContext for MockContext {
+    fn invocation_id(&self) -> &str {
+        "inv-1"
+    }
+    fn agent_name(&self) -> &str {
+        "test-agent"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn session_id(&self) -> &str {
+        "session-1"
+    }
+    fn branch(&self) -> &str {
+        ""
+    }
+    fn user_content(&self) -> &Content {
+        &self.user_content
+    }
+}
+
+#[async_trait]
+impl adk_core::CallbackContext for MockContext {
+    fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+        None
+    }
+}
+
+#[async_trait]
+impl InvocationContext for MockContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+        None
+    }
+    fn session(&self) -> &dyn Session {
+        &self.session
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+}
+
+// --- Helpers ---
+
+async fn run_agent(
+    calls: Arc<AtomicU32>,
+    before: Option<BeforeToolCallback>,
+    after: Option<AfterToolCallback>,
+) -> Vec<Event> {
+    let tool = FunctionTool::new("weather", "Looks up the weather", move |_ctx, args| {
+        let calls = calls.clone();
+        async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            match args["city"].as_str() {
+                Some("unknown") => Err(AdkError::Tool("no such city".into())),
+                city => Ok(json!({ "city": city, "forecast": "sunny" })),
+            }
+        }
+    });
+
+    let model = Arc::new(SingleCallModel { tool_name: "weather".to_string() });
+    let mut builder = LlmAgentBuilder::new("callback-agent").model(model).tool(Arc::new(tool));
+    if let Some(callback) = before {
+        builder = builder.before_tool_callback(callback);
+    }
+    if let Some(callback) = after {
+        builder = builder.after_tool_callback(callback);
+    }
+    let agent = builder.build().unwrap();
+
+    let mut stream = agent.run(Arc::new(MockContext::new())).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+    events
+}
+
+fn function_response(events: &[Event]) -> Value {
+    events
+        .iter()
+        .filter_map(|e| e.llm_response.content.as_ref())
+        .flat_map(|c| c.parts.iter())
+        .find_map(|p| match p {
+            Part::FunctionResponse { function_response, .. } => {
+                Some(function_response.response.clone())
+            }
+            _ => None,
+        })
+        .expect("no function response event")
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_before_tool_callback_can_rewrite_args() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let seen = Arc::new(Mutex::new(None));
+    let seen_clone = seen.clone();
+
+    let before: BeforeToolCallback = Box::new(move |_ctx, tool, mut args| {
+        *seen_clone.lock().unwrap() = Some((tool.name().to_string(), args.clone()));
+        Box::pin(async move {
+            args["city"] = json!("london");
+            Ok(BeforeToolResult::Continue(args))
+        })
+    });
+    let events = run_agent(calls.clone(), Some(before), None).await;
+
+    assert_eq!(
+        seen.lock().unwrap().clone(),
+        Some(("weather".to_string(), json!({ "city": "paris" })))
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(function_response(&events)["city"], "london");
+}
+
+#[tokio::test]
+async fn test_before_tool_callback_can_skip_tool() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let before: BeforeToolCallback = Box::new(|_ctx, _tool, _args| {
+        Box::pin(async move { Ok(BeforeToolResult::Skip(json!({ "cached": true }))) })
+    });
+
+    let events = run_agent(calls.clone(), Some(before), None).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(function_response(&events), json!({ "cached": true }));
+}
+
+#[tokio::test]
+async fn test_after_tool_callback_can_replace_result() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let after: AfterToolCallback = Box::new(|_ctx, _tool, args, result| {
+        Box::pin(async move {
+            assert_eq!(args, json!({ "city": "paris" }));
+            let mut result = result.expect("tool should succeed");
+            result["forecast"] = json!("[redacted]");
+            Ok(Some(result))
+        })
+    });
+
+    let events = run_agent(calls.clone(), None, Some(after)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(function_response(&events), json!({ "city": "paris", "forecast": "[redacted]" }));
+}
+
+#[tokio::test]
+async fn test_after_tool_callback_receives_error() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let seen_error = Arc::new(Mutex::new(None));
+    let seen_clone = seen_error.clone();
+
+    let before: BeforeToolCallback = Box::new(|_ctx, _tool, _args| {
+        Box::pin(async move { Ok(BeforeToolResult::Continue(json!({ "city": "unknown" }))) })
+    });
+    let after: AfterToolCallback = Box::new(move |_ctx, _tool, _args, result| {
+        *seen_clone.lock().unwrap() = result.err();
+        Box::pin(async move { Ok(None) })
+    });
+
+    let events = run_agent(calls, Some(before), Some(after)).await;
+
+    let error = seen_error.lock().unwrap().clone().expect("callback should see the error");
+    assert!(error.contains("no such city"));
+    // Returning None keeps the original error response
+    assert!(function_response(&events)["error"].as_str().unwrap().contains("no such city"));
+}
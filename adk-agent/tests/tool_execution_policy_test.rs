@@ -0,0 +1,298 @@
+use adk_agent::LlmAgentBuilder;
+use adk_core::{
+    AdkError, Agent, Content, Event, FinishReason, InvocationContext, Llm, LlmRequest, LlmResponse,
+    LlmResponseStream, Part, Result, RunConfig, Session, State, Tool, ToolExecutionPolicy,
+};
+use adk_tool::FunctionTool;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+// --- Mocks ---
+
+/// Calls `tool_name` once, then answers with text after seeing the function response.
+struct SingleCallModel {
+    tool_name: String,
+}
+
+#[async_trait]
+impl Llm for SingleCallModel {
+    fn name(&self) -> &str {
+        "single-call-model"
+    }
+
+    async fn generate_content(&self, req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        let answered = req
+            .contents
+            .iter()
+            .any(|c| c.parts.iter().any(|p| matches!(p, Part::FunctionResponse { .. })));
+
+        let part = if answered {
+            Part::Text { text: "done".to_string() }
+        } else {
+            Part::FunctionCall {
+                name: self.tool_name.clone(),
+                args: json!({}),
+                id: Some("call_1".to_string()),
+            }
+        };
+
+        let response = LlmResponse {
+            content: Some(Content { role: "model".to_string(), parts: vec![part] }),
+            usage_metadata: None,
+            finish_reason: Some(FinishReason::Stop),
+            partial: false,
+            turn_complete: true,
+            interrupted: false,
+            error_code: None,
+            error_message: None,
+            model_name: None,
+        };
+        let s = async_stream::stream! {
+            yield Ok(response);
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+struct MockSession;
+impl Session for MockSession {
+    fn id(&self) -> &str {
+        "session-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn state(&self) -> &dyn State {
+        &MockState
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        Vec::new()
+    }
+}
+
+struct MockState;
+impl State for MockState {
+    fn get(&self, _key: &str) -> Option<Value> {
+        None
+    }
+    fn set(&mut self, _key: String, _value: Value) {}
+    fn all(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+}
+
+struct MockContext {
+    session: MockSession,
+    user_content: Content,
+    config: RunConfig,
+}
+
+impl MockContext {
+    fn new() -> Self {
+        Self {
+            session: MockSession,
+            user_content: Content::new("user").with_text("call the tool"),
+            config: RunConfig::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl adk_core::ReadonlyContext for MockContext {
+    fn invocation_id(&self) -> &str {
+        "inv-1"
+    }
+    fn agent_name(&self) -> &str {
+        "test-agent"
+    }
+    fn user_id(&self) -> &str {
+        "user-1"
+    }
+    fn app_name(&self) -> &str {
+        "test-app"
+    }
+    fn session_id(&self) -> &str {
+        "session-1"
+    }
+    fn branch(&self) -> &str {
+        ""
+    }
+    fn user_content(&self) -> &Content {
+        &self.user_content
+    }
+}
+
+#[async_trait]
+impl adk_core::CallbackContext for MockContext {
+    fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+        None
+    }
+}
+
+#[async_trait]
+impl InvocationContext for MockContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+        None
+    }
+    fn session(&self) -> &dyn Session {
+        &self.session
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+}
+
+// --- Helpers ---
+
+async fn run_tool(tool: Arc<dyn Tool>, default_policy: Option<ToolExecutionPolicy>) -> Vec<Event> {
+    let model = Arc::new(SingleCallModel { tool_name: tool.name().to_string() });
+    let mut builder = LlmAgentBuilder::new("policy-agent").model(model).tool(tool);
+    if let Some(policy) = default_policy {
+        builder = builder.tool_execution_policy(policy);
+    }
+    let agent = builder.build().unwrap();
+
+    let mut stream = agent.run(Arc::new(MockContext::new())).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(result) = stream.next().await {
+        events.push(result.unwrap());
+    }
+    events
+}
+
+fn function_response(events: &[Event]) -> Value {
+    events
+        .iter()
+        .filter_map(|e| e.llm_response.content.as_ref())
+        .flat_map(|c| c.parts.iter())
+        .find_map(|p| match p {
+            Part::FunctionResponse { function_response, .. } => {
+                Some(function_response.response.clone())
+            }
+            _ => None,
+        })
+        .expect("no function response event")
+}
+
+/// A tool that fails with `error` for the first `failures` calls, then succeeds.
+fn flaky_tool(failures: u32, error: fn() -> AdkError, calls: Arc<AtomicU32>) -> FunctionTool {
+    FunctionTool::new("flaky", "Fails a few times", move |_ctx, _args| {
+        let calls = calls.clone();
+        async move {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= failures { Err(error()) } else { Ok(json!({ "call": call })) }
+        }
+    })
+}
+
+// --- Tests ---
+
+#[tokio::test]
+async fn test_tool_timeout_returns_structured_error() {
+    let tool = FunctionTool::new("hang", "Never finishes", |_ctx, _args| async move {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(json!("unreachable"))
+    })
+    .with_execution_policy(ToolExecutionPolicy::new().with_timeout(Duration::from_millis(20)));
+
+    let events = run_tool(Arc::new(tool), None).await;
+    let response = function_response(&events);
+
+    assert_eq!(response["error_type"], "timeout");
+    assert_eq!(response["timeout_ms"], 20);
+    assert_eq!(response["attempts"], 1);
+}
+
+#[tokio::test]
+async fn test_timed_out_tool_is_not_retried_by_default() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = calls.clone();
+    let tool = FunctionTool::new("charge_card", "Charges a card", move |_ctx, _args| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(json!("charged"))
+        }
+    });
+    let policy = ToolExecutionPolicy::new()
+        .with_timeout(Duration::from_millis(20))
+        .with_max_retries(3)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+
+    let events = run_tool(Arc::new(tool), Some(policy)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(function_response(&events)["attempts"], 1);
+}
+
+#[tokio::test]
+async fn test_transient_tool_errors_are_retried_with_default_policy() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let tool = flaky_tool(2, || AdkError::Tool("temporarily unavailable".into()), calls.clone());
+    let policy = ToolExecutionPolicy::new()
+        .with_max_retries(2)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+        .retry_on(|e| e.to_string().contains("temporarily"));
+
+    let events = run_tool(Arc::new(tool), Some(policy)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(function_response(&events), json!({ "call": 3 }));
+}
+
+#[tokio::test]
+async fn test_unclassified_tool_errors_run_once() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let tool = flaky_tool(1, || AdkError::Tool("missing argument 'id'".into()), calls.clone());
+    let policy = ToolExecutionPolicy::new()
+        .with_max_retries(3)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+
+    let events = run_tool(Arc::new(tool), Some(policy)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(function_response(&events)["error"].as_str().unwrap().contains("missing argument"));
+}
+
+#[tokio::test]
+async fn test_non_tool_errors_are_not_retried() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let tool = flaky_tool(1, || AdkError::Config("bad input".into()), calls.clone());
+    let policy =
+        ToolExecutionPolicy::new().with_max_retries(3).retry_on(|e| matches!(e, AdkError::Tool(_)));
+
+    let events = run_tool(Arc::new(tool), Some(policy)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(function_response(&events)["error"].as_str().unwrap().contains("bad input"));
+}
+
+#[tokio::test]
+async fn test_tool_policy_overrides_agent_default() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let tool = flaky_tool(1, || AdkError::Tool("flaky".into()), calls.clone())
+        .with_execution_policy(ToolExecutionPolicy::new());
+    let default_policy =
+        ToolExecutionPolicy::new().with_max_retries(3).retry_on(|e| matches!(e, AdkError::Tool(_)));
+
+    let events = run_tool(Arc::new(tool), Some(default_policy)).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(function_response(&events)["error"].as_str().unwrap().contains("flaky"));
+}
@@ -0,0 +1,48 @@
+#!/usr/bin/env bash
+# Run the artifact service conformance suite against a real S3-compatible store.
+#
+# Starts a throwaway MinIO container with Docker, creates a bucket, runs the tests with
+# the s3 feature, and removes the container afterwards. Set ADK_TEST_S3_ENDPOINT (plus
+# ADK_TEST_S3_BUCKET, ADK_TEST_S3_ACCESS_KEY_ID and ADK_TEST_S3_SECRET_ACCESS_KEY) to
+# use an existing store instead.
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+containers=()
+cleanup() {
+    if [ ${#containers[@]} -gt 0 ]; then
+        docker rm -f "${containers[@]}" >/dev/null
+    fi
+}
+trap cleanup EXIT
+
+wait_for() {
+    local name=$1
+    shift
+    for _ in $(seq 1 60); do
+        if docker exec "$name" "$@" >/dev/null 2>&1; then
+            return 0
+        fi
+        sleep 1
+    done
+    echo "$name did not become ready" >&2
+    exit 1
+}
+
+if [ -z "${ADK_TEST_S3_ENDPOINT:-}" ]; then
+    name=adk-artifact-test-minio-$$
+    docker run -d --rm --name "$name" -p 127.0.0.1::9000 \
+        -e MINIO_ROOT_USER=adk -e MINIO_ROOT_PASSWORD=adk-secret \
+        minio/minio server /data >/dev/null
+    containers+=("$name")
+    wait_for "$name" mc alias set local http://127.0.0.1:9000 adk adk-secret
+    docker exec "$name" mc mb --ignore-existing local/adk-artifacts >/dev/null
+    port=$(docker port "$name" 9000/tcp | head -n1 | cut -d: -f2)
+    export ADK_TEST_S3_ENDPOINT="http://127.0.0.1:$port"
+    export ADK_TEST_S3_BUCKET=adk-artifacts
+    export ADK_TEST_S3_ACCESS_KEY_ID=adk
+    export ADK_TEST_S3_SECRET_ACCESS_KEY=adk-secret
+fi
+
+cargo test -p adk-artifact --features s3 --test conformance_tests "$@"
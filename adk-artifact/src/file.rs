@@ -0,0 +1,348 @@
+use crate::service::*;
+use crate::stored::{self, ArtifactMetadata};
+use adk_core::{AdkError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// How many times a save retries when concurrent saves claim the version it picked.
+const MAX_VERSION_CLAIMS: usize = 16;
+
+/// Metadata file of one version, named after the version number.
+#[derive(Serialize, Deserialize)]
+struct VersionRecord {
+    #[serde(flatten)]
+    metadata: ArtifactMetadata,
+    size: u64,
+    sha256: String,
+    /// Name of the payload file in the same directory.
+    payload: String,
+}
+
+/// [`ArtifactService`] that stores artifacts as files under a root directory.
+///
+/// Each version is a small JSON metadata file named after the version number, next to
+/// a payload file holding the content as-is. Payloads are written under a temporary
+/// name and the metadata file is linked into place last, so readers never see a
+/// partial write, and concurrent saves, even from other processes sharing the
+/// directory, never claim the same version.
+///
+/// ```text
+/// <root>/<app>/<user>/sessions/<session>/<file>/<version>
+/// <root>/<app>/<user>/user/<user:file>/<version>
+/// ```
+///
+/// Path segments are percent-encoded, so any app, user, session or file name is safe.
+/// [`ArtifactInfo::uri`] is the payload's `file://` URL.
+///
+/// ```rust,no_run
+/// use adk_artifact::FileArtifactService;
+///
+/// let service = FileArtifactService::new("./artifacts").with_max_size(10 * 1024 * 1024);
+/// ```
+pub struct FileArtifactService {
+    root: PathBuf,
+    max_size: Option<usize>,
+}
+
+impl FileArtifactService {
+    /// Store artifacts under `root`, which is created on first save.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), max_size: None }
+    }
+
+    /// Reject artifacts larger than `bytes`.
+    pub fn with_max_size(mut self, bytes: usize) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn user_dir(&self, app_name: &str, user_id: &str) -> PathBuf {
+        self.root.join(stored::encode_segment(app_name)).join(stored::encode_segment(user_id))
+    }
+
+    fn file_dir(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+        file_name: &str,
+    ) -> PathBuf {
+        self.user_dir(app_name, user_id).join(stored::file_path(session_id, file_name))
+    }
+
+    /// Committed versions in `dir`, newest first.
+    async fn versions_in(dir: &Path) -> Result<Vec<i64>> {
+        let mut entries = match fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut versions = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            // Payloads and temporary files have non-numeric names
+            if let Some(version) = entry.file_name().to_str().and_then(|n| n.parse::<i64>().ok()) {
+                versions.push(version);
+            }
+        }
+        versions.sort_by(|a, b| b.cmp(a));
+        Ok(versions)
+    }
+
+    /// Decoded names of the file directories in `dir` that hold at least one version.
+    async fn file_names_in(dir: &Path, names: &mut Vec<String>) -> Result<()> {
+        let mut entries = match fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let Some(name) = entry.file_name().to_str().and_then(stored::decode_segment) else {
+                continue;
+            };
+            if !Self::versions_in(&entry.path()).await?.is_empty() {
+                names.push(name);
+            }
+        }
+        Ok(())
+    }
+
+    async fn resolve_version(dir: &Path, version: Option<i64>) -> Result<i64> {
+        match version {
+            Some(version) => Ok(version),
+            None => Self::versions_in(dir).await?.first().copied().ok_or_else(stored::not_found),
+        }
+    }
+
+    async fn read_record(dir: &Path, version: i64) -> Result<VersionRecord> {
+        match fs::read(dir.join(version.to_string())).await {
+            Ok(contents) => Ok(serde_json::from_slice(&contents)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(stored::not_found()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn open_payload(dir: &Path, record: &VersionRecord) -> Result<fs::File> {
+        match fs::File::open(dir.join(&record.payload)).await {
+            Ok(file) => Ok(file),
+            // Replaced or deleted since the record was read
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(stored::not_found()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn info(dir: &Path, file_name: &str, version: i64, record: VersionRecord) -> ArtifactInfo {
+        ArtifactInfo {
+            file_name: file_name.to_string(),
+            version,
+            mime_type: record.metadata.content_type,
+            size: record.size,
+            sha256: record.sha256,
+            uri: Some(file_uri(&dir.join(record.payload))),
+        }
+    }
+
+    /// Write `reader` as a new version of the file in `dir`, or over `version`.
+    async fn store(
+        &self,
+        dir: &Path,
+        file_name: &str,
+        metadata: ArtifactMetadata,
+        mut reader: ArtifactReader,
+        version: Option<i64>,
+        skip_if_unchanged: bool,
+    ) -> Result<ArtifactInfo> {
+        fs::create_dir_all(dir).await?;
+
+        let payload = format!("{}.payload", uuid::Uuid::new_v4());
+        let staged = dir.join(format!(".tmp-{payload}"));
+        let copied = async {
+            let mut file = fs::File::create(&staged).await?;
+            stored::copy_hashed(&mut reader, &mut file, file_name, self.max_size).await
+        }
+        .await;
+        let (size, sha256) = match copied {
+            Ok(copied) => copied,
+            Err(e) => {
+                let _ = fs::remove_file(&staged).await;
+                return Err(e);
+            }
+        };
+
+        if skip_if_unchanged && version.is_none() {
+            if let Some(&latest) = Self::versions_in(dir).await?.first() {
+                let record = Self::read_record(dir, latest).await?;
+                let latest = Self::info(dir, file_name, latest, record);
+                if is_unchanged(&latest, &metadata.content_type, &sha256) {
+                    fs::remove_file(&staged).await?;
+                    return Ok(latest);
+                }
+            }
+        }
+
+        fs::rename(&staged, dir.join(&payload)).await?;
+        let record = VersionRecord { metadata, size, sha256, payload };
+        let committed = Self::commit(dir, &record, version).await;
+        if committed.is_err() {
+            let _ = fs::remove_file(dir.join(&record.payload)).await;
+        }
+        Ok(Self::info(dir, file_name, committed?, record))
+    }
+
+    /// Publish the metadata file of a version whose payload is already in place.
+    async fn commit(dir: &Path, record: &VersionRecord, version: Option<i64>) -> Result<i64> {
+        let staged = dir.join(format!(".tmp-{}", uuid::Uuid::new_v4()));
+        fs::write(&staged, serde_json::to_vec(record)?).await?;
+
+        if let Some(version) = version {
+            let replaced = Self::read_record(dir, version).await.ok();
+            fs::rename(&staged, dir.join(version.to_string())).await?;
+            if let Some(replaced) = replaced {
+                let _ = fs::remove_file(dir.join(replaced.payload)).await;
+            }
+            return Ok(version);
+        }
+
+        let mut claimed = Err(AdkError::Artifact(format!(
+            "could not claim a version in {} after {MAX_VERSION_CLAIMS} attempts",
+            dir.display()
+        )));
+        for _ in 0..MAX_VERSION_CLAIMS {
+            let version = Self::versions_in(dir).await?.first().map_or(1, |latest| latest + 1);
+            // Linking fails if the version exists, so exactly one concurrent save wins it
+            match fs::hard_link(&staged, dir.join(version.to_string())).await {
+                Ok(()) => {
+                    claimed = Ok(version);
+                    break;
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    claimed = Err(e.into());
+                    break;
+                }
+            }
+        }
+        fs::remove_file(&staged).await?;
+        claimed
+    }
+}
+
+/// `file://` URL of `path`, made absolute against the working directory.
+fn file_uri(path: &Path) -> String {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~' | b'/' | b':') {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+#[async_trait]
+impl ArtifactService for FileArtifactService {
+    async fn save(&self, req: SaveRequest) -> Result<SaveResponse> {
+        let (payload, metadata) = stored::encode(&req.part)?;
+        stored::check_size(&req.file_name, payload.len(), self.max_size)?;
+
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let reader = Box::pin(std::io::Cursor::new(payload));
+        let info = self.store(&dir, &req.file_name, metadata, reader, req.version, false).await?;
+        Ok(SaveResponse { version: info.version })
+    }
+
+    async fn load(&self, req: LoadRequest) -> Result<LoadResponse> {
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let version = Self::resolve_version(&dir, req.version).await?;
+        let record = Self::read_record(&dir, version).await?;
+        let mut payload = Vec::new();
+        Self::open_payload(&dir, &record).await?.read_to_end(&mut payload).await?;
+        Ok(LoadResponse { part: stored::decode(payload, &record.metadata)? })
+    }
+
+    async fn delete(&self, req: DeleteRequest) -> Result<()> {
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let removed = match req.version {
+            Some(version) => match Self::read_record(&dir, version).await {
+                Ok(record) => {
+                    fs::remove_file(dir.join(version.to_string())).await?;
+                    fs::remove_file(dir.join(record.payload)).await
+                }
+                Err(_) => Ok(()),
+            },
+            None => fs::remove_dir_all(&dir).await,
+        };
+        match removed {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        // Drop the file's directory once its last version is gone; fails harmlessly
+        // while other versions or a concurrent save's staged file remain
+        let _ = fs::remove_dir(&dir).await;
+        Ok(())
+    }
+
+    async fn list(&self, req: ListRequest) -> Result<ListResponse> {
+        let user_dir = self.user_dir(&req.app_name, &req.user_id);
+        let mut file_names = Vec::new();
+        Self::file_names_in(
+            &user_dir.join("sessions").join(stored::encode_segment(&req.session_id)),
+            &mut file_names,
+        )
+        .await?;
+        Self::file_names_in(&user_dir.join("user"), &mut file_names).await?;
+
+        file_names.sort();
+        file_names.dedup();
+        Ok(ListResponse { file_names })
+    }
+
+    async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse> {
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let versions = Self::versions_in(&dir).await?;
+        if versions.is_empty() {
+            return Err(stored::not_found());
+        }
+        Ok(VersionsResponse { versions })
+    }
+
+    async fn info(&self, req: LoadRequest) -> Result<ArtifactInfo> {
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let version = Self::resolve_version(&dir, req.version).await?;
+        let record = Self::read_record(&dir, version).await?;
+        Ok(Self::info(&dir, &req.file_name, version, record))
+    }
+
+    async fn save_stream(&self, req: SaveStreamRequest) -> Result<ArtifactInfo> {
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let metadata =
+            ArtifactMetadata { kind: stored::PartKind::Inline, content_type: req.mime_type };
+        self.store(&dir, &req.file_name, metadata, req.reader, req.version, req.skip_if_unchanged)
+            .await
+    }
+
+    async fn load_stream(&self, req: LoadStreamRequest) -> Result<LoadStreamResponse> {
+        let dir = self.file_dir(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let version = Self::resolve_version(&dir, req.version).await?;
+        let record = Self::read_record(&dir, version).await?;
+        let mut file = Self::open_payload(&dir, &record).await?;
+        let info = Self::info(&dir, &req.file_name, version, record);
+
+        let (start, end) = req.range.unwrap_or(ByteRange::starting_at(0)).resolve(info.size)?;
+        file.seek(SeekFrom::Start(start)).await?;
+        Ok(LoadStreamResponse { info, reader: Box::pin(file.take(end - start)) })
+    }
+}
@@ -7,6 +7,8 @@
 //! This crate provides artifact storage for binary data:
 //!
 //! - [`InMemoryArtifactService`] - Simple in-memory storage
+//! - [`FileArtifactService`] - Versioned files on the local filesystem
+//! - `S3ArtifactService` - S3-compatible object stores (feature `s3`)
 //! - [`ArtifactService`] - Trait for custom backends
 //! - [`ScopedArtifacts`] - Session-scoped artifact access
 //!
@@ -26,14 +28,30 @@
 //! - Store generated images, PDFs, audio
 //! - Cache intermediate results
 //! - Share binary data between agent turns
+//!
+//! ## Large Artifacts
+//!
+//! [`ArtifactService::save_stream`] and [`ArtifactService::load_stream`] move content
+//! through async readers, with optional byte ranges, so large files never have to be
+//! held in memory by backends that stream natively. [`ArtifactInfo`] reports a
+//! version's size and SHA-256, and where the backend supports it a URI that can be
+//! handed to a model as `Part::FileData` instead of inlining the bytes.
 
+pub mod file;
 pub mod inmemory;
+#[cfg(feature = "s3")]
+pub mod s3;
 pub mod scoped;
 pub mod service;
+mod stored;
 
+pub use file::FileArtifactService;
 pub use inmemory::InMemoryArtifactService;
+#[cfg(feature = "s3")]
+pub use s3::{S3ArtifactService, S3Config};
 pub use scoped::ScopedArtifacts;
 pub use service::{
-    ArtifactService, DeleteRequest, ListRequest, ListResponse, LoadRequest, LoadResponse,
-    SaveRequest, SaveResponse, VersionsRequest, VersionsResponse,
+    ArtifactInfo, ArtifactReader, ArtifactService, ByteRange, DeleteRequest, ListRequest,
+    ListResponse, LoadRequest, LoadResponse, LoadStreamRequest, LoadStreamResponse, SaveRequest,
+    SaveResponse, SaveStreamRequest, VersionsRequest, VersionsResponse,
 };
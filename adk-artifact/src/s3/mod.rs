@@ -0,0 +1,644 @@
+//! S3-compatible artifact storage.
+//!
+//! Works with Amazon S3 and with compatible stores such as MinIO, Ceph or R2. Requests
+//! are signed with AWS Signature Version 4 and use path-style URLs
+//! (`<endpoint>/<bucket>/<key>`), which every compatible store accepts.
+
+mod signing;
+
+use crate::service::*;
+use crate::stored::{self, ArtifactMetadata, PartKind};
+use adk_core::{AdkError, Result};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio_util::io::StreamReader;
+
+/// How many times a save retries when concurrent saves claim the version it picked.
+const MAX_VERSION_CLAIMS: usize = 16;
+
+/// Object metadata header recording how the payload maps back to a `Part`.
+const KIND_HEADER: &str = "x-amz-meta-adk-kind";
+
+/// Object metadata header holding the hex SHA-256 of the payload.
+const SHA256_HEADER: &str = "x-amz-meta-adk-sha256";
+
+/// Connection settings for [`S3ArtifactService`].
+#[derive(Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+    /// Prepended to every object key, e.g. `"artifacts/"`.
+    pub prefix: String,
+}
+
+impl S3Config {
+    /// `endpoint` is the service URL without the bucket, such as
+    /// `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000`.
+    pub fn new(
+        endpoint: impl Into<String>,
+        region: impl Into<String>,
+        bucket: impl Into<String>,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            region: region.into(),
+            bucket: bucket.into(),
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+            prefix: String::new(),
+        }
+    }
+
+    /// Temporary credentials issued by STS need their session token on every request.
+    pub fn with_session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+}
+
+impl std::fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Config")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("access_key_id", &self.access_key_id)
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Request body, with what it is signed with.
+#[derive(Clone)]
+enum Payload {
+    Bytes(Vec<u8>),
+    /// Content spooled to a local file, streamed from disk when sent.
+    File {
+        path: PathBuf,
+        size: u64,
+        sha256: String,
+    },
+}
+
+impl Payload {
+    fn empty() -> Self {
+        Payload::Bytes(Vec::new())
+    }
+}
+
+/// [`ArtifactService`] backed by an S3-compatible object store.
+///
+/// Each version is one object, `<prefix><app>/<user>/sessions/<session>/<file>/<version>`
+/// or `<prefix><app>/<user>/user/<user:file>/<version>` for user-scoped files, stored with
+/// the part's MIME type as its `Content-Type`. New versions are created with a
+/// conditional `If-None-Match: *` put, so concurrent saves never overwrite each other.
+///
+/// Streamed saves are spooled to a temporary file to learn their size and hash, then
+/// uploaded from disk, so content never has to fit in memory.
+///
+/// ```rust,no_run
+/// use adk_artifact::{S3ArtifactService, S3Config};
+///
+/// let config = S3Config::new("http://localhost:9000", "us-east-1", "artifacts", "minio", "minio123");
+/// let service = S3ArtifactService::new(config).with_max_size(50 * 1024 * 1024);
+/// ```
+pub struct S3ArtifactService {
+    config: S3Config,
+    client: reqwest::Client,
+    max_size: Option<usize>,
+    presign_expiry: Option<Duration>,
+}
+
+impl S3ArtifactService {
+    pub fn new(config: S3Config) -> Self {
+        Self { config, client: reqwest::Client::new(), max_size: None, presign_expiry: None }
+    }
+
+    /// Use a preconfigured HTTP client, e.g. with custom timeouts or proxies.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Reject artifacts larger than `bytes`.
+    pub fn with_max_size(mut self, bytes: usize) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Report a presigned GET URL valid for `expiry` as each artifact's
+    /// [`ArtifactInfo::uri`], so models can fetch artifacts by reference.
+    ///
+    /// Anyone holding such a URL can read the artifact until it expires.
+    pub fn with_presigned_urls(mut self, expiry: Duration) -> Self {
+        self.presign_expiry = Some(expiry);
+        self
+    }
+
+    fn credentials(&self) -> signing::Credentials<'_> {
+        signing::Credentials {
+            access_key_id: &self.config.access_key_id,
+            secret_access_key: &self.config.secret_access_key,
+            region: &self.config.region,
+        }
+    }
+
+    fn user_prefix(&self, app_name: &str, user_id: &str) -> String {
+        format!(
+            "{}{}/{}/",
+            self.config.prefix,
+            stored::encode_segment(app_name),
+            stored::encode_segment(user_id)
+        )
+    }
+
+    fn file_prefix(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+        file_name: &str,
+    ) -> String {
+        format!(
+            "{}{}/",
+            self.user_prefix(app_name, user_id),
+            stored::file_path(session_id, file_name)
+        )
+    }
+
+    /// Canonical path of `key` (empty for the bucket itself) and the `Host` it is sent to.
+    fn locate(&self, key: &str) -> Result<(String, String)> {
+        let path = match key {
+            "" => format!("/{}", self.config.bucket),
+            key => format!("/{}/{key}", self.config.bucket),
+        };
+        let url = reqwest::Url::parse(&self.config.endpoint)
+            .map_err(|e| AdkError::Artifact(format!("invalid S3 endpoint: {e}")))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(AdkError::Artifact("S3 endpoint has no host".into())),
+        };
+        Ok((signing::uri_encode(&path, true), host))
+    }
+
+    fn presigned_url(&self, key: &str) -> Result<Option<String>> {
+        let Some(expiry) = self.presign_expiry else {
+            return Ok(None);
+        };
+        let (path, host) = self.locate(key)?;
+        let query = signing::presigned_query(
+            &self.credentials(),
+            "GET",
+            &host,
+            &path,
+            self.config.session_token.as_deref(),
+            expiry.as_secs(),
+            chrono::Utc::now(),
+        );
+        Ok(Some(format!("{}{path}?{query}", self.config.endpoint)))
+    }
+
+    /// Sign and send a request for `key` (empty for the bucket itself).
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        payload: Payload,
+    ) -> Result<reqwest::Response> {
+        let (path, host) = self.locate(key)?;
+        let query = signing::canonical_query(query);
+        let mut url = format!("{}{path}", self.config.endpoint);
+        if !query.is_empty() {
+            url = format!("{url}?{query}");
+        }
+
+        let (body, payload_hash, size) = match payload {
+            Payload::Bytes(bytes) => {
+                let hash = stored::sha256_hex(&bytes);
+                let size = bytes.len() as u64;
+                (reqwest::Body::from(bytes), hash, size)
+            }
+            Payload::File { path, size, sha256 } => {
+                (reqwest::Body::from(tokio::fs::File::open(path).await?), sha256, size)
+            }
+        };
+
+        let now = chrono::Utc::now();
+        let mut signed: BTreeMap<String, String> =
+            headers.iter().map(|(name, value)| (name.to_lowercase(), value.to_string())).collect();
+        signed.insert("host".into(), host);
+        signed.insert("x-amz-content-sha256".into(), payload_hash.clone());
+        signed.insert("x-amz-date".into(), now.format("%Y%m%dT%H%M%SZ").to_string());
+        if let Some(token) = &self.config.session_token {
+            signed.insert("x-amz-security-token".into(), token.clone());
+        }
+        let authorization = signing::authorization(
+            &self.credentials(),
+            method.as_str(),
+            &path,
+            &query,
+            &signed,
+            &payload_hash,
+            now,
+        );
+
+        let mut request = self.client.request(method.clone(), url).body(body);
+        for (name, value) in &signed {
+            // reqwest derives Host from the URL
+            if name != "host" {
+                request = request.header(name.as_str(), value.as_str());
+            }
+        }
+        if method == Method::PUT {
+            // Streamed bodies would otherwise be sent chunked, which S3 rejects
+            request = request.header("content-length", size);
+        }
+        request
+            .header("authorization", authorization)
+            .send()
+            .await
+            .map_err(|e| AdkError::Artifact(format!("S3 request failed: {e}")))
+    }
+
+    async fn error(response: reqwest::Response, action: &str) -> AdkError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let code = xml_values(&body, "Code").into_iter().next().unwrap_or_default();
+        AdkError::Artifact(format!("S3 {action} failed: {status} {code}").trim_end().to_string())
+    }
+
+    /// Every object key starting with `prefix`.
+    async fn list_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix)];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token));
+            }
+            let response = self.send(Method::GET, "", &query, &[], Payload::empty()).await?;
+            if !response.status().is_success() {
+                return Err(Self::error(response, "list").await);
+            }
+            let body = response
+                .text()
+                .await
+                .map_err(|e| AdkError::Artifact(format!("S3 list failed: {e}")))?;
+            keys.extend(xml_values(&body, "Key"));
+
+            let truncated = xml_values(&body, "IsTruncated").first().is_some_and(|t| t == "true");
+            token = xml_values(&body, "NextContinuationToken").into_iter().next();
+            if !truncated || token.is_none() {
+                return Ok(keys);
+            }
+        }
+    }
+
+    /// Versions stored under a file prefix, newest first.
+    async fn versions_at(&self, file_prefix: &str) -> Result<Vec<i64>> {
+        let mut versions: Vec<i64> = self
+            .list_keys(file_prefix)
+            .await?
+            .iter()
+            .filter_map(|key| key.strip_prefix(file_prefix)?.parse().ok())
+            .collect();
+        versions.sort_by(|a, b| b.cmp(a));
+        Ok(versions)
+    }
+
+    async fn resolve_version(&self, file_prefix: &str, version: Option<i64>) -> Result<i64> {
+        match version {
+            Some(version) => Ok(version),
+            None => {
+                self.versions_at(file_prefix).await?.first().copied().ok_or_else(stored::not_found)
+            }
+        }
+    }
+
+    /// Decoded file names directly under `prefix`.
+    async fn file_names_at(&self, prefix: &str, names: &mut Vec<String>) -> Result<()> {
+        for key in self.list_keys(prefix).await? {
+            let file = key.strip_prefix(prefix).and_then(|rest| rest.split('/').next());
+            if let Some(name) = file.and_then(stored::decode_segment) {
+                names.push(name);
+            }
+        }
+        Ok(())
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        payload: Payload,
+        metadata: &ArtifactMetadata,
+        sha256: &str,
+        create: bool,
+    ) -> Result<bool> {
+        let mut headers = vec![
+            ("content-type", metadata.content_type.as_str()),
+            (KIND_HEADER, metadata.kind.as_str()),
+            (SHA256_HEADER, sha256),
+        ];
+        if create {
+            headers.push(("if-none-match", "*"));
+        }
+        let response = self.send(Method::PUT, key, &[], &headers, payload).await?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            // Another save created the object first; 409 is returned while it is in flight
+            StatusCode::PRECONDITION_FAILED | StatusCode::CONFLICT if create => Ok(false),
+            _ => Err(Self::error(response, "put").await),
+        }
+    }
+
+    /// Upload `payload` as `version` of the file under `file_prefix`, or as the next
+    /// free version. Returns the version written.
+    async fn store(
+        &self,
+        file_prefix: &str,
+        file_name: &str,
+        payload: Payload,
+        metadata: &ArtifactMetadata,
+        sha256: &str,
+        version: Option<i64>,
+    ) -> Result<i64> {
+        if let Some(version) = version {
+            self.put(&format!("{file_prefix}{version}"), payload, metadata, sha256, false).await?;
+            return Ok(version);
+        }
+
+        for _ in 0..MAX_VERSION_CLAIMS {
+            let version =
+                self.versions_at(file_prefix).await?.first().map_or(1, |latest| latest + 1);
+            let key = format!("{file_prefix}{version}");
+            if self.put(&key, payload.clone(), metadata, sha256, true).await? {
+                return Ok(version);
+            }
+        }
+        Err(AdkError::Artifact(format!(
+            "could not claim a version of {file_name} after {MAX_VERSION_CLAIMS} attempts"
+        )))
+    }
+
+    /// GET an object, or a byte range of it, failing unless the store answers with
+    /// content.
+    async fn get(&self, key: &str, range: Option<(u64, u64)>) -> Result<reqwest::Response> {
+        let range = range.map(|(start, end)| format!("bytes={start}-{}", end - 1));
+        let headers: Vec<(&str, &str)> =
+            range.iter().map(|range| ("range", range.as_str())).collect();
+        let response = self.send(Method::GET, key, &[], &headers, Payload::empty()).await?;
+        match response.status() {
+            status if status.is_success() => Ok(response),
+            StatusCode::NOT_FOUND => Err(stored::not_found()),
+            _ => Err(Self::error(response, "get").await),
+        }
+    }
+
+    fn metadata(headers: &HeaderMap) -> ArtifactMetadata {
+        // Objects written by other tools carry no kind and load as inline data
+        ArtifactMetadata {
+            kind: header(headers, KIND_HEADER)
+                .as_deref()
+                .and_then(PartKind::parse)
+                .unwrap_or(PartKind::Inline),
+            content_type: header(headers, "content-type")
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+        }
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+}
+
+fn body_reader(response: reqwest::Response) -> ArtifactReader {
+    let stream = response.bytes_stream().map_err(std::io::Error::other);
+    Box::pin(StreamReader::new(stream))
+}
+
+#[async_trait]
+impl ArtifactService for S3ArtifactService {
+    async fn save(&self, req: SaveRequest) -> Result<SaveResponse> {
+        let (payload, metadata) = stored::encode(&req.part)?;
+        stored::check_size(&req.file_name, payload.len(), self.max_size)?;
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let sha256 = stored::sha256_hex(&payload);
+
+        let version = self
+            .store(
+                &prefix,
+                &req.file_name,
+                Payload::Bytes(payload),
+                &metadata,
+                &sha256,
+                req.version,
+            )
+            .await?;
+        Ok(SaveResponse { version })
+    }
+
+    async fn load(&self, req: LoadRequest) -> Result<LoadResponse> {
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let version = self.resolve_version(&prefix, req.version).await?;
+
+        let response = self.get(&format!("{prefix}{version}"), None).await?;
+        let metadata = Self::metadata(response.headers());
+        let payload = response
+            .bytes()
+            .await
+            .map_err(|e| AdkError::Artifact(format!("S3 get failed: {e}")))?;
+        Ok(LoadResponse { part: stored::decode(payload.to_vec(), &metadata)? })
+    }
+
+    async fn delete(&self, req: DeleteRequest) -> Result<()> {
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let keys = match req.version {
+            Some(version) => vec![format!("{prefix}{version}")],
+            None => self.list_keys(&prefix).await?,
+        };
+        for key in keys {
+            let response = self.send(Method::DELETE, &key, &[], &[], Payload::empty()).await?;
+            // Deleting a missing object succeeds on S3; some stores answer 404
+            if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+                return Err(Self::error(response, "delete").await);
+            }
+        }
+        Ok(())
+    }
+
+    async fn list(&self, req: ListRequest) -> Result<ListResponse> {
+        let user_prefix = self.user_prefix(&req.app_name, &req.user_id);
+        let mut file_names = Vec::new();
+        self.file_names_at(
+            &format!("{user_prefix}sessions/{}/", stored::encode_segment(&req.session_id)),
+            &mut file_names,
+        )
+        .await?;
+        self.file_names_at(&format!("{user_prefix}user/"), &mut file_names).await?;
+
+        file_names.sort();
+        file_names.dedup();
+        Ok(ListResponse { file_names })
+    }
+
+    async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse> {
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let versions = self.versions_at(&prefix).await?;
+        if versions.is_empty() {
+            return Err(stored::not_found());
+        }
+        Ok(VersionsResponse { versions })
+    }
+
+    async fn info(&self, req: LoadRequest) -> Result<ArtifactInfo> {
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let version = self.resolve_version(&prefix, req.version).await?;
+        let key = format!("{prefix}{version}");
+
+        let response = self.send(Method::HEAD, &key, &[], &[], Payload::empty()).await?;
+        match response.status() {
+            status if status.is_success() => {}
+            StatusCode::NOT_FOUND => return Err(stored::not_found()),
+            // HEAD responses have no body to read an error code from
+            status => return Err(AdkError::Artifact(format!("S3 head failed: {status}"))),
+        }
+        let headers = response.headers();
+        let size = header(headers, "content-length").and_then(|len| len.parse().ok());
+        let sha256 = match header(headers, SHA256_HEADER) {
+            Some(sha256) => sha256,
+            // Objects written by other tools are hashed on demand
+            None => {
+                let mut content = Vec::new();
+                let mut reader = body_reader(self.get(&key, None).await?);
+                tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut content).await?;
+                stored::sha256_hex(&content)
+            }
+        };
+        Ok(ArtifactInfo {
+            file_name: req.file_name,
+            version,
+            mime_type: Self::metadata(headers).content_type,
+            size: size.ok_or_else(|| AdkError::Artifact("S3 head returned no length".into()))?,
+            sha256,
+            uri: self.presigned_url(&key)?,
+        })
+    }
+
+    async fn save_stream(&self, mut req: SaveStreamRequest) -> Result<ArtifactInfo> {
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let spool = std::env::temp_dir().join(format!("adk-artifact-{}", uuid::Uuid::new_v4()));
+
+        let stored = async {
+            let mut file = tokio::fs::File::create(&spool).await?;
+            let (size, sha256) =
+                stored::copy_hashed(&mut req.reader, &mut file, &req.file_name, self.max_size)
+                    .await?;
+            drop(file);
+
+            if req.skip_if_unchanged && req.version.is_none() {
+                if let Ok(latest) = self.info(load_request(&req)).await {
+                    if is_unchanged(&latest, &req.mime_type, &sha256) {
+                        return Ok(latest);
+                    }
+                }
+            }
+
+            let metadata =
+                ArtifactMetadata { kind: PartKind::Inline, content_type: req.mime_type.clone() };
+            let payload = Payload::File { path: spool.clone(), size, sha256: sha256.clone() };
+            let version = self
+                .store(&prefix, &req.file_name, payload, &metadata, &sha256, req.version)
+                .await?;
+            Ok(ArtifactInfo {
+                file_name: req.file_name.clone(),
+                version,
+                mime_type: req.mime_type.clone(),
+                size,
+                sha256,
+                uri: self.presigned_url(&format!("{prefix}{version}"))?,
+            })
+        }
+        .await;
+        let _ = tokio::fs::remove_file(&spool).await;
+        stored
+    }
+
+    async fn load_stream(&self, req: LoadStreamRequest) -> Result<LoadStreamResponse> {
+        let info = self
+            .info(LoadRequest {
+                app_name: req.app_name.clone(),
+                user_id: req.user_id.clone(),
+                session_id: req.session_id.clone(),
+                file_name: req.file_name.clone(),
+                version: req.version,
+            })
+            .await?;
+        let (start, end) = req.range.unwrap_or(ByteRange::starting_at(0)).resolve(info.size)?;
+        if start == end {
+            return Ok(LoadStreamResponse { info, reader: Box::pin(tokio::io::empty()) });
+        }
+
+        let prefix = self.file_prefix(&req.app_name, &req.user_id, &req.session_id, &req.file_name);
+        let range = (start, end) != (0, info.size);
+        let response =
+            self.get(&format!("{prefix}{}", info.version), range.then_some((start, end))).await?;
+        Ok(LoadStreamResponse { info, reader: body_reader(response) })
+    }
+}
+
+/// Text of every `<tag>` element in an S3 XML response.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else { break };
+        values.push(
+            rest[..end]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&"),
+        );
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_values() {
+        let xml = "<ListBucketResult><IsTruncated>true</IsTruncated>\
+                   <Contents><Key>a/1</Key></Contents><Contents><Key>b&amp;c/2</Key></Contents>\
+                   <NextContinuationToken>t1</NextContinuationToken></ListBucketResult>";
+        assert_eq!(xml_values(xml, "Key"), ["a/1", "b&c/2"]);
+        assert_eq!(xml_values(xml, "IsTruncated"), ["true"]);
+        assert!(xml_values(xml, "Code").is_empty());
+    }
+}
@@ -1,7 +1,11 @@
-use crate::service::{ArtifactService, ListRequest, LoadRequest, SaveRequest};
+use crate::service::{
+    ArtifactInfo, ArtifactService, ByteRange, ListRequest, LoadRequest, LoadStreamRequest,
+    LoadStreamResponse, SaveRequest, SaveStreamRequest,
+};
 use adk_core::{Artifacts, Part, Result};
 use async_trait::async_trait;
 use std::sync::Arc;
+use tokio::io::AsyncRead;
 
 /// Scoped wrapper around ArtifactService that binds session context.
 ///
@@ -58,6 +62,59 @@ impl ScopedArtifacts {
     ) -> Self {
         Self { service, app_name, user_id, session_id }
     }
+
+    fn load_request(&self, name: &str) -> LoadRequest {
+        LoadRequest {
+            app_name: self.app_name.clone(),
+            user_id: self.user_id.clone(),
+            session_id: self.session_id.clone(),
+            file_name: name.to_string(),
+            version: None,
+        }
+    }
+
+    /// Describes the latest version of an artifact without loading it.
+    pub async fn info(&self, name: &str) -> Result<ArtifactInfo> {
+        self.service.info(self.load_request(name)).await
+    }
+
+    /// Saves an artifact from a reader, without holding it in memory on backends
+    /// that stream.
+    pub async fn save_stream(
+        &self,
+        name: &str,
+        mime_type: &str,
+        reader: impl AsyncRead + Send + 'static,
+    ) -> Result<ArtifactInfo> {
+        self.service
+            .save_stream(SaveStreamRequest::new(
+                self.app_name.clone(),
+                self.user_id.clone(),
+                self.session_id.clone(),
+                name,
+                mime_type,
+                reader,
+            ))
+            .await
+    }
+
+    /// Reads the latest version of an artifact, or a byte range of it, as a stream.
+    pub async fn load_stream(
+        &self,
+        name: &str,
+        range: Option<ByteRange>,
+    ) -> Result<LoadStreamResponse> {
+        self.service
+            .load_stream(LoadStreamRequest {
+                app_name: self.app_name.clone(),
+                user_id: self.user_id.clone(),
+                session_id: self.session_id.clone(),
+                file_name: name.to_string(),
+                version: None,
+                range,
+            })
+            .await
+    }
 }
 
 #[async_trait]
@@ -78,16 +135,7 @@ impl Artifacts for ScopedArtifacts {
     }
 
     async fn load(&self, name: &str) -> Result<Part> {
-        let resp = self
-            .service
-            .load(LoadRequest {
-                app_name: self.app_name.clone(),
-                user_id: self.user_id.clone(),
-                session_id: self.session_id.clone(),
-                file_name: name.to_string(),
-                version: None,
-            })
-            .await?;
+        let resp = self.service.load(self.load_request(name)).await?;
         Ok(resp.part)
     }
 
@@ -102,6 +150,19 @@ impl Artifacts for ScopedArtifacts {
             .await?;
         Ok(resp.file_names)
     }
+
+    async fn reference(&self, name: &str) -> Result<Option<Part>> {
+        // Only URIs a model can fetch itself are offered, not local `file://` paths
+        let info = self.info(name).await?;
+        let fetchable = info.uri.as_deref().is_some_and(|uri| {
+            ["https://", "http://", "gs://"].iter().any(|scheme| uri.starts_with(scheme))
+        });
+        Ok(if fetchable { info.to_file_part() } else { None })
+    }
+
+    async fn size(&self, name: &str) -> Result<Option<u64>> {
+        Ok(Some(self.info(name).await?.size))
+    }
 }
 
 #[cfg(test)]
@@ -207,4 +268,46 @@ mod tests {
             _ => panic!("Expected Text parts"),
         }
     }
+
+    #[tokio::test]
+    async fn test_scoped_artifacts_streaming() {
+        let service = Arc::new(InMemoryArtifactService::new());
+        let artifacts = ScopedArtifacts::new(
+            service,
+            "app".to_string(),
+            "user".to_string(),
+            "sess".to_string(),
+        );
+
+        let saved = artifacts
+            .save_stream("clip.wav", "audio/wav", std::io::Cursor::new(b"RIFF0123".to_vec()))
+            .await
+            .unwrap();
+        assert_eq!((saved.version, saved.size), (1, 8));
+        assert_eq!(artifacts.info("clip.wav").await.unwrap(), saved);
+
+        let mut loaded = Vec::new();
+        let mut response =
+            artifacts.load_stream("clip.wav", Some(ByteRange::starting_at(4))).await.unwrap();
+        tokio::io::AsyncReadExt::read_to_end(&mut response.reader, &mut loaded).await.unwrap();
+        assert_eq!(loaded, b"0123");
+
+        // The in-memory backend has no URIs to reference
+        assert_eq!(artifacts.reference("clip.wav").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_local_file_uris_are_not_offered_as_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifacts = ScopedArtifacts::new(
+            Arc::new(crate::FileArtifactService::new(dir.path())),
+            "app".to_string(),
+            "user".to_string(),
+            "sess".to_string(),
+        );
+
+        artifacts.save("notes.txt", &Part::Text { text: "hello".to_string() }).await.unwrap();
+        assert!(artifacts.info("notes.txt").await.unwrap().uri.unwrap().starts_with("file://"));
+        assert_eq!(artifacts.reference("notes.txt").await.unwrap(), None);
+    }
 }
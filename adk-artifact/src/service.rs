@@ -1,5 +1,11 @@
-use adk_core::{Part, Result};
+use crate::stored;
+use adk_core::{AdkError, Part, Result};
 use async_trait::async_trait;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Readable artifact content for the streaming APIs.
+pub type ArtifactReader = Pin<Box<dyn AsyncRead + Send>>;
 
 #[derive(Debug, Clone)]
 pub struct SaveRequest {
@@ -64,6 +70,143 @@ pub struct VersionsResponse {
     pub versions: Vec<i64>,
 }
 
+/// Describes a stored artifact version without its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactInfo {
+    pub file_name: String,
+    pub version: i64,
+    pub mime_type: String,
+    /// Content length in bytes.
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the content, for detecting duplicates.
+    pub sha256: String,
+    /// Where the content can be fetched without going through the service, if the
+    /// backend provides such a location.
+    pub uri: Option<String>,
+}
+
+impl ArtifactInfo {
+    /// A `Part::FileData` pointing at this version, for passing the artifact to a model
+    /// by reference instead of inlining its bytes. `None` without a [`uri`](Self::uri).
+    pub fn to_file_part(&self) -> Option<Part> {
+        self.uri
+            .as_ref()
+            .map(|uri| Part::FileData { mime_type: self.mime_type.clone(), file_uri: uri.clone() })
+    }
+}
+
+/// Saves an artifact from a reader instead of an in-memory [`Part`].
+///
+/// Streamed content loads back as `Part::InlineData` with `mime_type`.
+pub struct SaveStreamRequest {
+    pub app_name: String,
+    pub user_id: String,
+    pub session_id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub reader: ArtifactReader,
+    pub version: Option<i64>,
+    /// Return the latest version instead of writing a new one when its content and
+    /// MIME type are unchanged.
+    pub skip_if_unchanged: bool,
+}
+
+impl SaveStreamRequest {
+    pub fn new(
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+        session_id: impl Into<String>,
+        file_name: impl Into<String>,
+        mime_type: impl Into<String>,
+        reader: impl AsyncRead + Send + 'static,
+    ) -> Self {
+        Self {
+            app_name: app_name.into(),
+            user_id: user_id.into(),
+            session_id: session_id.into(),
+            file_name: file_name.into(),
+            mime_type: mime_type.into(),
+            reader: Box::pin(reader),
+            version: None,
+            skip_if_unchanged: false,
+        }
+    }
+
+    pub fn with_version(mut self, version: i64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn skip_if_unchanged(mut self) -> Self {
+        self.skip_if_unchanged = true;
+        self
+    }
+}
+
+impl std::fmt::Debug for SaveStreamRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SaveStreamRequest")
+            .field("app_name", &self.app_name)
+            .field("user_id", &self.user_id)
+            .field("session_id", &self.session_id)
+            .field("file_name", &self.file_name)
+            .field("mime_type", &self.mime_type)
+            .field("version", &self.version)
+            .field("skip_if_unchanged", &self.skip_if_unchanged)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Byte range of an artifact's content: `start` inclusive, `end` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    /// `None` reads to the end of the content.
+    pub end: Option<u64>,
+}
+
+impl ByteRange {
+    pub fn new(start: u64, end: u64) -> Self {
+        Self { start, end: Some(end) }
+    }
+
+    /// Everything from `start` to the end of the content.
+    pub fn starting_at(start: u64) -> Self {
+        Self { start, end: None }
+    }
+
+    /// Clamp the range to content of `size` bytes, returning the absolute
+    /// `(start, end)` offsets. Fails when the range starts past the content.
+    pub fn resolve(&self, size: u64) -> Result<(u64, u64)> {
+        let end = self.end.map_or(size, |end| end.min(size));
+        if self.start > end {
+            return Err(AdkError::Artifact(format!(
+                "range {}..{end} is outside the artifact's {size} bytes",
+                self.start
+            )));
+        }
+        Ok((self.start, end))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadStreamRequest {
+    pub app_name: String,
+    pub user_id: String,
+    pub session_id: String,
+    pub file_name: String,
+    pub version: Option<i64>,
+    /// Read only part of the content. `None` reads all of it.
+    pub range: Option<ByteRange>,
+}
+
+pub struct LoadStreamResponse {
+    /// The whole version, not just the requested range.
+    pub info: ArtifactInfo,
+    /// Content of the requested range.
+    pub reader: ArtifactReader,
+}
+
 #[async_trait]
 pub trait ArtifactService: Send + Sync {
     async fn save(&self, req: SaveRequest) -> Result<SaveResponse>;
@@ -71,4 +214,113 @@ pub trait ArtifactService: Send + Sync {
     async fn delete(&self, req: DeleteRequest) -> Result<()>;
     async fn list(&self, req: ListRequest) -> Result<ListResponse>;
     async fn versions(&self, req: VersionsRequest) -> Result<VersionsResponse>;
+
+    /// Describe a version without reading its content.
+    ///
+    /// The default loads the version; backends that store metadata separately
+    /// override it.
+    async fn info(&self, req: LoadRequest) -> Result<ArtifactInfo> {
+        let file_name = req.file_name.clone();
+        let version = match req.version {
+            Some(version) => version,
+            None => latest_version(self, &req).await?,
+        };
+        let part = self.load(LoadRequest { version: Some(version), ..req }).await?.part;
+        let (content, metadata) = stored::encode(&part)?;
+        Ok(stored::info(file_name, version, metadata.content_type, &content))
+    }
+
+    /// Save content read from `req.reader`.
+    ///
+    /// The default buffers the content and calls [`save`](Self::save); the
+    /// filesystem and S3 backends stream it instead.
+    async fn save_stream(&self, mut req: SaveStreamRequest) -> Result<ArtifactInfo> {
+        let mut content = Vec::new();
+        req.reader.read_to_end(&mut content).await?;
+        let sha256 = stored::sha256_hex(&content);
+
+        if req.skip_if_unchanged && req.version.is_none() {
+            if let Ok(latest) = self.info(load_request(&req)).await {
+                if is_unchanged(&latest, &req.mime_type, &sha256) {
+                    return Ok(latest);
+                }
+            }
+        }
+
+        let size = content.len() as u64;
+        let response = self
+            .save(SaveRequest {
+                app_name: req.app_name,
+                user_id: req.user_id,
+                session_id: req.session_id,
+                file_name: req.file_name.clone(),
+                part: Part::InlineData { mime_type: req.mime_type.clone(), data: content },
+                version: req.version,
+            })
+            .await?;
+        Ok(ArtifactInfo {
+            file_name: req.file_name,
+            version: response.version,
+            mime_type: req.mime_type,
+            size,
+            sha256,
+            uri: None,
+        })
+    }
+
+    /// Read a version's content, or a range of it, as a stream.
+    ///
+    /// The default loads the whole version into memory; the filesystem and S3
+    /// backends stream it instead.
+    async fn load_stream(&self, req: LoadStreamRequest) -> Result<LoadStreamResponse> {
+        let request = LoadRequest {
+            app_name: req.app_name,
+            user_id: req.user_id,
+            session_id: req.session_id,
+            file_name: req.file_name.clone(),
+            version: req.version,
+        };
+        let version = match req.version {
+            Some(version) => version,
+            None => latest_version(self, &request).await?,
+        };
+        let part = self.load(LoadRequest { version: Some(version), ..request }).await?.part;
+        let (content, metadata) = stored::encode(&part)?;
+        let info = stored::info(req.file_name, version, metadata.content_type, &content);
+
+        let (start, end) = req.range.unwrap_or(ByteRange::starting_at(0)).resolve(info.size)?;
+        let range = content[start as usize..end as usize].to_vec();
+        Ok(LoadStreamResponse { info, reader: Box::pin(std::io::Cursor::new(range)) })
+    }
+}
+
+async fn latest_version<S: ArtifactService + ?Sized>(
+    service: &S,
+    req: &LoadRequest,
+) -> Result<i64> {
+    let versions = service
+        .versions(VersionsRequest {
+            app_name: req.app_name.clone(),
+            user_id: req.user_id.clone(),
+            session_id: req.session_id.clone(),
+            file_name: req.file_name.clone(),
+        })
+        .await?;
+    versions.versions.first().copied().ok_or_else(stored::not_found)
+}
+
+/// Request for the latest version of the file `req` saves.
+pub(crate) fn load_request(req: &SaveStreamRequest) -> LoadRequest {
+    LoadRequest {
+        app_name: req.app_name.clone(),
+        user_id: req.user_id.clone(),
+        session_id: req.session_id.clone(),
+        file_name: req.file_name.clone(),
+        version: None,
+    }
+}
+
+/// Whether `latest` already holds content with this hash and MIME type.
+pub(crate) fn is_unchanged(latest: &ArtifactInfo, mime_type: &str, sha256: &str) -> bool {
+    latest.mime_type == mime_type && latest.sha256 == sha256
 }
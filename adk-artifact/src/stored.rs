@@ -0,0 +1,221 @@
+//! Encoding shared by the persistent backends.
+//!
+//! A stored artifact version is its payload bytes plus [`ArtifactMetadata`] saying how
+//! to turn them back into a [`Part`]. Text and inline data are stored as-is, so objects
+//! stay readable by other tools; anything else is stored as JSON.
+
+use crate::service::{ArtifactInfo, ArtifactReader};
+use adk_core::{AdkError, Part, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Prefix of file names shared by all of a user's sessions.
+pub(crate) const USER_SCOPE_PREFIX: &str = "user:";
+
+/// How the payload of a stored version maps back to a [`Part`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PartKind {
+    /// UTF-8 text of a [`Part::Text`].
+    Text,
+    /// Bytes of a [`Part::InlineData`].
+    Inline,
+    /// URI of a [`Part::FileData`].
+    File,
+    /// Any other part, serialized as JSON.
+    Json,
+}
+
+#[cfg(feature = "s3")]
+impl PartKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            PartKind::Text => "text",
+            PartKind::Inline => "inline",
+            PartKind::File => "file",
+            PartKind::Json => "json",
+        }
+    }
+
+    pub(crate) fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "text" => Some(PartKind::Text),
+            "inline" => Some(PartKind::Inline),
+            "file" => Some(PartKind::File),
+            "json" => Some(PartKind::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ArtifactMetadata {
+    pub kind: PartKind,
+    pub content_type: String,
+}
+
+pub(crate) fn encode(part: &Part) -> Result<(Vec<u8>, ArtifactMetadata)> {
+    let (kind, content_type, bytes) = match part {
+        Part::Text { text } => {
+            (PartKind::Text, "text/plain; charset=utf-8".to_string(), text.as_bytes().to_vec())
+        }
+        Part::InlineData { mime_type, data } => (PartKind::Inline, mime_type.clone(), data.clone()),
+        Part::FileData { mime_type, file_uri } => {
+            (PartKind::File, mime_type.clone(), file_uri.as_bytes().to_vec())
+        }
+        other => (PartKind::Json, "application/json".to_string(), serde_json::to_vec(other)?),
+    };
+    Ok((bytes, ArtifactMetadata { kind, content_type }))
+}
+
+pub(crate) fn decode(bytes: Vec<u8>, metadata: &ArtifactMetadata) -> Result<Part> {
+    let utf8 = |bytes: Vec<u8>| {
+        String::from_utf8(bytes)
+            .map_err(|_| AdkError::Artifact("stored artifact is not valid UTF-8".into()))
+    };
+    Ok(match metadata.kind {
+        PartKind::Text => Part::Text { text: utf8(bytes)? },
+        PartKind::Inline => {
+            Part::InlineData { mime_type: metadata.content_type.clone(), data: bytes }
+        }
+        PartKind::File => {
+            Part::FileData { mime_type: metadata.content_type.clone(), file_uri: utf8(bytes)? }
+        }
+        PartKind::Json => serde_json::from_slice(&bytes)?,
+    })
+}
+
+pub(crate) fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Describe in-memory content that has no external location.
+pub(crate) fn info(
+    file_name: String,
+    version: i64,
+    mime_type: String,
+    content: &[u8],
+) -> ArtifactInfo {
+    ArtifactInfo {
+        file_name,
+        version,
+        mime_type,
+        size: content.len() as u64,
+        sha256: sha256_hex(content),
+        uri: None,
+    }
+}
+
+/// Copy `reader` into `writer`, returning the content's size and SHA-256.
+///
+/// Fails as soon as more than `max_size` bytes have been read, so oversized uploads
+/// are never written in full.
+pub(crate) async fn copy_hashed(
+    reader: &mut ArtifactReader,
+    writer: &mut (impl AsyncWrite + Unpin),
+    file_name: &str,
+    max_size: Option<usize>,
+) -> Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        size += read as u64;
+        check_size(file_name, size as usize, max_size)?;
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read]).await?;
+    }
+    writer.flush().await?;
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+/// Reject payloads over `max_size` bytes.
+pub(crate) fn check_size(file_name: &str, size: usize, max_size: Option<usize>) -> Result<()> {
+    match max_size {
+        Some(max) if size > max => Err(AdkError::Artifact(format!(
+            "artifact {file_name} is {size} bytes, the limit is {max}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Path of the directory holding a file's versions, relative to a user's root.
+///
+/// `user:` files live under `user/`, session files under `sessions/<session>/`, so no
+/// session name can collide with the user scope.
+pub(crate) fn file_path(session_id: &str, file_name: &str) -> String {
+    if file_name.starts_with(USER_SCOPE_PREFIX) {
+        format!("user/{}", encode_segment(file_name))
+    } else {
+        format!("sessions/{}/{}", encode_segment(session_id), encode_segment(file_name))
+    }
+}
+
+/// Percent-encode `value` so it is a single safe path segment: only ASCII letters,
+/// digits, `-` and `_` are kept, and `.` is kept unless it leads the segment.
+pub(crate) fn encode_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for (i, byte) in value.bytes().enumerate() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' || (byte == b'.' && i > 0) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+pub(crate) fn decode_segment(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segment.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+pub(crate) fn not_found() -> AdkError {
+    AdkError::Artifact("artifact not found".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_round_trip() {
+        for value in ["report.pdf", "user:notes/today.md", "..", ".hidden", "naïve file"] {
+            let encoded = encode_segment(value);
+            assert!(!encoded.contains('/') && !encoded.starts_with('.'), "{encoded}");
+            assert_eq!(decode_segment(&encoded).as_deref(), Some(value));
+        }
+        assert_eq!(encode_segment("user:a.txt"), "user%3Aa.txt");
+    }
+
+    #[test]
+    fn test_parts_round_trip() {
+        let parts = [
+            Part::Text { text: "hello".into() },
+            Part::InlineData { mime_type: "image/png".into(), data: vec![0, 159, 146, 150] },
+            Part::FileData { mime_type: "application/pdf".into(), file_uri: "gs://b/f.pdf".into() },
+            Part::FunctionCall { name: "f".into(), args: serde_json::json!({"a": 1}), id: None },
+        ];
+        for part in parts {
+            let (bytes, metadata) = encode(&part).unwrap();
+            assert_eq!(decode(bytes, &metadata).unwrap(), part);
+        }
+    }
+}
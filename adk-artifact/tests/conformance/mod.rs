@@ -0,0 +1,418 @@
+//! Behaviour every `ArtifactService` backend must share.
+//!
+//! Each check takes a service and works under a fresh app name, so the checks can run
+//! concurrently against one shared store. `conformance_suite!` expands to one test per
+//! check for a backend; its argument is an async expression evaluating to
+//! `Option<service>`, where `None` skips the backend.
+
+use adk_artifact::{
+    ArtifactInfo, ArtifactService, ByteRange, DeleteRequest, ListRequest, LoadRequest,
+    LoadStreamRequest, SaveRequest, SaveStreamRequest, VersionsRequest,
+};
+use adk_core::Part;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+macro_rules! conformance_suite {
+    ($service:expr) => {
+        conformance_suite!(@tests $service;
+            save_assigns_increasing_versions,
+            explicit_version_overwrites,
+            parts_round_trip_with_their_mime_type,
+            load_missing_fails,
+            versions_are_newest_first,
+            list_includes_user_scoped_files,
+            user_scoped_files_are_shared_across_sessions,
+            delete_version_then_all,
+            names_with_special_characters_round_trip,
+            concurrent_saves_get_distinct_versions,
+            streamed_content_round_trips,
+            range_reads_are_clamped,
+            info_reports_size_and_hash,
+            unchanged_streams_reuse_the_latest_version,
+        );
+    };
+    (@tests $service:expr; $($check:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $check() {
+                if let Some(service) = $service.await {
+                    crate::conformance::$check(&service).await;
+                }
+            }
+        )*
+    };
+}
+
+fn unique_app() -> String {
+    format!("app-{}", uuid::Uuid::new_v4())
+}
+
+fn text(value: &str) -> Part {
+    Part::Text { text: value.to_string() }
+}
+
+async fn save(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    session_id: &str,
+    file_name: &str,
+    part: Part,
+) -> i64 {
+    service
+        .save(SaveRequest {
+            app_name: app_name.to_string(),
+            user_id: "user".to_string(),
+            session_id: session_id.to_string(),
+            file_name: file_name.to_string(),
+            part,
+            version: None,
+        })
+        .await
+        .unwrap()
+        .version
+}
+
+async fn load(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    session_id: &str,
+    file_name: &str,
+    version: Option<i64>,
+) -> adk_core::Result<Part> {
+    service
+        .load(LoadRequest {
+            app_name: app_name.to_string(),
+            user_id: "user".to_string(),
+            session_id: session_id.to_string(),
+            file_name: file_name.to_string(),
+            version,
+        })
+        .await
+        .map(|response| response.part)
+}
+
+async fn save_stream(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    file_name: &str,
+    content: Vec<u8>,
+    skip_if_unchanged: bool,
+) -> ArtifactInfo {
+    let mut request = SaveStreamRequest::new(
+        app_name,
+        "user",
+        "s1",
+        file_name,
+        "application/octet-stream",
+        std::io::Cursor::new(content),
+    );
+    request.skip_if_unchanged = skip_if_unchanged;
+    service.save_stream(request).await.unwrap()
+}
+
+async fn load_range(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    file_name: &str,
+    range: Option<ByteRange>,
+) -> adk_core::Result<Vec<u8>> {
+    let mut response = service
+        .load_stream(LoadStreamRequest {
+            app_name: app_name.to_string(),
+            user_id: "user".to_string(),
+            session_id: "s1".to_string(),
+            file_name: file_name.to_string(),
+            version: None,
+            range,
+        })
+        .await?;
+    let mut content = Vec::new();
+    response.reader.read_to_end(&mut content).await?;
+    Ok(content)
+}
+
+async fn info(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    file_name: &str,
+    version: Option<i64>,
+) -> adk_core::Result<ArtifactInfo> {
+    service
+        .info(LoadRequest {
+            app_name: app_name.to_string(),
+            user_id: "user".to_string(),
+            session_id: "s1".to_string(),
+            file_name: file_name.to_string(),
+            version,
+        })
+        .await
+}
+
+/// Content larger than any backend's copy buffer, with no repeating 256-byte blocks.
+fn large_content() -> Vec<u8> {
+    (0..300_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+async fn list(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    user_id: &str,
+    session_id: &str,
+) -> Vec<String> {
+    service
+        .list(ListRequest {
+            app_name: app_name.to_string(),
+            user_id: user_id.to_string(),
+            session_id: session_id.to_string(),
+        })
+        .await
+        .unwrap()
+        .file_names
+}
+
+async fn versions(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    session_id: &str,
+    file_name: &str,
+) -> adk_core::Result<Vec<i64>> {
+    service
+        .versions(VersionsRequest {
+            app_name: app_name.to_string(),
+            user_id: "user".to_string(),
+            session_id: session_id.to_string(),
+            file_name: file_name.to_string(),
+        })
+        .await
+        .map(|response| response.versions)
+}
+
+async fn delete(
+    service: &dyn ArtifactService,
+    app_name: &str,
+    session_id: &str,
+    file_name: &str,
+    version: Option<i64>,
+) {
+    service
+        .delete(DeleteRequest {
+            app_name: app_name.to_string(),
+            user_id: "user".to_string(),
+            session_id: session_id.to_string(),
+            file_name: file_name.to_string(),
+            version,
+        })
+        .await
+        .unwrap();
+}
+
+pub async fn save_assigns_increasing_versions(service: &impl ArtifactService) {
+    let app = unique_app();
+    assert_eq!(save(service, &app, "s1", "notes.txt", text("v1")).await, 1);
+    assert_eq!(save(service, &app, "s1", "notes.txt", text("v2")).await, 2);
+
+    assert_eq!(load(service, &app, "s1", "notes.txt", None).await.unwrap(), text("v2"));
+    assert_eq!(load(service, &app, "s1", "notes.txt", Some(1)).await.unwrap(), text("v1"));
+}
+
+pub async fn explicit_version_overwrites(service: &impl ArtifactService) {
+    let app = unique_app();
+    save(service, &app, "s1", "notes.txt", text("v1")).await;
+    let response = service
+        .save(SaveRequest {
+            app_name: app.clone(),
+            user_id: "user".to_string(),
+            session_id: "s1".to_string(),
+            file_name: "notes.txt".to_string(),
+            part: text("replaced"),
+            version: Some(1),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.version, 1);
+    assert_eq!(load(service, &app, "s1", "notes.txt", None).await.unwrap(), text("replaced"));
+    assert_eq!(versions(service, &app, "s1", "notes.txt").await.unwrap(), [1]);
+}
+
+pub async fn parts_round_trip_with_their_mime_type(service: &impl ArtifactService) {
+    let app = unique_app();
+    let parts = [
+        ("text.txt", text("héllo\nworld")),
+        (
+            "image.png",
+            Part::InlineData { mime_type: "image/png".to_string(), data: vec![0x89, b'P', 0, 255] },
+        ),
+        (
+            "remote.pdf",
+            Part::FileData {
+                mime_type: "application/pdf".to_string(),
+                file_uri: "gs://bucket/report.pdf".to_string(),
+            },
+        ),
+    ];
+    for (file_name, part) in parts {
+        save(service, &app, "s1", file_name, part.clone()).await;
+        assert_eq!(load(service, &app, "s1", file_name, None).await.unwrap(), part, "{file_name}");
+    }
+}
+
+pub async fn load_missing_fails(service: &impl ArtifactService) {
+    let app = unique_app();
+    assert!(load(service, &app, "s1", "missing.txt", None).await.is_err());
+
+    save(service, &app, "s1", "notes.txt", text("v1")).await;
+    assert!(load(service, &app, "s1", "notes.txt", Some(7)).await.is_err());
+    assert!(load(service, &app, "s2", "notes.txt", None).await.is_err());
+}
+
+pub async fn versions_are_newest_first(service: &impl ArtifactService) {
+    let app = unique_app();
+    assert!(versions(service, &app, "s1", "notes.txt").await.is_err());
+
+    for value in ["v1", "v2", "v3"] {
+        save(service, &app, "s1", "notes.txt", text(value)).await;
+    }
+    assert_eq!(versions(service, &app, "s1", "notes.txt").await.unwrap(), [3, 2, 1]);
+}
+
+pub async fn list_includes_user_scoped_files(service: &impl ArtifactService) {
+    let app = unique_app();
+    save(service, &app, "s1", "b.txt", text("b")).await;
+    save(service, &app, "s1", "b.txt", text("b2")).await;
+    save(service, &app, "s1", "a.txt", text("a")).await;
+    save(service, &app, "s1", "user:profile.json", text("{}")).await;
+    save(service, &app, "s2", "c.txt", text("c")).await;
+
+    assert_eq!(list(service, &app, "user", "s1").await, ["a.txt", "b.txt", "user:profile.json"]);
+    assert_eq!(list(service, &app, "user", "s3").await, ["user:profile.json"]);
+    assert!(list(service, &app, "other", "s1").await.is_empty());
+}
+
+pub async fn user_scoped_files_are_shared_across_sessions(service: &impl ArtifactService) {
+    let app = unique_app();
+    save(service, &app, "s1", "user:prefs.txt", text("dark mode")).await;
+    assert_eq!(save(service, &app, "s2", "user:prefs.txt", text("light mode")).await, 2);
+
+    assert_eq!(
+        load(service, &app, "s3", "user:prefs.txt", None).await.unwrap(),
+        text("light mode")
+    );
+    assert_eq!(versions(service, &app, "s1", "user:prefs.txt").await.unwrap(), [2, 1]);
+}
+
+pub async fn delete_version_then_all(service: &impl ArtifactService) {
+    let app = unique_app();
+    for value in ["v1", "v2", "v3"] {
+        save(service, &app, "s1", "notes.txt", text(value)).await;
+    }
+
+    delete(service, &app, "s1", "notes.txt", Some(2)).await;
+    assert_eq!(versions(service, &app, "s1", "notes.txt").await.unwrap(), [3, 1]);
+    assert!(load(service, &app, "s1", "notes.txt", Some(2)).await.is_err());
+
+    delete(service, &app, "s1", "notes.txt", None).await;
+    assert!(load(service, &app, "s1", "notes.txt", None).await.is_err());
+    assert!(list(service, &app, "user", "s1").await.is_empty());
+
+    // Deleting what is already gone succeeds
+    delete(service, &app, "s1", "notes.txt", None).await;
+    delete(service, &app, "s1", "notes.txt", Some(1)).await;
+}
+
+pub async fn names_with_special_characters_round_trip(service: &impl ArtifactService) {
+    let app = unique_app();
+    let session = "session/../with spaces";
+    let file_name = "reports/2024 Q1 – final?.pdf";
+    save(service, &app, session, file_name, text("quarterly")).await;
+    save(service, &app, session, ".hidden", text("dot")).await;
+
+    assert_eq!(load(service, &app, session, file_name, None).await.unwrap(), text("quarterly"));
+    assert_eq!(list(service, &app, "user", session).await, [".hidden", file_name]);
+    assert!(list(service, &app, "user", "session").await.is_empty());
+}
+
+pub async fn concurrent_saves_get_distinct_versions(service: &impl ArtifactService) {
+    let app = unique_app();
+    let (a, b, c, d) = tokio::join!(
+        save(service, &app, "s1", "log.txt", text("a")),
+        save(service, &app, "s1", "log.txt", text("b")),
+        save(service, &app, "s1", "log.txt", text("c")),
+        save(service, &app, "s1", "log.txt", text("d")),
+    );
+
+    let mut saved = vec![a, b, c, d];
+    saved.sort();
+    assert_eq!(saved, [1, 2, 3, 4]);
+    assert_eq!(versions(service, &app, "s1", "log.txt").await.unwrap(), [4, 3, 2, 1]);
+}
+
+pub async fn streamed_content_round_trips(service: &impl ArtifactService) {
+    let app = unique_app();
+    let content = large_content();
+    let saved = save_stream(service, &app, "video.bin", content.clone(), false).await;
+
+    assert_eq!(saved.version, 1);
+    assert_eq!(saved.size, content.len() as u64);
+    assert_eq!(saved.sha256, hex::encode(Sha256::digest(&content)));
+    assert_eq!(load_range(service, &app, "video.bin", None).await.unwrap(), content);
+    assert_eq!(
+        load(service, &app, "s1", "video.bin", None).await.unwrap(),
+        Part::InlineData { mime_type: "application/octet-stream".to_string(), data: content }
+    );
+}
+
+pub async fn range_reads_are_clamped(service: &impl ArtifactService) {
+    let app = unique_app();
+    let content = large_content();
+    save_stream(service, &app, "video.bin", content.clone(), false).await;
+
+    let range = load_range(service, &app, "video.bin", Some(ByteRange::new(100_000, 100_010)));
+    assert_eq!(range.await.unwrap(), &content[100_000..100_010]);
+    let tail = load_range(service, &app, "video.bin", Some(ByteRange::starting_at(299_990)));
+    assert_eq!(tail.await.unwrap(), &content[299_990..]);
+    let past_end = load_range(service, &app, "video.bin", Some(ByteRange::new(299_999, 400_000)));
+    assert_eq!(past_end.await.unwrap(), &content[299_999..]);
+    let empty = load_range(service, &app, "video.bin", Some(ByteRange::starting_at(300_000)));
+    assert!(empty.await.unwrap().is_empty());
+    let outside = load_range(service, &app, "video.bin", Some(ByteRange::starting_at(300_001)));
+    assert!(outside.await.is_err());
+}
+
+pub async fn info_reports_size_and_hash(service: &impl ArtifactService) {
+    let app = unique_app();
+    let data = vec![1, 2, 3, 4, 5];
+    let part = Part::InlineData { mime_type: "image/png".to_string(), data: data.clone() };
+    save(service, &app, "s1", "image.png", part.clone()).await;
+    save(service, &app, "s1", "image.png", text("replaced")).await;
+
+    let first = info(service, &app, "image.png", Some(1)).await.unwrap();
+    assert_eq!((first.version, first.mime_type.as_str(), first.size), (1, "image/png", 5));
+    assert_eq!(first.sha256, hex::encode(Sha256::digest(&data)));
+    assert_eq!(info(service, &app, "image.png", None).await.unwrap().version, 2);
+    assert!(info(service, &app, "missing.png", None).await.is_err());
+
+    // A URI, where the backend reports one, names this version's content
+    if let Some(reference) = first.to_file_part() {
+        assert!(matches!(reference, Part::FileData { mime_type, .. } if mime_type == "image/png"));
+    }
+}
+
+pub async fn unchanged_streams_reuse_the_latest_version(service: &impl ArtifactService) {
+    let app = unique_app();
+    let first = save_stream(service, &app, "data.csv", b"a,b\n1,2\n".to_vec(), true).await;
+    let again = save_stream(service, &app, "data.csv", b"a,b\n1,2\n".to_vec(), true).await;
+    assert_eq!(again, first);
+
+    // Without the flag, and once the content changes, new versions are written
+    assert_eq!(
+        save_stream(service, &app, "data.csv", b"a,b\n1,2\n".to_vec(), false).await.version,
+        2
+    );
+    assert_eq!(
+        save_stream(service, &app, "data.csv", b"a,b\n3,4\n".to_vec(), true).await.version,
+        3
+    );
+    assert_eq!(versions(service, &app, "s1", "data.csv").await.unwrap(), [3, 2, 1]);
+}
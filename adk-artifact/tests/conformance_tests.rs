@@ -0,0 +1,169 @@
+//! Runs the shared conformance checks against every artifact backend.
+//!
+//! The filesystem backend runs in a temporary directory. With the `s3` feature the S3
+//! backend runs against an in-process stand-in, and against a real S3-compatible store
+//! when `ADK_TEST_S3_ENDPOINT` is set; `scripts/test-s3.sh` starts MinIO in a container
+//! and runs this suite.
+
+#[macro_use]
+mod conformance;
+#[cfg(feature = "s3")]
+mod fake_s3;
+
+use adk_artifact::{
+    ArtifactService, FileArtifactService, LoadRequest, SaveRequest, SaveStreamRequest,
+};
+use adk_core::Part;
+use std::sync::LazyLock;
+
+/// Shared by every filesystem test; the checks isolate themselves by app name.
+static ROOT: LazyLock<tempfile::TempDir> = LazyLock::new(|| tempfile::tempdir().unwrap());
+
+fn save_request(file_name: &str, part: Part) -> SaveRequest {
+    SaveRequest {
+        app_name: format!("app-{}", uuid::Uuid::new_v4()),
+        user_id: "user".to_string(),
+        session_id: "session".to_string(),
+        file_name: file_name.to_string(),
+        part,
+        version: None,
+    }
+}
+
+async fn assert_max_size_enforced(service: &impl ArtifactService) {
+    let small = Part::InlineData { mime_type: "image/png".to_string(), data: vec![7; 16] };
+    service.save(save_request("small.png", small)).await.unwrap();
+
+    let large = Part::InlineData { mime_type: "image/png".to_string(), data: vec![7; 17] };
+    let err = service.save(save_request("large.png", large)).await.unwrap_err();
+    assert!(err.to_string().contains("limit is 16"), "{err}");
+
+    let streamed = save_request("large.png", Part::Text { text: String::new() });
+    let streamed = SaveStreamRequest::new(
+        streamed.app_name,
+        "user",
+        "session",
+        "large.png",
+        "image/png",
+        std::io::Cursor::new(vec![7; 17]),
+    );
+    let err = service.save_stream(streamed).await.unwrap_err();
+    assert!(err.to_string().contains("limit is 16"), "{err}");
+}
+
+mod inmemory {
+    use adk_artifact::InMemoryArtifactService;
+
+    conformance_suite!(async { Some(InMemoryArtifactService::new()) });
+}
+
+mod filesystem {
+    use adk_artifact::FileArtifactService;
+
+    conformance_suite!(async { Some(FileArtifactService::new(crate::ROOT.path())) });
+}
+
+#[tokio::test]
+async fn test_filesystem_max_size() {
+    let service = FileArtifactService::new(ROOT.path()).with_max_size(16);
+    assert_max_size_enforced(&service).await;
+}
+
+#[tokio::test]
+async fn test_filesystem_persists_without_leftover_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let request = save_request("notes.txt", Part::Text { text: "kept".to_string() });
+    let app_name = request.app_name.clone();
+    FileArtifactService::new(dir.path()).save(request).await.unwrap();
+
+    // A fresh instance over the same directory sees the saved version
+    let loaded = FileArtifactService::new(dir.path())
+        .load(LoadRequest {
+            app_name,
+            user_id: "user".to_string(),
+            session_id: "session".to_string(),
+            file_name: "notes.txt".to_string(),
+            version: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(loaded.part, Part::Text { text: "kept".to_string() });
+
+    let mut stack = vec![dir.path().to_path_buf()];
+    while let Some(path) = stack.pop() {
+        for entry in std::fs::read_dir(path).unwrap() {
+            let entry = entry.unwrap();
+            assert!(!entry.file_name().to_string_lossy().starts_with(".tmp"), "{entry:?}");
+            if entry.file_type().unwrap().is_dir() {
+                stack.push(entry.path());
+            }
+        }
+    }
+}
+
+#[cfg(feature = "s3")]
+mod s3 {
+    use adk_artifact::{ArtifactService, LoadRequest, S3ArtifactService, S3Config};
+    use adk_core::Part;
+    use std::time::Duration;
+
+    async fn fake() -> Option<S3ArtifactService> {
+        let endpoint = crate::fake_s3::start().await;
+        let config =
+            S3Config::new(endpoint, "us-east-1", "artifacts", "test", "secret").with_prefix("adk/");
+        Some(S3ArtifactService::new(config))
+    }
+
+    /// Reads the `ADK_TEST_S3_*` variables for a real store, or explains how to enable it.
+    async fn external() -> Option<S3ArtifactService> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let Some(endpoint) = var("ADK_TEST_S3_ENDPOINT") else {
+            eprintln!(
+                "skipping: ADK_TEST_S3_ENDPOINT is not set (see adk-artifact/scripts/test-s3.sh)"
+            );
+            return None;
+        };
+        let config = S3Config::new(
+            endpoint,
+            var("ADK_TEST_S3_REGION").unwrap_or_else(|| "us-east-1".to_string()),
+            var("ADK_TEST_S3_BUCKET").expect("ADK_TEST_S3_BUCKET"),
+            var("ADK_TEST_S3_ACCESS_KEY_ID").expect("ADK_TEST_S3_ACCESS_KEY_ID"),
+            var("ADK_TEST_S3_SECRET_ACCESS_KEY").expect("ADK_TEST_S3_SECRET_ACCESS_KEY"),
+        )
+        .with_prefix(format!("adk-test-{}/", uuid::Uuid::new_v4()));
+        Some(S3ArtifactService::new(config))
+    }
+
+    mod fake {
+        conformance_suite!(super::fake());
+    }
+
+    mod external {
+        conformance_suite!(super::external());
+    }
+
+    #[tokio::test]
+    async fn test_s3_presigned_urls() {
+        let service = fake().await.unwrap().with_presigned_urls(Duration::from_secs(600));
+        let request = crate::save_request("report.pdf", Part::Text { text: "q1".to_string() });
+        let load = LoadRequest {
+            app_name: request.app_name.clone(),
+            user_id: request.user_id.clone(),
+            session_id: request.session_id.clone(),
+            file_name: request.file_name.clone(),
+            version: None,
+        };
+        service.save(request).await.unwrap();
+
+        let uri = service.info(load).await.unwrap().uri.unwrap();
+        assert!(uri.contains("/artifacts/adk/app-"), "{uri}");
+        assert!(uri.contains("/report.pdf/1?X-Amz-Algorithm=AWS4-HMAC-SHA256&"), "{uri}");
+        assert!(uri.contains("X-Amz-Expires=600&X-Amz-SignedHeaders=host&X-Amz-Signature="));
+    }
+
+    #[tokio::test]
+    async fn test_s3_max_size() {
+        let service = fake().await.unwrap().with_max_size(16);
+        crate::assert_max_size_enforced(&service).await;
+    }
+}
@@ -0,0 +1,179 @@
+//! A minimal in-process S3 stand-in for the backend tests.
+//!
+//! Serves the path-style subset `S3ArtifactService` uses: object PUT (including
+//! `If-None-Match: *`), GET (including `Range`), HEAD and DELETE, and ListObjectsV2
+//! with a small page size so pagination is exercised. Requests must be signed, but
+//! signatures are not verified; the signing code has its own tests against the AWS
+//! examples. Payload hashes are checked, as S3 does.
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+const PAGE_SIZE: usize = 2;
+
+struct Object {
+    body: Bytes,
+    content_type: String,
+    /// `x-amz-meta-*` headers.
+    metadata: BTreeMap<String, String>,
+}
+
+type Bucket = Arc<Mutex<BTreeMap<String, Object>>>;
+
+/// Start the server and return its endpoint URL.
+pub async fn start() -> String {
+    let bucket: Bucket = Arc::default();
+    let app = Router::new()
+        .route("/{bucket}", get(list_objects))
+        .route("/{bucket}/{*key}", get(get_object).put(put_object).delete(delete_object))
+        .with_state(bucket);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    endpoint
+}
+
+fn error(status: StatusCode, code: &str) -> Response {
+    (status, format!("<Error><Code>{code}</Code></Error>")).into_response()
+}
+
+fn is_signed(headers: &HeaderMap) -> bool {
+    let has = |name: &str| headers.contains_key(name);
+    headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256 Credential="))
+        && has("x-amz-date")
+        && has("x-amz-content-sha256")
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+async fn put_object(
+    State(bucket): State<Bucket>,
+    Path((_, key)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !is_signed(&headers) {
+        return error(StatusCode::FORBIDDEN, "AccessDenied");
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    if header("content-length").is_none() {
+        return error(StatusCode::LENGTH_REQUIRED, "MissingContentLength");
+    }
+    if header("x-amz-content-sha256") != Some(hex::encode(Sha256::digest(&body))) {
+        return error(StatusCode::BAD_REQUEST, "XAmzContentSHA256Mismatch");
+    }
+    let mut objects = bucket.lock().unwrap();
+    if header("if-none-match").as_deref() == Some("*") && objects.contains_key(&key) {
+        return error(StatusCode::PRECONDITION_FAILED, "PreconditionFailed");
+    }
+    let metadata = headers
+        .iter()
+        .filter(|(name, _)| name.as_str().starts_with("x-amz-meta-"))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let object = Object {
+        body,
+        content_type: header("content-type").unwrap_or_else(|| "binary/octet-stream".into()),
+        metadata,
+    };
+    objects.insert(key, object);
+    StatusCode::OK.into_response()
+}
+
+/// Parse a single `bytes=<first>-<last>` range into an exclusive end.
+fn byte_range(value: &str, size: usize) -> Option<(usize, usize)> {
+    let (first, last) = value.strip_prefix("bytes=")?.split_once('-')?;
+    let (first, last): (usize, usize) = (first.parse().ok()?, last.parse().ok()?);
+    (first <= last && first < size).then(|| (first, (last + 1).min(size)))
+}
+
+/// Also serves HEAD, which axum routes here and answers without the body.
+async fn get_object(
+    State(bucket): State<Bucket>,
+    Path((_, key)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_signed(&headers) {
+        return error(StatusCode::FORBIDDEN, "AccessDenied");
+    }
+    let objects = bucket.lock().unwrap();
+    let Some(object) = objects.get(&key) else {
+        return error(StatusCode::NOT_FOUND, "NoSuchKey");
+    };
+    let (status, body) = match headers.get("range").and_then(|v| v.to_str().ok()) {
+        Some(range) => match byte_range(range, object.body.len()) {
+            Some((start, end)) => (StatusCode::PARTIAL_CONTENT, object.body.slice(start..end)),
+            None => return error(StatusCode::RANGE_NOT_SATISFIABLE, "InvalidRange"),
+        },
+        None => (StatusCode::OK, object.body.clone()),
+    };
+    let mut response = (status, body.clone()).into_response();
+    let response_headers = response.headers_mut();
+    response_headers.insert("content-length", body.len().into());
+    response_headers.insert("content-type", object.content_type.parse().unwrap());
+    for (name, value) in &object.metadata {
+        response_headers
+            .insert(name.parse::<axum::http::HeaderName>().unwrap(), value.parse().unwrap());
+    }
+    response
+}
+
+async fn delete_object(
+    State(bucket): State<Bucket>,
+    Path((_, key)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_signed(&headers) {
+        return error(StatusCode::FORBIDDEN, "AccessDenied");
+    }
+    bucket.lock().unwrap().remove(&key);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn list_objects(
+    State(bucket): State<Bucket>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_signed(&headers) {
+        return error(StatusCode::FORBIDDEN, "AccessDenied");
+    }
+    if query.get("list-type").map(String::as_str) != Some("2") {
+        return error(StatusCode::BAD_REQUEST, "InvalidArgument");
+    }
+    let prefix = query.get("prefix").cloned().unwrap_or_default();
+    let after = query.get("continuation-token").cloned().unwrap_or_default();
+
+    let objects = bucket.lock().unwrap();
+    let mut keys = objects
+        .keys()
+        .filter(|key| key.starts_with(&prefix) && key.as_str() > after.as_str())
+        .take(PAGE_SIZE + 1)
+        .collect::<Vec<_>>();
+    let truncated = keys.len() > PAGE_SIZE;
+    keys.truncate(PAGE_SIZE);
+
+    let mut xml = format!("<ListBucketResult><IsTruncated>{truncated}</IsTruncated>");
+    for key in &keys {
+        xml.push_str(&format!("<Contents><Key>{}</Key></Contents>", escape(key)));
+    }
+    if truncated {
+        let token = keys.last().map(|key| escape(key)).unwrap_or_default();
+        xml.push_str(&format!("<NextContinuationToken>{token}</NextContinuationToken>"));
+    }
+    xml.push_str("</ListBucketResult>");
+    xml.into_response()
+}
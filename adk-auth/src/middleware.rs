@@ -5,7 +5,7 @@
 
 use crate::audit::{AuditEvent, AuditOutcome, AuditSink};
 use crate::{AccessControl, Permission};
-use adk_core::{Result, Tool, ToolContext};
+use adk_core::{LlmRequest, Result, Tool, ToolContext, ToolExecutionPolicy};
 use async_trait::async_trait;
 use serde_json::Value;
 use std::sync::Arc;
@@ -74,6 +74,18 @@ impl<T: Tool + Send + Sync> Tool for ProtectedTool<T> {
         self.inner.response_schema()
     }
 
+    fn requires_confirmation(&self) -> bool {
+        self.inner.requires_confirmation()
+    }
+
+    fn process_llm_request(&self, request: &mut LlmRequest) {
+        self.inner.process_llm_request(request)
+    }
+
+    fn execution_policy(&self) -> Option<ToolExecutionPolicy> {
+        self.inner.execution_policy()
+    }
+
     async fn execute(&self, ctx: Arc<dyn ToolContext>, args: Value) -> Result<Value> {
         let user_id = ctx.user_id();
         let tool_name = self.name();
@@ -218,6 +230,18 @@ impl Tool for ProtectedToolDyn {
         self.inner.response_schema()
     }
 
+    fn requires_confirmation(&self) -> bool {
+        self.inner.requires_confirmation()
+    }
+
+    fn process_llm_request(&self, request: &mut LlmRequest) {
+        self.inner.process_llm_request(request)
+    }
+
+    fn execution_policy(&self) -> Option<ToolExecutionPolicy> {
+        self.inner.execution_policy()
+    }
+
     async fn execute(&self, ctx: Arc<dyn ToolContext>, args: Value) -> Result<Value> {
         let user_id = ctx.user_id();
         let tool_name = self.name();
@@ -93,19 +93,27 @@ pub async fn run_console(agent: Arc<dyn Agent>, app_name: String, user_id: Strin
 }
 
 /// StreamPrinter handles streaming output with special handling for:
-/// - `<think>` blocks: Displayed as `[think] ...` for reasoning models
+/// - `<think>` blocks and thinking parts: Displayed as `[think] ...` for reasoning models
 /// - Tool calls and responses: Formatted output
 /// - Regular text: Streamed directly to stdout
 #[derive(Default)]
 struct StreamPrinter {
     in_think_block: bool,
+    in_thinking_part: bool,
     think_buffer: String,
 }
 
 impl StreamPrinter {
     fn handle_part(&mut self, part: &Part) {
+        if self.in_thinking_part && !part.is_thinking() {
+            self.flush_think();
+            self.in_think_block = false;
+            self.in_thinking_part = false;
+        }
+
         match part {
             Part::Text { text } => self.handle_text_chunk(text),
+            Part::Thinking { thinking, .. } => self.handle_thinking_chunk(thinking),
             Part::FunctionCall { name, args, .. } => self.print_tool_call(name, args),
             Part::FunctionResponse { function_response, .. } => {
                 self.print_tool_response(&function_response.name, &function_response.response)
@@ -145,6 +153,15 @@ impl StreamPrinter {
         }
     }
 
+    fn handle_thinking_chunk(&mut self, chunk: &str) {
+        if !self.in_thinking_part {
+            self.in_think_block = true;
+            self.in_thinking_part = true;
+            self.think_buffer.clear();
+        }
+        self.think_buffer.push_str(chunk);
+    }
+
     fn print_visible(&self, text: &str) {
         if text.is_empty() {
             return;
@@ -170,6 +187,7 @@ impl StreamPrinter {
         if self.in_think_block {
             self.flush_think();
             self.in_think_block = false;
+            self.in_thinking_part = false;
         }
     }
 
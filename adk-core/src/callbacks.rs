@@ -1,4 +1,5 @@
-use crate::{CallbackContext, Content, LlmRequest, LlmResponse, ReadonlyContext, Result};
+use crate::{CallbackContext, Content, LlmRequest, LlmResponse, ReadonlyContext, Result, Tool};
+use serde_json::Value;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -47,18 +48,36 @@ pub type AfterModelCallback = Box<
         + Sync,
 >;
 
+/// Result from a BeforeTool callback
+#[derive(Debug)]
+pub enum BeforeToolResult {
+    /// Continue with the (possibly modified) arguments
+    Continue(Value),
+    /// Skip the tool call and use this result instead
+    Skip(Value),
+}
+
 // Tool callbacks
+// BeforeToolCallback receives the tool and its arguments; it can rewrite the arguments
+// or skip the call entirely
 pub type BeforeToolCallback = Box<
     dyn Fn(
             Arc<dyn CallbackContext>,
-        ) -> Pin<Box<dyn Future<Output = Result<Option<Content>>> + Send>>
+            Arc<dyn Tool>,
+            Value,
+        ) -> Pin<Box<dyn Future<Output = Result<BeforeToolResult>> + Send>>
         + Send
         + Sync,
 >;
+// AfterToolCallback receives the tool, its arguments and the result (or error message);
+// returning Some replaces the result sent back to the model
 pub type AfterToolCallback = Box<
     dyn Fn(
             Arc<dyn CallbackContext>,
-        ) -> Pin<Box<dyn Future<Output = Result<Option<Content>>> + Send>>
+            Arc<dyn Tool>,
+            Value,
+            std::result::Result<Value, String>,
+        ) -> Pin<Box<dyn Future<Output = Result<Option<Value>>> + Send>>
         + Send
         + Sync,
 >;
@@ -0,0 +1,214 @@
+//! Human-in-the-loop confirmation for sensitive tool calls.
+//!
+//! When a tool requires confirmation, the agent does not execute it. Instead it emits
+//! an event containing a function call named [`REQUEST_CONFIRMATION_FUNCTION_NAME`]
+//! whose arguments are a [`ToolConfirmationRequest`], marks that call as long-running,
+//! and pauses. The client answers by sending a message with a matching function
+//! response carrying a [`ToolConfirmation`], which resumes the original call.
+//!
+//! ```rust
+//! use adk_core::{Content, ToolConfirmation};
+//!
+//! // `confirmation_id` is the id of the adk_request_confirmation function call
+//! let reply = Content::new("user")
+//!     .with_tool_confirmation("confirm_call_1", ToolConfirmation::approve());
+//! assert_eq!(reply.parts.len(), 1);
+//! ```
+
+use crate::types::{Content, FunctionResponseData, Part};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Name of the synthetic function call used to request a human decision.
+pub const REQUEST_CONFIRMATION_FUNCTION_NAME: &str = "adk_request_confirmation";
+
+/// A pending tool call awaiting human approval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolConfirmationRequest {
+    /// Name of the tool the model wants to call.
+    pub tool_name: String,
+    /// Arguments the model passed to the tool.
+    pub args: Value,
+    /// ID of the original function call, if the provider assigned one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_call_id: Option<String>,
+    /// Optional text shown to the person asked to confirm.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl ToolConfirmationRequest {
+    /// Build the function call part that carries this request.
+    pub fn to_part(&self, confirmation_id: impl Into<String>) -> Part {
+        Part::FunctionCall {
+            name: REQUEST_CONFIRMATION_FUNCTION_NAME.to_string(),
+            args: serde_json::to_value(self).unwrap_or_default(),
+            id: Some(confirmation_id.into()),
+        }
+    }
+
+    /// Extract a confirmation request from a function call part, returning it
+    /// together with the confirmation id.
+    pub fn from_part(part: &Part) -> Option<(String, Self)> {
+        match part {
+            Part::FunctionCall { name, args, id: Some(id) }
+                if name == REQUEST_CONFIRMATION_FUNCTION_NAME =>
+            {
+                serde_json::from_value(args.clone()).ok().map(|request| (id.clone(), request))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A human decision on a [`ToolConfirmationRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolConfirmation {
+    /// Whether the tool call may proceed.
+    pub confirmed: bool,
+    /// Optional reason, returned to the model when the call is denied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ToolConfirmation {
+    /// Approve the pending tool call.
+    pub fn approve() -> Self {
+        Self { confirmed: true, reason: None }
+    }
+
+    /// Deny the pending tool call.
+    pub fn deny() -> Self {
+        Self { confirmed: false, reason: None }
+    }
+
+    /// Attach a reason to the decision.
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Build the function response part that answers the confirmation request.
+    pub fn to_part(&self, confirmation_id: impl Into<String>) -> Part {
+        Part::FunctionResponse {
+            function_response: FunctionResponseData {
+                name: REQUEST_CONFIRMATION_FUNCTION_NAME.to_string(),
+                response: serde_json::to_value(self).unwrap_or_default(),
+            },
+            id: Some(confirmation_id.into()),
+        }
+    }
+
+    /// Extract a decision from a function response part, returning it together with
+    /// the confirmation id.
+    pub fn from_part(part: &Part) -> Option<(String, Self)> {
+        match part {
+            Part::FunctionResponse { function_response, id: Some(id) }
+                if function_response.name == REQUEST_CONFIRMATION_FUNCTION_NAME =>
+            {
+                serde_json::from_value(function_response.response.clone())
+                    .ok()
+                    .map(|decision| (id.clone(), decision))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Decides which tool calls require human confirmation, in addition to tools that
+/// report [`Tool::requires_confirmation`](crate::Tool::requires_confirmation).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ToolConfirmationPolicy {
+    /// Only tools that declare it themselves require confirmation.
+    #[default]
+    Never,
+    /// Every tool call requires confirmation.
+    Always,
+    /// Calls to the named tools require confirmation.
+    PerTool(HashSet<String>),
+}
+
+impl ToolConfirmationPolicy {
+    /// Require confirmation for the named tools.
+    pub fn tools<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::PerTool(names.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns true if the policy requires confirmation for the given tool.
+    pub fn requires_confirmation(&self, tool_name: &str) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Always => true,
+            Self::PerTool(names) => names.contains(tool_name),
+        }
+    }
+}
+
+impl Content {
+    /// Add a decision for a pending tool confirmation request.
+    pub fn with_tool_confirmation(
+        mut self,
+        confirmation_id: impl Into<String>,
+        confirmation: ToolConfirmation,
+    ) -> Self {
+        self.parts.push(confirmation.to_part(confirmation_id));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_round_trip() {
+        let request = ToolConfirmationRequest {
+            tool_name: "send_payment".to_string(),
+            args: json!({"amount": 100}),
+            function_call_id: Some("call_1".to_string()),
+            hint: None,
+        };
+
+        let part = request.to_part("confirm_call_1");
+        let (id, parsed) = ToolConfirmationRequest::from_part(&part).unwrap();
+        assert_eq!(id, "confirm_call_1");
+        assert_eq!(parsed, request);
+    }
+
+    #[test]
+    fn test_confirmation_round_trip() {
+        let decision = ToolConfirmation::deny().with_reason("too expensive");
+
+        let part = decision.to_part("confirm_call_1");
+        let (id, parsed) = ToolConfirmation::from_part(&part).unwrap();
+        assert_eq!(id, "confirm_call_1");
+        assert_eq!(parsed, decision);
+    }
+
+    #[test]
+    fn test_from_part_ignores_other_functions() {
+        let part = Part::FunctionCall {
+            name: "send_payment".to_string(),
+            args: json!({}),
+            id: Some("call_1".to_string()),
+        };
+        assert!(ToolConfirmationRequest::from_part(&part).is_none());
+        assert!(ToolConfirmation::from_part(&part).is_none());
+    }
+
+    #[test]
+    fn test_policy() {
+        assert!(!ToolConfirmationPolicy::Never.requires_confirmation("delete"));
+        assert!(ToolConfirmationPolicy::Always.requires_confirmation("delete"));
+
+        let policy = ToolConfirmationPolicy::tools(["delete"]);
+        assert!(policy.requires_confirmation("delete"));
+        assert!(!policy.requires_confirmation("search"));
+    }
+}
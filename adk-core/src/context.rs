@@ -1,8 +1,9 @@
-use crate::{Agent, Result, types::Content};
+use crate::{AdkError, Agent, Event, Result, types::Content};
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+pub use tokio_util::sync::CancellationToken;
 
 #[async_trait]
 pub trait ReadonlyContext: Send + Sync {
@@ -39,6 +40,14 @@ pub trait Session: Send + Sync {
     fn append_to_history(&self, _content: Content) {
         // Default no-op - implementations can override to track history
     }
+    /// Returns the events of this session that requested tool confirmations, oldest first.
+    ///
+    /// Agents only resume a paused call that one of their own events requested here.
+    /// The default returns none, so sessions that don't track events cannot resume
+    /// confirmations.
+    fn confirmation_request_events(&self) -> Vec<Event> {
+        Vec::new()
+    }
 }
 
 #[async_trait]
@@ -54,6 +63,13 @@ pub trait InvocationContext: CallbackContext {
     fn run_config(&self) -> &RunConfig;
     fn end_invocation(&self);
     fn ended(&self) -> bool;
+
+    /// Fires when the invocation should stop early, e.g. because its client went away.
+    /// Agents check it between model calls, streamed chunks and tool calls. Contexts
+    /// that cannot be cancelled return a token that never fires.
+    fn cancellation_token(&self) -> CancellationToken {
+        CancellationToken::new()
+    }
 }
 
 // Placeholder service traits
@@ -62,19 +78,52 @@ pub trait Artifacts: Send + Sync {
     async fn save(&self, name: &str, data: &crate::Part) -> Result<i64>;
     async fn load(&self, name: &str) -> Result<crate::Part>;
     async fn list(&self) -> Result<Vec<String>>;
+
+    /// A `Part::FileData` referencing the latest version of `name`, so it can reach a
+    /// model without inlining its bytes. `None` when the backend has no URI a model can
+    /// fetch, such as a local `file://` path.
+    async fn reference(&self, _name: &str) -> Result<Option<crate::Part>> {
+        Ok(None)
+    }
+
+    /// The size in bytes of the latest version of `name`, read without loading its
+    /// content. `None` when the backend can only tell by loading it.
+    async fn size(&self, _name: &str) -> Result<Option<u64>> {
+        Ok(None)
+    }
 }
 
 #[async_trait]
 pub trait Memory: Send + Sync {
     async fn search(&self, query: &str) -> Result<Vec<MemoryEntry>>;
+
+    /// Remember `entry`. Returns its id.
+    async fn add(&self, _entry: MemoryEntry) -> Result<String> {
+        Err(AdkError::Memory("this memory does not support adding entries".into()))
+    }
+
+    /// Forget the entry with `id`. Returns whether it existed.
+    async fn delete(&self, _id: &str) -> Result<bool> {
+        Err(AdkError::Memory("this memory does not support deleting entries".into()))
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct MemoryEntry {
+    /// Id to pass to [`Memory::delete`]. `None` for new entries and for memories that
+    /// cannot be deleted individually.
+    pub id: Option<String>,
     pub content: Content,
     pub author: String,
 }
 
+impl MemoryEntry {
+    /// A new entry without an id, as passed to [`Memory::add`].
+    pub fn new(author: impl Into<String>, content: Content) -> Self {
+        Self { id: None, content, author: author.into() }
+    }
+}
+
 /// Streaming mode for agent responses.
 /// Matches ADK Python/Go specification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -101,6 +150,19 @@ pub enum IncludeContents {
     Default,
 }
 
+/// Controls which thinking parts from prior conversation history are sent back to the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IncludeThinking {
+    /// Strip all thinking from history
+    None,
+    /// Default - Keep thinking from the current turn only (everything after the latest user
+    /// message), which providers need to continue reasoning across tool calls
+    #[default]
+    CurrentTurn,
+    /// Keep all thinking in history
+    All,
+}
+
 #[derive(Debug, Clone)]
 pub struct RunConfig {
     pub streaming_mode: StreamingMode,
@@ -12,6 +12,10 @@ pub enum AdkError {
     #[error("Session error: {0}")]
     Session(String),
 
+    /// A conditional append found the session changed since the caller read it.
+    #[error("Stale session {session_id}: expected version {expected}, found {actual}")]
+    StaleSession { session_id: String, expected: u64, actual: u64 },
+
     #[error("Artifact error: {0}")]
     Artifact(String),
 
@@ -40,6 +44,12 @@ mod tests {
         assert_eq!(err.to_string(), "Agent error: test error");
     }
 
+    #[test]
+    fn test_stale_session_display() {
+        let err = AdkError::StaleSession { session_id: "s1".to_string(), expected: 3, actual: 5 };
+        assert_eq!(err.to_string(), "Stale session s1: expected version 3, found 5");
+    }
+
     #[test]
     fn test_error_from_io() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
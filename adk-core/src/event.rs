@@ -1,5 +1,7 @@
+use crate::confirmation::ToolConfirmationRequest;
 use crate::model::LlmResponse;
 use crate::types::Content;
+use crate::usage::UsageReport;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -10,6 +12,10 @@ pub const KEY_PREFIX_APP: &str = "app:";
 pub const KEY_PREFIX_TEMP: &str = "temp:";
 pub const KEY_PREFIX_USER: &str = "user:";
 
+/// Name of the function response part that persists a summary written by history
+/// compaction.
+pub const COMPACTION_SUMMARY_FUNCTION_NAME: &str = "adk_compaction_summary";
+
 /// Event represents a single interaction in a conversation.
 /// This struct embeds LlmResponse to match ADK-Go's design pattern.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +45,9 @@ pub struct Event {
     /// GCP Vertex Agent LLM response (for UI compatibility)
     #[serde(rename = "gcp.vertex.agent.llm_response", skip_serializing_if = "Option::is_none")]
     pub gcp_llm_response: Option<String>,
+    /// Token usage and cost accumulated by the runner, set on final responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_report: Option<UsageReport>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -48,6 +57,10 @@ pub struct EventActions {
     pub skip_summarization: bool,
     pub transfer_to_agent: Option<String>,
     pub escalate: bool,
+    /// Tool calls paused for human confirmation, keyed by the id of the
+    /// `adk_request_confirmation` function call that carries each request.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub requested_tool_confirmations: HashMap<String, ToolConfirmationRequest>,
 }
 
 impl Event {
@@ -66,6 +79,7 @@ impl Event {
             llm_request: None,
             gcp_llm_request: None,
             gcp_llm_response: None,
+            usage_report: None,
         }
     }
 
@@ -86,6 +100,7 @@ impl Event {
             llm_request: None,
             gcp_llm_request: None,
             gcp_llm_response: None,
+            usage_report: None,
         }
     }
 
@@ -176,6 +191,22 @@ impl Event {
         }
         ids
     }
+
+    /// Returns true if the event persists a compaction summary rather than a message.
+    ///
+    /// Agents read these events back to shorten the history they send to the model;
+    /// consumers that only care about the conversation can skip them.
+    pub fn is_compaction_summary(&self) -> bool {
+        self.llm_response.content.as_ref().is_some_and(|content| {
+            content.parts.iter().any(|part| {
+                matches!(
+                    part,
+                    crate::Part::FunctionResponse { function_response, .. }
+                        if function_response.name == COMPACTION_SUMMARY_FUNCTION_NAME
+                )
+            })
+        })
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +228,13 @@ mod tests {
         assert!(!actions.skip_summarization);
     }
 
+    #[test]
+    fn test_event_actions_deserialize_without_confirmations() {
+        let json = r#"{"state_delta":{},"artifact_delta":{},"skip_summarization":false,"transfer_to_agent":null,"escalate":false}"#;
+        let actions: EventActions = serde_json::from_str(json).unwrap();
+        assert!(actions.requested_tool_confirmations.is_empty());
+    }
+
     #[test]
     fn test_state_prefixes() {
         assert_eq!(KEY_PREFIX_APP, "app:");
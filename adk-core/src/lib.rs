@@ -62,6 +62,7 @@
 pub mod agent;
 pub mod agent_loader;
 pub mod callbacks;
+pub mod confirmation;
 pub mod context;
 pub mod error;
 pub mod event;
@@ -69,24 +70,37 @@ pub mod instruction_template;
 pub mod model;
 pub mod tool;
 pub mod types;
+pub mod usage;
 
 pub use agent::{Agent, EventStream};
 pub use agent_loader::{AgentLoader, MultiAgentLoader, SingleAgentLoader};
 pub use callbacks::{
     AfterAgentCallback, AfterModelCallback, AfterToolCallback, BeforeAgentCallback,
-    BeforeModelCallback, BeforeModelResult, BeforeToolCallback, GlobalInstructionProvider,
-    InstructionProvider,
+    BeforeModelCallback, BeforeModelResult, BeforeToolCallback, BeforeToolResult,
+    GlobalInstructionProvider, InstructionProvider,
+};
+pub use confirmation::{
+    REQUEST_CONFIRMATION_FUNCTION_NAME, ToolConfirmation, ToolConfirmationPolicy,
+    ToolConfirmationRequest,
 };
 pub use context::{
-    Artifacts, CallbackContext, IncludeContents, InvocationContext, Memory, MemoryEntry,
-    ReadonlyContext, ReadonlyState, RunConfig, Session, State, StreamingMode,
+    Artifacts, CallbackContext, CancellationToken, IncludeContents, IncludeThinking,
+    InvocationContext, Memory, MemoryEntry, ReadonlyContext, ReadonlyState, RunConfig, Session,
+    State, StreamingMode,
 };
 pub use error::{AdkError, Result};
-pub use event::{Event, EventActions, KEY_PREFIX_APP, KEY_PREFIX_TEMP, KEY_PREFIX_USER};
+pub use event::{
+    COMPACTION_SUMMARY_FUNCTION_NAME, Event, EventActions, KEY_PREFIX_APP, KEY_PREFIX_TEMP,
+    KEY_PREFIX_USER,
+};
 pub use instruction_template::inject_session_state;
 pub use model::{
     FinishReason, GenerateContentConfig, Llm, LlmRequest, LlmResponse, LlmResponseStream,
     UsageMetadata,
 };
-pub use tool::{Tool, ToolContext, ToolPredicate, Toolset};
+pub use tool::{
+    Tool, ToolContext, ToolExecution, ToolExecutionPolicy, ToolPredicate, ToolRetryPredicate,
+    Toolset,
+};
 pub use types::{Content, FunctionResponseData, Part};
+pub use usage::{ModelPricing, PriceTable, TokenUsage, UsageReport, UsageTotals};
@@ -42,13 +42,23 @@ pub struct LlmResponse {
     pub interrupted: bool,
     pub error_code: Option<String>,
     pub error_message: Option<String>,
+    /// Name of the model that produced this response, when a wrapper such as a
+    /// fallback chain needs to report which of several models answered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UsageMetadata {
     pub prompt_token_count: i32,
     pub candidates_token_count: i32,
     pub total_token_count: i32,
+    /// Portion of `prompt_token_count` served from the provider's prompt cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_content_token_count: Option<i32>,
+    /// Portion of `candidates_token_count` spent on reasoning before the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thoughts_token_count: Option<i32>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -96,6 +106,7 @@ impl LlmResponse {
             interrupted: false,
             error_code: None,
             error_message: None,
+            model_name: None,
         }
     }
 }
@@ -1,7 +1,9 @@
-use crate::{CallbackContext, EventActions, MemoryEntry, Result};
+use crate::{AdkError, CallbackContext, EventActions, LlmRequest, MemoryEntry, Result};
 use async_trait::async_trait;
 use serde_json::Value;
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -22,15 +24,235 @@ pub trait Tool: Send + Sync {
     fn is_long_running(&self) -> bool {
         false
     }
+
+    /// Indicates whether a human must approve each call before it executes.
+    /// Agents pause and emit a confirmation request instead of running the tool.
+    fn requires_confirmation(&self) -> bool {
+        false
+    }
     fn parameters_schema(&self) -> Option<Value> {
         None
     }
     fn response_schema(&self) -> Option<Value> {
         None
     }
+
+    /// Adjust the request before each model call of an agent that has this tool, for
+    /// example to attach content the tool's last response referred to. Runs before the
+    /// before-model callbacks. The default leaves the request unchanged.
+    fn process_llm_request(&self, _request: &mut LlmRequest) {}
+
+    /// Timeout and retry policy the agent applies when executing this tool.
+    /// Returning None defers to the agent's default policy.
+    fn execution_policy(&self) -> Option<ToolExecutionPolicy> {
+        None
+    }
     async fn execute(&self, ctx: Arc<dyn ToolContext>, args: Value) -> Result<Value>;
 }
 
+/// Decides whether a tool error is worth another attempt.
+pub type ToolRetryPredicate = Arc<dyn Fn(&AdkError) -> bool + Send + Sync>;
+
+/// Timeout and retry settings applied by agents when executing a tool.
+///
+/// Nothing is retried unless asked for: errors returned by the tool are retried when
+/// the [`retry_on`](Self::retry_on) predicate accepts them, and timed-out attempts
+/// only with [`retry_on_timeout`](Self::retry_on_timeout). A failed or abandoned call
+/// may have bad arguments or side effects that must not repeat. The default policy has
+/// no timeout and no retries.
+#[derive(Clone)]
+pub struct ToolExecutionPolicy {
+    /// Maximum duration of a single attempt. None means no limit.
+    pub timeout: Option<Duration>,
+    /// Number of additional attempts after a retryable failure.
+    pub max_retries: u32,
+    /// Delay before the first retry. Doubles with each subsequent retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between retries.
+    pub max_backoff: Duration,
+    /// Whether timed-out attempts are retried.
+    pub retry_on_timeout: bool,
+    retry_on: Option<ToolRetryPredicate>,
+}
+
+impl Default for ToolExecutionPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            retry_on_timeout: false,
+            retry_on: None,
+        }
+    }
+}
+
+impl ToolExecutionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum duration of a single attempt.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the number of retries after a retryable failure.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the initial and maximum delay between retries.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Retry timed-out attempts. Only enable this for idempotent tools: an abandoned
+    /// attempt may still have taken effect.
+    pub fn retry_on_timeout(mut self, retry: bool) -> Self {
+        self.retry_on_timeout = retry;
+        self
+    }
+
+    /// Retry tool errors `predicate` accepts, for example transient network failures.
+    /// Without a predicate tool errors are not retried.
+    pub fn retry_on<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&AdkError) -> bool + Send + Sync + 'static,
+    {
+        self.retry_on = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Whether a tool error may be retried.
+    pub fn should_retry(&self, error: &AdkError) -> bool {
+        self.retry_on.as_ref().is_some_and(|predicate| predicate(error))
+    }
+
+    /// Returns the delay before the given retry (1-based), using exponential backoff
+    /// capped at `max_backoff`.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Execute `tool` under this policy.
+    ///
+    /// Each attempt runs on a fresh context from `new_context`, so actions recorded by
+    /// failed attempts are discarded. Errors and timeouts are turned into the
+    /// `{"error": ...}` response agents send to the model; a timeout also reports
+    /// `error_type`, `timeout_ms` and `attempts`.
+    pub async fn run<F>(&self, tool: &dyn Tool, mut new_context: F, args: &Value) -> ToolExecution
+    where
+        F: FnMut() -> Arc<dyn ToolContext>,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            let ctx = new_context();
+            let execution = tool.execute(ctx.clone(), args.clone());
+            let outcome = match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, execution).await.ok(),
+                None => Some(execution.await),
+            };
+
+            let retryable = match &outcome {
+                Some(Ok(_)) => false,
+                Some(Err(e)) => self.should_retry(e),
+                None => self.retry_on_timeout,
+            };
+            if retryable && attempt <= self.max_retries {
+                tokio::time::sleep(self.backoff_for(attempt)).await;
+                continue;
+            }
+
+            return match outcome {
+                Some(Ok(value)) => ToolExecution {
+                    response: value.clone(),
+                    result: Ok(value),
+                    actions: ctx.actions(),
+                    attempts: attempt,
+                    timed_out: false,
+                },
+                Some(Err(e)) => ToolExecution {
+                    response: serde_json::json!({ "error": e.to_string() }),
+                    result: Err(e.to_string()),
+                    actions: ctx.actions(),
+                    attempts: attempt,
+                    timed_out: false,
+                },
+                None => {
+                    let timeout = self.timeout.unwrap_or_default();
+                    let error = format!("Tool {} timed out after {:?}", tool.name(), timeout);
+                    ToolExecution {
+                        response: serde_json::json!({
+                            "error": error,
+                            "error_type": "timeout",
+                            "timeout_ms": timeout.as_millis() as u64,
+                            "attempts": attempt,
+                        }),
+                        result: Err(error),
+                        actions: EventActions::default(),
+                        attempts: attempt,
+                        timed_out: true,
+                    }
+                }
+            };
+        }
+    }
+}
+
+impl fmt::Debug for ToolExecutionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolExecutionPolicy")
+            .field("timeout", &self.timeout)
+            .field("max_retries", &self.max_retries)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("retry_on_timeout", &self.retry_on_timeout)
+            .field("retry_on", &self.retry_on.as_ref().map(|_| "<predicate>"))
+            .finish()
+    }
+}
+
+impl PartialEq for ToolExecutionPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        self.timeout == other.timeout
+            && self.max_retries == other.max_retries
+            && self.initial_backoff == other.initial_backoff
+            && self.max_backoff == other.max_backoff
+            && self.retry_on_timeout == other.retry_on_timeout
+            && match (&self.retry_on, &other.retry_on) {
+                (None, None) => true,
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                _ => false,
+            }
+    }
+}
+
+impl Eq for ToolExecutionPolicy {}
+
+/// Outcome of running a tool under a [`ToolExecutionPolicy`].
+#[derive(Debug, Clone)]
+pub struct ToolExecution {
+    /// The final attempt's value, or its error message.
+    pub result: std::result::Result<Value, String>,
+    /// Function response payload for the model.
+    pub response: Value,
+    /// Actions the final attempt recorded; empty after a timeout.
+    pub actions: EventActions,
+    /// Number of attempts made, including the first.
+    pub attempts: u32,
+    /// Whether the final attempt timed out.
+    pub timed_out: bool,
+}
+
 #[async_trait]
 pub trait ToolContext: CallbackContext {
     fn function_call_id(&self) -> &str;
@@ -39,6 +261,22 @@ pub trait ToolContext: CallbackContext {
     /// Set the event actions (e.g., to trigger escalation or skip summarization).
     fn set_actions(&self, actions: EventActions);
     async fn search_memory(&self, query: &str) -> Result<Vec<MemoryEntry>>;
+
+    /// Remember `entry` in the user's memory. Returns its id.
+    ///
+    /// The default implementation fails with "memory is not available"; contexts
+    /// backed by a [`Memory`](crate::Memory) forward to [`Memory::add`](crate::Memory::add).
+    async fn save_memory(&self, _entry: MemoryEntry) -> Result<String> {
+        Err(AdkError::Memory("memory is not available".into()))
+    }
+
+    /// Forget the memory with `id`. Returns whether it existed.
+    ///
+    /// The default implementation fails with "memory is not available"; contexts
+    /// backed by a [`Memory`](crate::Memory) forward to [`Memory::delete`](crate::Memory::delete).
+    async fn forget_memory(&self, _id: &str) -> Result<bool> {
+        Err(AdkError::Memory("memory is not available".into()))
+    }
 }
 
 #[async_trait]
@@ -54,6 +292,7 @@ mod tests {
     use super::*;
     use crate::{Content, EventActions, ReadonlyContext, RunConfig};
     use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
 
     struct TestTool {
         name: String,
@@ -145,6 +384,132 @@ mod tests {
         assert_eq!(tool.name(), "test");
         assert_eq!(tool.description(), "test tool");
         assert!(!tool.is_long_running());
+        assert!(!tool.requires_confirmation());
+        assert!(tool.execution_policy().is_none());
+    }
+
+    #[test]
+    fn test_execution_policy_backoff() {
+        let policy = ToolExecutionPolicy::new()
+            .with_timeout(Duration::from_secs(10))
+            .with_max_retries(5)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+
+        assert_eq!(policy.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff_for(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff_for(40), Duration::from_millis(500));
+    }
+
+    /// Fails with a tool error on every call, counting the calls.
+    struct FailingTool {
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Tool for FailingTool {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn description(&self) -> &str {
+            "always fails"
+        }
+
+        async fn execute(&self, _ctx: Arc<dyn ToolContext>, _args: Value) -> Result<Value> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(AdkError::Tool("invalid arguments".into()))
+        }
+    }
+
+    fn new_context() -> Arc<dyn ToolContext> {
+        Arc::new(TestContext::new())
+    }
+
+    #[tokio::test]
+    async fn test_run_does_not_retry_unclassified_errors() {
+        let tool = FailingTool { calls: AtomicU32::new(0) };
+        let policy = ToolExecutionPolicy::new()
+            .with_max_retries(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let execution = policy.run(&tool, new_context, &Value::Null).await;
+
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(execution.attempts, 1);
+        assert_eq!(execution.response["error"], "Tool error: invalid arguments");
+    }
+
+    #[tokio::test]
+    async fn test_run_retries_errors_accepted_by_predicate() {
+        let tool = FailingTool { calls: AtomicU32::new(0) };
+        let policy = ToolExecutionPolicy::new()
+            .with_max_retries(2)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1))
+            .retry_on(|error| matches!(error, AdkError::Tool(_)));
+
+        let execution = policy.run(&tool, new_context, &Value::Null).await;
+
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(execution.attempts, 3);
+        assert!(execution.result.is_err());
+    }
+
+    /// Never finishes within a test timeout, counting the calls.
+    struct SlowTool {
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "never finishes"
+        }
+
+        async fn execute(&self, _ctx: Arc<dyn ToolContext>, _args: Value) -> Result<Value> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(Value::Null)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_does_not_retry_timeouts_by_default() {
+        let tool = SlowTool { calls: AtomicU32::new(0) };
+        let policy = ToolExecutionPolicy::new()
+            .with_timeout(Duration::from_millis(20))
+            .with_max_retries(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+        let execution = policy.run(&tool, new_context, &Value::Null).await;
+
+        assert!(execution.timed_out);
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(execution.response["attempts"], 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_retries_timeouts_when_enabled_and_reports_them() {
+        let tool = SlowTool { calls: AtomicU32::new(0) };
+        let policy = ToolExecutionPolicy::new()
+            .with_timeout(Duration::from_millis(20))
+            .with_max_retries(1)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1))
+            .retry_on_timeout(true);
+
+        let execution = policy.run(&tool, new_context, &Value::Null).await;
+
+        assert!(execution.timed_out);
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(execution.response["error_type"], "timeout");
+        assert_eq!(execution.response["timeout_ms"], 20);
+        assert_eq!(execution.response["attempts"], 2);
     }
 
     #[tokio::test]
@@ -15,6 +15,20 @@ pub struct Content {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Part {
+    /// Reasoning the model produced before its answer.
+    ///
+    /// `signature` is an opaque provider token (Anthropic thinking signature, Gemini
+    /// thought signature) that must be sent back unchanged when the part is replayed.
+    /// A part with empty `thinking` carries only a signature.
+    ///
+    /// Serialized like a Gemini thought part, `{"text": ..., "thought": true}`, so
+    /// clients render it apart from the answer. Declared before `Text` so untagged
+    /// deserialization checks for the `thought` flag first.
+    #[serde(with = "thinking_serde")]
+    Thinking {
+        thinking: String,
+        signature: Option<String>,
+    },
     Text {
         text: String,
     },
@@ -86,6 +100,42 @@ impl Content {
     }
 }
 
+mod thinking_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ThoughtPart {
+        text: String,
+        thought: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        thought_signature: Option<String>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        thinking: &str,
+        signature: &Option<String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        ThoughtPart {
+            text: thinking.to_string(),
+            thought: true,
+            thought_signature: signature.clone(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<(String, Option<String>), D::Error> {
+        let part = ThoughtPart::deserialize(deserializer)?;
+        if !part.thought {
+            return Err(D::Error::custom("not a thought part"));
+        }
+        Ok((part.text, part.thought_signature))
+    }
+}
+
 impl Part {
     /// Returns the text content if this is a Text part, None otherwise
     pub fn text(&self) -> Option<&str> {
@@ -112,6 +162,19 @@ impl Part {
         }
     }
 
+    /// Returns the reasoning text if this is a Thinking part
+    pub fn thinking(&self) -> Option<&str> {
+        match self {
+            Part::Thinking { thinking, .. } => Some(thinking.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is a Thinking part
+    pub fn is_thinking(&self) -> bool {
+        matches!(self, Part::Thinking { .. })
+    }
+
     /// Returns true if this part contains media (image, audio, video)
     pub fn is_media(&self) -> bool {
         matches!(self, Part::InlineData { .. } | Part::FileData { .. })
@@ -122,6 +185,11 @@ impl Part {
         Part::Text { text: text.into() }
     }
 
+    /// Create a new thinking part
+    pub fn thinking_part(thinking: impl Into<String>, signature: Option<String>) -> Self {
+        Part::Thinking { thinking: thinking.into(), signature }
+    }
+
     /// Create a new inline data part
     pub fn inline_data(mime_type: impl Into<String>, data: Vec<u8>) -> Self {
         Part::InlineData { mime_type: mime_type.into(), data }
@@ -173,6 +241,25 @@ mod tests {
         assert!(json.contains("test"));
     }
 
+    #[test]
+    fn test_part_thinking_serialization() {
+        let part = Part::thinking_part("step one", Some("sig".to_string()));
+        let json = serde_json::to_value(&part).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"text": "step one", "thought": true, "thoughtSignature": "sig"})
+        );
+
+        let back: Part = serde_json::from_value(json).unwrap();
+        assert_eq!(back, part);
+        assert_eq!(back.thinking(), Some("step one"));
+        assert_eq!(back.text(), None);
+
+        let text: Part =
+            serde_json::from_value(serde_json::json!({"text": "hi", "thought": false})).unwrap();
+        assert_eq!(text, Part::text_part("hi"));
+    }
+
     #[test]
     fn test_part_file_data_serialization() {
         let part = Part::FileData {
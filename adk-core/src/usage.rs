@@ -0,0 +1,254 @@
+//! Token usage and cost accounting.
+//!
+//! Providers report token counts per response as [`UsageMetadata`]. The types here
+//! accumulate those counts into [`TokenUsage`] totals, price them with a
+//! [`PriceTable`], and summarize an invocation as a [`UsageReport`].
+//!
+//! ```rust
+//! use adk_core::{ModelPricing, PriceTable, TokenUsage};
+//!
+//! let prices = PriceTable::new()
+//!     .with_model("gemini-2.5-flash", ModelPricing::new(0.30, 2.50).with_cached_input(0.075));
+//!
+//! let usage = TokenUsage { prompt_tokens: 1_000_000, completion_tokens: 100_000, ..Default::default() };
+//! let cost = prices.cost("gemini-2.5-flash-001", &usage).unwrap();
+//! assert!((cost - 0.55).abs() < 1e-9);
+//! ```
+
+use crate::model::UsageMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::AddAssign;
+
+/// Token counts accumulated over one or more model responses.
+///
+/// `cached_tokens` is the part of `prompt_tokens` read from a prompt cache and
+/// `reasoning_tokens` the part of `completion_tokens` spent on thinking.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cached_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Returns true if no tokens were counted.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Field-wise maximum of two usages.
+    ///
+    /// Streaming providers may report usage on several chunks of one response, each
+    /// a running total; merging with `max` counts such a response once.
+    pub fn max(&self, other: &Self) -> Self {
+        Self {
+            prompt_tokens: self.prompt_tokens.max(other.prompt_tokens),
+            completion_tokens: self.completion_tokens.max(other.completion_tokens),
+            cached_tokens: self.cached_tokens.max(other.cached_tokens),
+            reasoning_tokens: self.reasoning_tokens.max(other.reasoning_tokens),
+            total_tokens: self.total_tokens.max(other.total_tokens),
+        }
+    }
+
+    /// Field-wise saturating difference.
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        Self {
+            prompt_tokens: self.prompt_tokens.saturating_sub(other.prompt_tokens),
+            completion_tokens: self.completion_tokens.saturating_sub(other.completion_tokens),
+            cached_tokens: self.cached_tokens.saturating_sub(other.cached_tokens),
+            reasoning_tokens: self.reasoning_tokens.saturating_sub(other.reasoning_tokens),
+            total_tokens: self.total_tokens.saturating_sub(other.total_tokens),
+        }
+    }
+}
+
+impl From<&UsageMetadata> for TokenUsage {
+    fn from(usage: &UsageMetadata) -> Self {
+        let count = |n: i32| n.max(0) as u64;
+        let prompt_tokens = count(usage.prompt_token_count);
+        let completion_tokens = count(usage.candidates_token_count);
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            cached_tokens: usage.cached_content_token_count.map(count).unwrap_or(0),
+            reasoning_tokens: usage.thoughts_token_count.map(count).unwrap_or(0),
+            total_tokens: count(usage.total_token_count).max(prompt_tokens + completion_tokens),
+        }
+    }
+}
+
+impl AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cached_tokens += other.cached_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// Prices for one model, in currency units per million tokens.
+///
+/// Reasoning tokens are billed as output. Cached prompt tokens use
+/// `cached_input_per_million` when set and the regular input price otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_million: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self { input_per_million, output_per_million, cached_input_per_million: None }
+    }
+
+    pub fn with_cached_input(mut self, cached_input_per_million: f64) -> Self {
+        self.cached_input_per_million = Some(cached_input_per_million);
+        self
+    }
+
+    /// Cost of `usage` at these prices.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cached = usage.cached_tokens.min(usage.prompt_tokens);
+        let uncached = usage.prompt_tokens - cached;
+        let cached_price = self.cached_input_per_million.unwrap_or(self.input_per_million);
+        (uncached as f64 * self.input_per_million
+            + cached as f64 * cached_price
+            + usage.completion_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Per-model prices used to turn token counts into costs.
+///
+/// Lookups match the model name exactly, or else the longest configured name the
+/// model name starts with, so `"gpt-4o"` also prices `"gpt-4o-2024-08-06"`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceTable {
+    models: HashMap<String, ModelPricing>,
+}
+
+impl PriceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.insert(model, pricing);
+        self
+    }
+
+    pub fn insert(&mut self, model: impl Into<String>, pricing: ModelPricing) {
+        self.models.insert(model.into(), pricing);
+    }
+
+    pub fn get(&self, model: &str) -> Option<&ModelPricing> {
+        self.models.get(model).or_else(|| {
+            self.models
+                .iter()
+                .filter(|(name, _)| model.starts_with(name.as_str()))
+                .max_by_key(|(name, _)| name.len())
+                .map(|(_, pricing)| pricing)
+        })
+    }
+
+    /// Cost of `usage` on `model`, or `None` if the model has no price.
+    pub fn cost(&self, model: &str, usage: &TokenUsage) -> Option<f64> {
+        self.get(model).map(|pricing| pricing.cost(usage))
+    }
+}
+
+/// Token usage together with its cost.
+///
+/// `cost` is `None` when none of the counted tokens came from a priced model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub tokens: TokenUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, tokens: TokenUsage, cost: Option<f64>) {
+        self.tokens += tokens;
+        if let Some(cost) = cost {
+            *self.cost.get_or_insert(0.0) += cost;
+        }
+    }
+}
+
+/// Usage accumulated by the runner, attached to final response events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// Usage of the current invocation so far.
+    pub invocation: UsageTotals,
+    /// Invocation usage broken down by the agent that called the model.
+    #[serde(default)]
+    pub agents: BTreeMap<String, UsageTotals>,
+    /// Invocation usage broken down by model.
+    #[serde(default)]
+    pub models: BTreeMap<String, UsageTotals>,
+    /// Usage of the whole session, including earlier invocations.
+    pub session: UsageTotals,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_usage_from_metadata() {
+        let usage = TokenUsage::from(&UsageMetadata {
+            prompt_token_count: 100,
+            candidates_token_count: 40,
+            total_token_count: 140,
+            cached_content_token_count: Some(60),
+            thoughts_token_count: Some(25),
+        });
+        assert_eq!(usage.prompt_tokens, 100);
+        assert_eq!(usage.completion_tokens, 40);
+        assert_eq!(usage.cached_tokens, 60);
+        assert_eq!(usage.reasoning_tokens, 25);
+        assert_eq!(usage.total_tokens, 140);
+    }
+
+    #[test]
+    fn test_pricing_discounts_cached_tokens() {
+        let pricing = ModelPricing::new(2.0, 8.0).with_cached_input(0.5);
+        let usage = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 500_000,
+            cached_tokens: 400_000,
+            ..Default::default()
+        };
+        // 600k uncached * 2.0 + 400k cached * 0.5 + 500k output * 8.0
+        assert!((pricing.cost(&usage) - 5.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_price_table_prefers_longest_prefix() {
+        let prices = PriceTable::new()
+            .with_model("gpt-4o", ModelPricing::new(2.5, 10.0))
+            .with_model("gpt-4o-mini", ModelPricing::new(0.15, 0.6));
+
+        assert_eq!(prices.get("gpt-4o-mini-2024-07-18").unwrap().input_per_million, 0.15);
+        assert_eq!(prices.get("gpt-4o-2024-08-06").unwrap().input_per_million, 2.5);
+        assert!(prices.get("claude-sonnet-4").is_none());
+    }
+
+    #[test]
+    fn test_usage_totals_cost_stays_none_when_unpriced() {
+        let mut totals = UsageTotals::default();
+        totals.add(TokenUsage { prompt_tokens: 10, ..Default::default() }, None);
+        assert_eq!(totals.cost, None);
+        totals.add(TokenUsage { completion_tokens: 5, ..Default::default() }, Some(0.25));
+        assert_eq!(totals.cost, Some(0.25));
+        assert_eq!(totals.tokens.prompt_tokens, 10);
+        assert_eq!(totals.tokens.completion_tokens, 5);
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! Provides a builder pattern similar to LlmAgent and RealtimeAgent.
 
-use crate::checkpoint::Checkpointer;
-use crate::edge::{END, Edge, EdgeTarget, START};
+use crate::checkpoint::{Checkpointer, RetentionPolicy};
+use crate::edge::{END, Edge, EdgeTarget, START, SendTo};
 use crate::error::{GraphError, Result};
 use crate::graph::{CompiledGraph, StateGraph};
 use crate::node::{ExecutionConfig, FunctionNode, Node, NodeContext, NodeOutput};
+use crate::policy::NodePolicy;
 use crate::state::{State, StateSchema};
 use crate::stream::{StreamEvent, StreamMode};
 use adk_core::{Agent, Content, Event, EventStream, InvocationContext};
@@ -78,6 +79,11 @@ impl GraphAgent {
         &self.graph
     }
 
+    /// Get a shared handle to the underlying compiled graph
+    pub fn graph_arc(&self) -> Arc<CompiledGraph> {
+        self.graph.clone()
+    }
+
     /// Execute the graph directly (bypassing Agent trait)
     pub async fn invoke(&self, input: State, config: ExecutionConfig) -> Result<State> {
         self.graph.invoke(input, config).await
@@ -117,8 +123,8 @@ impl Agent for GraphAgent {
         // Map context to input state
         let input = (self.input_mapper)(ctx.as_ref());
 
-        // Create execution config from context
-        let config = ExecutionConfig::new(ctx.session_id());
+        // Create execution config from context; agent nodes run within this invocation
+        let config = ExecutionConfig::new(ctx.session_id()).with_invocation_context(ctx.clone());
 
         // Execute graph
         let graph = self.graph.clone();
@@ -220,7 +226,9 @@ pub struct GraphAgentBuilder {
     schema: StateSchema,
     nodes: Vec<Arc<dyn Node>>,
     edges: Vec<Edge>,
+    policies: HashMap<String, NodePolicy>,
     checkpointer: Option<Arc<dyn Checkpointer>>,
+    retention: Option<RetentionPolicy>,
     interrupt_before: Vec<String>,
     interrupt_after: Vec<String>,
     recursion_limit: usize,
@@ -239,7 +247,9 @@ impl GraphAgentBuilder {
             schema: StateSchema::simple(&["input", "output", "messages"]),
             nodes: vec![],
             edges: vec![],
+            policies: HashMap::new(),
             checkpointer: None,
+            retention: None,
             interrupt_before: vec![],
             interrupt_after: vec![],
             recursion_limit: 50,
@@ -338,6 +348,25 @@ impl GraphAgentBuilder {
         self
     }
 
+    /// Add a fan-out edge whose router returns the node invocations to run next
+    pub fn fan_out_edge<F>(mut self, source: &str, router: F, targets: &[&str]) -> Self
+    where
+        F: Fn(&State) -> Vec<SendTo> + Send + Sync + 'static,
+    {
+        self.edges.push(Edge::FanOut {
+            source: source.to_string(),
+            router: Arc::new(router),
+            targets: targets.iter().map(|s| s.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Set a node's retry, timeout and fallback policy
+    pub fn node_policy(mut self, node: &str, policy: NodePolicy) -> Self {
+        self.policies.insert(node.to_string(), policy);
+        self
+    }
+
     /// Set checkpointer
     pub fn checkpointer<C: Checkpointer + 'static>(mut self, checkpointer: C) -> Self {
         self.checkpointer = Some(Arc::new(checkpointer));
@@ -350,6 +379,12 @@ impl GraphAgentBuilder {
         self
     }
 
+    /// Set the retention policy applied to checkpoints
+    pub fn retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = Some(policy);
+        self
+    }
+
     /// Set nodes to interrupt before
     pub fn interrupt_before(mut self, nodes: &[&str]) -> Self {
         self.interrupt_before = nodes.iter().map(|s| s.to_string()).collect();
@@ -423,6 +458,7 @@ impl GraphAgentBuilder {
 
         // Add edges
         graph.edges = self.edges;
+        graph.policies = self.policies;
 
         // Compile
         let mut compiled = graph.compile()?;
@@ -431,6 +467,7 @@ impl GraphAgentBuilder {
         if let Some(cp) = self.checkpointer {
             compiled.checkpointer = Some(cp);
         }
+        compiled.retention = self.retention;
         compiled.interrupt_before = self.interrupt_before.into_iter().collect();
         compiled.interrupt_after = self.interrupt_after.into_iter().collect();
         compiled.recursion_limit = self.recursion_limit;
@@ -0,0 +1,349 @@
+//! Checkpointing for persistent graph state
+//!
+//! The executor saves a checkpoint before the first super-step and after every one,
+//! recording the state and what runs next. Outputs of tasks that finish inside a
+//! super-step are stored as [`PendingWrite`]s, so a run that fails part-way resumes
+//! without repeating them. A [`RetentionPolicy`] bounds how many checkpoints a thread
+//! keeps.
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresCheckpointer;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteCheckpointer;
+
+use crate::error::{GraphError, Result};
+use crate::state::{Checkpoint, PendingWrite};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Checkpointer trait for persistence
+#[async_trait]
+pub trait Checkpointer: Send + Sync {
+    /// Save a checkpoint
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<String>;
+
+    /// Load the latest checkpoint for a thread
+    async fn load(&self, thread_id: &str) -> Result<Option<Checkpoint>>;
+
+    /// Load a specific checkpoint by ID
+    async fn load_by_id(&self, checkpoint_id: &str) -> Result<Option<Checkpoint>>;
+
+    /// List all checkpoints for a thread (for time travel)
+    async fn list(&self, thread_id: &str) -> Result<Vec<Checkpoint>>;
+
+    /// Delete checkpoints for a thread
+    async fn delete(&self, thread_id: &str) -> Result<()>;
+
+    /// Store outputs of tasks completed in the super-step that started at
+    /// `checkpoint_id`, replacing earlier writes of the same tasks
+    ///
+    /// Without pending-write storage a failed super-step runs all its tasks again.
+    async fn put_writes(&self, _checkpoint_id: &str, _writes: &[PendingWrite]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Load the writes stored for a checkpoint
+    async fn get_writes(&self, _checkpoint_id: &str) -> Result<Vec<PendingWrite>> {
+        Ok(Vec::new())
+    }
+
+    /// Delete checkpoints by ID, with their pending writes
+    async fn delete_checkpoints(&self, _checkpoint_ids: &[String]) -> Result<()> {
+        Err(GraphError::CheckpointError(
+            "this checkpointer cannot delete individual checkpoints".to_string(),
+        ))
+    }
+
+    /// Delete the thread's checkpoints that `policy` does not keep, returning how many
+    /// were deleted
+    async fn prune(&self, thread_id: &str, policy: &RetentionPolicy) -> Result<usize> {
+        let checkpoints = self.list(thread_id).await?;
+        let expired = policy.expired(&checkpoints, Utc::now());
+        if !expired.is_empty() {
+            self.delete_checkpoints(&expired).await?;
+        }
+        Ok(expired.len())
+    }
+
+    /// Delete checkpoints created before `cutoff` on every thread, returning how many
+    /// were deleted
+    async fn delete_before(&self, _cutoff: DateTime<Utc>) -> Result<usize> {
+        Err(GraphError::CheckpointError(
+            "this checkpointer cannot delete checkpoints by age".to_string(),
+        ))
+    }
+}
+
+/// Which checkpoints of a thread to keep
+///
+/// Applied after every checkpoint the executor saves (see
+/// [`CompiledGraph::with_retention`](crate::graph::CompiledGraph::with_retention)). The
+/// latest checkpoint is kept unless it is past the TTL, so interrupted and failed runs
+/// can resume. Threads that are no longer run are not pruned; call
+/// [`Checkpointer::delete_before`] periodically to expire them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Keep at most this many checkpoints per thread (at least one)
+    pub keep_last: Option<usize>,
+    /// Keep older checkpoints only if an interrupt paused the run there
+    pub interrupts_only: bool,
+    /// Delete checkpoints older than this
+    pub ttl: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    /// Keep the `n` newest checkpoints of each thread
+    pub fn keep_last(n: usize) -> Self {
+        Self::default().with_keep_last(n)
+    }
+
+    /// Keep the latest checkpoint and those saved at interrupts
+    pub fn interrupts_only() -> Self {
+        Self::default().with_interrupts_only()
+    }
+
+    /// Keep checkpoints younger than `ttl`
+    pub fn ttl(ttl: Duration) -> Self {
+        Self::default().with_ttl(ttl)
+    }
+
+    /// Also keep at most `n` checkpoints
+    pub fn with_keep_last(mut self, n: usize) -> Self {
+        self.keep_last = Some(n);
+        self
+    }
+
+    /// Also drop older checkpoints not saved at an interrupt
+    pub fn with_interrupts_only(mut self) -> Self {
+        self.interrupts_only = true;
+        self
+    }
+
+    /// Also drop checkpoints older than `ttl`
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// IDs of the checkpoints the policy drops at `now`, given one thread's
+    /// checkpoints oldest first (as [`Checkpointer::list`] returns them)
+    pub fn expired(&self, checkpoints: &[Checkpoint], now: DateTime<Utc>) -> Vec<String> {
+        let cutoff = self
+            .ttl
+            .and_then(|ttl| chrono::Duration::from_std(ttl).ok())
+            .and_then(|ttl| now.checked_sub_signed(ttl));
+        let limit = self.keep_last.map(|n| n.max(1));
+
+        let mut kept = 0;
+        let mut expired = Vec::new();
+        for (index, checkpoint) in checkpoints.iter().rev().enumerate() {
+            let keep = cutoff.is_none_or(|cutoff| checkpoint.created_at >= cutoff)
+                && (index == 0 || !self.interrupts_only || checkpoint.interrupt().is_some())
+                && limit.is_none_or(|limit| kept < limit);
+            if keep {
+                kept += 1;
+            } else {
+                expired.push(checkpoint.checkpoint_id.clone());
+            }
+        }
+        expired
+    }
+}
+
+/// In-memory checkpointer for development and testing
+#[derive(Default)]
+pub struct MemoryCheckpointer {
+    checkpoints: Arc<RwLock<HashMap<String, Vec<Checkpoint>>>>,
+    writes: Arc<RwLock<HashMap<String, Vec<PendingWrite>>>>,
+}
+
+impl MemoryCheckpointer {
+    /// Create a new in-memory checkpointer
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Checkpointer for MemoryCheckpointer {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<String> {
+        let mut store = self.checkpoints.write().await;
+        let thread_checkpoints = store.entry(checkpoint.thread_id.clone()).or_insert_with(Vec::new);
+
+        let checkpoint_id = checkpoint.checkpoint_id.clone();
+        thread_checkpoints.push(checkpoint.clone());
+
+        Ok(checkpoint_id)
+    }
+
+    async fn load(&self, thread_id: &str) -> Result<Option<Checkpoint>> {
+        let store = self.checkpoints.read().await;
+        Ok(store.get(thread_id).and_then(|checkpoints| checkpoints.last()).cloned())
+    }
+
+    async fn load_by_id(&self, checkpoint_id: &str) -> Result<Option<Checkpoint>> {
+        let store = self.checkpoints.read().await;
+        for checkpoints in store.values() {
+            for checkpoint in checkpoints {
+                if checkpoint.checkpoint_id == checkpoint_id {
+                    return Ok(Some(checkpoint.clone()));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    async fn list(&self, thread_id: &str) -> Result<Vec<Checkpoint>> {
+        let store = self.checkpoints.read().await;
+        Ok(store.get(thread_id).cloned().unwrap_or_default())
+    }
+
+    async fn delete(&self, thread_id: &str) -> Result<()> {
+        let mut store = self.checkpoints.write().await;
+        if let Some(checkpoints) = store.remove(thread_id) {
+            let mut writes = self.writes.write().await;
+            for checkpoint in checkpoints {
+                writes.remove(&checkpoint.checkpoint_id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn put_writes(&self, checkpoint_id: &str, new_writes: &[PendingWrite]) -> Result<()> {
+        let mut writes = self.writes.write().await;
+        let stored = writes.entry(checkpoint_id.to_string()).or_default();
+        for write in new_writes {
+            stored.retain(|w| w.task_id != write.task_id);
+            stored.push(write.clone());
+        }
+        Ok(())
+    }
+
+    async fn get_writes(&self, checkpoint_id: &str) -> Result<Vec<PendingWrite>> {
+        let writes = self.writes.read().await;
+        Ok(writes.get(checkpoint_id).cloned().unwrap_or_default())
+    }
+
+    async fn delete_checkpoints(&self, checkpoint_ids: &[String]) -> Result<()> {
+        let mut store = self.checkpoints.write().await;
+        for checkpoints in store.values_mut() {
+            checkpoints.retain(|c| !checkpoint_ids.contains(&c.checkpoint_id));
+        }
+        store.retain(|_, checkpoints| !checkpoints.is_empty());
+
+        let mut writes = self.writes.write().await;
+        for id in checkpoint_ids {
+            writes.remove(id);
+        }
+        Ok(())
+    }
+
+    async fn delete_before(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let expired: Vec<String> = {
+            let store = self.checkpoints.read().await;
+            store
+                .values()
+                .flatten()
+                .filter(|c| c.created_at < cutoff)
+                .map(|c| c.checkpoint_id.clone())
+                .collect()
+        };
+        self.delete_checkpoints(&expired).await?;
+        Ok(expired.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interrupt::Interrupt;
+    use crate::state::State;
+
+    #[tokio::test]
+    async fn test_memory_checkpointer() {
+        let cp = MemoryCheckpointer::new();
+
+        // Create and save checkpoint
+        let checkpoint = Checkpoint::new("thread_1", State::new(), 0, vec!["node_a".to_string()]);
+        let id = cp.save(&checkpoint).await.unwrap();
+        assert!(!id.is_empty());
+
+        // Load latest
+        let loaded = cp.load("thread_1").await.unwrap();
+        assert!(loaded.is_some());
+        assert_eq!(loaded.unwrap().step, 0);
+
+        // Save another checkpoint
+        let checkpoint2 = Checkpoint::new("thread_1", State::new(), 1, vec!["node_b".to_string()]);
+        cp.save(&checkpoint2).await.unwrap();
+
+        // Load latest should return step 1
+        let loaded = cp.load("thread_1").await.unwrap();
+        assert_eq!(loaded.unwrap().step, 1);
+
+        // List should return both
+        let all = cp.list("thread_1").await.unwrap();
+        assert_eq!(all.len(), 2);
+
+        // Delete
+        cp.delete("thread_1").await.unwrap();
+        let loaded = cp.load("thread_1").await.unwrap();
+        assert!(loaded.is_none());
+    }
+
+    /// Checkpoints for steps 0..n, a minute apart and oldest first, ending a minute ago
+    fn history(n: usize, interrupted: &[usize]) -> Vec<Checkpoint> {
+        let now = Utc::now();
+        (0..n)
+            .map(|step| {
+                let mut checkpoint = Checkpoint::new("t", State::new(), step, vec![]);
+                checkpoint.checkpoint_id = step.to_string();
+                checkpoint.created_at = now - chrono::Duration::minutes((n - step) as i64);
+                if interrupted.contains(&step) {
+                    checkpoint = checkpoint.with_interrupt(&Interrupt::Before("review".into()));
+                }
+                checkpoint
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_retention_keep_last() {
+        let expired = RetentionPolicy::keep_last(2).expired(&history(5, &[]), Utc::now());
+        assert_eq!(expired, ["2", "1", "0"]);
+
+        // The latest checkpoint survives a limit of zero
+        let expired = RetentionPolicy::keep_last(0).expired(&history(2, &[]), Utc::now());
+        assert_eq!(expired, ["0"]);
+    }
+
+    #[test]
+    fn test_retention_interrupts_only() {
+        let policy = RetentionPolicy::interrupts_only();
+        assert_eq!(policy.expired(&history(5, &[1, 4]), Utc::now()), ["3", "2", "0"]);
+
+        let policy = policy.with_keep_last(2);
+        assert_eq!(policy.expired(&history(5, &[0, 1, 2]), Utc::now()), ["3", "1", "0"]);
+    }
+
+    #[test]
+    fn test_retention_ttl() {
+        let policy = RetentionPolicy::ttl(Duration::from_secs(150));
+        assert_eq!(policy.expired(&history(4, &[]), Utc::now()), ["1", "0"]);
+
+        // Expired threads lose their latest checkpoint too
+        let policy = RetentionPolicy::ttl(Duration::from_secs(30));
+        assert_eq!(policy.expired(&history(2, &[]), Utc::now()), ["1", "0"]);
+
+        assert!(RetentionPolicy::default().expired(&history(3, &[0]), Utc::now()).is_empty());
+    }
+}
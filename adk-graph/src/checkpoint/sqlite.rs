@@ -0,0 +1,272 @@
+use super::Checkpointer;
+use crate::error::{GraphError, Result};
+use crate::state::{Checkpoint, PendingWrite};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::QueryBuilder;
+
+type CheckpointRow = (String, String, String, i64, String, String, String, String);
+
+const CHECKPOINT_COLUMNS: &str =
+    "id, thread_id, state, step, pending_nodes, pending_sends, metadata, created_at";
+
+fn db_error(e: sqlx::Error) -> GraphError {
+    GraphError::CheckpointError(e.to_string())
+}
+
+fn checkpoint_from_row(row: CheckpointRow) -> Result<Checkpoint> {
+    let (id, thread_id, state, step, pending_nodes, pending_sends, metadata, created_at) = row;
+    Ok(Checkpoint {
+        checkpoint_id: id,
+        thread_id,
+        state: serde_json::from_str(&state)?,
+        step: step as usize,
+        pending_nodes: serde_json::from_str(&pending_nodes)?,
+        pending_sends: serde_json::from_str(&pending_sends)?,
+        metadata: serde_json::from_str(&metadata)?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)
+            .map_err(|e| GraphError::CheckpointError(e.to_string()))?
+            .with_timezone(&Utc),
+    })
+}
+
+/// SQLite checkpointer for production use
+pub struct SqliteCheckpointer {
+    pool: sqlx::SqlitePool,
+}
+
+impl SqliteCheckpointer {
+    /// Create a new SQLite checkpointer
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = sqlx::SqlitePool::connect(database_url).await.map_err(db_error)?;
+
+        // Create table
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS graph_checkpoints (
+                id TEXT PRIMARY KEY,
+                thread_id TEXT NOT NULL,
+                state TEXT NOT NULL,
+                step INTEGER NOT NULL,
+                pending_nodes TEXT NOT NULL,
+                metadata TEXT,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_graph_checkpoints_thread
+            ON graph_checkpoints(thread_id, created_at DESC)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+        // Added with fan-out edges; databases created earlier lack it
+        let has_pending_sends: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('graph_checkpoints') WHERE name = 'pending_sends'",
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(db_error)?;
+        if !has_pending_sends {
+            sqlx::query(
+                "ALTER TABLE graph_checkpoints ADD COLUMN pending_sends TEXT NOT NULL DEFAULT '[]'",
+            )
+            .execute(&pool)
+            .await
+            .map_err(db_error)?;
+        }
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS graph_checkpoint_writes (
+                checkpoint_id TEXT NOT NULL,
+                task_id TEXT NOT NULL,
+                node TEXT NOT NULL,
+                updates TEXT NOT NULL,
+                PRIMARY KEY (checkpoint_id, task_id)
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(Self { pool })
+    }
+
+    /// Create an in-memory SQLite checkpointer (for testing)
+    pub async fn in_memory() -> Result<Self> {
+        Self::new(":memory:").await
+    }
+}
+
+#[async_trait]
+impl Checkpointer for SqliteCheckpointer {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<String> {
+        let state_json = serde_json::to_string(&checkpoint.state)?;
+        let pending_json = serde_json::to_string(&checkpoint.pending_nodes)?;
+        let sends_json = serde_json::to_string(&checkpoint.pending_sends)?;
+        let metadata_json = serde_json::to_string(&checkpoint.metadata)?;
+        let created_at = checkpoint.created_at.to_rfc3339();
+
+        sqlx::query(&format!(
+            "INSERT INTO graph_checkpoints ({CHECKPOINT_COLUMNS}) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+        ))
+        .bind(&checkpoint.checkpoint_id)
+        .bind(&checkpoint.thread_id)
+        .bind(&state_json)
+        .bind(checkpoint.step as i64)
+        .bind(&pending_json)
+        .bind(&sends_json)
+        .bind(&metadata_json)
+        .bind(&created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(checkpoint.checkpoint_id.clone())
+    }
+
+    async fn load(&self, thread_id: &str) -> Result<Option<Checkpoint>> {
+        let row: Option<CheckpointRow> = sqlx::query_as(&format!(
+            "SELECT {CHECKPOINT_COLUMNS} FROM graph_checkpoints
+             WHERE thread_id = ? ORDER BY rowid DESC LIMIT 1"
+        ))
+        .bind(thread_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(checkpoint_from_row).transpose()
+    }
+
+    async fn load_by_id(&self, checkpoint_id: &str) -> Result<Option<Checkpoint>> {
+        let row: Option<CheckpointRow> = sqlx::query_as(&format!(
+            "SELECT {CHECKPOINT_COLUMNS} FROM graph_checkpoints WHERE id = ?"
+        ))
+        .bind(checkpoint_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(checkpoint_from_row).transpose()
+    }
+
+    async fn list(&self, thread_id: &str) -> Result<Vec<Checkpoint>> {
+        let rows: Vec<CheckpointRow> = sqlx::query_as(&format!(
+            "SELECT {CHECKPOINT_COLUMNS} FROM graph_checkpoints
+             WHERE thread_id = ? ORDER BY rowid ASC"
+        ))
+        .bind(thread_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(checkpoint_from_row).collect()
+    }
+
+    async fn delete(&self, thread_id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        sqlx::query(
+            "DELETE FROM graph_checkpoint_writes WHERE checkpoint_id IN
+             (SELECT id FROM graph_checkpoints WHERE thread_id = ?)",
+        )
+        .bind(thread_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        sqlx::query("DELETE FROM graph_checkpoints WHERE thread_id = ?")
+            .bind(thread_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn put_writes(&self, checkpoint_id: &str, writes: &[PendingWrite]) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for write in writes {
+            sqlx::query(
+                "INSERT OR REPLACE INTO graph_checkpoint_writes (checkpoint_id, task_id, node, updates)
+                 VALUES (?, ?, ?, ?)",
+            )
+            .bind(checkpoint_id)
+            .bind(&write.task_id)
+            .bind(&write.node)
+            .bind(serde_json::to_string(&write.updates)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn get_writes(&self, checkpoint_id: &str) -> Result<Vec<PendingWrite>> {
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT task_id, node, updates FROM graph_checkpoint_writes
+             WHERE checkpoint_id = ? ORDER BY rowid ASC",
+        )
+        .bind(checkpoint_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter()
+            .map(|(task_id, node, updates)| {
+                Ok(PendingWrite { task_id, node, updates: serde_json::from_str(&updates)? })
+            })
+            .collect()
+    }
+
+    async fn delete_checkpoints(&self, checkpoint_ids: &[String]) -> Result<()> {
+        if checkpoint_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        // Stay well below SQLite's limit on bound parameters
+        for chunk in checkpoint_ids.chunks(500) {
+            for table in
+                ["graph_checkpoint_writes WHERE checkpoint_id", "graph_checkpoints WHERE id"]
+            {
+                let mut query = QueryBuilder::new(format!("DELETE FROM {table} IN ("));
+                let mut ids = query.separated(", ");
+                for id in chunk {
+                    ids.push_bind(id);
+                }
+                query.push(")");
+                query.build().execute(&mut *tx).await.map_err(db_error)?;
+            }
+        }
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn delete_before(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, created_at FROM graph_checkpoints")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(db_error)?;
+
+        // Timestamps are RFC 3339 text, so compare them parsed
+        let mut expired = Vec::new();
+        for (id, created_at) in rows {
+            let created_at = DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|e| GraphError::CheckpointError(e.to_string()))?;
+            if created_at < cutoff {
+                expired.push(id);
+            }
+        }
+
+        self.delete_checkpoints(&expired).await?;
+        Ok(expired.len())
+    }
+}
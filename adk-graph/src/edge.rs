@@ -3,6 +3,7 @@
 //! Edges define how execution flows between nodes.
 
 use crate::state::State;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -43,6 +44,34 @@ impl From<&str> for EdgeTarget {
 /// Router function type
 pub type RouterFn = Arc<dyn Fn(&State) -> String + Send + Sync>;
 
+/// Fan-out router function type
+pub type FanOutFn = Arc<dyn Fn(&State) -> Vec<SendTo> + Send + Sync>;
+
+/// One invocation of a node requested by a fan-out edge
+///
+/// The node sees the graph state with `input` laid over it, so each invocation can
+/// work on its own item. What it returns is merged through the channel reducers like
+/// any other node output.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SendTo {
+    /// Node to run
+    pub node: String,
+    /// Values that replace the graph state's for this invocation only
+    pub input: State,
+}
+
+impl SendTo {
+    /// Create a send to `node` with the given input
+    pub fn new(node: &str, input: State) -> Self {
+        Self { node: node.to_string(), input }
+    }
+
+    /// Create a send to `node` whose input is a single value
+    pub fn with_value(node: &str, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        Self::new(node, State::from([(key.to_string(), value.into())]))
+    }
+}
+
 /// Edge type
 #[derive(Clone)]
 pub enum Edge {
@@ -60,6 +89,16 @@ pub enum Edge {
 
     /// Entry edge: from START to first node(s)
     Entry { targets: Vec<String> },
+
+    /// Fan-out edge: run any number of node invocations in parallel, each with its own
+    /// input
+    FanOut {
+        source: String,
+        /// Router function returns the invocations to run; none ends this path
+        router: FanOutFn,
+        /// Nodes the router may send to (for validation and documentation)
+        targets: Vec<String>,
+    },
 }
 
 impl std::fmt::Debug for Edge {
@@ -74,6 +113,9 @@ impl std::fmt::Debug for Edge {
                 .field("targets", targets)
                 .finish(),
             Self::Entry { targets } => f.debug_struct("Entry").field("targets", targets).finish(),
+            Self::FanOut { source, targets, .. } => {
+                f.debug_struct("FanOut").field("source", source).field("targets", targets).finish()
+            }
         }
     }
 }
@@ -278,6 +320,13 @@ mod tests {
         assert_eq!(router(&state), "done");
     }
 
+    #[test]
+    fn test_send_to_with_value() {
+        let send = SendTo::with_value("summarize", "document", "doc-1");
+        assert_eq!(send.node, "summarize");
+        assert_eq!(send.input.get("document"), Some(&json!("doc-1")));
+    }
+
     #[test]
     fn test_edge_target_from_str() {
         assert_eq!(EdgeTarget::from("node_a"), EdgeTarget::Node("node_a".to_string()));
@@ -29,13 +29,27 @@ pub enum GraphError {
     #[error("Recursion limit exceeded: {0} steps")]
     RecursionLimitExceeded(usize),
 
+    /// Execution was cancelled through its cancellation token before this step
+    #[error("Execution cancelled at step {0}")]
+    Cancelled(usize),
+
     /// Execution was interrupted
     #[error("Execution interrupted: {0:?}")]
     Interrupted(Box<InterruptedExecution>),
 
     /// Node execution failed
     #[error("Node '{node}' execution failed: {message}")]
-    NodeExecutionFailed { node: String, message: String },
+    NodeExecutionFailed {
+        node: String,
+        message: String,
+        /// The underlying error, when the failure has one
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// A node attempt ran longer than its policy's timeout
+    #[error("Node '{node}' timed out after {timeout:?}")]
+    NodeTimeout { node: String, timeout: std::time::Duration },
 
     /// State serialization error
     #[error("State serialization error: {0}")]
@@ -45,6 +59,10 @@ pub enum GraphError {
     #[error("Checkpoint error: {0}")]
     CheckpointError(String),
 
+    /// No checkpoint with this ID, or none for this thread
+    #[error("Checkpoint not found: {0}")]
+    CheckpointNotFound(String),
+
     /// Router returned unknown target
     #[error("Router returned unknown target: {0}")]
     UnknownRouteTarget(String),
@@ -61,12 +79,31 @@ pub enum GraphError {
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
 
-    /// Database error (when sqlite feature enabled)
-    #[cfg(feature = "sqlite")]
+    /// Database error (when the sqlite or postgres feature is enabled)
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
 }
 
+impl GraphError {
+    /// Node execution failed with a message
+    pub fn node_failed(node: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::NodeExecutionFailed { node: node.into(), message: message.into(), source: None }
+    }
+
+    /// Node execution failed because of `source`, which is kept as the error's cause
+    pub fn node_failed_with_source(
+        node: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        Self::NodeExecutionFailed {
+            node: node.into(),
+            message: source.to_string(),
+            source: Some(Box::new(source)),
+        }
+    }
+}
+
 /// Information about an interrupted execution
 #[derive(Debug, Clone)]
 pub struct InterruptedExecution {
@@ -2,13 +2,19 @@
 //!
 //! Executes graphs using the Pregel model with super-steps.
 
+use crate::checkpoint::Checkpointer;
+use crate::edge::SendTo;
 use crate::error::{GraphError, InterruptedExecution, Result};
 use crate::graph::CompiledGraph;
 use crate::interrupt::Interrupt;
-use crate::node::{ExecutionConfig, NodeContext};
-use crate::state::{Checkpoint, State};
+use crate::node::{ExecutionConfig, MessageSender, Node, NodeContext, NodeOutput};
+use crate::policy::Fallback;
+use crate::state::{Checkpoint, PendingWrite, State};
 use crate::stream::{StreamEvent, StreamMode};
+use futures::future::Either;
 use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Result of a super-step execution
@@ -20,6 +26,65 @@ pub struct SuperStepResult {
     pub interrupt: Option<Interrupt>,
     /// Stream events generated
     pub events: Vec<StreamEvent>,
+    /// Writes of the tasks that completed
+    pub writes: Vec<PendingWrite>,
+    /// Tasks that failed for good and route to their node's fallback
+    pub fallbacks: Vec<Fallback>,
+}
+
+/// A node invocation scheduled for a super-step
+struct Task {
+    /// The node name, or `{node}:{send index}` for sends
+    id: String,
+    node_name: String,
+    node: Arc<dyn Node>,
+    ctx: NodeContext,
+}
+
+/// How a task ended once its node's policy was applied
+enum TaskOutput {
+    Output(NodeOutput),
+    /// Failed for good and routed to the node's fallback
+    Fallback(Fallback),
+}
+
+/// Run a task under its node's policy, streaming the node's messages to `messages` and
+/// pushing retry and fallback events to `events`
+///
+/// A task that fails for good ends with its fallback when the node has one, and with
+/// the error otherwise.
+async fn run_task(
+    graph: &CompiledGraph,
+    task: &Task,
+    messages: Option<&MessageSender>,
+    events: &mut Vec<StreamEvent>,
+) -> Result<TaskOutput> {
+    let policy = graph.policies.get(&task.node_name).cloned().unwrap_or_default();
+    let (output, attempts) =
+        policy.execute(&task.node_name, task.node.as_ref(), &task.ctx, messages, events).await;
+    let error = match output {
+        Ok(output) => return Ok(TaskOutput::Output(output)),
+        Err(error) => error,
+    };
+
+    match Fallback::new(&task.node_name, &policy, &error, attempts) {
+        Some(fallback) => {
+            tracing::warn!(
+                node = %task.node_name,
+                fallback = %fallback.target,
+                %error,
+                "node failed, running fallback"
+            );
+            events.push(StreamEvent::node_fallback(
+                &task.node_name,
+                task.ctx.step,
+                &fallback.target,
+                &error.to_string(),
+            ));
+            Ok(TaskOutput::Fallback(fallback))
+        }
+        None => Err(error),
+    }
 }
 
 /// Pregel-based executor for graphs
@@ -29,56 +94,62 @@ pub struct PregelExecutor<'a> {
     state: State,
     step: usize,
     pending_nodes: Vec<String>,
+    pending_sends: Vec<SendTo>,
+    /// Checkpoint the current super-step started from
+    checkpoint_id: Option<String>,
+    /// Writes an earlier attempt at the current super-step stored, by task ID
+    recorded_writes: HashMap<String, PendingWrite>,
+    /// Resumed from an `interrupt_before` pause, so the next step must not pause again
+    resuming_before: bool,
 }
 
 impl<'a> PregelExecutor<'a> {
     /// Create a new executor
     pub fn new(graph: &'a CompiledGraph, config: ExecutionConfig) -> Self {
-        Self { graph, config, state: State::new(), step: 0, pending_nodes: vec![] }
+        Self {
+            graph,
+            config,
+            state: State::new(),
+            step: 0,
+            pending_nodes: vec![],
+            pending_sends: vec![],
+            checkpoint_id: None,
+            recorded_writes: HashMap::new(),
+            resuming_before: false,
+        }
     }
 
     /// Run the graph to completion
     pub async fn run(&mut self, input: State) -> Result<State> {
-        // Initialize state
-        self.state = self.initialize_state(input).await?;
-        self.pending_nodes = self.graph.get_entry_nodes();
+        self.start(input).await?;
 
         // Main execution loop
-        while !self.pending_nodes.is_empty() {
+        while self.has_pending() {
+            if self.config.is_cancelled() {
+                return Err(GraphError::Cancelled(self.step));
+            }
+
             // Check recursion limit
             if self.step >= self.config.recursion_limit {
                 return Err(GraphError::RecursionLimitExceeded(self.step));
             }
 
             // Execute super-step
-            let result = self.execute_super_step().await?;
+            let result = self.execute_cancellable_super_step().await?;
 
             // Handle interrupts
-            if let Some(interrupt) = result.interrupt {
-                let checkpoint_id = self.save_checkpoint().await?;
+            if let Some(interrupt) = &result.interrupt {
+                let checkpoint_id = self.pause(interrupt, &result).await?;
                 return Err(GraphError::Interrupted(Box::new(InterruptedExecution::new(
                     self.config.thread_id.clone(),
                     checkpoint_id,
-                    interrupt,
+                    interrupt.clone(),
                     self.state.clone(),
                     self.step,
                 ))));
             }
 
-            // Save checkpoint after each step
-            self.save_checkpoint().await?;
-
-            // Check if we're done (all paths led to END)
-            if self.graph.leads_to_end(&result.executed_nodes, &self.state) {
-                let next = self.graph.get_next_nodes(&result.executed_nodes, &self.state);
-                if next.is_empty() {
-                    break;
-                }
-            }
-
-            // Determine next nodes
-            self.pending_nodes = self.graph.get_next_nodes(&result.executed_nodes, &self.state);
-            self.step += 1;
+            self.finish_step(&result).await?;
         }
 
         Ok(self.state.clone())
@@ -91,15 +162,10 @@ impl<'a> PregelExecutor<'a> {
         mode: StreamMode,
     ) -> impl futures::Stream<Item = Result<StreamEvent>> + 'a {
         async_stream::stream! {
-            // Initialize state
-            match self.initialize_state(input).await {
-                Ok(state) => self.state = state,
-                Err(e) => {
-                    yield Err(e);
-                    return;
-                }
+            if let Err(e) = self.start(input).await {
+                yield Err(e);
+                return;
             }
-            self.pending_nodes = self.graph.get_entry_nodes();
 
             // Stream initial state if requested
             if matches!(mode, StreamMode::Values) {
@@ -107,7 +173,12 @@ impl<'a> PregelExecutor<'a> {
             }
 
             // Main execution loop
-            while !self.pending_nodes.is_empty() {
+            while self.has_pending() {
+                if self.config.is_cancelled() {
+                    yield Err(GraphError::Cancelled(self.step));
+                    return;
+                }
+
                 // Check recursion limit
                 if self.step >= self.config.recursion_limit {
                     yield Err(GraphError::RecursionLimitExceeded(self.step));
@@ -116,7 +187,8 @@ impl<'a> PregelExecutor<'a> {
 
                 // Emit node_start events BEFORE execution (in Debug mode)
                 if matches!(mode, StreamMode::Debug | StreamMode::Custom | StreamMode::Messages) {
-                    for node_name in &self.pending_nodes {
+                    let sent = self.pending_sends.iter().map(|send| &send.node);
+                    for node_name in self.pending_nodes.iter().chain(sent) {
                         yield Ok(StreamEvent::node_start(node_name, self.step));
                     }
                 }
@@ -124,43 +196,77 @@ impl<'a> PregelExecutor<'a> {
                 // For Messages mode, stream from nodes directly
                 if matches!(mode, StreamMode::Messages) {
                     let mut result = SuperStepResult::default();
-
-                    for node_name in &self.pending_nodes {
-                        if let Some(node) = self.graph.nodes.get(node_name) {
-                            let ctx = NodeContext::new(self.state.clone(), self.config.clone(), self.step);
-                            let start = std::time::Instant::now();
-
-                            let mut node_stream = node.execute_stream(&ctx);
-                            let mut collected_events = Vec::new();
-
-                            while let Some(event_result) = node_stream.next().await {
-                                match event_result {
-                                    Ok(event) => {
-                                        // Yield Message events immediately
-                                        if matches!(event, StreamEvent::Message { .. }) {
-                                            yield Ok(event.clone());
-                                        }
-                                        collected_events.push(event);
-                                    }
-                                    Err(e) => {
-                                        yield Err(e);
-                                        return;
-                                    }
+                    let tasks = match self.tasks() {
+                        Ok(tasks) => tasks,
+                        Err(e) => {
+                            yield Err(e);
+                            return;
+                        }
+                    };
+
+                    for task in tasks {
+                        let node_name = task.node_name.clone();
+                        let start = std::time::Instant::now();
+
+                        // Run the task once under its policy, yielding the node's messages
+                        // while it runs
+                        let (sender, mut messages) = tokio::sync::mpsc::unbounded_channel();
+                        let mut policy_events = Vec::new();
+                        let output = {
+                            let run = run_task(self.graph, &task, Some(&sender), &mut policy_events);
+                            tokio::pin!(run);
+                            loop {
+                                let next = tokio::select! {
+                                    output = &mut run => Either::Right(output),
+                                    Some(message) = messages.recv() => Either::Left(message),
+                                };
+                                match next {
+                                    Either::Left(message) => yield Ok(message),
+                                    Either::Right(output) => break output,
                                 }
                             }
+                        };
+                        while let Ok(message) = messages.try_recv() {
+                            yield Ok(message);
+                        }
+                        for event in policy_events {
+                            yield Ok(event);
+                        }
 
-                            let duration_ms = start.elapsed().as_millis() as u64;
-                            result.executed_nodes.push(node_name.clone());
-                            result.events.push(StreamEvent::node_end(node_name, self.step, duration_ms));
-                            result.events.extend(collected_events);
-
-                            // Get output from execute for state updates
-                            if let Ok(output) = node.execute(&ctx).await {
+                        let mut collected_events = Vec::new();
+                        match output {
+                            Ok(TaskOutput::Output(output)) => {
+                                if !result.executed_nodes.contains(&node_name) {
+                                    result.executed_nodes.push(node_name.clone());
+                                }
                                 for (key, value) in output.updates {
                                     self.graph.schema.apply_update(&mut self.state, &key, value);
                                 }
+                                // Messages of nodes that only report them in their output
+                                for event in &output.events {
+                                    if matches!(event, StreamEvent::Message { .. }) {
+                                        yield Ok(event.clone());
+                                    }
+                                }
+                                collected_events = output.events;
+                            }
+                            Ok(TaskOutput::Fallback(fallback)) => {
+                                self.graph.schema.apply_update(
+                                    &mut self.state,
+                                    &fallback.error_channel,
+                                    fallback.error.clone(),
+                                );
+                                result.fallbacks.push(fallback);
+                            }
+                            Err(e) => {
+                                yield Err(e);
+                                return;
                             }
                         }
+
+                        let duration_ms = start.elapsed().as_millis() as u64;
+                        result.events.push(StreamEvent::node_end(&node_name, self.step, duration_ms));
+                        result.events.extend(collected_events);
                     }
 
                     // Yield node_end events
@@ -170,13 +276,15 @@ impl<'a> PregelExecutor<'a> {
                         }
                     }
 
-                    self.pending_nodes = self.graph.get_next_nodes(&result.executed_nodes, &self.state);
-                    self.step += 1;
+                    if let Err(e) = self.finish_step(&result).await {
+                        yield Err(e);
+                        return;
+                    }
                     continue;
                 }
 
                 // Execute super-step (non-streaming)
-                let result = match self.execute_super_step().await {
+                let result = match self.execute_cancellable_super_step().await {
                     Ok(r) => r,
                     Err(e) => {
                         yield Err(e);
@@ -210,7 +318,11 @@ impl<'a> PregelExecutor<'a> {
                 }
 
                 // Handle interrupts
-                if let Some(interrupt) = result.interrupt {
+                if let Some(interrupt) = &result.interrupt {
+                    if let Err(e) = self.pause(interrupt, &result).await {
+                        yield Err(e);
+                        return;
+                    }
                     yield Ok(StreamEvent::interrupted(
                         result.executed_nodes.first().map(|s| s.as_str()).unwrap_or("unknown"),
                         &interrupt.to_string(),
@@ -218,126 +330,324 @@ impl<'a> PregelExecutor<'a> {
                     return;
                 }
 
-                // Check if done
-                if self.graph.leads_to_end(&result.executed_nodes, &self.state) {
-                    let next = self.graph.get_next_nodes(&result.executed_nodes, &self.state);
-                    if next.is_empty() {
-                        break;
-                    }
+                if let Err(e) = self.finish_step(&result).await {
+                    yield Err(e);
+                    return;
                 }
-
-                self.pending_nodes = self.graph.get_next_nodes(&result.executed_nodes, &self.state);
-                self.step += 1;
             }
 
             yield Ok(StreamEvent::done(self.state.clone(), self.step + 1));
         }
     }
 
-    /// Initialize state from input and/or checkpoint
-    async fn initialize_state(&self, input: State) -> Result<State> {
-        // Start with schema defaults
-        let mut state = self.graph.schema.initialize_state();
-
-        // If resuming from checkpoint, load it
-        if let Some(checkpoint_id) = &self.config.resume_from {
-            if let Some(cp) = self.graph.checkpointer.as_ref() {
-                if let Some(checkpoint) = cp.load_by_id(checkpoint_id).await? {
-                    state = checkpoint.state;
+    /// Set up state and the first super-step from input and/or checkpoint
+    ///
+    /// A checkpoint with work left, saved when the run was interrupted or failed, is
+    /// resumed from its pending super-step. Otherwise the run starts at the entry
+    /// nodes, from the checkpointed state if there is one.
+    async fn start(&mut self, input: State) -> Result<()> {
+        match self.load_checkpoint().await? {
+            Some(checkpoint) if checkpoint.has_pending() => {
+                if let Some(cp) = self.resumable_writes(&checkpoint).await? {
+                    self.recorded_writes = cp
+                        .get_writes(&checkpoint.checkpoint_id)
+                        .await?
+                        .into_iter()
+                        .map(|write| (write.task_id.clone(), write))
+                        .collect();
                 }
+                self.resuming_before = matches!(checkpoint.interrupt(), Some(Interrupt::Before(_)));
+                self.state = checkpoint.state;
+                self.step = checkpoint.step;
+                self.pending_nodes = checkpoint.pending_nodes;
+                self.pending_sends = checkpoint.pending_sends;
+                self.checkpoint_id = Some(checkpoint.checkpoint_id);
+                self.merge_input(input);
+            }
+            checkpoint => {
+                self.state = match checkpoint {
+                    Some(checkpoint) => {
+                        self.checkpoint_id = Some(checkpoint.checkpoint_id);
+                        checkpoint.state
+                    }
+                    None => self.graph.schema.initialize_state(),
+                };
+                self.merge_input(input);
+                self.pending_nodes = self.graph.get_entry_nodes();
+                self.save_checkpoint(None).await?;
             }
-        } else if let Some(cp) = self.graph.checkpointer.as_ref() {
-            // Try to load latest checkpoint for thread
-            if let Some(checkpoint) = cp.load(&self.config.thread_id).await? {
-                state = checkpoint.state;
+        }
+        Ok(())
+    }
+
+    /// Load the checkpoint to start from: `resume_from`, or the thread's latest
+    async fn load_checkpoint(&self) -> Result<Option<Checkpoint>> {
+        let Some(cp) = &self.graph.checkpointer else {
+            return Ok(None);
+        };
+        match &self.config.resume_from {
+            Some(checkpoint_id) => cp.load_by_id(checkpoint_id).await,
+            None => cp.load(&self.config.thread_id).await,
+        }
+    }
+
+    /// The checkpointer holding writes to reuse when resuming from `checkpoint`
+    ///
+    /// Only the thread's latest checkpoint qualifies: the step an earlier checkpoint
+    /// started has since completed, and resuming there replays it in full.
+    async fn resumable_writes(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> Result<Option<&Arc<dyn Checkpointer>>> {
+        let Some(cp) = &self.graph.checkpointer else {
+            return Ok(None);
+        };
+        if self.config.resume_from.is_some() {
+            let latest = cp.load(&checkpoint.thread_id).await?;
+            if latest.is_none_or(|latest| latest.checkpoint_id != checkpoint.checkpoint_id) {
+                return Ok(None);
             }
         }
+        Ok(Some(cp))
+    }
 
-        // Merge input into state
+    /// Merge input into state
+    fn merge_input(&mut self, input: State) {
         for (key, value) in input {
-            self.graph.schema.apply_update(&mut state, &key, value);
+            self.graph.schema.apply_update(&mut self.state, &key, value);
         }
+    }
 
-        Ok(state)
+    /// Whether a super-step is scheduled
+    fn has_pending(&self) -> bool {
+        !self.pending_nodes.is_empty() || !self.pending_sends.is_empty()
     }
 
-    /// Execute one super-step (plan -> execute -> update)
-    async fn execute_super_step(&mut self) -> Result<SuperStepResult> {
-        let mut result = SuperStepResult::default();
+    /// Schedule the nodes and sends that follow the executed nodes, and the fallbacks
+    /// of nodes that failed
+    ///
+    /// Nothing is scheduled once all paths led to END.
+    fn schedule_next(&mut self, result: &SuperStepResult) -> Result<()> {
+        let executed = &result.executed_nodes;
+        let mut next = self.graph.get_next_nodes(executed, &self.state);
+        for fallback in &result.fallbacks {
+            if !next.contains(&fallback.target) {
+                next.push(fallback.target.clone());
+            }
+        }
+        let sends = self.graph.get_sends(executed, &self.state)?;
+        if next.is_empty() && sends.is_empty() && self.graph.leads_to_end(executed, &self.state) {
+            self.pending_nodes.clear();
+            self.pending_sends.clear();
+            return Ok(());
+        }
 
-        // Check for interrupt_before
-        for node_name in &self.pending_nodes {
-            if self.graph.interrupt_before.contains(node_name) {
-                return Ok(SuperStepResult {
-                    interrupt: Some(Interrupt::Before(node_name.clone())),
-                    ..Default::default()
+        self.pending_nodes = next;
+        self.pending_sends = sends;
+        self.step += 1;
+        Ok(())
+    }
+
+    /// Schedule what follows a completed super-step and checkpoint it
+    async fn finish_step(&mut self, result: &SuperStepResult) -> Result<()> {
+        self.schedule_next(result)?;
+        self.save_checkpoint(None).await?;
+        Ok(())
+    }
+
+    /// Checkpoint a super-step that was interrupted, returning the checkpoint ID
+    ///
+    /// After `interrupt_after` the run resumes with the nodes that follow. Otherwise it
+    /// resumes with the interrupted super-step, reusing the writes of tasks that did
+    /// complete.
+    async fn pause(&mut self, interrupt: &Interrupt, result: &SuperStepResult) -> Result<String> {
+        if matches!(interrupt, Interrupt::After(_)) {
+            self.schedule_next(result)?;
+            return self.save_checkpoint(Some(interrupt)).await;
+        }
+
+        let checkpoint_id = self.save_checkpoint(Some(interrupt)).await?;
+        if let Some(cp) = &self.graph.checkpointer {
+            if !result.writes.is_empty() {
+                cp.put_writes(&checkpoint_id, &result.writes).await?;
+            }
+        }
+        Ok(checkpoint_id)
+    }
+
+    /// Node invocations of the current super-step: each pending node on the shared
+    /// state, then each send on the state with its input laid over it
+    fn tasks(&self) -> Result<Vec<Task>> {
+        let mut tasks = Vec::new();
+
+        for name in &self.pending_nodes {
+            if let Some(node) = self.graph.nodes.get(name) {
+                tasks.push(Task {
+                    id: name.clone(),
+                    node_name: name.clone(),
+                    node: node.clone(),
+                    ctx: NodeContext::new(self.state.clone(), self.config.clone(), self.step),
                 });
             }
         }
 
-        // Execute all pending nodes in parallel
-        let nodes: Vec<_> = self
-            .pending_nodes
-            .iter()
-            .filter_map(|name| self.graph.nodes.get(name).map(|n| (name.clone(), n.clone())))
-            .collect();
+        for (index, send) in self.pending_sends.iter().enumerate() {
+            let node = self
+                .graph
+                .nodes
+                .get(&send.node)
+                .ok_or_else(|| GraphError::NodeNotFound(send.node.clone()))?;
+            let mut state = self.state.clone();
+            state.extend(send.input.clone());
+            let mut ctx = NodeContext::new(state, self.config.clone(), self.step);
+            ctx.send_index = Some(index);
+            tasks.push(Task {
+                id: format!("{}:{index}", send.node),
+                node_name: send.node.clone(),
+                node: node.clone(),
+                ctx,
+            });
+        }
+
+        Ok(tasks)
+    }
 
-        let futures: Vec<_> = nodes
+    /// Execute one super-step, abandoning it if the run is cancelled first. Its
+    /// updates are not applied, so a resumed run repeats it.
+    async fn execute_cancellable_super_step(&mut self) -> Result<SuperStepResult> {
+        let Some(token) = self.config.cancellation_token.clone() else {
+            return self.execute_super_step().await;
+        };
+        let step = self.step;
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(GraphError::Cancelled(step)),
+            result = self.execute_super_step() => result,
+        }
+    }
+
+    /// Execute one super-step (plan -> execute -> update)
+    async fn execute_super_step(&mut self) -> Result<SuperStepResult> {
+        let mut result = SuperStepResult::default();
+        let tasks = self.tasks()?;
+
+        // Check for interrupt_before, unless resuming from that interrupt
+        if !std::mem::take(&mut self.resuming_before) {
+            for task in &tasks {
+                if self.graph.interrupt_before.contains(&task.node_name) {
+                    return Ok(SuperStepResult {
+                        interrupt: Some(Interrupt::Before(task.node_name.clone())),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        // Execute all tasks in parallel
+        let mut recorded = std::mem::take(&mut self.recorded_writes);
+        let concurrency = tasks.len().max(1);
+        let graph = self.graph;
+        let futures: Vec<_> = tasks
             .into_iter()
-            .map(|(name, node)| {
-                let ctx = NodeContext::new(self.state.clone(), self.config.clone(), self.step);
-                let step = self.step;
+            .map(|task| {
+                let recorded = recorded.remove(&task.id);
+                let checkpointer = graph.checkpointer.clone();
+                let checkpoint_id = self.checkpoint_id.clone();
                 async move {
+                    let mut events = Vec::new();
+
+                    // Completed by an earlier attempt at this super-step
+                    if let Some(write) = recorded {
+                        let output = NodeOutput { updates: write.updates, ..Default::default() };
+                        return (
+                            task.id,
+                            task.node_name,
+                            Ok(TaskOutput::Output(output)),
+                            events,
+                            0,
+                        );
+                    }
+
                     let start = Instant::now();
-                    let output = node.execute(&ctx).await;
+                    let mut output = run_task(graph, &task, None, &mut events).await;
                     let duration_ms = start.elapsed().as_millis() as u64;
-                    (name, output, duration_ms, step)
+
+                    // Store the write right away, so it survives a failure elsewhere in the
+                    // step. A fallback is not stored: the node is tried again on resume.
+                    match (&output, checkpointer, checkpoint_id) {
+                        (Ok(TaskOutput::Output(out)), Some(cp), Some(checkpoint_id))
+                            if out.interrupt.is_none() =>
+                        {
+                            let write = PendingWrite {
+                                task_id: task.id.clone(),
+                                node: task.node_name.clone(),
+                                updates: out.updates.clone(),
+                            };
+                            if let Err(e) = cp.put_writes(&checkpoint_id, &[write]).await {
+                                output = Err(e);
+                            }
+                        }
+                        _ => {}
+                    }
+                    (task.id, task.node_name, output, events, duration_ms)
                 }
             })
             .collect();
 
-        let outputs: Vec<_> =
-            stream::iter(futures).buffer_unordered(self.pending_nodes.len()).collect().await;
-
-        // Collect all updates and check for errors/interrupts
-        let mut all_updates = Vec::new();
+        // Outputs come back in task order, so reducers see sends in the order the
+        // router returned them
+        let outputs: Vec<_> = stream::iter(futures).buffered(concurrency).collect().await;
 
-        for (node_name, output_result, duration_ms, step) in outputs {
-            result.executed_nodes.push(node_name.clone());
-            result.events.push(StreamEvent::node_end(&node_name, step, duration_ms));
+        // Collect all writes and check for errors/interrupts
+        for (task_id, node_name, output_result, events, duration_ms) in outputs {
+            result.events.extend(events);
+            result.events.push(StreamEvent::node_end(&node_name, self.step, duration_ms));
 
             match output_result {
-                Ok(output) => {
-                    // Check for dynamic interrupt
+                Ok(TaskOutput::Output(output)) => {
+                    if !result.executed_nodes.contains(&node_name) {
+                        result.executed_nodes.push(node_name.clone());
+                    }
+
+                    // Check for dynamic interrupt; the first one pauses the run
                     if let Some(interrupt) = output.interrupt {
-                        return Ok(SuperStepResult {
-                            interrupt: Some(interrupt),
-                            executed_nodes: result.executed_nodes,
-                            events: result.events,
-                        });
+                        result.interrupt.get_or_insert(interrupt);
+                        continue;
                     }
 
                     // Collect custom events
                     result.events.extend(output.events);
 
-                    // Collect updates
-                    all_updates.push(output.updates);
-                }
-                Err(e) => {
-                    return Err(GraphError::NodeExecutionFailed {
+                    result.writes.push(PendingWrite {
+                        task_id,
                         node: node_name,
-                        message: e.to_string(),
+                        updates: output.updates,
                     });
                 }
+                // Its successors do not run; the fallback runs instead
+                Ok(TaskOutput::Fallback(fallback)) => result.fallbacks.push(fallback),
+                Err(e) => return Err(e),
             }
         }
 
+        // An interrupted super-step is not applied; it runs again on resume
+        if result.interrupt.is_some() {
+            return Ok(result);
+        }
+
         // Apply all updates atomically using reducers
-        for updates in all_updates {
-            for (key, value) in updates {
-                self.graph.schema.apply_update(&mut self.state, &key, value);
+        for write in &result.writes {
+            for (key, value) in &write.updates {
+                self.graph.schema.apply_update(&mut self.state, key, value.clone());
             }
         }
+        for fallback in &result.fallbacks {
+            self.graph.schema.apply_update(
+                &mut self.state,
+                &fallback.error_channel,
+                fallback.error.clone(),
+            );
+        }
 
         // Check for interrupt_after
         for node_name in &result.executed_nodes {
@@ -352,18 +662,33 @@ impl<'a> PregelExecutor<'a> {
         Ok(result)
     }
 
-    /// Save a checkpoint
-    async fn save_checkpoint(&self) -> Result<String> {
-        if let Some(cp) = &self.graph.checkpointer {
-            let checkpoint = Checkpoint::new(
-                &self.config.thread_id,
-                self.state.clone(),
-                self.step,
-                self.pending_nodes.clone(),
-            );
-            return cp.save(&checkpoint).await;
+    /// Save a checkpoint of the state and the super-step that runs next, then apply
+    /// the graph's retention policy
+    async fn save_checkpoint(&mut self, interrupt: Option<&Interrupt>) -> Result<String> {
+        let Some(cp) = &self.graph.checkpointer else {
+            return Ok(String::new());
+        };
+
+        let mut checkpoint = Checkpoint::new(
+            &self.config.thread_id,
+            self.state.clone(),
+            self.step,
+            self.pending_nodes.clone(),
+        )
+        .with_pending_sends(self.pending_sends.clone());
+        if let Some(parent) = &self.checkpoint_id {
+            checkpoint = checkpoint.with_parent(parent);
         }
-        Ok(String::new())
+        if let Some(interrupt) = interrupt {
+            checkpoint = checkpoint.with_interrupt(interrupt);
+        }
+        let checkpoint_id = cp.save(&checkpoint).await?;
+
+        if let Some(policy) = &self.graph.retention {
+            cp.prune(&self.config.thread_id, policy).await?;
+        }
+        self.checkpoint_id = Some(checkpoint_id.clone());
+        Ok(checkpoint_id)
     }
 }
 
@@ -404,13 +729,25 @@ impl CompiledGraph {
     ) -> Result<()> {
         if let Some(cp) = &self.checkpointer {
             if let Some(checkpoint) = cp.load(thread_id).await? {
-                let mut state = checkpoint.state;
+                let mut state = checkpoint.state.clone();
                 for (key, value) in updates {
                     self.schema.apply_update(&mut state, &key, value);
                 }
-                let new_checkpoint =
-                    Checkpoint::new(thread_id, state, checkpoint.step, checkpoint.pending_nodes);
-                cp.save(&new_checkpoint).await?;
+
+                // Keep what runs next, the interrupt and the stored writes, so the run
+                // resumes where it paused
+                let mut updated =
+                    Checkpoint::new(thread_id, state, checkpoint.step, checkpoint.pending_nodes)
+                        .with_pending_sends(checkpoint.pending_sends);
+                updated.metadata = checkpoint.metadata;
+                let updated = updated.with_parent(&checkpoint.checkpoint_id);
+                cp.save(&updated).await?;
+
+                let writes = cp.get_writes(&checkpoint.checkpoint_id).await?;
+                if !writes.is_empty() {
+                    cp.put_writes(&updated.checkpoint_id, &writes).await?;
+                }
+                self.apply_retention(cp, thread_id).await?;
             }
         }
         Ok(())
@@ -545,4 +882,54 @@ mod tests {
             result
         );
     }
+
+    #[tokio::test]
+    async fn test_cancellation_stops_before_next_step() {
+        let token = adk_core::CancellationToken::new();
+        let node_token = token.clone();
+        let graph = StateGraph::with_channels(&["count"])
+            .add_node_fn("loop", move |ctx| {
+                let token = node_token.clone();
+                async move {
+                    let count = ctx.get("count").and_then(|v| v.as_i64()).unwrap_or(0) + 1;
+                    if count == 3 {
+                        token.cancel();
+                    }
+                    Ok(NodeOutput::new().with_update("count", json!(count)))
+                }
+            })
+            .add_edge(START, "loop")
+            .add_edge("loop", "loop")
+            .compile()
+            .unwrap();
+
+        let config = ExecutionConfig::new("test").with_cancellation_token(token);
+        let result = graph.invoke(State::new(), config).await;
+
+        assert!(matches!(result, Err(GraphError::Cancelled(3))), "got: {:?}", result);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_abandons_running_step() {
+        let graph = StateGraph::with_channels(&["value"])
+            .add_node_fn("stall", |_ctx| async {
+                futures::future::pending::<()>().await;
+                Ok(NodeOutput::new())
+            })
+            .add_edge(START, "stall")
+            .add_edge("stall", END)
+            .compile()
+            .unwrap();
+
+        let token = adk_core::CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+        let config = ExecutionConfig::new("test").with_cancellation_token(token);
+        let result = graph.invoke(State::new(), config).await;
+
+        assert!(matches!(result, Err(GraphError::Cancelled(0))), "got: {:?}", result);
+    }
 }
@@ -1,9 +1,10 @@
 //! StateGraph builder for constructing graphs
 
-use crate::checkpoint::Checkpointer;
-use crate::edge::{END, Edge, EdgeTarget, RouterFn, START};
+use crate::checkpoint::{Checkpointer, RetentionPolicy};
+use crate::edge::{END, Edge, EdgeTarget, RouterFn, START, SendTo};
 use crate::error::{GraphError, Result};
 use crate::node::{FunctionNode, Node, NodeContext, NodeOutput};
+use crate::policy::NodePolicy;
 use crate::state::{State, StateSchema};
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
@@ -17,12 +18,14 @@ pub struct StateGraph {
     pub nodes: HashMap<String, Arc<dyn Node>>,
     /// Registered edges
     pub edges: Vec<Edge>,
+    /// Retry, timeout and fallback policies by node
+    pub policies: HashMap<String, NodePolicy>,
 }
 
 impl StateGraph {
     /// Create a new graph with the given state schema
     pub fn new(schema: StateSchema) -> Self {
-        Self { schema, nodes: HashMap::new(), edges: vec![] }
+        Self { schema, nodes: HashMap::new(), edges: vec![], policies: HashMap::new() }
     }
 
     /// Create with a simple schema (just channel names, all overwrite)
@@ -116,6 +119,38 @@ impl StateGraph {
         self
     }
 
+    /// Add a fan-out edge whose router returns the node invocations to run next
+    ///
+    /// Every [`SendTo`] becomes its own invocation of the target node, all run in the
+    /// same super-step, which gives map-reduce over a list:
+    ///
+    /// ```ignore
+    /// .add_fan_out_edges(
+    ///     "split",
+    ///     |state| documents(state).map(|doc| SendTo::with_value("summarize", "document", doc)).collect(),
+    ///     &["summarize"],
+    /// )
+    /// ```
+    pub fn add_fan_out_edges<F>(mut self, source: &str, router: F, targets: &[&str]) -> Self
+    where
+        F: Fn(&State) -> Vec<SendTo> + Send + Sync + 'static,
+    {
+        self.edges.push(Edge::FanOut {
+            source: source.to_string(),
+            router: Arc::new(router),
+            targets: targets.iter().map(|s| s.to_string()).collect(),
+        });
+
+        self
+    }
+
+    /// Run `node` under `policy`: retry failed attempts, time them out, and route to a
+    /// fallback node once it has failed for good
+    pub fn with_node_policy(mut self, node: &str, policy: NodePolicy) -> Self {
+        self.policies.insert(node.to_string(), policy);
+        self
+    }
+
     /// Compile the graph for execution
     pub fn compile(self) -> Result<CompiledGraph> {
         self.validate()?;
@@ -124,7 +159,9 @@ impl StateGraph {
             schema: self.schema,
             nodes: self.nodes,
             edges: self.edges,
+            policies: self.policies,
             checkpointer: None,
+            retention: None,
             interrupt_before: HashSet::new(),
             interrupt_after: HashSet::new(),
             recursion_limit: 50,
@@ -171,6 +208,27 @@ impl StateGraph {
                         }
                     }
                 }
+                Edge::FanOut { source, targets, .. } => {
+                    if !self.nodes.contains_key(source) {
+                        return Err(GraphError::NodeNotFound(source.clone()));
+                    }
+                    for target in targets {
+                        if !self.nodes.contains_key(target) {
+                            return Err(GraphError::EdgeTargetNotFound(target.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (node, policy) in &self.policies {
+            if !self.nodes.contains_key(node) {
+                return Err(GraphError::NodeNotFound(node.clone()));
+            }
+            if let Some(fallback) = &policy.fallback {
+                if !self.nodes.contains_key(fallback) {
+                    return Err(GraphError::EdgeTargetNotFound(fallback.clone()));
+                }
             }
         }
 
@@ -183,7 +241,9 @@ pub struct CompiledGraph {
     pub(crate) schema: StateSchema,
     pub(crate) nodes: HashMap<String, Arc<dyn Node>>,
     pub(crate) edges: Vec<Edge>,
+    pub(crate) policies: HashMap<String, NodePolicy>,
     pub(crate) checkpointer: Option<Arc<dyn Checkpointer>>,
+    pub(crate) retention: Option<RetentionPolicy>,
     pub(crate) interrupt_before: HashSet<String>,
     pub(crate) interrupt_after: HashSet<String>,
     pub(crate) recursion_limit: usize,
@@ -202,6 +262,12 @@ impl CompiledGraph {
         self
     }
 
+    /// Prune each thread's checkpoints with `policy` after every checkpoint is saved
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = Some(policy);
+        self
+    }
+
     /// Configure interrupt before specific nodes
     pub fn with_interrupt_before(mut self, nodes: &[&str]) -> Self {
         self.interrupt_before = nodes.iter().map(|s| s.to_string()).collect();
@@ -259,6 +325,29 @@ impl CompiledGraph {
         next
     }
 
+    /// Get the invocations requested by fan-out edges from the executed nodes
+    ///
+    /// Fails if a router sends to a node that is not among its edge's targets.
+    pub fn get_sends(&self, executed: &[String], state: &State) -> Result<Vec<SendTo>> {
+        let mut sends = Vec::new();
+
+        for edge in &self.edges {
+            if let Edge::FanOut { source, router, targets } = edge {
+                if !executed.contains(source) {
+                    continue;
+                }
+                for send in router(state) {
+                    if !targets.contains(&send.node) {
+                        return Err(GraphError::UnknownRouteTarget(send.node));
+                    }
+                    sends.push(send);
+                }
+            }
+        }
+
+        Ok(sends)
+    }
+
     /// Check if any of the executed nodes lead to END
     pub fn leads_to_end(&self, executed: &[String], state: &State) -> bool {
         for edge in &self.edges {
@@ -279,6 +368,11 @@ impl CompiledGraph {
                         }
                     }
                 }
+                Edge::FanOut { source, router, .. }
+                    if executed.contains(source) && router(state).is_empty() =>
+                {
+                    return true;
+                }
                 _ => {}
             }
         }
@@ -354,4 +448,64 @@ mod tests {
         let next = graph.get_next_nodes(&["router".to_string()], &state);
         assert_eq!(next, vec!["path_b".to_string()]);
     }
+
+    #[test]
+    fn test_fan_out_edges() {
+        let graph = StateGraph::with_channels(&["items"])
+            .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+            .add_node_fn("work", |_ctx| async { Ok(NodeOutput::new()) })
+            .add_edge(START, "split")
+            .add_fan_out_edges(
+                "split",
+                |state| {
+                    let items = state.get("items").and_then(|v| v.as_array()).cloned();
+                    items
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|item| SendTo::with_value("work", "item", item))
+                        .collect()
+                },
+                &["work"],
+            )
+            .add_edge("work", END)
+            .compile()
+            .unwrap();
+
+        let executed = ["split".to_string()];
+        let mut state = State::new();
+        state.insert("items".to_string(), json!(["a", "b"]));
+        let sends = graph.get_sends(&executed, &state).unwrap();
+        assert_eq!(
+            sends,
+            vec![SendTo::with_value("work", "item", "a"), SendTo::with_value("work", "item", "b")]
+        );
+        assert!(graph.get_next_nodes(&executed, &state).is_empty());
+        assert!(!graph.leads_to_end(&executed, &state));
+
+        // Nothing to send ends the path
+        state.insert("items".to_string(), json!([]));
+        assert!(graph.get_sends(&executed, &state).unwrap().is_empty());
+        assert!(graph.leads_to_end(&executed, &state));
+    }
+
+    #[test]
+    fn test_fan_out_to_unknown_node() {
+        let graph = StateGraph::with_channels(&[])
+            .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+            .add_node_fn("work", |_ctx| async { Ok(NodeOutput::new()) })
+            .add_edge(START, "split")
+            .add_fan_out_edges("split", |_| vec![SendTo::new("other", State::new())], &["work"])
+            .compile()
+            .unwrap();
+
+        let err = graph.get_sends(&["split".to_string()], &State::new()).unwrap_err();
+        assert!(matches!(err, GraphError::UnknownRouteTarget(node) if node == "other"));
+
+        let invalid = StateGraph::with_channels(&[])
+            .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+            .add_edge(START, "split")
+            .add_fan_out_edges("split", |_| Vec::new(), &["missing"])
+            .compile();
+        assert!(matches!(invalid, Err(GraphError::EdgeTargetNotFound(_))));
+    }
 }
@@ -0,0 +1,121 @@
+//! Time travel: checkpoint history, forks, edits and state diffs
+//!
+//! Any checkpoint can start a new branch. [`CompiledGraph::fork`] copies it to another
+//! thread, [`CompiledGraph::update_state_as`] edits a thread's state as if a node had
+//! written it, and invoking the thread then runs the graph from there.
+
+use crate::checkpoint::Checkpointer;
+use crate::error::{GraphError, Result};
+use crate::graph::CompiledGraph;
+use crate::node::ExecutionConfig;
+use crate::state::{Checkpoint, State, StateDiff};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+impl CompiledGraph {
+    /// Checkpoints of a thread, newest first
+    pub async fn get_state_history(&self, thread_id: &str) -> Result<Vec<Checkpoint>> {
+        let Some(cp) = &self.checkpointer else {
+            return Ok(Vec::new());
+        };
+        let mut checkpoints = cp.list(thread_id).await?;
+        checkpoints.reverse();
+        Ok(checkpoints)
+    }
+
+    /// Load a checkpoint by ID
+    pub async fn get_checkpoint(&self, checkpoint_id: &str) -> Result<Checkpoint> {
+        self.require_checkpointer()?
+            .load_by_id(checkpoint_id)
+            .await?
+            .ok_or_else(|| GraphError::CheckpointNotFound(checkpoint_id.to_string()))
+    }
+
+    /// Copy a checkpoint to `thread_id`, which continues from it when invoked
+    ///
+    /// The copy keeps the checkpoint's pending work, which runs in full on the fork:
+    /// writes stored during the original run are not reused. Forking to the
+    /// checkpoint's own thread makes it the thread's latest checkpoint again.
+    pub async fn fork(&self, checkpoint_id: &str, thread_id: &str) -> Result<Checkpoint> {
+        let cp = self.require_checkpointer()?;
+        let source = self.get_checkpoint(checkpoint_id).await?;
+
+        let mut fork = Checkpoint::new(thread_id, source.state, source.step, source.pending_nodes)
+            .with_pending_sends(source.pending_sends);
+        fork.metadata = source.metadata;
+        let fork = fork.with_parent(checkpoint_id);
+        cp.save(&fork).await?;
+        self.apply_retention(cp, thread_id).await?;
+        Ok(fork)
+    }
+
+    /// Fork `checkpoint_id` to the configured thread and run the graph from it
+    pub async fn replay(&self, checkpoint_id: &str, config: ExecutionConfig) -> Result<State> {
+        self.fork(checkpoint_id, &config.thread_id).await?;
+        let config = ExecutionConfig { resume_from: None, ..config };
+        self.invoke(State::new(), config).await
+    }
+
+    /// Apply `updates` to a thread's latest state as if `as_node` had returned them
+    ///
+    /// The updates go through the channel reducers, and the nodes that follow
+    /// `as_node` are scheduled next, replacing whatever the thread had pending.
+    pub async fn update_state_as(
+        &self,
+        thread_id: &str,
+        as_node: &str,
+        updates: impl IntoIterator<Item = (String, Value)>,
+    ) -> Result<Checkpoint> {
+        let cp = self.require_checkpointer()?;
+        if !self.nodes.contains_key(as_node) {
+            return Err(GraphError::NodeNotFound(as_node.to_string()));
+        }
+        let checkpoint = cp.load(thread_id).await?.ok_or_else(|| {
+            GraphError::CheckpointNotFound(format!("no checkpoint for thread '{thread_id}'"))
+        })?;
+
+        let mut state = checkpoint.state;
+        for (key, value) in updates {
+            self.schema.apply_update(&mut state, &key, value);
+        }
+
+        let executed = [as_node.to_string()];
+        let next = self.get_next_nodes(&executed, &state);
+        let sends = self.get_sends(&executed, &state)?;
+        let step =
+            if next.is_empty() && sends.is_empty() { checkpoint.step } else { checkpoint.step + 1 };
+
+        let updated = Checkpoint::new(thread_id, state, step, next)
+            .with_pending_sends(sends)
+            .with_parent(&checkpoint.checkpoint_id)
+            .with_metadata("as_node", json!(as_node));
+        cp.save(&updated).await?;
+        self.apply_retention(cp, thread_id).await?;
+        Ok(updated)
+    }
+
+    /// Compare the states of two checkpoints
+    pub async fn diff_checkpoints(&self, from_id: &str, to_id: &str) -> Result<StateDiff> {
+        let from = self.get_checkpoint(from_id).await?;
+        let to = self.get_checkpoint(to_id).await?;
+        Ok(StateDiff::between(&from.state, &to.state))
+    }
+
+    fn require_checkpointer(&self) -> Result<&Arc<dyn Checkpointer>> {
+        self.checkpointer
+            .as_ref()
+            .ok_or_else(|| GraphError::CheckpointError("graph has no checkpointer".to_string()))
+    }
+
+    /// Prune the thread with the graph's retention policy, if it has one
+    pub(crate) async fn apply_retention(
+        &self,
+        cp: &Arc<dyn Checkpointer>,
+        thread_id: &str,
+    ) -> Result<()> {
+        if let Some(policy) = &self.retention {
+            cp.prune(thread_id, policy).await?;
+        }
+        Ok(())
+    }
+}
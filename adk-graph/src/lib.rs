@@ -14,9 +14,12 @@
 //! - **Graph-Based Workflows**: Define agent workflows as directed graphs
 //! - **Cyclic Support**: Native support for loops and iterative reasoning
 //! - **Conditional Routing**: Dynamic edge routing based on state
+//! - **Fan-Out and Subgraphs**: Map-reduce with `SendTo`, compiled graphs as nodes
 //! - **State Management**: Typed state with reducers (overwrite, append, sum, custom)
-//! - **Checkpointing**: Persistent state after each step
+//! - **Checkpointing**: Persistent state after each step (memory, SQLite, PostgreSQL), with retention policies
+//! - **Fault Tolerance**: Per-node retries with backoff, timeouts and fallback nodes
 //! - **Human-in-the-Loop**: Interrupt before/after nodes, dynamic interrupts
+//! - **Time Travel**: Fork threads from past checkpoints, edit state as a node, diff checkpoints
 //! - **Streaming**: Multiple stream modes (values, updates, messages, debug)
 //! - **ADK Integration**: Full callback support, works with existing runners
 //!
@@ -70,40 +73,59 @@ pub mod edge;
 pub mod error;
 pub mod executor;
 pub mod graph;
+pub mod history;
 pub mod interrupt;
 pub mod node;
+pub mod policy;
 pub mod state;
 pub mod stream;
+pub mod subgraph;
 
 // Re-exports
 pub use agent::{GraphAgent, GraphAgentBuilder};
-pub use checkpoint::{Checkpointer, MemoryCheckpointer};
-pub use edge::{END, Edge, EdgeTarget, Router, START};
+pub use checkpoint::{Checkpointer, MemoryCheckpointer, RetentionPolicy};
+pub use edge::{END, Edge, EdgeTarget, Router, START, SendTo};
 pub use error::{GraphError, InterruptedExecution, Result};
 pub use executor::PregelExecutor;
 pub use graph::{CompiledGraph, StateGraph};
 pub use interrupt::{Interrupt, interrupt, interrupt_with_data};
-pub use node::{AgentNode, ExecutionConfig, FunctionNode, Node, NodeContext, NodeOutput};
-pub use state::{Channel, Checkpoint, Reducer, State, StateSchema, StateSchemaBuilder};
+pub use node::{
+    AgentNode, ExecutionConfig, FunctionNode, MessageSender, Node, NodeContext, NodeOutput,
+};
+pub use policy::{Backoff, Fallback, NodePolicy, RetryPolicy};
+pub use state::{
+    Channel, Checkpoint, PendingWrite, Reducer, State, StateDiff, StateSchema, StateSchemaBuilder,
+    ValueChange,
+};
 pub use stream::{StreamEvent, StreamMode};
+pub use subgraph::SubgraphNode;
 
+#[cfg(feature = "postgres")]
+pub use checkpoint::PostgresCheckpointer;
 #[cfg(feature = "sqlite")]
 pub use checkpoint::SqliteCheckpointer;
 
 /// Prelude module for convenient imports
 pub mod prelude {
     pub use crate::agent::{GraphAgent, GraphAgentBuilder};
-    pub use crate::checkpoint::{Checkpointer, MemoryCheckpointer};
-    pub use crate::edge::{END, Edge, EdgeTarget, Router, START};
+    pub use crate::checkpoint::{Checkpointer, MemoryCheckpointer, RetentionPolicy};
+    pub use crate::edge::{END, Edge, EdgeTarget, Router, START, SendTo};
     pub use crate::error::{GraphError, InterruptedExecution, Result};
     pub use crate::graph::{CompiledGraph, StateGraph};
     pub use crate::interrupt::{Interrupt, interrupt, interrupt_with_data};
     pub use crate::node::{
         AgentNode, ExecutionConfig, FunctionNode, Node, NodeContext, NodeOutput,
     };
-    pub use crate::state::{Channel, Checkpoint, Reducer, State, StateSchema, StateSchemaBuilder};
+    pub use crate::policy::{Backoff, NodePolicy, RetryPolicy};
+    pub use crate::state::{
+        Channel, Checkpoint, PendingWrite, Reducer, State, StateDiff, StateSchema,
+        StateSchemaBuilder, ValueChange,
+    };
     pub use crate::stream::{StreamEvent, StreamMode};
+    pub use crate::subgraph::SubgraphNode;
 
+    #[cfg(feature = "postgres")]
+    pub use crate::checkpoint::PostgresCheckpointer;
     #[cfg(feature = "sqlite")]
     pub use crate::checkpoint::SqliteCheckpointer;
 
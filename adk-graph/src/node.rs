@@ -2,7 +2,7 @@
 //!
 //! Nodes are the computational units in a graph. They receive state and return updates.
 
-use crate::error::Result;
+use crate::error::{GraphError, Result};
 use crate::interrupt::Interrupt;
 use crate::state::State;
 use crate::stream::StreamEvent;
@@ -24,6 +24,11 @@ pub struct ExecutionConfig {
     pub recursion_limit: usize,
     /// Additional configuration
     pub metadata: HashMap<String, Value>,
+    /// Invocation running the graph, whose session, artifacts, memory and run config
+    /// are shared with agents in [`AgentNode`]s
+    pub invocation_context: Option<Arc<dyn adk_core::InvocationContext>>,
+    /// Stops the run before its next super-step, abandoning the current one
+    pub cancellation_token: Option<adk_core::CancellationToken>,
 }
 
 impl ExecutionConfig {
@@ -34,6 +39,8 @@ impl ExecutionConfig {
             resume_from: None,
             recursion_limit: 50,
             metadata: HashMap::new(),
+            invocation_context: None,
+            cancellation_token: None,
         }
     }
 
@@ -54,6 +61,25 @@ impl ExecutionConfig {
         self.metadata.insert(key.to_string(), value);
         self
     }
+
+    /// Run agent nodes within the given invocation, stopping when it is cancelled
+    pub fn with_invocation_context(mut self, ctx: Arc<dyn adk_core::InvocationContext>) -> Self {
+        self.cancellation_token = Some(ctx.cancellation_token());
+        self.invocation_context = Some(ctx);
+        self
+    }
+
+    /// Stop the run when `token` is cancelled. The run fails with
+    /// [`GraphError::Cancelled`]; the last checkpoint is kept, so it can be resumed.
+    pub fn with_cancellation_token(mut self, token: adk_core::CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    /// Whether the run has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.as_ref().is_some_and(|token| token.is_cancelled())
+    }
 }
 
 impl Default for ExecutionConfig {
@@ -70,12 +96,15 @@ pub struct NodeContext {
     pub config: ExecutionConfig,
     /// Current step number
     pub step: usize,
+    /// Position among the step's fan-out sends, when a fan-out edge started this
+    /// invocation
+    pub send_index: Option<usize>,
 }
 
 impl NodeContext {
     /// Create a new node context
     pub fn new(state: State, config: ExecutionConfig, step: usize) -> Self {
-        Self { state, config, step }
+        Self { state, config, step, send_index: None }
     }
 
     /// Get a value from state
@@ -150,6 +179,20 @@ pub trait Node: Send + Sync {
     /// Execute the node and return state updates
     async fn execute(&self, ctx: &NodeContext) -> Result<NodeOutput>;
 
+    /// Execute the node, sending [`StreamEvent::Message`]s to `messages` as they are
+    /// produced
+    ///
+    /// Used by [`StreamMode::Messages`](crate::stream::StreamMode::Messages). The default
+    /// wraps execute and sends nothing; message events in its output are streamed once
+    /// the node finishes.
+    async fn execute_with_messages(
+        &self,
+        ctx: &NodeContext,
+        _messages: &MessageSender,
+    ) -> Result<NodeOutput> {
+        self.execute(ctx).await
+    }
+
     /// Stream execution events (default: wraps execute)
     fn execute_stream<'a>(
         &'a self,
@@ -172,6 +215,9 @@ pub trait Node: Send + Sync {
 /// Type alias for boxed node
 pub type BoxedNode = Box<dyn Node>;
 
+/// Channel a node streams its messages to while it runs
+pub type MessageSender = tokio::sync::mpsc::UnboundedSender<StreamEvent>;
+
 /// Type alias for async function signature
 pub type AsyncNodeFn = Box<
     dyn Fn(NodeContext) -> Pin<Box<dyn Future<Output = Result<NodeOutput>> + Send>> + Send + Sync,
@@ -201,8 +247,12 @@ impl Node for FunctionNode {
     }
 
     async fn execute(&self, ctx: &NodeContext) -> Result<NodeOutput> {
-        let ctx_owned =
-            NodeContext { state: ctx.state.clone(), config: ctx.config.clone(), step: ctx.step };
+        let ctx_owned = NodeContext {
+            state: ctx.state.clone(),
+            config: ctx.config.clone(),
+            step: ctx.step,
+            send_index: ctx.send_index,
+        };
         (self.func)(ctx_owned).await
     }
 }
@@ -238,9 +288,14 @@ pub type AgentOutputMapper =
     Box<dyn Fn(&[adk_core::Event]) -> HashMap<String, Value> + Send + Sync>;
 
 /// Wrapper to use an existing ADK Agent as a graph node
+///
+/// When the graph runs with an invocation context (see
+/// [`ExecutionConfig::with_invocation_context`], set by `GraphAgent`), the agent shares
+/// its session, artifacts, memory and run config. Keys the agent writes to state through
+/// `state_delta` become graph state updates, except `temp:` keys; the output mapper's
+/// updates take precedence. Errors from the agent fail the node.
 pub struct AgentNode {
     name: String,
-    #[allow(dead_code)]
     agent: Arc<dyn adk_core::Agent>,
     /// Map state to agent input content
     input_mapper: AgentInputMapper,
@@ -277,6 +332,65 @@ impl AgentNode {
         self.output_mapper = Box::new(mapper);
         self
     }
+
+    /// Start the agent on the node's input
+    async fn run(&self, ctx: &NodeContext) -> Result<adk_core::EventStream> {
+        let invocation_ctx = Arc::new(GraphInvocationContext::new(
+            ctx.config.thread_id.clone(),
+            (self.input_mapper)(&ctx.state),
+            self.agent.clone(),
+            ctx.config.invocation_context.clone(),
+            ctx.config.cancellation_token.clone().unwrap_or_default(),
+        ));
+        self.agent
+            .run(invocation_ctx)
+            .await
+            .map_err(|e| GraphError::node_failed_with_source(&self.name, e))
+    }
+
+    /// Run the agent to completion, sending the text of each event to `messages`
+    async fn collect(
+        &self,
+        ctx: &NodeContext,
+        messages: Option<&MessageSender>,
+    ) -> Result<NodeOutput> {
+        use futures::StreamExt;
+
+        let mut stream = self.run(ctx).await?;
+        let mut events = Vec::new();
+        while let Some(result) = stream.next().await {
+            let event = result.map_err(|e| GraphError::node_failed_with_source(&self.name, e))?;
+            if let (Some(messages), Some(content)) = (messages, event.content()) {
+                let text: String = content.parts.iter().filter_map(|p| p.text()).collect();
+                if !text.is_empty() {
+                    // The executor stops listening only once the node is done
+                    let _ = messages.send(StreamEvent::message(&self.name, &text, false));
+                }
+            }
+            events.push(event);
+        }
+
+        // Convert agent events to stream events for tracing
+        let mut output = NodeOutput::new().with_updates(self.updates(&events));
+        for event in &events {
+            if let Ok(json) = serde_json::to_value(event) {
+                output = output.with_event(StreamEvent::custom(&self.name, "agent_event", json));
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// State updates for the events the agent produced
+    fn updates(&self, events: &[adk_core::Event]) -> HashMap<String, Value> {
+        let mut updates: HashMap<String, Value> = events
+            .iter()
+            .flat_map(|event| event.actions.state_delta.clone())
+            .filter(|(key, _)| !key.starts_with(adk_core::KEY_PREFIX_TEMP))
+            .collect();
+        updates.extend((self.output_mapper)(events));
+        updates
+    }
 }
 
 /// Default input mapper - looks for "messages" or "input" in state
@@ -335,40 +449,15 @@ impl Node for AgentNode {
     }
 
     async fn execute(&self, ctx: &NodeContext) -> Result<NodeOutput> {
-        use futures::StreamExt;
-
-        // Map state to input content
-        let content = (self.input_mapper)(&ctx.state);
-
-        // Create a graph invocation context with the agent
-        let invocation_ctx = Arc::new(GraphInvocationContext::new(
-            ctx.config.thread_id.clone(),
-            content,
-            self.agent.clone(),
-        ));
-
-        // Run the agent and collect events
-        let stream = self.agent.run(invocation_ctx).await.map_err(|e| {
-            crate::error::GraphError::NodeExecutionFailed {
-                node: self.name.clone(),
-                message: e.to_string(),
-            }
-        })?;
-
-        let events: Vec<adk_core::Event> = stream.filter_map(|r| async { r.ok() }).collect().await;
-
-        // Map events to state updates
-        let updates = (self.output_mapper)(&events);
-
-        // Convert agent events to stream events for tracing
-        let mut output = NodeOutput::new().with_updates(updates);
-        for event in &events {
-            if let Ok(json) = serde_json::to_value(event) {
-                output = output.with_event(StreamEvent::custom(&self.name, "agent_event", json));
-            }
-        }
+        self.collect(ctx, None).await
+    }
 
-        Ok(output)
+    async fn execute_with_messages(
+        &self,
+        ctx: &NodeContext,
+        messages: &MessageSender,
+    ) -> Result<NodeOutput> {
+        self.collect(ctx, Some(messages)).await
     }
 
     fn execute_stream<'a>(
@@ -377,26 +466,12 @@ impl Node for AgentNode {
     ) -> Pin<Box<dyn futures::Stream<Item = Result<StreamEvent>> + Send + 'a>> {
         use futures::StreamExt;
         let name = self.name.clone();
-        let agent = self.agent.clone();
-        let input_mapper = &self.input_mapper;
-        let thread_id = ctx.config.thread_id.clone();
-        let content = (input_mapper)(&ctx.state);
 
         Box::pin(async_stream::stream! {
-            eprintln!("DEBUG: AgentNode::execute_stream called for {}", name);
-            let invocation_ctx = Arc::new(GraphInvocationContext::new(
-                thread_id,
-                content,
-                agent.clone(),
-            ));
-
-            let stream = match agent.run(invocation_ctx).await {
+            let stream = match self.run(ctx).await {
                 Ok(s) => s,
                 Err(e) => {
-                    yield Err(crate::error::GraphError::NodeExecutionFailed {
-                        node: name.clone(),
-                        message: e.to_string(),
-                    });
+                    yield Err(e);
                     return;
                 }
             };
@@ -421,10 +496,7 @@ impl Node for AgentNode {
                         all_events.push(event);
                     }
                     Err(e) => {
-                        yield Err(crate::error::GraphError::NodeExecutionFailed {
-                            node: name.clone(),
-                            message: e.to_string(),
-                        });
+                        yield Err(GraphError::node_failed_with_source(&name, e));
                         return;
                     }
                 }
@@ -440,14 +512,19 @@ impl Node for AgentNode {
     }
 }
 
-/// Full InvocationContext implementation for running agents within graph nodes
+/// InvocationContext for running agents within graph nodes
+///
+/// Delegates to the invocation running the graph when there is one. The conversation
+/// history is the node's own: just the input mapped from graph state.
 struct GraphInvocationContext {
     invocation_id: String,
     user_content: adk_core::Content,
     agent: Arc<dyn adk_core::Agent>,
-    session: Arc<GraphSession>,
+    session: GraphSession,
+    parent: Option<Arc<dyn adk_core::InvocationContext>>,
     run_config: adk_core::RunConfig,
     ended: std::sync::atomic::AtomicBool,
+    cancellation_token: adk_core::CancellationToken,
 }
 
 impl GraphInvocationContext {
@@ -455,9 +532,14 @@ impl GraphInvocationContext {
         session_id: String,
         user_content: adk_core::Content,
         agent: Arc<dyn adk_core::Agent>,
+        parent: Option<Arc<dyn adk_core::InvocationContext>>,
+        cancellation_token: adk_core::CancellationToken,
     ) -> Self {
-        let invocation_id = uuid::Uuid::new_v4().to_string();
-        let session = Arc::new(GraphSession::new(session_id));
+        let invocation_id = match &parent {
+            Some(parent) => parent.invocation_id().to_string(),
+            None => uuid::Uuid::new_v4().to_string(),
+        };
+        let session = GraphSession::new(session_id, parent.clone());
         // Add user content to history
         session.append_content(user_content.clone());
         Self {
@@ -465,8 +547,10 @@ impl GraphInvocationContext {
             user_content,
             agent,
             session,
+            parent,
             run_config: adk_core::RunConfig::default(),
             ended: std::sync::atomic::AtomicBool::new(false),
+            cancellation_token,
         }
     }
 }
@@ -482,19 +566,19 @@ impl adk_core::ReadonlyContext for GraphInvocationContext {
     }
 
     fn user_id(&self) -> &str {
-        "graph_user"
+        adk_core::Session::user_id(&self.session)
     }
 
     fn app_name(&self) -> &str {
-        "graph_app"
+        adk_core::Session::app_name(&self.session)
     }
 
     fn session_id(&self) -> &str {
-        &self.session.id
+        adk_core::Session::id(&self.session)
     }
 
     fn branch(&self) -> &str {
-        "main"
+        self.parent.as_ref().map_or("main", |parent| parent.branch())
     }
 
     fn user_content(&self) -> &adk_core::Content {
@@ -506,7 +590,7 @@ impl adk_core::ReadonlyContext for GraphInvocationContext {
 #[async_trait]
 impl adk_core::CallbackContext for GraphInvocationContext {
     fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
-        None
+        self.parent.as_ref().and_then(|parent| parent.artifacts())
     }
 }
 
@@ -518,15 +602,15 @@ impl adk_core::InvocationContext for GraphInvocationContext {
     }
 
     fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
-        None
+        self.parent.as_ref().and_then(|parent| parent.memory())
     }
 
     fn session(&self) -> &dyn adk_core::Session {
-        self.session.as_ref()
+        &self.session
     }
 
     fn run_config(&self) -> &adk_core::RunConfig {
-        &self.run_config
+        self.parent.as_ref().map_or(&self.run_config, |parent| parent.run_config())
     }
 
     fn end_invocation(&self) {
@@ -535,19 +619,26 @@ impl adk_core::InvocationContext for GraphInvocationContext {
 
     fn ended(&self) -> bool {
         self.ended.load(std::sync::atomic::Ordering::SeqCst)
+            || self.parent.as_ref().is_some_and(|parent| parent.ended())
+    }
+
+    fn cancellation_token(&self) -> adk_core::CancellationToken {
+        self.cancellation_token.clone()
     }
 }
 
-/// Minimal Session implementation for graph execution
+/// Session for graph execution, backed by the parent invocation's session when there
+/// is one
 struct GraphSession {
     id: String,
     state: GraphState,
+    parent: Option<Arc<dyn adk_core::InvocationContext>>,
     history: std::sync::RwLock<Vec<adk_core::Content>>,
 }
 
 impl GraphSession {
-    fn new(id: String) -> Self {
-        Self { id, state: GraphState::new(), history: std::sync::RwLock::new(Vec::new()) }
+    fn new(id: String, parent: Option<Arc<dyn adk_core::InvocationContext>>) -> Self {
+        Self { id, state: GraphState::new(), parent, history: std::sync::RwLock::new(Vec::new()) }
     }
 
     fn append_content(&self, content: adk_core::Content) {
@@ -559,19 +650,22 @@ impl GraphSession {
 
 impl adk_core::Session for GraphSession {
     fn id(&self) -> &str {
-        &self.id
+        self.parent.as_ref().map_or(&self.id, |parent| parent.session().id())
     }
 
     fn app_name(&self) -> &str {
-        "graph_app"
+        self.parent.as_ref().map_or("graph_app", |parent| parent.session().app_name())
     }
 
     fn user_id(&self) -> &str {
-        "graph_user"
+        self.parent.as_ref().map_or("graph_user", |parent| parent.session().user_id())
     }
 
     fn state(&self) -> &dyn adk_core::State {
-        &self.state
+        match &self.parent {
+            Some(parent) => parent.session().state(),
+            None => &self.state,
+        }
     }
 
     fn conversation_history(&self) -> Vec<adk_core::Content> {
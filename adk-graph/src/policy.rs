@@ -0,0 +1,320 @@
+//! Per-node retry, timeout and fallback policies
+//!
+//! A [`NodePolicy`] wraps every execution of a node: each attempt can be bounded by a
+//! timeout, failed attempts are retried with backoff, and a node that still fails can
+//! route to a fallback node instead of failing the run.
+//!
+//! ```rust,ignore
+//! let graph = StateGraph::with_channels(&["query", "results", "error"])
+//!     .add_node_fn("search", search)
+//!     .add_node_fn("cached_search", cached_search)
+//!     .with_node_policy(
+//!         "search",
+//!         NodePolicy::new()
+//!             .with_retry(RetryPolicy::new(3).with_backoff(Backoff::fixed(Duration::from_secs(1))))
+//!             .with_timeout(Duration::from_secs(30))
+//!             .with_fallback("cached_search"),
+//!     )
+//!     // ...
+//! ```
+
+use crate::error::{GraphError, Result};
+use crate::node::{MessageSender, Node, NodeContext, NodeOutput};
+use crate::stream::StreamEvent;
+use serde_json::{Value, json};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// State channel a failed node's error is recorded in before its fallback runs
+pub const DEFAULT_ERROR_CHANNEL: &str = "error";
+
+/// Decides whether an error is worth another attempt
+pub type RetryPredicate = Arc<dyn Fn(&GraphError) -> bool + Send + Sync>;
+
+/// Delay before each retry
+#[derive(Clone, Debug, PartialEq)]
+pub enum Backoff {
+    /// The same delay before every retry
+    Fixed(Duration),
+    /// `initial`, multiplied by `factor` after each retry, up to `max`
+    Exponential { initial: Duration, factor: f64, max: Duration },
+}
+
+impl Backoff {
+    /// The same delay before every retry
+    pub fn fixed(delay: Duration) -> Self {
+        Self::Fixed(delay)
+    }
+
+    /// Doubling delays starting at `initial`, up to `max`
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Self::Exponential { initial, factor: 2.0, max }
+    }
+
+    /// Delay before retry number `retry`, counting from 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        match self {
+            Self::Fixed(delay) => *delay,
+            Self::Exponential { initial, factor, max } => {
+                let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+                let delay = initial.as_secs_f64() * factor.powi(exponent);
+                Duration::try_from_secs_f64(delay).map_or(*max, |delay| delay.min(*max))
+            }
+        }
+    }
+}
+
+impl Default for Backoff {
+    /// 500ms, doubling up to 30s
+    fn default() -> Self {
+        Self::exponential(Duration::from_millis(500), Duration::from_secs(30))
+    }
+}
+
+/// How often and when a failing node is run again
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first
+    pub max_attempts: u32,
+    /// Delay before each retry
+    pub backoff: Backoff,
+    retry_on: Option<RetryPredicate>,
+}
+
+impl RetryPolicy {
+    /// Run the node up to `max_attempts` times, retrying every error
+    pub fn new(max_attempts: u32) -> Self {
+        Self { max_attempts: max_attempts.max(1), backoff: Backoff::default(), retry_on: None }
+    }
+
+    /// Set the delay before each retry
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Only retry errors `predicate` accepts; others fail the node right away
+    ///
+    /// Errors are attributed to the node, so they arrive as
+    /// [`GraphError::NodeExecutionFailed`] or [`GraphError::NodeTimeout`].
+    pub fn retry_on<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&GraphError) -> bool + Send + Sync + 'static,
+    {
+        self.retry_on = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Whether `error` may be retried
+    pub fn should_retry(&self, error: &GraphError) -> bool {
+        self.retry_on.as_ref().is_none_or(|predicate| predicate(error))
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("retry_on", &self.retry_on.as_ref().map(|_| "<predicate>"))
+            .finish()
+    }
+}
+
+/// Retry, timeout and fallback behaviour of one node
+#[derive(Clone, Debug)]
+pub struct NodePolicy {
+    /// Retries after a failed attempt; without one the node runs once
+    pub retry: Option<RetryPolicy>,
+    /// Limit on each attempt
+    pub timeout: Option<Duration>,
+    /// Node to run instead of the node's successors once it has failed for good
+    pub fallback: Option<String>,
+    /// State channel the error is recorded in before the fallback runs
+    pub error_channel: String,
+}
+
+impl Default for NodePolicy {
+    fn default() -> Self {
+        Self {
+            retry: None,
+            timeout: None,
+            fallback: None,
+            error_channel: DEFAULT_ERROR_CHANNEL.to_string(),
+        }
+    }
+}
+
+impl NodePolicy {
+    /// A policy that runs the node once, without a timeout or fallback
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retry failed attempts
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Fail each attempt that takes longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Route to `node` once the node has failed for good
+    ///
+    /// The error is recorded in the state as `{"node", "message", "attempts"}`, so the
+    /// fallback, or a [`Router::on_error`](crate::edge::Router::on_error) after it, can
+    /// act on it.
+    pub fn with_fallback(mut self, node: &str) -> Self {
+        self.fallback = Some(node.to_string());
+        self
+    }
+
+    /// Record the error in `channel` instead of [`DEFAULT_ERROR_CHANNEL`]
+    pub fn with_error_channel(mut self, channel: &str) -> Self {
+        self.error_channel = channel.to_string();
+        self
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.retry.as_ref().map_or(1, |retry| retry.max_attempts)
+    }
+
+    /// Run `node` under this policy
+    ///
+    /// Errors are attributed to `node_name`. With `messages`, each attempt streams the
+    /// node's messages to it. A [`StreamEvent::NodeRetry`] is pushed to `events` before
+    /// every retry. Returns the outcome and the attempts made.
+    pub(crate) async fn execute(
+        &self,
+        node_name: &str,
+        node: &dyn Node,
+        ctx: &NodeContext,
+        messages: Option<&MessageSender>,
+        events: &mut Vec<StreamEvent>,
+    ) -> (Result<NodeOutput>, u32) {
+        let max_attempts = self.max_attempts();
+        let mut attempt = 1;
+        loop {
+            let error = match self.attempt(node_name, node, ctx, messages).await {
+                Ok(output) => return (Ok(output), attempt),
+                Err(error) => error,
+            };
+
+            let retry = match &self.retry {
+                Some(retry) if attempt < max_attempts && retry.should_retry(&error) => retry,
+                _ => return (Err(error), attempt),
+            };
+            let delay = retry.backoff.delay(attempt);
+            tracing::warn!(node = node_name, attempt, %error, "node failed, retrying");
+            events.push(StreamEvent::node_retry(
+                node_name,
+                ctx.step,
+                attempt,
+                max_attempts,
+                &error.to_string(),
+                delay.as_millis() as u64,
+            ));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn attempt(
+        &self,
+        node_name: &str,
+        node: &dyn Node,
+        ctx: &NodeContext,
+        messages: Option<&MessageSender>,
+    ) -> Result<NodeOutput> {
+        let execution = match messages {
+            Some(messages) => node.execute_with_messages(ctx, messages),
+            None => node.execute(ctx),
+        };
+        let result = match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, execution).await {
+                Ok(result) => result,
+                Err(_) => {
+                    return Err(GraphError::NodeTimeout { node: node_name.to_string(), timeout });
+                }
+            },
+            None => execution.await,
+        };
+
+        result.map_err(|error| match error {
+            // Already attributed to a node, e.g. by AgentNode
+            error @ GraphError::NodeExecutionFailed { .. } => error,
+            error @ GraphError::NodeTimeout { .. } => error,
+            error => GraphError::node_failed_with_source(node_name, error),
+        })
+    }
+}
+
+/// A node that failed for good and was routed to its fallback
+#[derive(Clone, Debug)]
+pub struct Fallback {
+    /// The node that failed
+    pub node: String,
+    /// The fallback node scheduled in its place
+    pub target: String,
+    /// State channel the error is recorded in
+    pub error_channel: String,
+    /// The recorded error: `{"node", "message", "attempts"}`
+    pub error: Value,
+}
+
+impl Fallback {
+    pub(crate) fn new(
+        node: &str,
+        policy: &NodePolicy,
+        error: &GraphError,
+        attempts: u32,
+    ) -> Option<Self> {
+        let target = policy.fallback.clone()?;
+        let message = match error {
+            GraphError::NodeExecutionFailed { message, .. } => message.clone(),
+            error => error.to_string(),
+        };
+        Some(Self {
+            node: node.to_string(),
+            target,
+            error_channel: policy.error_channel.clone(),
+            error: json!({ "node": node, "message": message, "attempts": attempts }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delays() {
+        let fixed = Backoff::fixed(Duration::from_millis(100));
+        assert_eq!(fixed.delay(1), Duration::from_millis(100));
+        assert_eq!(fixed.delay(5), Duration::from_millis(100));
+
+        let exponential = Backoff::exponential(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(exponential.delay(1), Duration::from_millis(100));
+        assert_eq!(exponential.delay(2), Duration::from_millis(200));
+        assert_eq!(exponential.delay(4), Duration::from_millis(800));
+        assert_eq!(exponential.delay(5), Duration::from_secs(1));
+        assert_eq!(exponential.delay(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_retry_predicate() {
+        let policy =
+            RetryPolicy::new(3).retry_on(|error| matches!(error, GraphError::NodeTimeout { .. }));
+        let timeout =
+            GraphError::NodeTimeout { node: "n".to_string(), timeout: Duration::from_secs(1) };
+        assert!(policy.should_retry(&timeout));
+        assert!(!policy.should_retry(&GraphError::node_failed("n", "bad input")));
+        assert!(RetryPolicy::new(3).should_retry(&GraphError::node_failed("n", "bad input")));
+        assert_eq!(RetryPolicy::new(0).max_attempts, 1);
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! Provides typed state with reducers for controlling how updates are merged.
 
+use crate::edge::SendTo;
+use crate::interrupt::Interrupt;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 /// Graph state - a map of channel names to values
@@ -217,6 +219,9 @@ pub struct Checkpoint {
     pub step: usize,
     /// Nodes pending execution
     pub pending_nodes: Vec<String>,
+    /// Fan-out invocations pending execution
+    #[serde(default)]
+    pub pending_sends: Vec<SendTo>,
     /// Additional metadata
     pub metadata: HashMap<String, Value>,
     /// Creation timestamp
@@ -232,6 +237,7 @@ impl Checkpoint {
             state,
             step,
             pending_nodes,
+            pending_sends: Vec::new(),
             metadata: HashMap::new(),
             created_at: chrono::Utc::now(),
         }
@@ -242,6 +248,110 @@ impl Checkpoint {
         self.metadata.insert(key.to_string(), value);
         self
     }
+
+    /// Set the pending fan-out invocations
+    pub fn with_pending_sends(mut self, sends: Vec<SendTo>) -> Self {
+        self.pending_sends = sends;
+        self
+    }
+
+    /// Mark this as the checkpoint saved when `interrupt` paused the run
+    pub fn with_interrupt(self, interrupt: &Interrupt) -> Self {
+        let value = serde_json::to_value(interrupt).unwrap_or(Value::Null);
+        self.with_metadata(INTERRUPT_METADATA_KEY, value)
+    }
+
+    /// The interrupt that paused the run at this checkpoint, if any
+    pub fn interrupt(&self) -> Option<Interrupt> {
+        self.metadata
+            .get(INTERRUPT_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Whether the run still had work to do when this checkpoint was saved
+    pub fn has_pending(&self) -> bool {
+        !self.pending_nodes.is_empty() || !self.pending_sends.is_empty()
+    }
+
+    /// Record the checkpoint this one follows
+    pub fn with_parent(self, checkpoint_id: &str) -> Self {
+        self.with_metadata(PARENT_METADATA_KEY, json!(checkpoint_id))
+    }
+
+    /// The checkpoint this one follows: the previous step, or the checkpoint it was
+    /// forked or edited from
+    pub fn parent_id(&self) -> Option<&str> {
+        self.metadata.get(PARENT_METADATA_KEY).and_then(|value| value.as_str())
+    }
+}
+
+/// Metadata key recording the interrupt a checkpoint was saved at
+const INTERRUPT_METADATA_KEY: &str = "interrupt";
+/// Metadata key recording the checkpoint a checkpoint follows
+const PARENT_METADATA_KEY: &str = "parent_id";
+
+/// Channels that differ between two states
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Channels only the second state has
+    pub added: BTreeMap<String, Value>,
+    /// Channels only the first state has
+    pub removed: BTreeMap<String, Value>,
+    /// Channels whose value changed
+    pub changed: BTreeMap<String, ValueChange>,
+}
+
+/// A channel value before and after
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange {
+    /// Value in the first state
+    pub before: Value,
+    /// Value in the second state
+    pub after: Value,
+}
+
+impl StateDiff {
+    /// Compare `before` with `after`
+    pub fn between(before: &State, after: &State) -> Self {
+        let mut diff = Self::default();
+        for (key, old) in before {
+            match after.get(key) {
+                None => {
+                    diff.removed.insert(key.clone(), old.clone());
+                }
+                Some(new) if new != old => {
+                    let change = ValueChange { before: old.clone(), after: new.clone() };
+                    diff.changed.insert(key.clone(), change);
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new) in after {
+            if !before.contains_key(key) {
+                diff.added.insert(key.clone(), new.clone());
+            }
+        }
+        diff
+    }
+
+    /// Whether the states are equal
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Output of a completed task, stored until its super-step finishes
+///
+/// When a super-step fails part-way, the tasks that completed keep their writes, so
+/// resuming from the checkpoint the step started at does not run them again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingWrite {
+    /// Task within the super-step: the node name, or `{node}:{send index}` for sends
+    pub task_id: String,
+    /// Node that produced the updates
+    pub node: String,
+    /// State updates returned by the node
+    pub updates: HashMap<String, Value>,
 }
 
 #[cfg(test)]
@@ -315,4 +425,31 @@ mod tests {
         schema.apply_update(&mut state, "max", json!(8));
         assert_eq!(state.get("max"), Some(&json!(8.0)));
     }
+
+    #[test]
+    fn test_state_diff() {
+        let before = State::from([
+            ("kept".to_string(), json!(1)),
+            ("edited".to_string(), json!("draft")),
+            ("dropped".to_string(), json!(true)),
+        ]);
+        let after = State::from([
+            ("kept".to_string(), json!(1)),
+            ("edited".to_string(), json!("final")),
+            ("new".to_string(), json!([1])),
+        ]);
+
+        let diff = StateDiff::between(&before, &after);
+
+        assert_eq!(diff.added, BTreeMap::from([("new".to_string(), json!([1]))]));
+        assert_eq!(diff.removed, BTreeMap::from([("dropped".to_string(), json!(true))]));
+        assert_eq!(
+            diff.changed,
+            BTreeMap::from([(
+                "edited".to_string(),
+                ValueChange { before: json!("draft"), after: json!("final") }
+            )])
+        );
+        assert!(StateDiff::between(&after, &after).is_empty());
+    }
 }
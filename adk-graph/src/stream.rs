@@ -46,6 +46,20 @@ pub enum StreamEvent {
     /// Node completed execution
     NodeEnd { node: String, step: usize, duration_ms: u64 },
 
+    /// A node attempt failed and the node runs again after `delay_ms`
+    NodeRetry {
+        node: String,
+        step: usize,
+        /// The attempt that failed, counting from 1
+        attempt: u32,
+        max_attempts: u32,
+        error: String,
+        delay_ms: u64,
+    },
+
+    /// A node failed for good and its fallback runs next
+    NodeFallback { node: String, step: usize, fallback: String, error: String },
+
     /// Super-step completed
     StepComplete { step: usize, nodes_executed: Vec<String> },
 
@@ -95,6 +109,35 @@ impl StreamEvent {
         Self::NodeEnd { node: node.to_string(), step, duration_ms }
     }
 
+    /// Create a node retry event
+    pub fn node_retry(
+        node: &str,
+        step: usize,
+        attempt: u32,
+        max_attempts: u32,
+        error: &str,
+        delay_ms: u64,
+    ) -> Self {
+        Self::NodeRetry {
+            node: node.to_string(),
+            step,
+            attempt,
+            max_attempts,
+            error: error.to_string(),
+            delay_ms,
+        }
+    }
+
+    /// Create a node fallback event
+    pub fn node_fallback(node: &str, step: usize, fallback: &str, error: &str) -> Self {
+        Self::NodeFallback {
+            node: node.to_string(),
+            step,
+            fallback: fallback.to_string(),
+            error: error.to_string(),
+        }
+    }
+
     /// Create a step complete event
     pub fn step_complete(step: usize, nodes_executed: Vec<String>) -> Self {
         Self::StepComplete { step, nodes_executed }
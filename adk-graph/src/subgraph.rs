@@ -0,0 +1,210 @@
+//! Subgraphs: compiled graphs used as nodes of another graph
+//!
+//! A subgraph keeps its own state schema. Channel mappings decide what it reads from
+//! the parent state and what it writes back.
+
+use crate::error::{GraphError, Result};
+use crate::graph::CompiledGraph;
+use crate::node::{ExecutionConfig, Node, NodeContext, NodeOutput};
+use crate::state::State;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Node that runs a compiled graph to completion
+///
+/// The child runs on its own thread, `{parent thread}:{node name}`, with
+/// `:{send index}` appended when a fan-out edge started it, so a child with a
+/// checkpointer keeps checkpoints separate from the parent's. When the child is
+/// interrupted the node reports the same interrupt, which pauses the parent. Invoking
+/// the parent thread again re-runs the node, and the child picks up its checkpointed
+/// state with the parent's current values mapped over it.
+///
+/// # Example
+/// ```ignore
+/// let research = StateGraph::with_channels(&["query", "notes"])
+///     // ...
+///     .compile()?
+///     .with_checkpointer(MemoryCheckpointer::new());
+///
+/// let graph = StateGraph::with_channels(&["question", "notes"])
+///     .add_node(
+///         SubgraphNode::new("research", research)
+///             .map_input("question", "query")
+///             .map_output("notes", "notes"),
+///     )
+///     .add_edge(START, "research")
+///     .add_edge("research", END)
+///     .compile()?;
+/// ```
+pub struct SubgraphNode {
+    name: String,
+    graph: Arc<CompiledGraph>,
+    /// (parent channel, child channel)
+    input_mapping: Vec<(String, String)>,
+    /// (child channel, parent channel)
+    output_mapping: Vec<(String, String)>,
+}
+
+impl SubgraphNode {
+    /// Create a node that runs `graph`
+    pub fn new(name: &str, graph: impl Into<Arc<CompiledGraph>>) -> Self {
+        Self {
+            name: name.to_string(),
+            graph: graph.into(),
+            input_mapping: vec![],
+            output_mapping: vec![],
+        }
+    }
+
+    /// Pass the parent's `parent_key` to the child as `child_key`
+    ///
+    /// Without input mappings the child receives every parent value whose key is one of
+    /// its channels, or the whole parent state if its schema declares none.
+    pub fn map_input(mut self, parent_key: &str, child_key: &str) -> Self {
+        self.input_mapping.push((parent_key.to_string(), child_key.to_string()));
+        self
+    }
+
+    /// Write the child's final `child_key` to the parent's `parent_key`
+    ///
+    /// Without output mappings every child value that differs from what the child
+    /// received is written back under the same key.
+    pub fn map_output(mut self, child_key: &str, parent_key: &str) -> Self {
+        self.output_mapping.push((child_key.to_string(), parent_key.to_string()));
+        self
+    }
+
+    /// Get the wrapped graph
+    pub fn graph(&self) -> &CompiledGraph {
+        &self.graph
+    }
+
+    /// Thread the child runs on for the given invocation
+    pub fn thread_id(&self, ctx: &NodeContext) -> String {
+        match ctx.send_index {
+            Some(index) => format!("{}:{}:{index}", ctx.config.thread_id, self.name),
+            None => format!("{}:{}", ctx.config.thread_id, self.name),
+        }
+    }
+
+    fn child_input(&self, state: &State) -> State {
+        if !self.input_mapping.is_empty() {
+            return self
+                .input_mapping
+                .iter()
+                .filter_map(|(parent, child)| state.get(parent).map(|v| (child.clone(), v.clone())))
+                .collect();
+        }
+
+        let channels = &self.graph.schema().channels;
+        state
+            .iter()
+            .filter(|(key, _)| channels.is_empty() || channels.contains_key(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    fn parent_updates(&self, input: &State, output: State) -> HashMap<String, serde_json::Value> {
+        if !self.output_mapping.is_empty() {
+            return self
+                .output_mapping
+                .iter()
+                .filter_map(|(child, parent)| {
+                    output.get(child).map(|v| (parent.clone(), v.clone()))
+                })
+                .collect();
+        }
+
+        output.into_iter().filter(|(key, value)| input.get(key) != Some(value)).collect()
+    }
+}
+
+#[async_trait]
+impl Node for SubgraphNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn execute(&self, ctx: &NodeContext) -> Result<NodeOutput> {
+        let input = self.child_input(&ctx.state);
+        let config = ExecutionConfig {
+            thread_id: self.thread_id(ctx),
+            resume_from: None,
+            recursion_limit: ctx.config.recursion_limit,
+            metadata: ctx.config.metadata.clone(),
+            invocation_context: ctx.config.invocation_context.clone(),
+            cancellation_token: ctx.config.cancellation_token.clone(),
+        };
+
+        match self.graph.invoke(input.clone(), config).await {
+            Ok(output) => Ok(NodeOutput {
+                updates: self.parent_updates(&input, output),
+                ..Default::default()
+            }),
+            Err(GraphError::Interrupted(interrupted)) => {
+                Ok(NodeOutput::new().with_interrupt(interrupted.interrupt))
+            }
+            Err(e @ GraphError::Cancelled(_)) => Err(e),
+            Err(e) => Err(GraphError::node_failed_with_source(&self.name, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::{END, START};
+    use crate::graph::StateGraph;
+    use serde_json::json;
+
+    fn child() -> CompiledGraph {
+        StateGraph::with_channels(&["query", "answer"])
+            .add_node_fn("answer", |ctx| async move {
+                let query = ctx.get("query").and_then(|v| v.as_str()).unwrap_or_default();
+                Ok(NodeOutput::new().with_update("answer", json!(format!("re: {query}"))))
+            })
+            .add_edge(START, "answer")
+            .add_edge("answer", END)
+            .compile()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_thread_id() {
+        let node = SubgraphNode::new("research", child());
+        let mut ctx = NodeContext::new(State::new(), ExecutionConfig::new("main"), 0);
+        assert_eq!(node.thread_id(&ctx), "main:research");
+
+        ctx.send_index = Some(2);
+        assert_eq!(node.thread_id(&ctx), "main:research:2");
+    }
+
+    #[tokio::test]
+    async fn test_default_mapping_uses_child_channels() {
+        let node = SubgraphNode::new("research", child());
+        let state = State::from([
+            ("query".to_string(), json!("tides")),
+            ("unrelated".to_string(), json!(1)),
+        ]);
+
+        let output =
+            node.execute(&NodeContext::new(state, ExecutionConfig::new("t"), 0)).await.unwrap();
+
+        // Only what the child changed comes back
+        assert_eq!(output.updates, HashMap::from([("answer".to_string(), json!("re: tides"))]));
+    }
+
+    #[tokio::test]
+    async fn test_explicit_mapping() {
+        let node = SubgraphNode::new("research", child())
+            .map_input("question", "query")
+            .map_output("answer", "notes");
+        let state = State::from([("question".to_string(), json!("tides"))]);
+
+        let output =
+            node.execute(&NodeContext::new(state, ExecutionConfig::new("t"), 0)).await.unwrap();
+
+        assert_eq!(output.updates, HashMap::from([("notes".to_string(), json!("re: tides"))]));
+    }
+}
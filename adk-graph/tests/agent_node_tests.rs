@@ -0,0 +1,392 @@
+//! AgentNode tests
+
+use adk_agent::LlmAgentBuilder;
+use adk_core::{
+    AdkError, Agent, Artifacts, CallbackContext, Content, Event, EventStream, InvocationContext,
+    Llm, LlmRequest, LlmResponse, LlmResponseStream, Memory, MemoryEntry, Part, ReadonlyContext,
+    RunConfig, Session, StreamingMode,
+};
+use adk_graph::edge::{END, START};
+use adk_graph::error::GraphError;
+use adk_graph::graph::StateGraph;
+use adk_graph::node::{AgentNode, ExecutionConfig, Node, NodeContext};
+use adk_graph::policy::{NodePolicy, RetryPolicy};
+use adk_graph::state::State;
+use adk_graph::stream::{StreamEvent, StreamMode};
+use adk_tool::FunctionTool;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// What the probe agent saw of its invocation context
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Observed {
+    user_id: String,
+    session_id: String,
+    user_text: String,
+    topic: Option<Value>,
+    has_artifacts: bool,
+    has_memory: bool,
+    streaming_mode: Option<StreamingMode>,
+}
+
+/// Records its context, then emits a reply with a state delta, optionally followed by
+/// an error
+struct ProbeAgent {
+    observed: Arc<Mutex<Observed>>,
+    fail: bool,
+}
+
+impl ProbeAgent {
+    fn new(fail: bool) -> (Self, Arc<Mutex<Observed>>) {
+        let observed = Arc::new(Mutex::new(Observed::default()));
+        (Self { observed: observed.clone(), fail }, observed)
+    }
+}
+
+#[async_trait]
+impl Agent for ProbeAgent {
+    fn name(&self) -> &str {
+        "probe"
+    }
+
+    fn description(&self) -> &str {
+        "Records its invocation context"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> adk_core::Result<EventStream> {
+        *self.observed.lock().unwrap() = Observed {
+            user_id: ctx.user_id().to_string(),
+            session_id: ctx.session_id().to_string(),
+            user_text: ctx.user_content().parts.iter().filter_map(Part::text).collect(),
+            topic: ctx.session().state().get("topic"),
+            has_artifacts: ctx.artifacts().is_some(),
+            has_memory: ctx.memory().is_some(),
+            streaming_mode: Some(ctx.run_config().streaming_mode),
+        };
+
+        let mut event = Event::new(ctx.invocation_id());
+        event.author = "probe".to_string();
+        event.set_content(Content::new("model").with_text("done"));
+        event.actions.state_delta.insert("summary".to_string(), json!("short"));
+        event.actions.state_delta.insert("temp:scratch".to_string(), json!(1));
+
+        let mut results = vec![Ok(event)];
+        if self.fail {
+            results.push(Err(AdkError::Agent("model quota exceeded".to_string())));
+        }
+        Ok(Box::pin(futures::stream::iter(results)))
+    }
+}
+
+/// Calls the `record` tool, then answers once it sees the tool's response
+struct RecordingModel;
+
+#[async_trait]
+impl Llm for RecordingModel {
+    fn name(&self) -> &str {
+        "recording-model"
+    }
+
+    async fn generate_content(
+        &self,
+        req: LlmRequest,
+        _stream: bool,
+    ) -> adk_core::Result<LlmResponseStream> {
+        let answered = req
+            .contents
+            .iter()
+            .any(|c| c.parts.iter().any(|p| matches!(p, Part::FunctionResponse { .. })));
+        let part = if answered {
+            Part::Text { text: "recorded".to_string() }
+        } else {
+            Part::FunctionCall {
+                name: "record".to_string(),
+                args: json!({}),
+                id: Some("call_1".to_string()),
+            }
+        };
+        let response = LlmResponse::new(Content { role: "model".to_string(), parts: vec![part] });
+        Ok(Box::pin(futures::stream::iter(vec![Ok(response)])))
+    }
+}
+
+struct ParentState(HashMap<String, Value>);
+
+impl adk_core::State for ParentState {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.0.get(key).cloned()
+    }
+    fn set(&mut self, key: String, value: Value) {
+        self.0.insert(key, value);
+    }
+    fn all(&self) -> HashMap<String, Value> {
+        self.0.clone()
+    }
+}
+
+struct ParentSession(ParentState);
+
+impl Session for ParentSession {
+    fn id(&self) -> &str {
+        "session-7"
+    }
+    fn app_name(&self) -> &str {
+        "research"
+    }
+    fn user_id(&self) -> &str {
+        "alice"
+    }
+    fn state(&self) -> &dyn adk_core::State {
+        &self.0
+    }
+    fn conversation_history(&self) -> Vec<Content> {
+        Vec::new()
+    }
+}
+
+struct NoArtifacts;
+
+#[async_trait]
+impl Artifacts for NoArtifacts {
+    async fn save(&self, _name: &str, _data: &Part) -> adk_core::Result<i64> {
+        Ok(1)
+    }
+    async fn load(&self, _name: &str) -> adk_core::Result<Part> {
+        Err(AdkError::Artifact("not found".to_string()))
+    }
+    async fn list(&self) -> adk_core::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+struct NoMemory;
+
+#[async_trait]
+impl Memory for NoMemory {
+    async fn search(&self, _query: &str) -> adk_core::Result<Vec<MemoryEntry>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Invocation that runs the graph
+struct ParentContext {
+    session: ParentSession,
+    content: Content,
+    run_config: RunConfig,
+}
+
+impl ParentContext {
+    fn new() -> Arc<Self> {
+        let state = HashMap::from([("topic".to_string(), json!("tides"))]);
+        Arc::new(Self {
+            session: ParentSession(ParentState(state)),
+            content: Content::new("user").with_text("start"),
+            run_config: RunConfig { streaming_mode: StreamingMode::None },
+        })
+    }
+}
+
+impl ReadonlyContext for ParentContext {
+    fn invocation_id(&self) -> &str {
+        "inv-1"
+    }
+    fn agent_name(&self) -> &str {
+        "graph"
+    }
+    fn user_id(&self) -> &str {
+        "alice"
+    }
+    fn app_name(&self) -> &str {
+        "research"
+    }
+    fn session_id(&self) -> &str {
+        "session-7"
+    }
+    fn branch(&self) -> &str {
+        "graph"
+    }
+    fn user_content(&self) -> &Content {
+        &self.content
+    }
+}
+
+#[async_trait]
+impl CallbackContext for ParentContext {
+    fn artifacts(&self) -> Option<Arc<dyn Artifacts>> {
+        Some(Arc::new(NoArtifacts))
+    }
+}
+
+#[async_trait]
+impl InvocationContext for ParentContext {
+    fn agent(&self) -> Arc<dyn Agent> {
+        unimplemented!()
+    }
+    fn memory(&self) -> Option<Arc<dyn Memory>> {
+        Some(Arc::new(NoMemory))
+    }
+    fn session(&self) -> &dyn Session {
+        &self.session
+    }
+    fn run_config(&self) -> &RunConfig {
+        &self.run_config
+    }
+    fn end_invocation(&self) {}
+    fn ended(&self) -> bool {
+        false
+    }
+}
+
+fn input(text: &str) -> State {
+    let mut state = State::new();
+    state.insert("input".to_string(), json!(text));
+    state
+}
+
+#[tokio::test]
+async fn test_agent_node_shares_parent_invocation() {
+    let (agent, observed) = ProbeAgent::new(false);
+    let node = AgentNode::new(Arc::new(agent));
+    let config = ExecutionConfig::new("thread-1").with_invocation_context(ParentContext::new());
+
+    node.execute(&NodeContext::new(input("summarize"), config, 0)).await.unwrap();
+
+    assert_eq!(
+        *observed.lock().unwrap(),
+        Observed {
+            user_id: "alice".to_string(),
+            session_id: "session-7".to_string(),
+            user_text: "summarize".to_string(),
+            topic: Some(json!("tides")),
+            has_artifacts: true,
+            has_memory: true,
+            streaming_mode: Some(StreamingMode::None),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_agent_node_without_invocation() {
+    let (agent, observed) = ProbeAgent::new(false);
+    let node = AgentNode::new(Arc::new(agent));
+
+    node.execute(&NodeContext::new(input("hi"), ExecutionConfig::new("thread-1"), 0))
+        .await
+        .unwrap();
+
+    let observed = observed.lock().unwrap().clone();
+    assert_eq!(
+        (observed.user_id.as_str(), observed.session_id.as_str()),
+        ("graph_user", "thread-1")
+    );
+    assert!(!observed.has_artifacts && !observed.has_memory);
+    assert_eq!(observed.topic, None);
+}
+
+#[tokio::test]
+async fn test_agent_node_maps_state_delta() {
+    let (agent, _) = ProbeAgent::new(false);
+    let node = AgentNode::new(Arc::new(agent));
+
+    let output = node
+        .execute(&NodeContext::new(input("hi"), ExecutionConfig::new("thread-1"), 0))
+        .await
+        .unwrap();
+
+    assert_eq!(output.updates.get("summary"), Some(&json!("short")));
+    assert_eq!(
+        output.updates.get("messages"),
+        Some(&json!([{"role": "assistant", "content": "done"}]))
+    );
+    assert!(!output.updates.contains_key("temp:scratch"));
+}
+
+#[tokio::test]
+async fn test_agent_node_propagates_agent_errors() {
+    let (agent, _) = ProbeAgent::new(true);
+    let node = AgentNode::new(Arc::new(agent));
+
+    let err = node
+        .execute(&NodeContext::new(input("hi"), ExecutionConfig::new("thread-1"), 0))
+        .await
+        .err()
+        .expect("agent error should fail the node");
+
+    let GraphError::NodeExecutionFailed { node, message, source } = err else {
+        panic!("expected NodeExecutionFailed, got {err:?}");
+    };
+    assert_eq!(node, "probe");
+    assert!(message.contains("model quota exceeded"), "{message}");
+    let source = source.expect("original error is kept");
+    assert!(matches!(source.downcast_ref::<AdkError>(), Some(AdkError::Agent(_))));
+}
+
+#[tokio::test]
+async fn test_agent_errors_fail_the_graph() {
+    let (agent, _) = ProbeAgent::new(true);
+    let graph = StateGraph::with_channels(&["input", "messages", "summary"])
+        .add_node(AgentNode::new(Arc::new(agent)))
+        .add_edge(START, "probe")
+        .add_edge("probe", END)
+        .compile()
+        .unwrap();
+
+    let err = graph.invoke(input("hi"), ExecutionConfig::new("thread-1")).await.unwrap_err();
+
+    // Reported once, not wrapped again by the executor
+    assert_eq!(err.to_string(), "Node 'probe' execution failed: Agent error: model quota exceeded");
+}
+
+#[tokio::test]
+async fn test_messages_mode_runs_agent_node_once() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = calls.clone();
+    let tool = FunctionTool::new("record", "Records a call", move |_ctx, _args| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(json!({ "ok": true }))
+        }
+    });
+    let agent = LlmAgentBuilder::new("recorder")
+        .model(Arc::new(RecordingModel))
+        .tool(Arc::new(tool))
+        .build()
+        .unwrap();
+
+    let graph = StateGraph::with_channels(&["input", "messages"])
+        .add_node(AgentNode::new(Arc::new(agent)))
+        .add_edge(START, "recorder")
+        .add_edge("recorder", END)
+        .with_node_policy(
+            "recorder",
+            NodePolicy::new().with_retry(RetryPolicy::new(3)).with_timeout(Duration::from_secs(5)),
+        )
+        .compile()
+        .unwrap();
+
+    let events: Vec<StreamEvent> = graph
+        .stream(input("record it"), ExecutionConfig::new("thread-1"), StreamMode::Messages)
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        StreamEvent::Message { node, content, .. } if node == "recorder" && content == "recorded"
+    )));
+    let Some(StreamEvent::Done { state, .. }) = events.last() else {
+        panic!("expected the run to finish, got {events:?}");
+    };
+    assert_eq!(state["messages"], json!([{"role": "assistant", "content": "recorded"}]));
+}
@@ -1,8 +1,15 @@
 //! Checkpoint tests
 
-use adk_graph::checkpoint::{Checkpointer, MemoryCheckpointer};
+use adk_graph::checkpoint::{Checkpointer, MemoryCheckpointer, RetentionPolicy};
+use adk_graph::edge::{END, START};
+use adk_graph::error::GraphError;
+use adk_graph::graph::StateGraph;
+use adk_graph::interrupt::Interrupt;
+use adk_graph::node::{ExecutionConfig, NodeContext, NodeOutput};
 use adk_graph::state::{Checkpoint, State};
 use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[tokio::test]
 async fn test_memory_checkpointer_save_and_load() {
@@ -172,3 +179,124 @@ async fn test_checkpoint_ordering() {
         assert_eq!(cp.step, i);
     }
 }
+
+/// Counts runs of a node and fails its first `failures` runs
+fn flaky_node(
+    name: &'static str,
+    runs: Arc<AtomicUsize>,
+    failures: usize,
+) -> impl Fn(NodeContext) -> futures::future::BoxFuture<'static, adk_graph::Result<NodeOutput>>
++ Send
++ Sync
++ 'static {
+    move |_ctx| {
+        let run = runs.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            if run < failures {
+                return Err(GraphError::node_failed(name, "transient failure"));
+            }
+            Ok(NodeOutput::new().with_update(name, json!(run + 1)))
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_failed_step_resumes_without_rerunning_completed_nodes() {
+    let a_runs = Arc::new(AtomicUsize::new(0));
+    let b_runs = Arc::new(AtomicUsize::new(0));
+    let checkpointer = Arc::new(MemoryCheckpointer::new());
+
+    let graph = StateGraph::with_channels(&["a", "b", "joined"])
+        .add_node_fn("a", flaky_node("a", a_runs.clone(), 0))
+        .add_node_fn("b", flaky_node("b", b_runs.clone(), 1))
+        .add_node_fn("join", |ctx| async move {
+            let joined = json!([ctx.get("a").cloned(), ctx.get("b").cloned()]);
+            Ok(NodeOutput::new().with_update("joined", joined))
+        })
+        .add_edge(START, "a")
+        .add_edge(START, "b")
+        .add_edge("a", "join")
+        .add_edge("b", "join")
+        .add_edge("join", END)
+        .compile()
+        .unwrap()
+        .with_checkpointer_arc(checkpointer.clone());
+
+    let err = graph.invoke(State::new(), ExecutionConfig::new("flaky")).await.unwrap_err();
+    assert!(err.to_string().contains("transient failure"));
+    // The failed step is still pending, with the write of the node that completed
+    let pending = checkpointer.load("flaky").await.unwrap().unwrap();
+    let mut pending_nodes = pending.pending_nodes.clone();
+    pending_nodes.sort();
+    assert_eq!(pending_nodes, vec!["a", "b"]);
+    let writes = checkpointer.get_writes(&pending.checkpoint_id).await.unwrap();
+    assert_eq!(writes.iter().map(|w| w.task_id.as_str()).collect::<Vec<_>>(), vec!["a"]);
+
+    let result = graph.invoke(State::new(), ExecutionConfig::new("flaky")).await.unwrap();
+
+    assert_eq!(result.get("joined"), Some(&json!([1, 2])));
+    assert_eq!(a_runs.load(Ordering::SeqCst), 1);
+    assert_eq!(b_runs.load(Ordering::SeqCst), 2);
+    // Finished: the latest checkpoint has nothing left to run
+    assert!(!checkpointer.load("flaky").await.unwrap().unwrap().has_pending());
+}
+
+#[tokio::test]
+async fn test_resume_after_interrupt_before() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let graph = StateGraph::with_channels(&["plan", "execute"])
+        .add_node_fn("plan", |_ctx| async {
+            Ok(NodeOutput::new().with_update("plan", json!("ship it")))
+        })
+        .add_node_fn("execute", flaky_node("execute", runs.clone(), 0))
+        .add_edge(START, "plan")
+        .add_edge("plan", "execute")
+        .add_edge("execute", END)
+        .compile()
+        .unwrap()
+        .with_checkpointer(MemoryCheckpointer::new())
+        .with_interrupt_before(&["execute"]);
+
+    let err = graph.invoke(State::new(), ExecutionConfig::new("hitl")).await.unwrap_err();
+    let GraphError::Interrupted(interrupted) = err else {
+        panic!("expected an interrupt, got {err:?}");
+    };
+    assert!(matches!(interrupted.interrupt, Interrupt::Before(ref node) if node == "execute"));
+    assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+    // Resuming runs the node it paused before instead of pausing again
+    let result = graph.invoke(State::new(), ExecutionConfig::new("hitl")).await.unwrap();
+
+    assert_eq!(result.get("execute"), Some(&json!(1)));
+    assert_eq!(result.get("plan"), Some(&json!("ship it")));
+}
+
+#[tokio::test]
+async fn test_retention_bounds_checkpoints() {
+    let checkpointer = Arc::new(MemoryCheckpointer::new());
+    let graph = StateGraph::with_channels(&["count"])
+        .add_node_fn("increment", |ctx| async move {
+            let count = ctx.get("count").and_then(|v| v.as_i64()).unwrap_or(0);
+            Ok(NodeOutput::new().with_update("count", json!(count + 1)))
+        })
+        .add_edge(START, "increment")
+        .add_conditional_edges(
+            "increment",
+            |state| {
+                let count = state.get("count").and_then(|v| v.as_i64()).unwrap_or(0);
+                if count < 5 { "increment".to_string() } else { END.to_string() }
+            },
+            [("increment", "increment"), (END, END)],
+        )
+        .compile()
+        .unwrap()
+        .with_checkpointer_arc(checkpointer.clone())
+        .with_retention(RetentionPolicy::keep_last(2));
+
+    let result = graph.invoke(State::new(), ExecutionConfig::new("loop")).await.unwrap();
+
+    assert_eq!(result.get("count"), Some(&json!(5)));
+    let checkpoints = checkpointer.list("loop").await.unwrap();
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(checkpoints[1].state.get("count"), Some(&json!(5)));
+}
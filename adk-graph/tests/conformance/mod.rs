@@ -0,0 +1,271 @@
+//! Behaviour every `Checkpointer` backend must share.
+//!
+//! Each check takes a checkpointer and works on fresh thread IDs, so the checks can run
+//! concurrently against one shared database. `conformance_suite!` expands to one test
+//! per check for a backend; its argument is an async expression evaluating to
+//! `Option<checkpointer>`, where `None` skips the backend.
+
+use adk_graph::checkpoint::{Checkpointer, RetentionPolicy};
+use adk_graph::edge::SendTo;
+use adk_graph::interrupt::Interrupt;
+use adk_graph::state::{Checkpoint, PendingWrite, State};
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Reads a database URL for an external backend, or explains how to enable it.
+#[allow(dead_code)]
+pub fn database_url(var: &str) -> Option<String> {
+    let url = std::env::var(var).ok().filter(|url| !url.is_empty());
+    if url.is_none() {
+        eprintln!("skipping: {var} is not set (see adk-graph/scripts/test-postgres.sh)");
+    }
+    url
+}
+
+macro_rules! conformance_suite {
+    ($checkpointer:expr) => {
+        conformance_suite!(@tests $checkpointer;
+            load_returns_latest,
+            load_missing_thread_is_none,
+            load_by_id,
+            list_is_oldest_first_and_per_thread,
+            every_field_round_trips,
+            delete_removes_thread_and_writes,
+            writes_round_trip_and_replace,
+            delete_checkpoints_removes_writes,
+            prune_keeps_last,
+            prune_keeps_interrupts,
+            prune_expires_by_ttl,
+            delete_before_spans_threads,
+        );
+    };
+    (@tests $checkpointer:expr; $($check:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $check() {
+                if let Some(checkpointer) = $checkpointer.await {
+                    crate::conformance::$check(&checkpointer).await;
+                }
+            }
+        )*
+    };
+}
+
+fn unique_thread() -> String {
+    format!("thread-{}", uuid::Uuid::new_v4())
+}
+
+fn state(step: usize) -> State {
+    State::from([("step".to_string(), json!(step))])
+}
+
+fn write(task_id: &str, value: i64) -> PendingWrite {
+    PendingWrite {
+        task_id: task_id.to_string(),
+        node: task_id.split(':').next().unwrap().to_string(),
+        updates: HashMap::from([("value".to_string(), json!(value))]),
+    }
+}
+
+/// Saves `count` checkpoints one minute apart, ending a minute ago, and returns them
+/// oldest first
+async fn save_history(
+    checkpointer: &dyn Checkpointer,
+    thread_id: &str,
+    count: usize,
+) -> Vec<Checkpoint> {
+    let start = Utc::now() - Duration::minutes(count as i64);
+    let mut saved = Vec::new();
+    for step in 0..count {
+        let mut checkpoint = Checkpoint::new(thread_id, state(step), step, vec!["next".into()]);
+        checkpoint.created_at = start + Duration::minutes(step as i64);
+        checkpointer.save(&checkpoint).await.unwrap();
+        saved.push(checkpoint);
+    }
+    saved
+}
+
+async fn listed_ids(checkpointer: &dyn Checkpointer, thread_id: &str) -> Vec<String> {
+    checkpointer.list(thread_id).await.unwrap().into_iter().map(|c| c.checkpoint_id).collect()
+}
+
+fn ids(checkpoints: &[&Checkpoint]) -> Vec<String> {
+    checkpoints.iter().map(|c| c.checkpoint_id.clone()).collect()
+}
+
+pub async fn load_returns_latest(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let saved = save_history(checkpointer, &thread, 3).await;
+
+    let latest = checkpointer.load(&thread).await.unwrap().unwrap();
+    assert_eq!(latest.checkpoint_id, saved[2].checkpoint_id);
+    assert_eq!(latest.state, state(2));
+}
+
+pub async fn load_missing_thread_is_none(checkpointer: &dyn Checkpointer) {
+    assert!(checkpointer.load(&unique_thread()).await.unwrap().is_none());
+    assert!(checkpointer.list(&unique_thread()).await.unwrap().is_empty());
+}
+
+pub async fn load_by_id(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let saved = save_history(checkpointer, &thread, 2).await;
+
+    let first = checkpointer.load_by_id(&saved[0].checkpoint_id).await.unwrap().unwrap();
+    assert_eq!(first.thread_id, thread);
+    assert_eq!(first.step, 0);
+    assert!(checkpointer.load_by_id("no-such-checkpoint").await.unwrap().is_none());
+}
+
+pub async fn list_is_oldest_first_and_per_thread(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let other = unique_thread();
+    // Same timestamp, so order must follow the order of saving
+    let created_at = Utc::now();
+    let mut saved = Vec::new();
+    for step in 0..4 {
+        let mut checkpoint = Checkpoint::new(&thread, state(step), step, vec![]);
+        checkpoint.created_at = created_at;
+        checkpointer.save(&checkpoint).await.unwrap();
+        saved.push(checkpoint.checkpoint_id);
+    }
+    save_history(checkpointer, &other, 1).await;
+
+    assert_eq!(listed_ids(checkpointer, &thread).await, saved);
+    assert_eq!(checkpointer.list(&other).await.unwrap().len(), 1);
+}
+
+pub async fn every_field_round_trips(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let state = State::from([
+        ("messages".to_string(), json!([{"role": "user", "content": "hi"}])),
+        ("count".to_string(), json!(3)),
+    ]);
+    let checkpoint = Checkpoint::new(&thread, state, 7, vec!["a".into(), "b".into()])
+        .with_pending_sends(vec![
+            SendTo::with_value("summarize", "document", json!("alpha")),
+            SendTo::with_value("summarize", "document", json!("beta")),
+        ])
+        .with_interrupt(&Interrupt::Before("b".into()))
+        .with_metadata("source", json!("conformance"));
+    checkpointer.save(&checkpoint).await.unwrap();
+
+    let loaded = checkpointer.load(&thread).await.unwrap().unwrap();
+    assert_eq!(loaded.checkpoint_id, checkpoint.checkpoint_id);
+    assert_eq!(loaded.thread_id, checkpoint.thread_id);
+    assert_eq!(loaded.state, checkpoint.state);
+    assert_eq!(loaded.step, 7);
+    assert_eq!(loaded.pending_nodes, checkpoint.pending_nodes);
+    assert_eq!(loaded.pending_sends, checkpoint.pending_sends);
+    assert_eq!(loaded.metadata, checkpoint.metadata);
+    assert!(matches!(loaded.interrupt(), Some(Interrupt::Before(node)) if node == "b"));
+    // Backends may store timestamps at microsecond precision
+    let drift = (loaded.created_at - checkpoint.created_at).num_milliseconds().abs();
+    assert!(drift < 1, "created_at drifted by {drift}ms");
+}
+
+pub async fn delete_removes_thread_and_writes(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let other = unique_thread();
+    let saved = save_history(checkpointer, &thread, 2).await;
+    save_history(checkpointer, &other, 1).await;
+    checkpointer.put_writes(&saved[1].checkpoint_id, &[write("a", 1)]).await.unwrap();
+
+    checkpointer.delete(&thread).await.unwrap();
+
+    assert!(checkpointer.load(&thread).await.unwrap().is_none());
+    assert!(checkpointer.get_writes(&saved[1].checkpoint_id).await.unwrap().is_empty());
+    assert!(checkpointer.load(&other).await.unwrap().is_some());
+}
+
+pub async fn writes_round_trip_and_replace(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let saved = save_history(checkpointer, &thread, 2).await;
+    let (first, second) = (&saved[0].checkpoint_id, &saved[1].checkpoint_id);
+
+    checkpointer.put_writes(first, &[write("a", 1), write("send:0", 2)]).await.unwrap();
+    checkpointer.put_writes(first, &[write("send:1", 3)]).await.unwrap();
+    // A retried task replaces its earlier write
+    checkpointer.put_writes(first, &[write("a", 10)]).await.unwrap();
+    checkpointer.put_writes(second, &[write("b", 4)]).await.unwrap();
+
+    let mut writes = checkpointer.get_writes(first).await.unwrap();
+    writes.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+    assert_eq!(writes, vec![write("a", 10), write("send:0", 2), write("send:1", 3)]);
+    assert_eq!(writes[1].node, "send");
+    assert_eq!(checkpointer.get_writes(second).await.unwrap(), vec![write("b", 4)]);
+    assert!(checkpointer.get_writes("no-such-checkpoint").await.unwrap().is_empty());
+}
+
+pub async fn delete_checkpoints_removes_writes(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let saved = save_history(checkpointer, &thread, 3).await;
+    checkpointer.put_writes(&saved[0].checkpoint_id, &[write("a", 1)]).await.unwrap();
+
+    checkpointer.delete_checkpoints(&ids(&[&saved[0], &saved[2]])).await.unwrap();
+    checkpointer.delete_checkpoints(&[]).await.unwrap();
+
+    assert_eq!(listed_ids(checkpointer, &thread).await, ids(&[&saved[1]]));
+    assert!(checkpointer.get_writes(&saved[0].checkpoint_id).await.unwrap().is_empty());
+}
+
+pub async fn prune_keeps_last(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let saved = save_history(checkpointer, &thread, 5).await;
+
+    let pruned = checkpointer.prune(&thread, &RetentionPolicy::keep_last(2)).await.unwrap();
+
+    assert_eq!(pruned, 3);
+    assert_eq!(listed_ids(checkpointer, &thread).await, ids(&[&saved[3], &saved[4]]));
+    // Nothing left to prune
+    assert_eq!(checkpointer.prune(&thread, &RetentionPolicy::keep_last(2)).await.unwrap(), 0);
+}
+
+pub async fn prune_keeps_interrupts(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    let mut saved = Vec::new();
+    for step in 0..4 {
+        let mut checkpoint = Checkpoint::new(&thread, state(step), step, vec!["review".into()]);
+        if step == 1 {
+            checkpoint = checkpoint.with_interrupt(&Interrupt::Before("review".into()));
+        }
+        checkpointer.save(&checkpoint).await.unwrap();
+        saved.push(checkpoint);
+    }
+
+    checkpointer.prune(&thread, &RetentionPolicy::interrupts_only()).await.unwrap();
+
+    assert_eq!(listed_ids(checkpointer, &thread).await, ids(&[&saved[1], &saved[3]]));
+}
+
+pub async fn prune_expires_by_ttl(checkpointer: &dyn Checkpointer) {
+    let thread = unique_thread();
+    // Ten, nine, ... one minutes old
+    let saved = save_history(checkpointer, &thread, 10).await;
+
+    let policy = RetentionPolicy::ttl(std::time::Duration::from_secs(150));
+    checkpointer.prune(&thread, &policy).await.unwrap();
+
+    assert_eq!(listed_ids(checkpointer, &thread).await, ids(&[&saved[8], &saved[9]]));
+}
+
+pub async fn delete_before_spans_threads(checkpointer: &dyn Checkpointer) {
+    let (old, recent) = (unique_thread(), unique_thread());
+    let long_ago = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+    for thread in [&old, &recent] {
+        let mut checkpoint = Checkpoint::new(thread, state(0), 0, vec![]);
+        checkpoint.created_at = long_ago;
+        checkpointer.save(&checkpoint).await.unwrap();
+        checkpointer.put_writes(&checkpoint.checkpoint_id, &[write("a", 1)]).await.unwrap();
+    }
+    let kept = save_history(checkpointer, &recent, 1).await;
+
+    let cutoff = Utc.with_ymd_and_hms(2001, 1, 1, 0, 0, 0).unwrap();
+    let deleted = checkpointer.delete_before(cutoff).await.unwrap();
+
+    // Other runs against the same database may have left old checkpoints behind
+    assert!(deleted >= 2, "deleted {deleted}");
+    assert!(checkpointer.load(&old).await.unwrap().is_none());
+    assert_eq!(listed_ids(checkpointer, &recent).await, ids(&[&kept[0]]));
+}
@@ -0,0 +1,30 @@
+//! Runs the shared conformance checks against every checkpointer backend.
+//!
+//! SQLite runs in memory. PostgreSQL runs when `ADK_TEST_POSTGRES_URL` points at a
+//! database; `scripts/test-postgres.sh` starts one in a container and runs this suite.
+
+#[macro_use]
+mod conformance;
+
+mod memory {
+    use adk_graph::checkpoint::MemoryCheckpointer;
+
+    conformance_suite!(async { Some(MemoryCheckpointer::new()) });
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use adk_graph::checkpoint::SqliteCheckpointer;
+
+    conformance_suite!(async { Some(SqliteCheckpointer::in_memory().await.unwrap()) });
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use adk_graph::checkpoint::PostgresCheckpointer;
+
+    conformance_suite!(async {
+        let url = crate::conformance::database_url("ADK_TEST_POSTGRES_URL")?;
+        Some(PostgresCheckpointer::new(&url).await.unwrap())
+    });
+}
@@ -0,0 +1,165 @@
+//! Time travel tests: history, forks, edits as a node, replay and diffs
+
+use adk_graph::checkpoint::MemoryCheckpointer;
+use adk_graph::edge::{END, START};
+use adk_graph::error::GraphError;
+use adk_graph::graph::{CompiledGraph, StateGraph};
+use adk_graph::interrupt::Interrupt;
+use adk_graph::node::{ExecutionConfig, NodeOutput};
+use adk_graph::state::{State, ValueChange};
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// draft -> review -> publish, pausing before review; counts publish runs
+fn editorial_graph(published: Arc<AtomicUsize>) -> CompiledGraph {
+    StateGraph::with_channels(&["topic", "draft", "approved", "post"])
+        .add_node_fn("draft", |ctx| async move {
+            let topic = ctx.get("topic").and_then(|v| v.as_str()).unwrap_or_default();
+            Ok(NodeOutput::new().with_update("draft", json!(format!("post on {topic}"))))
+        })
+        .add_node_fn("review", |ctx| async move {
+            let approved = ctx.get("draft").and_then(|v| v.as_str()).is_some();
+            Ok(NodeOutput::new().with_update("approved", json!(approved)))
+        })
+        .add_node_fn("publish", move |ctx| {
+            let published = published.clone();
+            async move {
+                published.fetch_add(1, Ordering::SeqCst);
+                let draft = ctx.get("draft").cloned().unwrap_or_default();
+                Ok(NodeOutput::new().with_update("post", draft))
+            }
+        })
+        .add_edge(START, "draft")
+        .add_edge("draft", "review")
+        .add_edge("review", "publish")
+        .add_edge("publish", END)
+        .compile()
+        .unwrap()
+        .with_checkpointer(MemoryCheckpointer::new())
+        .with_interrupt_before(&["review"])
+}
+
+fn topic(topic: &str) -> State {
+    State::from([("topic".to_string(), json!(topic))])
+}
+
+async fn pause_before_review(graph: &CompiledGraph, thread_id: &str) -> String {
+    let err = graph.invoke(topic("tides"), ExecutionConfig::new(thread_id)).await.unwrap_err();
+    let GraphError::Interrupted(interrupted) = err else {
+        panic!("expected an interrupt, got {err:?}");
+    };
+    assert!(matches!(interrupted.interrupt, Interrupt::Before(ref node) if node == "review"));
+    interrupted.checkpoint_id
+}
+
+#[tokio::test]
+async fn test_history_is_newest_first_and_linked() {
+    let graph = editorial_graph(Arc::new(AtomicUsize::new(0)));
+    let paused = pause_before_review(&graph, "history").await;
+
+    let history = graph.get_state_history("history").await.unwrap();
+
+    assert_eq!(history[0].checkpoint_id, paused);
+    assert_eq!(history[0].pending_nodes, vec!["review"]);
+    // Each checkpoint follows the one after it in the list
+    for pair in history.windows(2) {
+        assert_eq!(pair[0].parent_id(), Some(pair[1].checkpoint_id.as_str()));
+    }
+    assert_eq!(history.last().unwrap().parent_id(), None);
+    assert!(graph.get_state_history("unknown").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_fork_edit_and_run_leaves_original_thread() {
+    let published = Arc::new(AtomicUsize::new(0));
+    let graph = editorial_graph(published.clone());
+    let paused = pause_before_review(&graph, "original").await;
+
+    let fork = graph.fork(&paused, "branch").await.unwrap();
+    assert_eq!(fork.parent_id(), Some(paused.as_str()));
+    graph.update_state("branch", [("draft".to_string(), json!("edited post"))]).await.unwrap();
+    let result = graph.invoke(State::new(), ExecutionConfig::new("branch")).await.unwrap();
+
+    assert_eq!(result.get("post"), Some(&json!("edited post")));
+    assert_eq!(published.load(Ordering::SeqCst), 1);
+    // The original thread is still paused with its own draft
+    let original = graph.get_checkpoint(&paused).await.unwrap();
+    assert_eq!(graph.get_state_history("original").await.unwrap()[0].checkpoint_id, paused);
+    assert_eq!(original.state.get("draft"), Some(&json!("post on tides")));
+}
+
+#[tokio::test]
+async fn test_update_state_as_node_skips_it() {
+    let published = Arc::new(AtomicUsize::new(0));
+    let graph = editorial_graph(published.clone());
+    pause_before_review(&graph, "as-node").await;
+
+    // A human does the review instead of the node
+    let updated = graph
+        .update_state_as("as-node", "review", [("approved".to_string(), json!(false))])
+        .await
+        .unwrap();
+    assert_eq!(updated.pending_nodes, vec!["publish"]);
+
+    let result = graph.invoke(State::new(), ExecutionConfig::new("as-node")).await.unwrap();
+
+    assert_eq!(result.get("approved"), Some(&json!(false)));
+    assert_eq!(result.get("post"), Some(&json!("post on tides")));
+    assert_eq!(published.load(Ordering::SeqCst), 1);
+
+    let err = graph.update_state_as("as-node", "missing", []).await.unwrap_err();
+    assert!(matches!(err, GraphError::NodeNotFound(node) if node == "missing"));
+    let err = graph.update_state_as("no-thread", "review", []).await.unwrap_err();
+    assert!(matches!(err, GraphError::CheckpointNotFound(_)));
+}
+
+#[tokio::test]
+async fn test_replay_from_past_checkpoint() {
+    let published = Arc::new(AtomicUsize::new(0));
+    let graph = editorial_graph(published.clone()).with_interrupt_before(&[]);
+    graph.invoke(topic("tides"), ExecutionConfig::new("replay")).await.unwrap();
+    assert_eq!(published.load(Ordering::SeqCst), 1);
+
+    // The checkpoint saved before publish ran
+    let history = graph.get_state_history("replay").await.unwrap();
+    let before_publish = history.iter().find(|c| c.pending_nodes == ["publish"]).unwrap();
+
+    let result =
+        graph.replay(&before_publish.checkpoint_id, ExecutionConfig::new("replay")).await.unwrap();
+
+    assert_eq!(result.get("post"), Some(&json!("post on tides")));
+    assert_eq!(published.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_diff_checkpoints() {
+    let graph = editorial_graph(Arc::new(AtomicUsize::new(0)));
+    let paused = pause_before_review(&graph, "diff").await;
+    let updated = graph
+        .update_state_as("diff", "review", [("approved".to_string(), json!(true))])
+        .await
+        .unwrap();
+    let first = graph.get_state_history("diff").await.unwrap().pop().unwrap();
+
+    let diff = graph.diff_checkpoints(&paused, &updated.checkpoint_id).await.unwrap();
+    assert_eq!(diff.added.get("approved"), Some(&json!(true)));
+    assert!(diff.removed.is_empty() && diff.changed.is_empty());
+
+    let diff = graph.diff_checkpoints(&first.checkpoint_id, &paused).await.unwrap();
+    assert_eq!(diff.added.get("draft"), Some(&json!("post on tides")));
+
+    let rejected = graph
+        .update_state_as("diff", "review", [("approved".to_string(), json!(false))])
+        .await
+        .unwrap();
+    let diff =
+        graph.diff_checkpoints(&updated.checkpoint_id, &rejected.checkpoint_id).await.unwrap();
+    assert_eq!(
+        diff.changed.get("approved"),
+        Some(&ValueChange { before: json!(true), after: json!(false) })
+    );
+
+    let err = graph.diff_checkpoints(&paused, "missing").await.unwrap_err();
+    assert!(matches!(err, GraphError::CheckpointNotFound(id) if id == "missing"));
+}
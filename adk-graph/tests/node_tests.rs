@@ -94,10 +94,7 @@ async fn test_node_context_methods() {
 #[tokio::test]
 async fn test_node_error_handling() {
     let node = FunctionNode::new("error_node", |_ctx| async move {
-        Err(GraphError::NodeExecutionFailed {
-            node: "error_node".to_string(),
-            message: "Test error".to_string(),
-        })
+        Err(GraphError::node_failed("error_node", "Test error"))
     });
 
     let config = ExecutionConfig::new("test-thread");
@@ -106,7 +103,7 @@ async fn test_node_error_handling() {
 
     assert!(result.is_err());
     match result {
-        Err(GraphError::NodeExecutionFailed { node, message }) => {
+        Err(GraphError::NodeExecutionFailed { node, message, .. }) => {
             assert_eq!(node, "error_node");
             assert_eq!(message, "Test error");
         }
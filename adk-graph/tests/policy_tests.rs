@@ -0,0 +1,205 @@
+//! Retry, timeout and fallback policy tests
+
+use adk_graph::checkpoint::MemoryCheckpointer;
+use adk_graph::edge::{END, START};
+use adk_graph::error::GraphError;
+use adk_graph::graph::StateGraph;
+use adk_graph::node::{ExecutionConfig, NodeOutput};
+use adk_graph::policy::{Backoff, NodePolicy, RetryPolicy};
+use adk_graph::state::State;
+use adk_graph::stream::{StreamEvent, StreamMode};
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn retries(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(max_attempts).with_backoff(Backoff::fixed(Duration::ZERO))
+}
+
+/// fetch -> report, where fetch fails until its `failures` run out
+fn flaky_graph(failures: usize, policy: NodePolicy) -> (StateGraph, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let graph = StateGraph::with_channels(&["data", "report", "error"])
+        .add_node_fn("fetch", move |_ctx| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if call < failures {
+                    return Err(GraphError::node_failed("fetch", format!("unavailable ({call})")));
+                }
+                Ok(NodeOutput::new().with_update("data", json!("fresh")))
+            }
+        })
+        .add_node_fn("report", |ctx| async move {
+            let data = ctx.get("data").cloned().unwrap_or_default();
+            Ok(NodeOutput::new().with_update("report", data))
+        })
+        .add_node_fn("cached", |_ctx| async {
+            Ok(NodeOutput::new().with_update("data", json!("cached")))
+        })
+        .add_edge(START, "fetch")
+        .add_edge("fetch", "report")
+        .add_edge("cached", "report")
+        .add_edge("report", END)
+        .with_node_policy("fetch", policy);
+    (graph, calls)
+}
+
+#[tokio::test]
+async fn test_retry_until_success_reports_attempts() {
+    let (graph, calls) = flaky_graph(2, NodePolicy::new().with_retry(retries(3)));
+    let graph = graph.compile().unwrap();
+
+    let events: Vec<_> = graph
+        .stream(State::new(), ExecutionConfig::new("retry"), StreamMode::Debug)
+        .collect()
+        .await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    let retries: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            Ok(StreamEvent::NodeRetry { node, attempt, max_attempts, error, .. }) => {
+                Some((node.clone(), *attempt, *max_attempts, error.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        retries,
+        vec![
+            (
+                "fetch".to_string(),
+                1,
+                3,
+                "Node 'fetch' execution failed: unavailable (0)".to_string()
+            ),
+            (
+                "fetch".to_string(),
+                2,
+                3,
+                "Node 'fetch' execution failed: unavailable (1)".to_string()
+            ),
+        ]
+    );
+    let Some(Ok(StreamEvent::Done { state, .. })) = events.last() else {
+        panic!("expected the run to finish, got {:?}", events.last());
+    };
+    assert_eq!(state.get("report"), Some(&json!("fresh")));
+}
+
+#[tokio::test]
+async fn test_exhausted_retries_fail_the_run() {
+    let (graph, calls) = flaky_graph(5, NodePolicy::new().with_retry(retries(3)));
+    let graph = graph.compile().unwrap();
+
+    let err = graph.invoke(State::new(), ExecutionConfig::new("fail")).await.unwrap_err();
+    assert!(matches!(&err, GraphError::NodeExecutionFailed { node, .. } if node == "fetch"));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_retry_predicate_skips_permanent_errors() {
+    let policy = NodePolicy::new()
+        .with_retry(retries(5).retry_on(|error| matches!(error, GraphError::NodeTimeout { .. })));
+    let (graph, calls) = flaky_graph(1, policy);
+    let graph = graph.compile().unwrap();
+
+    assert!(graph.invoke(State::new(), ExecutionConfig::new("permanent")).await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_fallback_records_error_and_replaces_successors() {
+    let policy = NodePolicy::new().with_retry(retries(2)).with_fallback("cached");
+    let (graph, calls) = flaky_graph(usize::MAX, policy);
+    let graph = graph.compile().unwrap().with_checkpointer(MemoryCheckpointer::new());
+
+    let events: Vec<_> = graph
+        .stream(State::new(), ExecutionConfig::new("fallback"), StreamMode::Debug)
+        .collect()
+        .await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    let fallback = events.iter().find_map(|event| match event {
+        Ok(StreamEvent::NodeFallback { node, fallback, .. }) => Some((node, fallback)),
+        _ => None,
+    });
+    assert_eq!(fallback, Some((&"fetch".to_string(), &"cached".to_string())));
+
+    // fetch's own successor ran only once, after the fallback
+    let report_runs = events
+        .iter()
+        .filter(|e| matches!(e, Ok(StreamEvent::NodeEnd { node, .. }) if node == "report"))
+        .count();
+    assert_eq!(report_runs, 1);
+
+    let state = graph.get_state("fallback").await.unwrap().unwrap();
+    assert_eq!(state.get("report"), Some(&json!("cached")));
+    assert_eq!(
+        state.get("error"),
+        Some(&json!({"node": "fetch", "message": "unavailable (1)", "attempts": 2}))
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timeout_each_attempt() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let graph = StateGraph::with_channels(&["answer", "failure"])
+        .add_node_fn("slow", move |_ctx| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(NodeOutput::new().with_update("answer", json!("late")))
+            }
+        })
+        .add_node_fn("apologize", |_ctx| async {
+            Ok(NodeOutput::new().with_update("answer", json!("sorry")))
+        })
+        .add_edge(START, "slow")
+        .add_edge("slow", END)
+        .add_edge("apologize", END)
+        .with_node_policy(
+            "slow",
+            NodePolicy::new()
+                .with_timeout(Duration::from_secs(5))
+                .with_retry(
+                    RetryPolicy::new(2).with_backoff(Backoff::fixed(Duration::from_secs(1))),
+                )
+                .with_fallback("apologize")
+                .with_error_channel("failure"),
+        )
+        .compile()
+        .unwrap();
+
+    let result = graph.invoke(State::new(), ExecutionConfig::new("timeout")).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(result.get("answer"), Some(&json!("sorry")));
+    assert_eq!(
+        result.get("failure"),
+        Some(&json!({"node": "slow", "message": "Node 'slow' timed out after 5s", "attempts": 2}))
+    );
+}
+
+#[test]
+fn test_policy_nodes_are_validated() {
+    let unknown_node = StateGraph::with_channels(&[])
+        .add_node_fn("work", |_ctx| async { Ok(NodeOutput::new()) })
+        .add_edge(START, "work")
+        .with_node_policy("missing", NodePolicy::new())
+        .compile();
+    assert!(matches!(unknown_node, Err(GraphError::NodeNotFound(node)) if node == "missing"));
+
+    let unknown_fallback = StateGraph::with_channels(&[])
+        .add_node_fn("work", |_ctx| async { Ok(NodeOutput::new()) })
+        .add_edge(START, "work")
+        .with_node_policy("work", NodePolicy::new().with_fallback("missing"))
+        .compile();
+    assert!(
+        matches!(unknown_fallback, Err(GraphError::EdgeTargetNotFound(node)) if node == "missing")
+    );
+}
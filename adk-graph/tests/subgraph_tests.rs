@@ -0,0 +1,282 @@
+//! Fan-out (map-reduce) and subgraph tests
+
+use adk_graph::checkpoint::{Checkpointer, MemoryCheckpointer};
+use adk_graph::edge::{END, START, SendTo};
+use adk_graph::error::GraphError;
+use adk_graph::graph::{CompiledGraph, StateGraph};
+use adk_graph::interrupt::Interrupt;
+use adk_graph::node::{ExecutionConfig, NodeOutput};
+use adk_graph::state::{State, StateSchema};
+use adk_graph::stream::{StreamEvent, StreamMode};
+use adk_graph::subgraph::SubgraphNode;
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn documents(docs: &[&str]) -> State {
+    State::from([("documents".to_string(), json!(docs))])
+}
+
+/// One send per document
+fn per_document(node: &'static str) -> impl Fn(&State) -> Vec<SendTo> + Send + Sync {
+    move |state| {
+        let docs = state.get("documents").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        docs.into_iter().map(|doc| SendTo::with_value(node, "document", doc)).collect()
+    }
+}
+
+fn map_reduce_schema() -> StateSchema {
+    StateSchema::builder().channel("documents").list_channel("summaries").channel("report").build()
+}
+
+#[tokio::test]
+async fn test_map_reduce_over_documents() {
+    let docs = ["alpha", "beta", "gamma"];
+    // Every summarizer waits for the others, so this only finishes if they run together
+    let barrier = Arc::new(tokio::sync::Barrier::new(docs.len()));
+
+    let graph = StateGraph::new(map_reduce_schema())
+        .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+        .add_node_fn("summarize", move |ctx| {
+            let barrier = barrier.clone();
+            async move {
+                barrier.wait().await;
+                let doc = ctx.get("document").and_then(|v| v.as_str()).unwrap().to_string();
+                Ok(NodeOutput::new().with_update("summaries", json!([doc.to_uppercase()])))
+            }
+        })
+        .add_node_fn("combine", |ctx| async move {
+            let summaries: Vec<String> = ctx.get_as("summaries").unwrap_or_default();
+            Ok(NodeOutput::new().with_update("report", json!(summaries.join(", "))))
+        })
+        .add_edge(START, "split")
+        .add_fan_out_edges("split", per_document("summarize"), &["summarize"])
+        .add_edge("summarize", "combine")
+        .add_edge("combine", END)
+        .compile()
+        .unwrap();
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        graph.invoke(documents(&docs), ExecutionConfig::new("docs")),
+    )
+    .await
+    .expect("summaries should run in parallel")
+    .unwrap();
+
+    // Merged through the append reducer in send order; combine runs once
+    assert_eq!(result.get("summaries"), Some(&json!(["ALPHA", "BETA", "GAMMA"])));
+    assert_eq!(result.get("report"), Some(&json!("ALPHA, BETA, GAMMA")));
+    // Per-send input does not leak into the graph state
+    assert!(!result.contains_key("document"));
+}
+
+#[tokio::test]
+async fn test_fan_out_with_nothing_to_send() {
+    let graph = StateGraph::new(map_reduce_schema())
+        .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+        .add_node_fn("summarize", |_ctx| async {
+            Ok(NodeOutput::new().with_update("summaries", json!(["unexpected"])))
+        })
+        .add_edge(START, "split")
+        .add_fan_out_edges("split", per_document("summarize"), &["summarize"])
+        .add_edge("summarize", END)
+        .compile()
+        .unwrap();
+
+    let result = graph.invoke(documents(&[]), ExecutionConfig::new("empty")).await.unwrap();
+
+    assert_eq!(result.get("summaries"), Some(&json!([])));
+}
+
+#[tokio::test]
+async fn test_fan_out_streams_each_invocation() {
+    let graph = StateGraph::new(map_reduce_schema())
+        .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+        .add_node_fn("summarize", |ctx| async move {
+            let doc = ctx.get("document").cloned().unwrap();
+            Ok(NodeOutput::new().with_update("summaries", json!([doc])))
+        })
+        .add_edge(START, "split")
+        .add_fan_out_edges("split", per_document("summarize"), &["summarize"])
+        .add_edge("summarize", END)
+        .compile()
+        .unwrap();
+
+    let events: Vec<_> = graph
+        .stream(documents(&["a", "b"]), ExecutionConfig::new("stream"), StreamMode::Debug)
+        .collect()
+        .await;
+
+    let ended = events
+        .iter()
+        .filter(|e| matches!(e, Ok(StreamEvent::NodeEnd { node, .. }) if node == "summarize"))
+        .count();
+    assert_eq!(ended, 2);
+    let Some(Ok(StreamEvent::Done { state, .. })) = events.last() else {
+        panic!("expected Done, got {:?}", events.last());
+    };
+    assert_eq!(state.get("summaries"), Some(&json!(["a", "b"])));
+}
+
+/// Child graph with its own schema: drafts a reply to `query`
+fn drafting_graph() -> CompiledGraph {
+    StateGraph::with_channels(&["query", "draft"])
+        .add_node_fn("draft", |ctx| async move {
+            let query = ctx.get("query").and_then(|v| v.as_str()).unwrap_or_default();
+            Ok(NodeOutput::new().with_update("draft", json!(format!("draft for {query}"))))
+        })
+        .add_edge(START, "draft")
+        .add_edge("draft", END)
+        .compile()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_subgraph_with_channel_mapping() {
+    let graph = StateGraph::with_channels(&["question", "answer"])
+        .add_node(
+            SubgraphNode::new("drafting", drafting_graph())
+                .map_input("question", "query")
+                .map_output("draft", "answer"),
+        )
+        .add_edge(START, "drafting")
+        .add_edge("drafting", END)
+        .compile()
+        .unwrap();
+
+    let input = State::from([("question".to_string(), json!("tides"))]);
+    let result = graph.invoke(input, ExecutionConfig::new("parent")).await.unwrap();
+
+    assert_eq!(result.get("answer"), Some(&json!("draft for tides")));
+    // Child-only channels stay in the child
+    assert!(!result.contains_key("query") && !result.contains_key("draft"));
+}
+
+#[tokio::test]
+async fn test_fan_out_to_subgraph() {
+    let checkpointer = Arc::new(MemoryCheckpointer::new());
+    let child = drafting_graph().with_checkpointer_arc(checkpointer.clone());
+
+    let graph = StateGraph::new(
+        StateSchema::builder().channel("documents").list_channel("summaries").build(),
+    )
+    .add_node_fn("split", |_ctx| async { Ok(NodeOutput::new()) })
+    .add_node(
+        SubgraphNode::new("drafting", child)
+            .map_input("document", "query")
+            .map_output("draft", "summaries"),
+    )
+    .add_edge(START, "split")
+    .add_fan_out_edges("split", per_document("drafting"), &["drafting"])
+    .add_edge("drafting", END)
+    .compile()
+    .unwrap();
+
+    let result = graph.invoke(documents(&["a", "b"]), ExecutionConfig::new("run")).await.unwrap();
+
+    assert_eq!(result.get("summaries"), Some(&json!(["draft for a", "draft for b"])));
+    // Each invocation checkpointed on its own child thread
+    let second = checkpointer.load("run:drafting:1").await.unwrap().unwrap();
+    assert_eq!(second.state.get("draft"), Some(&json!("draft for b")));
+}
+
+#[tokio::test]
+async fn test_interrupt_and_resume_inside_subgraph() {
+    let drafts = Arc::new(AtomicUsize::new(0));
+    let drafted = drafts.clone();
+    let child = Arc::new(
+        StateGraph::with_channels(&["topic", "draft", "approved"])
+            .add_node_fn("draft", move |ctx| {
+                let drafted = drafted.clone();
+                async move {
+                    if ctx.get("draft").is_some() {
+                        return Ok(NodeOutput::new());
+                    }
+                    drafted.fetch_add(1, Ordering::SeqCst);
+                    let topic = ctx.get("topic").and_then(|v| v.as_str()).unwrap_or_default();
+                    Ok(NodeOutput::new().with_update("draft", json!(format!("post on {topic}"))))
+                }
+            })
+            .add_node_fn("review", |ctx| async move {
+                if ctx.get("approved") != Some(&json!(true)) {
+                    return Ok(NodeOutput::interrupt("approve the draft"));
+                }
+                Ok(NodeOutput::new())
+            })
+            .add_edge(START, "draft")
+            .add_edge("draft", "review")
+            .add_edge("review", END)
+            .compile()
+            .unwrap()
+            .with_checkpointer(MemoryCheckpointer::new()),
+    );
+
+    let graph = StateGraph::with_channels(&["topic", "approved", "post"])
+        .add_node(
+            SubgraphNode::new("writer", child.clone())
+                .map_input("topic", "topic")
+                .map_input("approved", "approved")
+                .map_output("draft", "post"),
+        )
+        .add_node_fn("publish", |ctx| async move {
+            let post = ctx.get("post").and_then(|v| v.as_str()).unwrap_or_default();
+            Ok(NodeOutput::new().with_update("post", json!(format!("published: {post}"))))
+        })
+        .add_edge(START, "writer")
+        .add_edge("writer", "publish")
+        .add_edge("publish", END)
+        .compile()
+        .unwrap()
+        .with_checkpointer(MemoryCheckpointer::new());
+
+    let input = State::from([("topic".to_string(), json!("tides"))]);
+    let err = graph.invoke(input, ExecutionConfig::new("blog")).await.unwrap_err();
+
+    let GraphError::Interrupted(interrupted) = err else {
+        panic!("expected the subgraph interrupt, got {err:?}");
+    };
+    assert!(matches!(
+        &interrupted.interrupt,
+        Interrupt::Dynamic { message, .. } if message == "approve the draft"
+    ));
+    assert_eq!(interrupted.thread_id, "blog");
+    let child_state = child.get_state("blog:writer").await.unwrap().unwrap();
+    assert_eq!(child_state.get("draft"), Some(&json!("post on tides")));
+
+    // Approve on the parent thread and resume
+    graph.update_state("blog", [("approved".to_string(), json!(true))]).await.unwrap();
+    let result = graph.invoke(State::new(), ExecutionConfig::new("blog")).await.unwrap();
+
+    assert_eq!(result.get("post"), Some(&json!("published: post on tides")));
+    // The child resumed from its checkpoint instead of drafting again
+    assert_eq!(drafts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_subgraph_errors_name_the_subgraph() {
+    let child = StateGraph::with_channels(&[])
+        .add_node_fn("fetch", |_ctx| async {
+            Err(GraphError::node_failed("fetch", "connection refused"))
+        })
+        .add_edge(START, "fetch")
+        .add_edge("fetch", END)
+        .compile()
+        .unwrap();
+
+    let graph = StateGraph::with_channels(&[])
+        .add_node(SubgraphNode::new("research", child))
+        .add_edge(START, "research")
+        .add_edge("research", END)
+        .compile()
+        .unwrap();
+
+    let err = graph.invoke(State::new(), ExecutionConfig::new("t")).await.unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Node 'research' execution failed: Node 'fetch' execution failed: connection refused"
+    );
+}
@@ -99,6 +99,19 @@ impl Guardrail for PiiRedactor {
                         new_parts.push(part.clone());
                     }
                 }
+                Part::Thinking { thinking, signature } => {
+                    let (redacted, found) = self.redact(thinking);
+                    if !found.is_empty() {
+                        any_redacted = true;
+                        redacted_types.extend(found);
+                        new_parts.push(Part::Thinking {
+                            thinking: redacted,
+                            signature: signature.clone(),
+                        });
+                    } else {
+                        new_parts.push(part.clone());
+                    }
+                }
                 _ => new_parts.push(part.clone()),
             }
         }
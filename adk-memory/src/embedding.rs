@@ -0,0 +1,125 @@
+//! Text embedding for semantic memory.
+//!
+//! [`SemanticMemoryService`](crate::SemanticMemoryService) turns memories and queries
+//! into vectors with an [`Embedder`]. [`HashingEmbedder`] needs no model and is
+//! deterministic, which suits tests; model-backed embedders live behind features
+//! (`gemini`) or in their provider crates (`adk-mistralrs`).
+
+use adk_core::Result;
+use async_trait::async_trait;
+
+#[cfg(feature = "gemini")]
+mod gemini;
+
+#[cfg(feature = "gemini")]
+pub use gemini::GeminiEmbedder;
+
+/// Turns text into dense vectors whose cosine similarity reflects relatedness.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Embed stored documents. Returns one vector per text, all of the same dimension.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embed a search query. Models that embed queries and documents differently
+    /// override this; the default embeds it like a document.
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        let mut vectors = self.embed(&[query.to_string()]).await?;
+        vectors
+            .pop()
+            .ok_or_else(|| adk_core::AdkError::Memory("embedder returned no vector".into()))
+    }
+}
+
+/// Deterministic embedder that hashes word stems into a fixed number of buckets.
+///
+/// Texts sharing words (after lowercasing and stripping common English suffixes) get
+/// similar vectors, so it ranks by lexical overlap rather than meaning. Use it in tests
+/// and offline setups; use a model-backed [`Embedder`] for real semantic search.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    /// Default number of buckets.
+    pub const DEFAULT_DIMENSIONS: usize = 256;
+
+    pub fn new(dimensions: usize) -> Self {
+        assert!(dimensions > 0, "HashingEmbedder needs at least one dimension");
+        Self { dimensions }
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Embed one text synchronously.
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+            let hash = fnv1a(stem(&word.to_lowercase()).as_bytes());
+            let bucket = (hash % self.dimensions as u64) as usize;
+            // The top hash bit picks the sign, so colliding words tend to cancel out
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign;
+        }
+        vector
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DIMENSIONS)
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+}
+
+/// Strips common English inflections so "walks", "walked" and "walk" share a bucket.
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.chars().count() >= 3 {
+                return stem;
+            }
+        }
+    }
+    word
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("walked"), "walk");
+        assert_eq!(stem("walks"), "walk");
+        assert_eq!(stem("is"), "is");
+        assert_eq!(stem("bus"), "bus");
+    }
+
+    #[test]
+    fn test_hashing_is_deterministic() {
+        let embedder = HashingEmbedder::new(64);
+        let a = embedder.embed_text("The user prefers dark mode");
+        assert_eq!(a, embedder.embed_text("the USER prefers dark mode!"));
+        assert_eq!(a.len(), 64);
+        assert!(embedder.embed_text("").iter().all(|v| *v == 0.0));
+    }
+}
@@ -0,0 +1,76 @@
+use super::Embedder;
+use adk_core::{AdkError, Result};
+use adk_gemini::{Gemini, Model, TaskType};
+use async_trait::async_trait;
+
+/// [`Embedder`] backed by the Gemini embedding API.
+///
+/// Documents are embedded for retrieval and queries as retrieval queries, as the API
+/// recommends for search.
+#[derive(Clone)]
+pub struct GeminiEmbedder {
+    client: Gemini,
+    output_dimensionality: Option<i32>,
+}
+
+impl GeminiEmbedder {
+    /// Embed with `text-embedding-004`.
+    pub fn new(api_key: impl AsRef<str>) -> Result<Self> {
+        Self::with_model(api_key, Model::TextEmbedding004)
+    }
+
+    pub fn with_model(api_key: impl AsRef<str>, model: impl Into<Model>) -> Result<Self> {
+        let client = Gemini::with_model(api_key, model)
+            .map_err(|e| AdkError::Memory(format!("failed to create Gemini client: {}", e)))?;
+        Ok(Self::from_client(client))
+    }
+
+    /// Use an existing client; its model must be an embedding model.
+    pub fn from_client(client: Gemini) -> Self {
+        Self { client, output_dimensionality: None }
+    }
+
+    /// Truncate embeddings to `dimensions` values.
+    pub fn with_output_dimensionality(mut self, dimensions: i32) -> Self {
+        self.output_dimensionality = Some(dimensions);
+        self
+    }
+}
+
+#[async_trait]
+impl Embedder for GeminiEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut request = self
+            .client
+            .embed_content()
+            .with_chunks(texts.to_vec())
+            .with_task_type(TaskType::RetrievalDocument);
+        if let Some(dimensions) = self.output_dimensionality {
+            request = request.with_output_dimensionality(dimensions);
+        }
+
+        let response = request
+            .execute_batch()
+            .await
+            .map_err(|e| AdkError::Memory(format!("Gemini embedding failed: {}", e)))?;
+        Ok(response.embeddings.into_iter().map(|embedding| embedding.values).collect())
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        let mut request =
+            self.client.embed_content().with_text(query).with_task_type(TaskType::RetrievalQuery);
+        if let Some(dimensions) = self.output_dimensionality {
+            request = request.with_output_dimensionality(dimensions);
+        }
+
+        let response = request
+            .execute()
+            .await
+            .map_err(|e| AdkError::Memory(format!("Gemini embedding failed: {}", e)))?;
+        Ok(response.embedding.values)
+    }
+}
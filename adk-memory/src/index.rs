@@ -0,0 +1,106 @@
+//! Exact nearest-neighbour search over normalized vectors.
+
+/// In-process vector index ranking items by cosine similarity.
+///
+/// Vectors are normalized on insert and searched exhaustively, which is exact and fast
+/// enough for the tens of thousands of memories a single app or user accumulates.
+#[derive(Debug, Clone)]
+pub struct VectorIndex<T> {
+    entries: Vec<(Vec<f32>, T)>,
+}
+
+impl<T> VectorIndex<T> {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Add `item` under `vector`. Zero vectors are kept but never match.
+    pub fn insert(&mut self, vector: Vec<f32>, item: T) {
+        self.entries.push((normalize(vector), item));
+    }
+
+    /// Remove every item for which `remove` returns true. Returns how many went.
+    pub fn remove_where(&mut self, mut remove: impl FnMut(&T) -> bool) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|(_, item)| !remove(item));
+        before - self.entries.len()
+    }
+
+    /// All items, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter().map(|(_, item)| item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The `k` items most similar to `query` that pass `filter` and score above
+    /// `min_score`, best first, with their cosine similarity.
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        min_score: f32,
+        mut filter: impl FnMut(&T) -> bool,
+    ) -> Vec<(f32, &T)> {
+        let query = normalize(query.to_vec());
+        let mut hits: Vec<(f32, &T)> = self
+            .entries
+            .iter()
+            .filter(|(vector, _)| vector.len() == query.len())
+            .map(|(vector, item)| (dot(vector, &query), item))
+            .filter(|(score, item)| *score > min_score && filter(item))
+            .collect();
+        hits.sort_by(|a, b| b.0.total_cmp(&a.0));
+        hits.truncate(k);
+        hits
+    }
+}
+
+impl<T> Default for VectorIndex<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = dot(&vector, &vector).sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_ranks_by_cosine_similarity() {
+        let mut index = VectorIndex::new();
+        index.insert(vec![1.0, 0.0], "east");
+        index.insert(vec![0.0, 2.0], "north");
+        index.insert(vec![1.0, 1.0], "north-east");
+        index.insert(vec![0.0, 0.0], "nowhere");
+
+        let hits = index.search(&[3.0, 0.5], 10, 0.0, |_| true);
+        let items: Vec<_> = hits.iter().map(|(_, item)| **item).collect();
+        assert_eq!(items, ["east", "north-east", "north"]);
+        assert!((hits[0].0 - 0.986).abs() < 1e-3);
+
+        let hits = index.search(&[3.0, 0.5], 1, 0.0, |item| *item != "east");
+        assert_eq!(*hits[0].1, "north-east");
+
+        assert_eq!(index.remove_where(|item| item.starts_with("north")), 2);
+        assert_eq!(index.iter().copied().collect::<Vec<_>>(), ["east", "nowhere"]);
+    }
+}
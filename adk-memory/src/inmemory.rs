@@ -1,6 +1,7 @@
 use crate::service::*;
 use adk_core::{Part, Result};
 use async_trait::async_trait;
+use chrono::Utc;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
@@ -12,11 +13,13 @@ struct MemoryKey {
 
 #[derive(Clone)]
 struct StoredEntry {
+    /// Session the entry was remembered from; `None` for entries added directly.
+    session_id: Option<String>,
     entry: MemoryEntry,
     words: HashSet<String>,
 }
 
-type MemoryStore = HashMap<MemoryKey, HashMap<String, Vec<StoredEntry>>>;
+type MemoryStore = HashMap<MemoryKey, Vec<StoredEntry>>;
 
 pub struct InMemoryMemoryService {
     store: Arc<RwLock<MemoryStore>>,
@@ -41,6 +44,34 @@ impl InMemoryMemoryService {
         words
     }
 
+    fn key(app_name: &str, user_id: &str) -> MemoryKey {
+        MemoryKey { app_name: app_name.to_string(), user_id: user_id.to_string() }
+    }
+
+    /// Index `entries` for search, dropping those without any text.
+    fn stored(session_id: Option<&str>, entries: Vec<MemoryEntry>) -> Vec<StoredEntry> {
+        entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.ensure_id();
+                let words = Self::extract_words_from_content(&entry.content);
+                StoredEntry { session_id: session_id.map(str::to_string), entry, words }
+            })
+            .filter(|e| !e.words.is_empty())
+            .collect()
+    }
+
+    /// Remove the entries of `key` matching `remove`, returning how many went.
+    fn remove_where(&self, key: &MemoryKey, remove: impl Fn(&StoredEntry) -> bool) -> usize {
+        let mut store = self.store.write().unwrap();
+        let Some(entries) = store.get_mut(key) else {
+            return 0;
+        };
+        let before = entries.len();
+        entries.retain(|stored| !remove(stored));
+        before - entries.len()
+    }
+
     fn has_intersection(set1: &HashSet<String>, set2: &HashSet<String>) -> bool {
         if set1.is_empty() || set2.is_empty() {
             return false;
@@ -64,48 +95,107 @@ impl MemoryService for InMemoryMemoryService {
         session_id: &str,
         entries: Vec<MemoryEntry>,
     ) -> Result<()> {
-        let key = MemoryKey { app_name: app_name.to_string(), user_id: user_id.to_string() };
+        let stored_entries = Self::stored(Some(session_id), entries);
 
-        let stored_entries: Vec<StoredEntry> = entries
-            .into_iter()
-            .map(|entry| {
-                let words = Self::extract_words_from_content(&entry.content);
-                StoredEntry { entry, words }
+        let mut store = self.store.write().unwrap();
+        let user_entries = store.entry(Self::key(app_name, user_id)).or_default();
+        user_entries.retain(|stored| stored.session_id.as_deref() != Some(session_id));
+        user_entries.extend(stored_entries);
+
+        Ok(())
+    }
+
+    async fn search(&self, req: SearchRequest) -> Result<SearchResponse> {
+        let query_words = Self::extract_words(&req.query);
+
+        let store = self.store.read().unwrap();
+        let entries = match store.get(&Self::key(&req.app_name, &req.user_id)) {
+            Some(e) => e,
+            None => return Ok(SearchResponse::default()),
+        };
+
+        let memories = entries
+            .iter()
+            .filter(|stored| {
+                Self::has_intersection(&stored.words, &query_words) && req.matches(&stored.entry)
             })
-            .filter(|e| !e.words.is_empty())
+            .map(|stored| stored.entry.clone())
+            .take(req.limit.unwrap_or(usize::MAX))
             .collect();
 
-        if stored_entries.is_empty() {
-            return Ok(());
-        }
+        Ok(SearchResponse { memories, scores: Vec::new() })
+    }
+
+    async fn add(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        entries: Vec<MemoryEntry>,
+    ) -> Result<Vec<String>> {
+        let stored_entries = Self::stored(None, entries);
+        let ids = stored_entries.iter().map(|stored| stored.entry.id.clone()).collect();
 
         let mut store = self.store.write().unwrap();
-        let sessions = store.entry(key).or_default();
-        sessions.insert(session_id.to_string(), stored_entries);
+        store.entry(Self::key(app_name, user_id)).or_default().extend(stored_entries);
+
+        Ok(ids)
+    }
 
+    async fn list(&self, req: ListRequest) -> Result<Vec<MemoryEntry>> {
+        let now = Utc::now();
+        let store = self.store.read().unwrap();
+        let mut memories: Vec<MemoryEntry> = store
+            .get(&Self::key(&req.app_name, &req.user_id))
+            .into_iter()
+            .flatten()
+            .filter(|stored| req.session_id.is_none() || stored.session_id == req.session_id)
+            .filter(|stored| !stored.entry.is_expired(now))
+            .map(|stored| stored.entry.clone())
+            .collect();
+        memories.sort_by_key(|entry| entry.timestamp);
+        Ok(memories)
+    }
+
+    async fn update(&self, app_name: &str, user_id: &str, entry: MemoryEntry) -> Result<()> {
+        let mut store = self.store.write().unwrap();
+        let stored = store
+            .get_mut(&Self::key(app_name, user_id))
+            .and_then(|entries| entries.iter_mut().find(|stored| stored.entry.id == entry.id))
+            .ok_or_else(|| entry_not_found(&entry.id))?;
+        stored.words = Self::extract_words_from_content(&entry.content);
+        stored.entry = entry;
         Ok(())
     }
 
-    async fn search(&self, req: SearchRequest) -> Result<SearchResponse> {
-        let query_words = Self::extract_words(&req.query);
+    async fn delete(&self, app_name: &str, user_id: &str, id: &str) -> Result<bool> {
+        Ok(self.remove_where(&Self::key(app_name, user_id), |stored| stored.entry.id == id) > 0)
+    }
 
-        let key = MemoryKey { app_name: req.app_name, user_id: req.user_id };
+    async fn delete_session(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<usize> {
+        Ok(self.remove_where(&Self::key(app_name, user_id), |stored| {
+            stored.session_id.as_deref() == Some(session_id)
+        }))
+    }
 
-        let store = self.store.read().unwrap();
-        let sessions = match store.get(&key) {
-            Some(s) => s,
-            None => return Ok(SearchResponse { memories: Vec::new() }),
-        };
+    async fn delete_user(&self, app_name: &str, user_id: &str) -> Result<usize> {
+        let removed = self.store.write().unwrap().remove(&Self::key(app_name, user_id));
+        Ok(removed.map_or(0, |entries| entries.len()))
+    }
 
-        let mut memories = Vec::new();
-        for stored_entries in sessions.values() {
-            for stored in stored_entries {
-                if Self::has_intersection(&stored.words, &query_words) {
-                    memories.push(stored.entry.clone());
-                }
-            }
+    async fn purge_expired(&self) -> Result<usize> {
+        let now = Utc::now();
+        let mut store = self.store.write().unwrap();
+        let mut purged = 0;
+        for entries in store.values_mut() {
+            let before = entries.len();
+            entries.retain(|stored| !stored.entry.is_expired(now));
+            purged += before - entries.len();
         }
-
-        Ok(SearchResponse { memories })
+        Ok(purged)
     }
 }
@@ -6,8 +6,11 @@
 //!
 //! This crate provides long-term memory capabilities:
 //!
-//! - [`InMemoryMemoryService`] - Simple in-memory memory storage
+//! - [`InMemoryMemoryService`] - Simple in-memory memory storage with keyword matching
+//! - [`SemanticMemoryService`] - Ranked search over embeddings in a local vector index
+//! - [`Embedder`] - Trait for embedding providers, with the deterministic [`HashingEmbedder`]
 //! - [`MemoryService`] - Trait for custom backends
+//! - [`ScopedMemory`] - User-scoped `adk_core::Memory` over any service
 //! - [`MemoryEntry`] - Structured memory with metadata
 //!
 //! ## Quick Start
@@ -24,12 +27,25 @@
 //! ## Features
 //!
 //! - Per-user memory isolation
-//! - Semantic search queries
-//! - Metadata filtering
+//! - Semantic search queries with relevance scores
+//! - Limit, time range and author filters
+//! - Listing, updating and deleting entries by id, session or user
+//! - Per-entry expiry with [`MemoryService::purge_expired`]
 //! - Automatic context injection
+//! - `gemini` feature: [`GeminiEmbedder`](embedding::GeminiEmbedder) for Gemini embedding models
 
+pub mod embedding;
+pub mod index;
 pub mod inmemory;
+pub mod scoped;
+pub mod semantic;
 pub mod service;
 
+#[cfg(feature = "gemini")]
+pub use embedding::GeminiEmbedder;
+pub use embedding::{Embedder, HashingEmbedder};
+pub use index::VectorIndex;
 pub use inmemory::InMemoryMemoryService;
-pub use service::{MemoryEntry, MemoryService, SearchRequest, SearchResponse};
+pub use scoped::ScopedMemory;
+pub use semantic::SemanticMemoryService;
+pub use service::{ListRequest, MemoryEntry, MemoryService, SearchRequest, SearchResponse};
@@ -0,0 +1,70 @@
+use crate::service::{MemoryEntry, MemoryService, SearchRequest};
+use adk_core::{Memory, Result};
+use async_trait::async_trait;
+use chrono::Duration;
+use std::sync::Arc;
+
+/// Scoped wrapper around [`MemoryService`] that binds app and user.
+///
+/// Implements the simple `adk_core::Memory` trait that agents and tools see, so it can
+/// be passed as the runner's memory. Entries added through it belong to no session.
+///
+/// ```rust,no_run
+/// use adk_memory::{InMemoryMemoryService, ScopedMemory};
+/// use std::sync::Arc;
+///
+/// let memory = ScopedMemory::new(Arc::new(InMemoryMemoryService::new()), "my_app", "user_123")
+///     .with_ttl(chrono::Duration::days(90));
+/// ```
+pub struct ScopedMemory {
+    service: Arc<dyn MemoryService>,
+    app_name: String,
+    user_id: String,
+    ttl: Option<Duration>,
+}
+
+impl ScopedMemory {
+    pub fn new(
+        service: Arc<dyn MemoryService>,
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+    ) -> Self {
+        Self { service, app_name: app_name.into(), user_id: user_id.into(), ttl: None }
+    }
+
+    /// Expire entries added through this wrapper `ttl` after they are added.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+}
+
+#[async_trait]
+impl Memory for ScopedMemory {
+    async fn search(&self, query: &str) -> Result<Vec<adk_core::MemoryEntry>> {
+        let response =
+            self.service.search(SearchRequest::new(&self.app_name, &self.user_id, query)).await?;
+        Ok(response
+            .memories
+            .into_iter()
+            .map(|entry| adk_core::MemoryEntry {
+                id: Some(entry.id),
+                content: entry.content,
+                author: entry.author,
+            })
+            .collect())
+    }
+
+    async fn add(&self, entry: adk_core::MemoryEntry) -> Result<String> {
+        let mut memory = MemoryEntry::new(entry.author, entry.content);
+        if let Some(ttl) = self.ttl {
+            memory = memory.with_ttl(ttl);
+        }
+        let mut ids = self.service.add(&self.app_name, &self.user_id, vec![memory]).await?;
+        ids.pop().ok_or_else(|| adk_core::AdkError::Memory("memory entry has no text".into()))
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        self.service.delete(&self.app_name, &self.user_id, id).await
+    }
+}
@@ -0,0 +1,250 @@
+use crate::embedding::Embedder;
+use crate::index::VectorIndex;
+use crate::service::*;
+use adk_core::{AdkError, Content, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct MemoryKey {
+    app_name: String,
+    user_id: String,
+}
+
+struct IndexedMemory {
+    /// Session the entry was remembered from; `None` for entries added directly.
+    session_id: Option<String>,
+    entry: MemoryEntry,
+}
+
+/// [`MemoryService`] that ranks memories by embedding similarity to the query.
+///
+/// Entries are embedded once when their session is added and kept in a per-user
+/// [`VectorIndex`]. Searches return the top matches with their cosine similarity in
+/// [`SearchResponse::scores`].
+///
+/// ```rust,no_run
+/// use adk_memory::{HashingEmbedder, MemoryService, SearchRequest, SemanticMemoryService};
+///
+/// # async fn example() -> adk_core::Result<()> {
+/// let service = SemanticMemoryService::new(HashingEmbedder::default()).with_default_limit(5);
+/// let response = service
+///     .search(SearchRequest::new("my_app", "user_123", "theme preference").with_author("user"))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SemanticMemoryService {
+    embedder: Arc<dyn Embedder>,
+    indexes: RwLock<HashMap<MemoryKey, VectorIndex<IndexedMemory>>>,
+    default_limit: usize,
+    min_score: f32,
+}
+
+impl SemanticMemoryService {
+    /// Default number of memories a search returns when the request sets no limit.
+    pub const DEFAULT_LIMIT: usize = 10;
+
+    pub fn new(embedder: impl Embedder + 'static) -> Self {
+        Self::with_embedder(Arc::new(embedder))
+    }
+
+    pub fn with_embedder(embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            embedder,
+            indexes: RwLock::new(HashMap::new()),
+            default_limit: Self::DEFAULT_LIMIT,
+            min_score: 0.0,
+        }
+    }
+
+    /// Number of memories returned when a request sets no limit.
+    pub fn with_default_limit(mut self, limit: usize) -> Self {
+        self.default_limit = limit;
+        self
+    }
+
+    /// Drop matches whose similarity is not above `min_score` (default 0.0).
+    pub fn with_min_score(mut self, min_score: f32) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    fn content_text(content: &Content) -> String {
+        content.parts.iter().filter_map(|part| part.text()).collect::<Vec<_>>().join("\n")
+    }
+
+    fn key(app_name: &str, user_id: &str) -> MemoryKey {
+        MemoryKey { app_name: app_name.to_string(), user_id: user_id.to_string() }
+    }
+
+    /// Embed the entries that have text, giving each an id if it has none.
+    async fn embed_entries(
+        &self,
+        entries: Vec<MemoryEntry>,
+    ) -> Result<Vec<(Vec<f32>, MemoryEntry)>> {
+        let (texts, entries): (Vec<String>, Vec<MemoryEntry>) = entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.ensure_id();
+                (Self::content_text(&entry.content), entry)
+            })
+            .filter(|(text, _)| !text.trim().is_empty())
+            .unzip();
+
+        let vectors =
+            if texts.is_empty() { Vec::new() } else { self.embedder.embed(&texts).await? };
+        if vectors.len() != entries.len() {
+            return Err(AdkError::Memory(format!(
+                "embedder returned {} vectors for {} entries",
+                vectors.len(),
+                entries.len()
+            )));
+        }
+        Ok(vectors.into_iter().zip(entries).collect())
+    }
+
+    /// Remove the entries of `key` matching `remove`, returning how many went.
+    fn remove_where(&self, key: &MemoryKey, remove: impl FnMut(&IndexedMemory) -> bool) -> usize {
+        let mut indexes = self.indexes.write().unwrap();
+        indexes.get_mut(key).map_or(0, |index| index.remove_where(remove))
+    }
+}
+
+#[async_trait]
+impl MemoryService for SemanticMemoryService {
+    async fn add_session(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+        entries: Vec<MemoryEntry>,
+    ) -> Result<()> {
+        let embedded = self.embed_entries(entries).await?;
+
+        let mut indexes = self.indexes.write().unwrap();
+        let index = indexes.entry(Self::key(app_name, user_id)).or_default();
+        // Adding a session again replaces what was remembered from it
+        index.remove_where(|memory| memory.session_id.as_deref() == Some(session_id));
+        for (vector, entry) in embedded {
+            index.insert(vector, IndexedMemory { session_id: Some(session_id.to_string()), entry });
+        }
+
+        Ok(())
+    }
+
+    async fn search(&self, req: SearchRequest) -> Result<SearchResponse> {
+        let key = Self::key(&req.app_name, &req.user_id);
+        if self.indexes.read().unwrap().get(&key).is_none_or(|index| index.is_empty()) {
+            return Ok(SearchResponse::default());
+        }
+
+        let query = self.embedder.embed_query(&req.query).await?;
+
+        let indexes = self.indexes.read().unwrap();
+        let Some(index) = indexes.get(&key) else {
+            return Ok(SearchResponse::default());
+        };
+        let limit = req.limit.unwrap_or(self.default_limit);
+        let (scores, memories) = index
+            .search(&query, limit, self.min_score, |memory| req.matches(&memory.entry))
+            .into_iter()
+            .map(|(score, memory)| (score, memory.entry.clone()))
+            .unzip();
+
+        Ok(SearchResponse { memories, scores })
+    }
+
+    async fn add(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        entries: Vec<MemoryEntry>,
+    ) -> Result<Vec<String>> {
+        let embedded = self.embed_entries(entries).await?;
+        let ids = embedded.iter().map(|(_, entry)| entry.id.clone()).collect();
+
+        let mut indexes = self.indexes.write().unwrap();
+        let index = indexes.entry(Self::key(app_name, user_id)).or_default();
+        for (vector, entry) in embedded {
+            index.insert(vector, IndexedMemory { session_id: None, entry });
+        }
+
+        Ok(ids)
+    }
+
+    async fn list(&self, req: ListRequest) -> Result<Vec<MemoryEntry>> {
+        let now = Utc::now();
+        let indexes = self.indexes.read().unwrap();
+        let mut memories: Vec<MemoryEntry> = indexes
+            .get(&Self::key(&req.app_name, &req.user_id))
+            .into_iter()
+            .flat_map(|index| index.iter())
+            .filter(|memory| req.session_id.is_none() || memory.session_id == req.session_id)
+            .filter(|memory| !memory.entry.is_expired(now))
+            .map(|memory| memory.entry.clone())
+            .collect();
+        memories.sort_by_key(|entry| entry.timestamp);
+        Ok(memories)
+    }
+
+    async fn update(&self, app_name: &str, user_id: &str, entry: MemoryEntry) -> Result<()> {
+        let key = Self::key(app_name, user_id);
+        let id = entry.id.clone();
+        let session_of = |indexes: &HashMap<MemoryKey, VectorIndex<IndexedMemory>>| {
+            indexes
+                .get(&key)
+                .and_then(|index| index.iter().find(|memory| memory.entry.id == id))
+                .map(|memory| memory.session_id.clone())
+        };
+        if session_of(&self.indexes.read().unwrap()).is_none() {
+            return Err(entry_not_found(&id));
+        }
+
+        // The text may have changed, so the entry is embedded again
+        let (vector, entry) = self
+            .embed_entries(vec![entry])
+            .await?
+            .pop()
+            .ok_or_else(|| AdkError::Memory(format!("memory entry {id} has no text")))?;
+
+        let mut indexes = self.indexes.write().unwrap();
+        // Deleted while embedding
+        let session_id = session_of(&indexes).ok_or_else(|| entry_not_found(&id))?;
+        let index = indexes.entry(key.clone()).or_default();
+        index.remove_where(|memory| memory.entry.id == id);
+        index.insert(vector, IndexedMemory { session_id, entry });
+        Ok(())
+    }
+
+    async fn delete(&self, app_name: &str, user_id: &str, id: &str) -> Result<bool> {
+        Ok(self.remove_where(&Self::key(app_name, user_id), |memory| memory.entry.id == id) > 0)
+    }
+
+    async fn delete_session(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<usize> {
+        Ok(self.remove_where(&Self::key(app_name, user_id), |memory| {
+            memory.session_id.as_deref() == Some(session_id)
+        }))
+    }
+
+    async fn delete_user(&self, app_name: &str, user_id: &str) -> Result<usize> {
+        let removed = self.indexes.write().unwrap().remove(&Self::key(app_name, user_id));
+        Ok(removed.map_or(0, |index| index.len()))
+    }
+
+    async fn purge_expired(&self) -> Result<usize> {
+        let now = Utc::now();
+        let mut indexes = self.indexes.write().unwrap();
+        Ok(indexes
+            .values_mut()
+            .map(|index| index.remove_where(|memory| memory.entry.is_expired(now)))
+            .sum())
+    }
+}
@@ -1,28 +1,163 @@
-use adk_core::{Content, Result};
+use adk_core::{AdkError, Content, Result};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 
 #[derive(Debug, Clone)]
 pub struct MemoryEntry {
+    /// Identifies the entry within its user's memories. Services assign an id to
+    /// entries added with an empty one.
+    pub id: String,
     pub content: Content,
     pub author: String,
     pub timestamp: DateTime<Utc>,
+    /// When set, the entry is no longer returned after this time and is removed by
+    /// [`MemoryService::purge_expired`].
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone)]
+impl MemoryEntry {
+    /// A memory recorded now, with a fresh id and no expiry.
+    pub fn new(author: impl Into<String>, content: Content) -> Self {
+        Self {
+            id: new_memory_id(),
+            content,
+            author: author.into(),
+            timestamp: Utc::now(),
+            expires_at: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn with_expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Expire the entry `ttl` after its timestamp.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.expires_at = Some(self.timestamp + ttl);
+        self
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Give the entry a fresh id if it has none.
+    pub(crate) fn ensure_id(&mut self) {
+        if self.id.is_empty() {
+            self.id = new_memory_id();
+        }
+    }
+}
+
+fn new_memory_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SearchRequest {
     pub query: String,
     pub user_id: String,
     pub app_name: String,
+    /// Maximum number of memories to return. Services apply their own default when unset.
+    pub limit: Option<usize>,
+    /// Only return memories recorded at or after this time.
+    pub after: Option<DateTime<Utc>>,
+    /// Only return memories recorded before this time.
+    pub before: Option<DateTime<Utc>>,
+    /// Only return memories written by this author.
+    pub author: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+impl SearchRequest {
+    pub fn new(
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+        query: impl Into<String>,
+    ) -> Self {
+        Self {
+            query: query.into(),
+            user_id: user_id.into(),
+            app_name: app_name.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_after(mut self, after: DateTime<Utc>) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    pub fn with_before(mut self, before: DateTime<Utc>) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Whether `entry` passes the time range and author filters and has not expired.
+    pub fn matches(&self, entry: &MemoryEntry) -> bool {
+        !entry.is_expired(Utc::now())
+            && self.after.is_none_or(|after| entry.timestamp >= after)
+            && self.before.is_none_or(|before| entry.timestamp < before)
+            && self.author.as_ref().is_none_or(|author| &entry.author == author)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SearchResponse {
+    /// Matching memories, most relevant first.
     pub memories: Vec<MemoryEntry>,
+    /// Relevance of each memory, in `memories` order. Empty when the service does not
+    /// rank its results.
+    pub scores: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListRequest {
+    pub app_name: String,
+    pub user_id: String,
+    /// Only list the entries remembered from this session.
+    pub session_id: Option<String>,
+}
+
+impl ListRequest {
+    pub fn new(app_name: impl Into<String>, user_id: impl Into<String>) -> Self {
+        Self { app_name: app_name.into(), user_id: user_id.into(), session_id: None }
+    }
+
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
 }
 
+/// Long-term memory storage, scoped by app and user.
+///
+/// Only [`add_session`](Self::add_session) and [`search`](Self::search) are required.
+/// The lifecycle methods default to returning an error, so backends that predate them
+/// keep compiling; every built-in service implements them.
 #[async_trait]
 pub trait MemoryService: Send + Sync {
+    /// Remember `entries` from a session, replacing what was remembered from it before.
     async fn add_session(
         &self,
         app_name: &str,
@@ -30,5 +165,61 @@ pub trait MemoryService: Send + Sync {
         session_id: &str,
         entries: Vec<MemoryEntry>,
     ) -> Result<()>;
+
     async fn search(&self, req: SearchRequest) -> Result<SearchResponse>;
+
+    /// Remember `entries` outside any session, so re-adding or deleting a session
+    /// leaves them alone. Returns their ids.
+    async fn add(
+        &self,
+        _app_name: &str,
+        _user_id: &str,
+        _entries: Vec<MemoryEntry>,
+    ) -> Result<Vec<String>> {
+        Err(unsupported("add"))
+    }
+
+    /// Unexpired entries, oldest first.
+    async fn list(&self, _req: ListRequest) -> Result<Vec<MemoryEntry>> {
+        Err(unsupported("list"))
+    }
+
+    /// Replace the entry with `entry.id`. Fails if there is none.
+    async fn update(&self, _app_name: &str, _user_id: &str, _entry: MemoryEntry) -> Result<()> {
+        Err(unsupported("update"))
+    }
+
+    /// Delete the entry with `id`. Returns whether it existed.
+    async fn delete(&self, _app_name: &str, _user_id: &str, _id: &str) -> Result<bool> {
+        Err(unsupported("delete"))
+    }
+
+    /// Delete everything remembered from a session. Returns how many entries went.
+    async fn delete_session(
+        &self,
+        _app_name: &str,
+        _user_id: &str,
+        _session_id: &str,
+    ) -> Result<usize> {
+        Err(unsupported("delete_session"))
+    }
+
+    /// Delete all of a user's memories. Returns how many entries went.
+    async fn delete_user(&self, _app_name: &str, _user_id: &str) -> Result<usize> {
+        Err(unsupported("delete_user"))
+    }
+
+    /// Delete expired entries across all users. Returns how many entries went.
+    async fn purge_expired(&self) -> Result<usize> {
+        Err(unsupported("purge_expired"))
+    }
+}
+
+fn unsupported(operation: &str) -> AdkError {
+    AdkError::Memory(format!("memory service does not support {operation}"))
+}
+
+/// Error for an update to an entry that does not exist.
+pub(crate) fn entry_not_found(id: &str) -> AdkError {
+    AdkError::Memory(format!("memory entry not found: {id}"))
 }
@@ -0,0 +1,157 @@
+use adk_core::{Content, Memory};
+use adk_memory::*;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+fn entry(text: &str) -> MemoryEntry {
+    MemoryEntry::new("user", Content::new("user").with_text(text))
+}
+
+fn texts(entries: &[MemoryEntry]) -> Vec<String> {
+    entries.iter().map(|m| m.content.parts.iter().filter_map(|p| p.text()).collect()).collect()
+}
+
+fn services() -> Vec<(&'static str, Arc<dyn MemoryService>)> {
+    vec![
+        ("in_memory", Arc::new(InMemoryMemoryService::new())),
+        ("semantic", Arc::new(SemanticMemoryService::new(HashingEmbedder::default()))),
+    ]
+}
+
+#[tokio::test]
+async fn test_list_and_delete_by_id() {
+    for (name, service) in services() {
+        service
+            .add_session(
+                "app",
+                "user",
+                "s1",
+                vec![
+                    entry("I live in Lisbon").with_timestamp(Utc::now() - Duration::minutes(2)),
+                    entry("I work as a pilot").with_timestamp(Utc::now() - Duration::minutes(1)),
+                ],
+            )
+            .await
+            .unwrap();
+        let ids = service.add("app", "user", vec![entry("My cat is called Miso")]).await.unwrap();
+        assert_eq!(ids.len(), 1, "{name}");
+
+        let all = service.list(ListRequest::new("app", "user")).await.unwrap();
+        assert_eq!(
+            texts(&all),
+            ["I live in Lisbon", "I work as a pilot", "My cat is called Miso"],
+            "{name}"
+        );
+        assert!(all.iter().all(|m| !m.id.is_empty()), "{name}");
+        assert_eq!(all[2].id, ids[0], "{name}");
+
+        let from_session =
+            service.list(ListRequest::new("app", "user").with_session("s1")).await.unwrap();
+        assert_eq!(from_session.len(), 2, "{name}");
+
+        assert!(service.delete("app", "user", &all[0].id).await.unwrap(), "{name}");
+        assert!(!service.delete("app", "user", &all[0].id).await.unwrap(), "{name}");
+        assert!(!service.delete("app", "other", &all[1].id).await.unwrap(), "{name}");
+
+        let response = service.search(SearchRequest::new("app", "user", "Lisbon")).await.unwrap();
+        assert!(response.memories.is_empty(), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn test_update_replaces_entry_and_its_search_terms() {
+    for (name, service) in services() {
+        let ids =
+            service.add("app", "user", vec![entry("My favourite colour is blue")]).await.unwrap();
+
+        let mut updated = entry("My favourite colour is green").with_id(&ids[0]);
+        updated.author = "assistant".to_string();
+        service.update("app", "user", updated).await.unwrap();
+
+        let response = service.search(SearchRequest::new("app", "user", "green")).await.unwrap();
+        assert_eq!(texts(&response.memories), ["My favourite colour is green"], "{name}");
+        assert_eq!(response.memories[0].id, ids[0], "{name}");
+        assert_eq!(response.memories[0].author, "assistant", "{name}");
+        assert_eq!(service.list(ListRequest::new("app", "user")).await.unwrap().len(), 1, "{name}");
+
+        let missing = service.update("app", "user", entry("unknown").with_id("missing")).await;
+        assert!(missing.is_err(), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn test_delete_session_and_user() {
+    for (name, service) in services() {
+        service
+            .add_session("app", "user", "s1", vec![entry("alpha one"), entry("alpha two")])
+            .await
+            .unwrap();
+        service.add_session("app", "user", "s2", vec![entry("alpha three")]).await.unwrap();
+        service.add("app", "user", vec![entry("alpha saved")]).await.unwrap();
+        service.add_session("app", "other", "s1", vec![entry("alpha other")]).await.unwrap();
+
+        assert_eq!(service.delete_session("app", "user", "s1").await.unwrap(), 2, "{name}");
+        // Re-adding a session never touches entries added outside it
+        service.add_session("app", "user", "s2", Vec::new()).await.unwrap();
+        let left = service.list(ListRequest::new("app", "user")).await.unwrap();
+        assert_eq!(texts(&left), ["alpha saved"], "{name}");
+
+        assert_eq!(service.delete_user("app", "user").await.unwrap(), 1, "{name}");
+        assert!(service.list(ListRequest::new("app", "user")).await.unwrap().is_empty(), "{name}");
+        assert_eq!(
+            service.list(ListRequest::new("app", "other")).await.unwrap().len(),
+            1,
+            "{name}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_expired_entries_are_hidden_then_purged() {
+    for (name, service) in services() {
+        service
+            .add(
+                "app",
+                "user",
+                vec![
+                    entry("old fact about tea").with_expires_at(Utc::now() - Duration::seconds(1)),
+                    entry("fresh fact about tea").with_ttl(Duration::hours(1)),
+                ],
+            )
+            .await
+            .unwrap();
+
+        let response = service.search(SearchRequest::new("app", "user", "tea")).await.unwrap();
+        assert_eq!(texts(&response.memories), ["fresh fact about tea"], "{name}");
+        let listed = service.list(ListRequest::new("app", "user")).await.unwrap();
+        assert_eq!(texts(&listed), ["fresh fact about tea"], "{name}");
+
+        assert_eq!(service.purge_expired().await.unwrap(), 1, "{name}");
+        assert_eq!(service.purge_expired().await.unwrap(), 0, "{name}");
+    }
+}
+
+#[tokio::test]
+async fn test_scoped_memory() {
+    let service = Arc::new(InMemoryMemoryService::new());
+    let memory = ScopedMemory::new(service.clone(), "app", "user").with_ttl(Duration::days(1));
+
+    let id = memory
+        .add(adk_core::MemoryEntry::new(
+            "assistant",
+            Content::new("user").with_text("Prefers window seats"),
+        ))
+        .await
+        .unwrap();
+
+    let found = memory.search("window seats").await.unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id.as_deref(), Some(id.as_str()));
+    assert_eq!(found[0].author, "assistant");
+
+    let stored = service.list(ListRequest::new("app", "user")).await.unwrap();
+    assert!(stored[0].expires_at.is_some());
+
+    assert!(memory.delete(&id).await.unwrap());
+    assert!(memory.search("window seats").await.unwrap().is_empty());
+}
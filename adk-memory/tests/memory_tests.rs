@@ -8,14 +8,18 @@ async fn test_add_and_search() {
 
     let entries = vec![
         MemoryEntry {
+            id: String::new(),
             content: Content::new("assistant").with_text("The weather is sunny today"),
             author: "assistant".to_string(),
             timestamp: Utc::now(),
+            expires_at: None,
         },
         MemoryEntry {
+            id: String::new(),
             content: Content::new("assistant").with_text("I like programming in Rust"),
             author: "assistant".to_string(),
             timestamp: Utc::now(),
+            expires_at: None,
         },
     ];
 
@@ -26,6 +30,7 @@ async fn test_add_and_search() {
             query: "weather sunny".to_string(),
             user_id: "user1".to_string(),
             app_name: "app1".to_string(),
+            ..Default::default()
         })
         .await
         .unwrap();
@@ -41,9 +46,11 @@ async fn test_search_no_results() {
     let service = InMemoryMemoryService::new();
 
     let entries = vec![MemoryEntry {
+        id: String::new(),
         content: Content::new("assistant").with_text("The weather is sunny"),
         author: "assistant".to_string(),
         timestamp: Utc::now(),
+        expires_at: None,
     }];
 
     service.add_session("app1", "user1", "session1", entries).await.unwrap();
@@ -53,6 +60,7 @@ async fn test_search_no_results() {
             query: "programming rust".to_string(),
             user_id: "user1".to_string(),
             app_name: "app1".to_string(),
+            ..Default::default()
         })
         .await
         .unwrap();
@@ -70,9 +78,11 @@ async fn test_multiple_sessions() {
             "user1",
             "session1",
             vec![MemoryEntry {
+                id: String::new(),
                 content: Content::new("assistant").with_text("First session content"),
                 author: "assistant".to_string(),
                 timestamp: Utc::now(),
+                expires_at: None,
             }],
         )
         .await
@@ -84,9 +94,11 @@ async fn test_multiple_sessions() {
             "user1",
             "session2",
             vec![MemoryEntry {
+                id: String::new(),
                 content: Content::new("assistant").with_text("Second session content"),
                 author: "assistant".to_string(),
                 timestamp: Utc::now(),
+                expires_at: None,
             }],
         )
         .await
@@ -97,6 +109,7 @@ async fn test_multiple_sessions() {
             query: "session content".to_string(),
             user_id: "user1".to_string(),
             app_name: "app1".to_string(),
+            ..Default::default()
         })
         .await
         .unwrap();
@@ -114,9 +127,11 @@ async fn test_user_isolation() {
             "user1",
             "session1",
             vec![MemoryEntry {
+                id: String::new(),
                 content: Content::new("assistant").with_text("User1 data"),
                 author: "assistant".to_string(),
                 timestamp: Utc::now(),
+                expires_at: None,
             }],
         )
         .await
@@ -128,9 +143,11 @@ async fn test_user_isolation() {
             "user2",
             "session1",
             vec![MemoryEntry {
+                id: String::new(),
                 content: Content::new("assistant").with_text("User2 data"),
                 author: "assistant".to_string(),
                 timestamp: Utc::now(),
+                expires_at: None,
             }],
         )
         .await
@@ -141,6 +158,7 @@ async fn test_user_isolation() {
             query: "data".to_string(),
             user_id: "user1".to_string(),
             app_name: "app1".to_string(),
+            ..Default::default()
         })
         .await
         .unwrap();
@@ -156,9 +174,11 @@ async fn test_empty_content_filtered() {
     let service = InMemoryMemoryService::new();
 
     let entries = vec![MemoryEntry {
+        id: String::new(),
         content: Content::new("assistant"),
         author: "assistant".to_string(),
         timestamp: Utc::now(),
+        expires_at: None,
     }];
 
     service.add_session("app1", "user1", "session1", entries).await.unwrap();
@@ -168,6 +188,7 @@ async fn test_empty_content_filtered() {
             query: "anything".to_string(),
             user_id: "user1".to_string(),
             app_name: "app1".to_string(),
+            ..Default::default()
         })
         .await
         .unwrap();
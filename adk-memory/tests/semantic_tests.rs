@@ -0,0 +1,140 @@
+use adk_core::{Content, Result};
+use adk_memory::*;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+
+fn entry(author: &str, text: &str, minutes_ago: i64) -> MemoryEntry {
+    MemoryEntry {
+        id: String::new(),
+        content: Content::new("user").with_text(text),
+        author: author.to_string(),
+        timestamp: Utc::now() - Duration::minutes(minutes_ago),
+        expires_at: None,
+    }
+}
+
+fn texts(response: &SearchResponse) -> Vec<String> {
+    response
+        .memories
+        .iter()
+        .map(|m| m.content.parts.iter().filter_map(|p| p.text()).collect())
+        .collect()
+}
+
+async fn service_with_memories() -> SemanticMemoryService {
+    let service = SemanticMemoryService::new(HashingEmbedder::default());
+    service
+        .add_session(
+            "app",
+            "user",
+            "session1",
+            vec![
+                entry("user", "I prefer dark mode in every editor", 30),
+                entry("assistant", "Noted, dark mode it is", 29),
+                entry("user", "My favourite language is Rust", 20),
+                entry("user", "Book a table for two on Friday", 10),
+            ],
+        )
+        .await
+        .unwrap();
+    service
+}
+
+#[tokio::test]
+async fn test_search_ranks_by_similarity() {
+    let service = service_with_memories().await;
+
+    let response =
+        service.search(SearchRequest::new("app", "user", "dark mode editors")).await.unwrap();
+
+    assert_eq!(texts(&response), ["I prefer dark mode in every editor", "Noted, dark mode it is"]);
+    assert_eq!(response.scores.len(), 2);
+    assert!(response.scores[0] > response.scores[1]);
+    assert!(response.scores.iter().all(|s| *s > 0.0 && *s <= 1.0 + f32::EPSILON));
+}
+
+#[tokio::test]
+async fn test_search_limit() {
+    let service = service_with_memories().await.with_default_limit(1);
+
+    let response = service.search(SearchRequest::new("app", "user", "dark mode")).await.unwrap();
+    assert_eq!(response.memories.len(), 1);
+
+    let response =
+        service.search(SearchRequest::new("app", "user", "dark mode").with_limit(5)).await.unwrap();
+    assert_eq!(response.memories.len(), 2);
+}
+
+#[tokio::test]
+async fn test_search_filters_author_and_time_range() {
+    let service = service_with_memories().await;
+
+    let response = service
+        .search(SearchRequest::new("app", "user", "dark mode").with_author("assistant"))
+        .await
+        .unwrap();
+    assert_eq!(texts(&response), ["Noted, dark mode it is"]);
+
+    let now = Utc::now();
+    let response = service
+        .search(
+            SearchRequest::new("app", "user", "dark mode rust table friday")
+                .with_after(now - Duration::minutes(25))
+                .with_before(now - Duration::minutes(5)),
+        )
+        .await
+        .unwrap();
+    let mut found = texts(&response);
+    found.sort();
+    assert_eq!(found, ["Book a table for two on Friday", "My favourite language is Rust"]);
+}
+
+#[tokio::test]
+async fn test_readding_session_replaces_memories_and_users_are_isolated() {
+    let service = service_with_memories().await;
+    service
+        .add_session("app", "user", "session1", vec![entry("user", "I switched to light mode", 0)])
+        .await
+        .unwrap();
+
+    let response = service.search(SearchRequest::new("app", "user", "mode")).await.unwrap();
+    assert_eq!(texts(&response), ["I switched to light mode"]);
+
+    let response = service.search(SearchRequest::new("app", "other", "mode")).await.unwrap();
+    assert!(response.memories.is_empty());
+}
+
+/// Embeds queries along a fixed axis to check that searches use `embed_query`.
+struct QueryAwareEmbedder;
+
+#[async_trait]
+impl Embedder for QueryAwareEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts
+            .iter()
+            .map(|t| if t.contains("first") { vec![1.0, 0.0] } else { vec![0.0, 1.0] })
+            .collect())
+    }
+
+    async fn embed_query(&self, _query: &str) -> Result<Vec<f32>> {
+        Ok(vec![0.0, 1.0])
+    }
+}
+
+#[tokio::test]
+async fn test_search_uses_query_embedding() {
+    let service = SemanticMemoryService::new(QueryAwareEmbedder);
+    service
+        .add_session(
+            "app",
+            "user",
+            "session1",
+            vec![entry("user", "first", 2), entry("user", "second", 1), entry("user", "   ", 0)],
+        )
+        .await
+        .unwrap();
+
+    let response = service.search(SearchRequest::new("app", "user", "first")).await.unwrap();
+    assert_eq!(texts(&response), ["second"]);
+    assert!((response.scores[0] - 1.0).abs() < 1e-6);
+}
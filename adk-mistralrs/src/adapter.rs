@@ -478,6 +478,8 @@ impl MistralRsAdapterModel {
                 .iter()
                 .filter_map(|part| match part {
                     Part::Text { text } => Some(text.as_str()),
+                    // mistral.rs has no separate reasoning channel, so thinking is not sent back
+                    Part::Thinking { .. } => None,
                     _ => None,
                 })
                 .collect::<Vec<_>>()
@@ -503,6 +505,7 @@ impl MistralRsAdapterModel {
             prompt_token_count: response.usage.prompt_tokens as i32,
             candidates_token_count: response.usage.completion_tokens as i32,
             total_token_count: response.usage.total_tokens as i32,
+            ..Default::default()
         });
 
         let finish_reason =
@@ -521,6 +524,7 @@ impl MistralRsAdapterModel {
             interrupted: false,
             error_code: None,
             error_message: None,
+            model_name: None,
         }
     }
 }
@@ -582,6 +586,7 @@ impl Llm for MistralRsAdapterModel {
                                                 interrupted: false,
                                                 error_code: None,
                                                 error_message: None,
+                                                model_name: None,
                                             };
                                             yield Ok(response);
                                         }
@@ -592,6 +597,7 @@ impl Llm for MistralRsAdapterModel {
                                         prompt_token_count: final_response.usage.prompt_tokens as i32,
                                         candidates_token_count: final_response.usage.completion_tokens as i32,
                                         total_token_count: final_response.usage.total_tokens as i32,
+                                        ..Default::default()
                                     });
 
                                     let response = LlmResponse {
@@ -603,6 +609,7 @@ impl Llm for MistralRsAdapterModel {
                                         interrupted: false,
                                         error_code: None,
                                         error_message: None,
+                                        model_name: None,
                                     };
                                     yield Ok(response);
                                 }
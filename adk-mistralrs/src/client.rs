@@ -329,6 +329,8 @@ impl MistralRsModel {
                 .iter()
                 .filter_map(|part| match part {
                     Part::Text { text } => Some(text.as_str()),
+                    // mistral.rs has no separate reasoning channel, so thinking is not sent back
+                    Part::Thinking { .. } => None,
                     _ => None,
                 })
                 .collect::<Vec<_>>()
@@ -354,6 +356,7 @@ impl MistralRsModel {
             prompt_token_count: response.usage.prompt_tokens as i32,
             candidates_token_count: response.usage.completion_tokens as i32,
             total_token_count: response.usage.total_tokens as i32,
+            ..Default::default()
         });
 
         let finish_reason =
@@ -372,6 +375,7 @@ impl MistralRsModel {
             interrupted: false,
             error_code: None,
             error_message: None,
+            model_name: None,
         }
     }
 }
@@ -427,6 +431,7 @@ impl Llm for MistralRsModel {
                                                 interrupted: false,
                                                 error_code: None,
                                                 error_message: None,
+                                                model_name: None,
                                             };
                                             yield Ok(response);
                                         }
@@ -437,6 +442,7 @@ impl Llm for MistralRsModel {
                                         prompt_token_count: final_response.usage.prompt_tokens as i32,
                                         candidates_token_count: final_response.usage.completion_tokens as i32,
                                         total_token_count: final_response.usage.total_tokens as i32,
+                                        ..Default::default()
                                     });
 
                                     let response = LlmResponse {
@@ -448,6 +454,7 @@ impl Llm for MistralRsModel {
                                         interrupted: false,
                                         error_code: None,
                                         error_message: None,
+                                        model_name: None,
                                     };
                                     yield Ok(response);
                                 }
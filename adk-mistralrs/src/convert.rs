@@ -28,6 +28,8 @@ pub fn content_to_message(content: &Content) -> IndexMap<String, Value> {
         .iter()
         .filter_map(|part| match part {
             Part::Text { text } => Some(text.clone()),
+            // mistral.rs has no separate reasoning channel, so thinking is not sent back
+            Part::Thinking { .. } => None,
             _ => None,
         })
         .collect();
@@ -658,6 +660,8 @@ pub fn extract_text_from_content(content: &Content) -> String {
         .iter()
         .filter_map(|part| match part {
             Part::Text { text } => Some(text.as_str()),
+            // mistral.rs has no separate reasoning channel, so thinking is not sent back
+            Part::Thinking { .. } => None,
             _ => None,
         })
         .collect::<Vec<_>>()
@@ -344,6 +344,15 @@ impl std::fmt::Debug for MistralRsEmbeddingModel {
     }
 }
 
+/// Lets a local embedding model back `adk_memory::SemanticMemoryService`.
+#[cfg(feature = "memory")]
+#[async_trait::async_trait]
+impl adk_memory::Embedder for MistralRsEmbeddingModel {
+    async fn embed(&self, texts: &[String]) -> adk_core::Result<Vec<Vec<f32>>> {
+        self.embed_batch(texts).await.map_err(|e| adk_core::AdkError::Memory(e.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
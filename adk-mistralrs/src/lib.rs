@@ -154,6 +154,7 @@
 //! - `mkl` - Intel MKL acceleration
 //! - `accelerate` - Apple Accelerate framework
 //! - `flash-attn` - Flash Attention (requires CUDA)
+//! - `memory` - Implement `adk_memory::Embedder` for [`MistralRsEmbeddingModel`]
 
 mod adapter;
 mod client;
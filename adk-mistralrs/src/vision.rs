@@ -361,6 +361,8 @@ impl MistralRsVisionModel {
                 .iter()
                 .filter_map(|part| match part {
                     Part::Text { text } => Some(text.as_str()),
+                    // mistral.rs has no separate reasoning channel, so thinking is not sent back
+                    Part::Thinking { .. } => None,
                     _ => None,
                 })
                 .collect::<Vec<_>>()
@@ -423,6 +425,7 @@ impl MistralRsVisionModel {
             prompt_token_count: response.usage.prompt_tokens as i32,
             candidates_token_count: response.usage.completion_tokens as i32,
             total_token_count: response.usage.total_tokens as i32,
+            ..Default::default()
         });
 
         let finish_reason =
@@ -441,6 +444,7 @@ impl MistralRsVisionModel {
             interrupted: false,
             error_code: None,
             error_message: None,
+            model_name: None,
         }
     }
 }
@@ -492,6 +496,7 @@ impl Llm for MistralRsVisionModel {
                                                 interrupted: false,
                                                 error_code: None,
                                                 error_message: None,
+                                                model_name: None,
                                             };
                                             yield Ok(response);
                                         }
@@ -502,6 +507,7 @@ impl Llm for MistralRsVisionModel {
                                         prompt_token_count: final_response.usage.prompt_tokens as i32,
                                         candidates_token_count: final_response.usage.completion_tokens as i32,
                                         total_token_count: final_response.usage.total_tokens as i32,
+                                        ..Default::default()
                                     });
 
                                     let response = LlmResponse {
@@ -513,6 +519,7 @@ impl Llm for MistralRsVisionModel {
                                         interrupted: false,
                                         error_code: None,
                                         error_message: None,
+                                        model_name: None,
                                     };
                                     yield Ok(response);
                                 }
@@ -7,7 +7,7 @@ use async_stream::try_stream;
 use async_trait::async_trait;
 use claudius::{
     Anthropic, ContentBlock, ContentBlockDelta, ContentBlockDeltaEvent, MessageStreamEvent,
-    StopReason, TextDelta,
+    SignatureDelta, StopReason, TextDelta, ThinkingDelta,
 };
 use futures::StreamExt;
 use std::pin::pin;
@@ -121,19 +121,26 @@ impl Llm for AnthropicClient {
 
                     match event {
                         MessageStreamEvent::ContentBlockStart(start_event) => {
-                            // Check if this is a tool_use block
+                            // Tool use blocks are assembled from deltas
                             let index = start_event.index;
-                            if let ContentBlock::ToolUse(tool_use) = start_event.content_block {
-                                current_tool_index = Some(index);
-                                // Ensure vector is large enough
-                                while current_tool_calls.len() <= index {
-                                    current_tool_calls.push((String::new(), String::new(), String::new()));
+                            match start_event.content_block {
+                                ContentBlock::ToolUse(tool_use) => {
+                                    current_tool_index = Some(index);
+                                    // Ensure vector is large enough
+                                    while current_tool_calls.len() <= index {
+                                        current_tool_calls.push((String::new(), String::new(), String::new()));
+                                    }
+                                    current_tool_calls[index] = (
+                                        tool_use.id.clone(),
+                                        tool_use.name.clone(),
+                                        String::new(),
+                                    );
+                                }
+                                // Redacted thinking arrives whole; it has no deltas
+                                ContentBlock::RedactedThinking(redacted) => {
+                                    yield convert::from_thinking_delta("", Some(&redacted.data));
                                 }
-                                current_tool_calls[index] = (
-                                    tool_use.id.clone(),
-                                    tool_use.name.clone(),
-                                    String::new(),
-                                );
+                                _ => {}
                             }
                         }
                         MessageStreamEvent::ContentBlockDelta(ContentBlockDeltaEvent { index, delta }) => {
@@ -143,6 +150,14 @@ impl Llm for AnthropicClient {
                                         yield convert::from_text_delta(&text);
                                     }
                                 }
+                                ContentBlockDelta::ThinkingDelta(ThinkingDelta { thinking }) => {
+                                    if !thinking.is_empty() {
+                                        yield convert::from_thinking_delta(&thinking, None);
+                                    }
+                                }
+                                ContentBlockDelta::SignatureDelta(SignatureDelta { signature }) => {
+                                    yield convert::from_thinking_delta("", Some(&signature));
+                                }
                                 ContentBlockDelta::InputJsonDelta(json_delta) => {
                                     // Accumulate tool call arguments
                                     if let Some(idx) = current_tool_index {
@@ -195,6 +210,7 @@ impl Llm for AnthropicClient {
                                         prompt_token_count: 0,
                                         candidates_token_count: delta_event.usage.output_tokens,
                                         total_token_count: delta_event.usage.output_tokens,
+                                        ..Default::default()
                                     }),
                                     finish_reason,
                                     partial: false,
@@ -202,6 +218,7 @@ impl Llm for AnthropicClient {
                                     interrupted: false,
                                     error_code: None,
                                     error_message: None,
+                                    model_name: None,
                                 };
                             }
                         }
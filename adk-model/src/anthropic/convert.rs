@@ -2,9 +2,9 @@
 
 use adk_core::{Content, FinishReason, LlmResponse, Part, UsageMetadata};
 use claudius::{
-    ContentBlock, Message, MessageCreateParams, MessageParam, MessageRole, Model, StopReason,
-    SystemPrompt, TextBlock, ToolParam, ToolResultBlock, ToolResultBlockContent, ToolUnionParam,
-    ToolUseBlock,
+    ContentBlock, Message, MessageCreateParams, MessageParam, MessageRole, Model,
+    RedactedThinkingBlock, StopReason, SystemPrompt, TextBlock, ThinkingBlock, ToolParam,
+    ToolResultBlock, ToolResultBlockContent, ToolUnionParam, ToolUseBlock,
 };
 use serde_json::Value;
 use std::collections::HashMap;
@@ -17,10 +17,22 @@ pub fn content_to_message(content: &Content) -> MessageParam {
         _ => MessageRole::User,
     };
 
-    let blocks: Vec<ContentBlock> = content
-        .parts
+    let blocks: Vec<ContentBlock> = merge_thinking(&content.parts)
         .iter()
         .filter_map(|part| match part {
+            // Anthropic rejects thinking without the signature it was issued with
+            Part::Thinking { thinking, signature: Some(signature) } => {
+                if thinking.is_empty() {
+                    Some(ContentBlock::RedactedThinking(RedactedThinkingBlock {
+                        data: signature.clone(),
+                    }))
+                } else {
+                    Some(ContentBlock::Thinking(ThinkingBlock {
+                        thinking: thinking.clone(),
+                        signature: signature.clone(),
+                    }))
+                }
+            }
             Part::Text { text } => {
                 if text.is_empty() {
                     None
@@ -60,6 +72,27 @@ pub fn content_to_message(content: &Content) -> MessageParam {
     MessageParam::new_with_blocks(blocks, role)
 }
 
+/// Join runs of consecutive thinking parts, such as streamed thinking deltas followed
+/// by their signature, back into one part per thinking block.
+fn merge_thinking(parts: &[Part]) -> Vec<Part> {
+    let mut merged: Vec<Part> = Vec::with_capacity(parts.len());
+    for part in parts {
+        if let (
+            Some(Part::Thinking { thinking, signature }),
+            Part::Thinking { thinking: next, signature: next_signature },
+        ) = (merged.last_mut(), part)
+        {
+            thinking.push_str(next);
+            if next_signature.is_some() {
+                *signature = next_signature.clone();
+            }
+            continue;
+        }
+        merged.push(part.clone());
+    }
+    merged
+}
+
 /// Convert ADK tools to Claudius ToolUnionParam format.
 pub fn convert_tools(tools: &HashMap<String, Value>) -> Vec<ToolUnionParam> {
     tools
@@ -100,6 +133,19 @@ pub fn from_anthropic_message(message: &Message) -> LlmResponse {
                     id: Some(tool_use.id.clone()),
                 });
             }
+            ContentBlock::Thinking(thinking_block) => {
+                parts.push(Part::Thinking {
+                    thinking: thinking_block.thinking.clone(),
+                    signature: Some(thinking_block.signature.clone()),
+                });
+            }
+            // Redacted thinking is encrypted; only its data needs to round-trip
+            ContentBlock::RedactedThinking(redacted) => {
+                parts.push(Part::Thinking {
+                    thinking: String::new(),
+                    signature: Some(redacted.data.clone()),
+                });
+            }
             _ => {}
         }
     }
@@ -107,10 +153,16 @@ pub fn from_anthropic_message(message: &Message) -> LlmResponse {
     let content =
         if parts.is_empty() { None } else { Some(Content { role: "model".to_string(), parts }) };
 
+    // Anthropic reports cache reads apart from input tokens; count them as prompt
+    // tokens so `cached_content_token_count` is a portion of the prompt, as elsewhere.
+    let cache_read = message.usage.cache_read_input_tokens.unwrap_or(0);
+    let prompt_tokens = message.usage.input_tokens + cache_read;
     let usage_metadata = Some(UsageMetadata {
-        prompt_token_count: message.usage.input_tokens,
+        prompt_token_count: prompt_tokens,
         candidates_token_count: message.usage.output_tokens,
-        total_token_count: (message.usage.input_tokens + message.usage.output_tokens),
+        total_token_count: prompt_tokens + message.usage.output_tokens,
+        cached_content_token_count: (cache_read > 0).then_some(cache_read),
+        thoughts_token_count: None,
     });
 
     let finish_reason = message.stop_reason.as_ref().map(|sr| match sr {
@@ -130,6 +182,7 @@ pub fn from_anthropic_message(message: &Message) -> LlmResponse {
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
@@ -147,6 +200,28 @@ pub fn from_text_delta(text: &str) -> LlmResponse {
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
+    }
+}
+
+/// Convert a streaming thinking or signature delta to ADK LlmResponse.
+pub fn from_thinking_delta(thinking: &str, signature: Option<&str>) -> LlmResponse {
+    LlmResponse {
+        content: Some(Content {
+            role: "model".to_string(),
+            parts: vec![Part::Thinking {
+                thinking: thinking.to_string(),
+                signature: signature.map(String::from),
+            }],
+        }),
+        usage_metadata: None,
+        finish_reason: None,
+        partial: true,
+        turn_complete: false,
+        interrupted: false,
+        error_code: None,
+        error_message: None,
+        model_name: None,
     }
 }
 
@@ -169,6 +244,7 @@ pub fn create_tool_call_response(
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
@@ -234,6 +310,26 @@ mod tests {
         assert!(matches!(msg.role, MessageRole::Assistant));
     }
 
+    #[test]
+    fn test_content_to_message_merges_streamed_thinking() {
+        let content = Content {
+            role: "model".to_string(),
+            parts: vec![
+                Part::thinking_part("Let me ", None),
+                Part::thinking_part("think.", None),
+                Part::thinking_part("", Some("sig".to_string())),
+                Part::Text { text: "Answer".to_string() },
+            ],
+        };
+        let merged = merge_thinking(&content.parts);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0], Part::thinking_part("Let me think.", Some("sig".to_string())));
+
+        // Unsigned thinking stays unsigned, so content_to_message leaves it out
+        let unsigned = merge_thinking(&[Part::thinking_part("draft", None)]);
+        assert!(matches!(&unsigned[0], Part::Thinking { signature: None, .. }));
+    }
+
     #[test]
     fn test_convert_tools() {
         let mut tools = HashMap::new();
@@ -110,7 +110,6 @@ impl Llm for DeepSeekClient {
         let api_key = self.config.api_key.clone();
         let chat_request = self.build_request(&request, stream);
         let client = self.client.clone();
-        let thinking_enabled = self.config.thinking_enabled;
 
         let response_stream = try_stream! {
             let response = client
@@ -141,9 +140,6 @@ impl Llm for DeepSeekClient {
                 let mut tool_call_accumulators: std::collections::HashMap<u32, (String, String, String)> =
                     std::collections::HashMap::new();
 
-                // For reasoning content, accumulate across chunks
-                let mut reasoning_buffer = String::new();
-
                 while let Some(chunk_result) = byte_stream.next().await {
                     let chunk = chunk_result
                         .map_err(|e| AdkError::Model(format!("Stream read error: {}", e)))?;
@@ -165,29 +161,26 @@ impl Llm for DeepSeekClient {
                                     if let Some(choice) = chunk_response.choices.first() {
                                         // Handle tool call accumulation
                                         if let Some(delta) = &choice.delta {
-                                            // Accumulate reasoning content
+                                            // Stream reasoning content as thinking parts
                                             if let Some(reasoning) = &delta.reasoning_content {
                                                 if !reasoning.is_empty() {
-                                                    reasoning_buffer.push_str(reasoning);
-
-                                                    // Emit reasoning as streaming text if thinking is enabled
-                                                    if thinking_enabled {
-                                                        yield LlmResponse {
-                                                            content: Some(adk_core::Content {
-                                                                role: "model".to_string(),
-                                                                parts: vec![Part::Text {
-                                                                    text: reasoning.clone(),
-                                                                }],
-                                                            }),
-                                                            usage_metadata: None,
-                                                            finish_reason: None,
-                                                            partial: true,
-                                                            turn_complete: false,
-                                                            interrupted: false,
-                                                            error_code: None,
-                                                            error_message: None,
-                                                        };
-                                                    }
+                                                    yield LlmResponse {
+                                                        content: Some(adk_core::Content {
+                                                            role: "model".to_string(),
+                                                            parts: vec![Part::Thinking {
+                                                                thinking: reasoning.clone(),
+                                                                signature: None,
+                                                            }],
+                                                        }),
+                                                        usage_metadata: None,
+                                                        finish_reason: None,
+                                                        partial: true,
+                                                        turn_complete: false,
+                                                        interrupted: false,
+                                                        error_code: None,
+                                                        error_message: None,
+                                                        model_name: None,
+                                                    };
                                                 }
                                             }
 
@@ -280,6 +273,10 @@ impl Llm for DeepSeekClient {
                                                         prompt_token_count: u.prompt_tokens as i32,
                                                         candidates_token_count: u.completion_tokens as i32,
                                                         total_token_count: u.total_tokens as i32,
+                                                        cached_content_token_count: u
+                                                            .prompt_cache_hit_tokens
+                                                            .map(|n| n as i32),
+                                                        thoughts_token_count: u.reasoning_tokens.map(|n| n as i32),
                                                     }
                                                 }),
                                                 finish_reason,
@@ -288,6 +285,7 @@ impl Llm for DeepSeekClient {
                                                 interrupted: false,
                                                 error_code: None,
                                                 error_message: None,
+                                                model_name: None,
                                             };
                                         } else {
                                             // Emit partial text content (non-reasoning)
@@ -308,6 +306,7 @@ impl Llm for DeepSeekClient {
                                                             interrupted: false,
                                                             error_code: None,
                                                             error_message: None,
+                                                            model_name: None,
                                                         };
                                                     }
                                                 }
@@ -168,11 +168,11 @@ pub struct Usage {
     pub completion_tokens: u32,
     pub total_tokens: u32,
     /// Tokens used for reasoning (thinking mode).
-    #[serde(default, rename = "reasoning_tokens")]
-    pub _reasoning_tokens: Option<u32>,
+    #[serde(default)]
+    pub reasoning_tokens: Option<u32>,
     /// Cache hit tokens for prefix caching.
-    #[serde(default, rename = "prompt_cache_hit_tokens")]
-    pub _prompt_cache_hit_tokens: Option<u32>,
+    #[serde(default)]
+    pub prompt_cache_hit_tokens: Option<u32>,
     #[serde(default, rename = "prompt_cache_miss_tokens")]
     pub _prompt_cache_miss_tokens: Option<u32>,
 }
@@ -210,6 +210,8 @@ pub fn content_to_message(content: &Content) -> Message {
                 text_parts
                     .push(serde_json::to_string(&function_response.response).unwrap_or_default());
             }
+            // The API rejects reasoning_content in requests, so thinking is never sent back
+            Part::Thinking { .. } => {}
             _ => {} // Skip other part types
         }
     }
@@ -269,9 +271,7 @@ pub fn from_response(response: &ChatCompletionResponse) -> LlmResponse {
             // Add reasoning content if present (thinking mode)
             if let Some(reasoning) = &msg.reasoning_content {
                 if !reasoning.is_empty() {
-                    parts.push(Part::Text {
-                        text: format!("<thinking>\n{}\n</thinking>\n\n", reasoning),
-                    });
+                    parts.push(Part::Thinking { thinking: reasoning.clone(), signature: None });
                 }
             }
 
@@ -314,6 +314,8 @@ pub fn from_response(response: &ChatCompletionResponse) -> LlmResponse {
         prompt_token_count: u.prompt_tokens as i32,
         candidates_token_count: u.completion_tokens as i32,
         total_token_count: u.total_tokens as i32,
+        cached_content_token_count: u.prompt_cache_hit_tokens.map(|n| n as i32),
+        thoughts_token_count: u.reasoning_tokens.map(|n| n as i32),
     });
 
     LlmResponse {
@@ -325,6 +327,7 @@ pub fn from_response(response: &ChatCompletionResponse) -> LlmResponse {
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
@@ -347,5 +350,6 @@ pub fn create_tool_call_response(
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
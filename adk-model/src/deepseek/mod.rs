@@ -45,7 +45,8 @@
 //!
 //! When using `deepseek-reasoner` or enabling thinking mode, the model outputs
 //! its chain-of-thought reasoning before providing the final answer. The reasoning
-//! is returned in `reasoning_content` and automatically formatted with `<thinking>` tags.
+//! is returned in `reasoning_content` and surfaced as [`Part::Thinking`](adk_core::Part::Thinking)
+//! parts, streamed ahead of the answer. Thinking is not sent back to the API.
 
 mod client;
 mod config;
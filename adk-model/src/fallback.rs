@@ -0,0 +1,612 @@
+//! Retrying model wrapper with fallback to alternative models.
+//!
+//! [`FallbackLlm`] wraps an ordered chain of models. Each request goes to the first
+//! model; retryable errors (rate limits, overloaded or unavailable servers, network
+//! failures) are retried with exponential backoff and jitter, honoring any retry-after
+//! hint in the error. Once a model has used up its retries the request moves on to the
+//! next model in the chain. Fatal errors, such as invalid requests, are returned
+//! immediately.
+//!
+//! ```rust,ignore
+//! use adk_model::{FallbackLlm, GeminiModel, RetryPolicy};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let model = FallbackLlm::new(Arc::new(GeminiModel::new(&api_key, "gemini-2.5-flash")?))
+//!     .with_fallback(Arc::new(GeminiModel::new(&api_key, "gemini-2.0-flash")?))
+//!     .with_retry_policy(RetryPolicy::new().with_max_retries(2));
+//! ```
+//!
+//! Responses carry the name of the model that answered in
+//! [`LlmResponse::model_name`](adk_core::LlmResponse::model_name).
+
+use adk_core::{AdkError, Llm, LlmRequest, LlmResponseStream, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How a model error should be handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The request may succeed if retried, optionally after the given delay.
+    Retryable { retry_after: Option<Duration> },
+    /// Retrying will not help; the error is returned to the caller.
+    Fatal,
+}
+
+/// Classifies errors from a model, see [`classify_error`] for the default.
+pub type ErrorClassifier = Arc<dyn Fn(&AdkError) -> ErrorClass + Send + Sync>;
+
+/// Retry and backoff settings applied to each model in a [`FallbackLlm`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries per model after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between retries. A retry-after hint longer than
+    /// this moves on to the next model instead of waiting.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with 3 retries and 500ms initial backoff capped at 30s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of retries per model.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the initial and maximum delay between retries.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Delay before the given retry (1-based): exponential backoff capped at
+    /// `max_backoff`, with "equal jitter" so the delay falls in `[backoff/2, backoff]`.
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let backoff = self.initial_backoff.saturating_mul(1u32 << exponent).min(self.max_backoff);
+        let half = backoff / 2;
+        half + half.mul_f64(jitter())
+    }
+}
+
+/// Random fraction in `[0, 1)` without pulling in an RNG dependency.
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(std::time::SystemTime::UNIX_EPOCH.elapsed().unwrap_or_default().as_nanos());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// HTTP statuses worth retrying: request timeout, rate limits, server errors and
+/// Anthropic's 529 overload.
+const RETRYABLE_STATUSES: [u16; 7] = [408, 429, 500, 502, 503, 504, 529];
+
+/// Reason phrases of the retryable statuses.
+const RETRYABLE_REASONS: [&str; 6] = [
+    "request timeout",
+    "too many requests",
+    "internal server error",
+    "bad gateway",
+    "service unavailable",
+    "gateway timeout",
+];
+
+/// Reason phrases of common client error statuses.
+const FATAL_REASONS: [&str; 7] = [
+    "bad request",
+    "unauthorized",
+    "payment required",
+    "forbidden",
+    "not found",
+    "conflict",
+    "unprocessable",
+];
+
+/// Status names that providers report without a code: gRPC statuses from Gemini and
+/// error types from Anthropic.
+const RETRYABLE_STATUS_NAMES: [&str; 5] = [
+    "resource_exhausted",
+    "unavailable",
+    "deadline_exceeded",
+    "rate_limit_error",
+    "overloaded_error",
+];
+
+/// Default error classification.
+///
+/// IO errors are retryable when their kind is a timeout or a dropped connection.
+/// Model errors are retryable when they carry an HTTP 408, 429, 500, 502, 503, 504
+/// or 529 status code; any other status code is fatal. Without a status code, only
+/// the reason phrase of one of those statuses or a provider status name such as
+/// `RESOURCE_EXHAUSTED` or `overloaded_error` makes an error retryable. Everything
+/// else is fatal. A retry-after hint is extracted from the message when present (e.g.
+/// `Retry-After: 20`, `try again in 1.5s`, `"retryDelay": "30s"`).
+pub fn classify_error(error: &AdkError) -> ErrorClass {
+    let message = match error {
+        AdkError::Model(message) => message.to_lowercase(),
+        AdkError::Io(e) => {
+            use std::io::ErrorKind;
+            return match e.kind() {
+                ErrorKind::TimedOut
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::NotConnected
+                | ErrorKind::BrokenPipe
+                | ErrorKind::UnexpectedEof
+                | ErrorKind::Interrupted => ErrorClass::Retryable { retry_after: None },
+                _ => ErrorClass::Fatal,
+            };
+        }
+        _ => return ErrorClass::Fatal,
+    };
+
+    let retryable = match http_status(&message) {
+        Some(status) => RETRYABLE_STATUSES.contains(&status),
+        None => {
+            RETRYABLE_REASONS.iter().any(|reason| message.contains(reason))
+                || message
+                    .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .any(|token| RETRYABLE_STATUS_NAMES.contains(&token))
+        }
+    };
+    if retryable {
+        ErrorClass::Retryable { retry_after: parse_retry_after(&message) }
+    } else {
+        ErrorClass::Fatal
+    }
+}
+
+/// The HTTP status code in a lowercased error message: a three-digit number at the
+/// start, after an opening parenthesis or the word `status`, `code` or `http`, or
+/// before a reason phrase. Other numbers, such as token limits, are not statuses.
+fn http_status(message: &str) -> Option<u16> {
+    let bytes = message.as_bytes();
+    let mut start = 0;
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_digit() {
+            start += 1;
+            continue;
+        }
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |len| start + len);
+        let standalone = (start == 0 || !bytes[start - 1].is_ascii_alphanumeric())
+            && (end == bytes.len() || !bytes[end].is_ascii_alphanumeric());
+        if standalone && end - start == 3 {
+            let before = message[..start].trim_end().trim_end_matches([':', '=']).trim_end();
+            let after = message[end..].trim_start();
+            let in_status_position = before.is_empty()
+                || before.ends_with('(')
+                || ["status", "code", "http"].iter().any(|word| before.ends_with(word))
+                || RETRYABLE_REASONS.iter().chain(&FATAL_REASONS).any(|r| after.starts_with(r));
+            let status: u16 = message[start..end].parse().ok()?;
+            if in_status_position && (100..600).contains(&status) {
+                return Some(status);
+            }
+        }
+        start = end;
+    }
+    None
+}
+
+/// Find a retry delay in a lowercased error message. Bare numbers are seconds.
+fn parse_retry_after(message: &str) -> Option<Duration> {
+    const HINTS: [&str; 5] =
+        ["retry-after", "retry_after", "retry after", "retrydelay", "try again in"];
+
+    let start = HINTS.iter().filter_map(|hint| message.find(hint).map(|i| i + hint.len())).min()?;
+    let rest = &message[start..];
+    let digits_start = rest.find(|c: char| c.is_ascii_digit())?;
+    // The number should directly follow the hint, not appear somewhere later on
+    if digits_start > 4 {
+        return None;
+    }
+    let rest = &rest[digits_start..];
+    let digits_end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+    let value: f64 = rest[..digits_end].parse().ok()?;
+    let unit = rest[digits_end..].trim_start();
+
+    let seconds = if unit.starts_with("ms") {
+        value / 1000.0
+    } else if unit.starts_with('m') {
+        value * 60.0
+    } else {
+        value
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// An [`Llm`] that retries transient failures and falls back to other models.
+///
+/// The wrapper reports the primary model's name. A request counts as successful once
+/// the model has produced its first response; errors later in a stream are passed
+/// through unchanged.
+pub struct FallbackLlm {
+    name: String,
+    models: Vec<Arc<dyn Llm>>,
+    retry_policy: RetryPolicy,
+    classifier: ErrorClassifier,
+}
+
+impl FallbackLlm {
+    /// Wrap a primary model. Without fallbacks this is a retrying wrapper.
+    pub fn new(primary: Arc<dyn Llm>) -> Self {
+        Self {
+            name: primary.name().to_string(),
+            models: vec![primary],
+            retry_policy: RetryPolicy::default(),
+            classifier: Arc::new(classify_error),
+        }
+    }
+
+    /// Append a model to try after the previous ones are exhausted.
+    pub fn with_fallback(mut self, model: Arc<dyn Llm>) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Append several fallback models, in order.
+    pub fn with_fallbacks(mut self, models: impl IntoIterator<Item = Arc<dyn Llm>>) -> Self {
+        self.models.extend(models);
+        self
+    }
+
+    /// Override the name reported by [`Llm::name`].
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set the retry policy applied to each model.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Replace the default [`classify_error`] classification.
+    pub fn with_error_classifier(
+        mut self,
+        classifier: impl Fn(&AdkError) -> ErrorClass + Send + Sync + 'static,
+    ) -> Self {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Models in the order they are tried.
+    pub fn models(&self) -> &[Arc<dyn Llm>] {
+        &self.models
+    }
+
+    /// Call one model and wait for its first response, so errors that providers
+    /// report as the first stream item can be retried too.
+    async fn try_model(
+        model: &Arc<dyn Llm>,
+        mut req: LlmRequest,
+        stream: bool,
+    ) -> Result<LlmResponseStream> {
+        req.model = model.name().to_string();
+        let mut responses = model.generate_content(req, stream).await?;
+        let first = match responses.next().await {
+            Some(Ok(first)) => first,
+            Some(Err(e)) => return Err(e),
+            None => return Ok(responses),
+        };
+
+        let model_name = model.name().to_string();
+        let responses =
+            futures::stream::once(async move { Ok(first) }).chain(responses).map(move |result| {
+                result.map(|mut response| {
+                    // Nested wrappers already recorded the innermost model
+                    response.model_name.get_or_insert_with(|| model_name.clone());
+                    response
+                })
+            });
+        Ok(Box::pin(responses))
+    }
+}
+
+#[async_trait]
+impl Llm for FallbackLlm {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn generate_content(&self, req: LlmRequest, stream: bool) -> Result<LlmResponseStream> {
+        let mut last_error = None;
+
+        for (index, model) in self.models.iter().enumerate() {
+            let mut retry = 0;
+            loop {
+                let error = match Self::try_model(model, req.clone(), stream).await {
+                    Ok(responses) => {
+                        if index > 0 {
+                            tracing::info!(model = %model.name(), attempt = retry + 1, "fallback model answered");
+                        }
+                        return Ok(responses);
+                    }
+                    Err(e) => e,
+                };
+
+                let retry_after = match (self.classifier)(&error) {
+                    ErrorClass::Fatal => return Err(error),
+                    ErrorClass::Retryable { retry_after } => retry_after,
+                };
+                if retry >= self.retry_policy.max_retries
+                    || retry_after.is_some_and(|delay| delay > self.retry_policy.max_backoff)
+                {
+                    last_error = Some(error);
+                    break;
+                }
+
+                retry += 1;
+                let delay = retry_after.unwrap_or_else(|| self.retry_policy.backoff_for(retry));
+                tracing::warn!(
+                    model = %model.name(),
+                    retry,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %error,
+                    "retrying model request"
+                );
+                tokio::time::sleep(delay).await;
+            }
+
+            if let (Some(next), Some(error)) = (self.models.get(index + 1), &last_error) {
+                tracing::warn!(
+                    from = %model.name(),
+                    to = %next.name(),
+                    error = %error,
+                    "falling back to next model"
+                );
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| AdkError::Config("FallbackLlm has no models".into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adk_core::{Content, LlmResponse};
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails with the queued errors, one per call, then answers with text. Errors
+    /// are reported either from `generate_content` or as the first stream item.
+    struct FlakyLlm {
+        name: String,
+        errors: Mutex<Vec<AdkError>>,
+        in_stream: bool,
+        calls: AtomicU32,
+        models_requested: Mutex<Vec<String>>,
+    }
+
+    impl FlakyLlm {
+        fn new(name: &str, errors: Vec<AdkError>) -> Self {
+            Self {
+                name: name.to_string(),
+                errors: Mutex::new(errors.into_iter().rev().collect()),
+                in_stream: false,
+                calls: AtomicU32::new(0),
+                models_requested: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn failing_in_stream(mut self) -> Self {
+            self.in_stream = true;
+            self
+        }
+    }
+
+    #[async_trait]
+    impl Llm for FlakyLlm {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn generate_content(
+            &self,
+            req: LlmRequest,
+            _stream: bool,
+        ) -> Result<LlmResponseStream> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.models_requested.lock().unwrap().push(req.model);
+            let result = match self.errors.lock().unwrap().pop() {
+                Some(e) if !self.in_stream => return Err(e),
+                Some(e) => Err(e),
+                None => Ok(LlmResponse::new(Content::new("model").with_text(&self.name))),
+            };
+            Ok(Box::pin(futures::stream::once(async move { result })))
+        }
+    }
+
+    fn rate_limited() -> AdkError {
+        AdkError::Model("Groq API error (429 Too Many Requests): slow down".into())
+    }
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy::new()
+            .with_max_retries(max_retries)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(10))
+    }
+
+    async fn answer(llm: &FallbackLlm) -> Result<LlmResponse> {
+        let mut stream = llm.generate_content(LlmRequest::new("primary", vec![]), false).await?;
+        stream.next().await.unwrap()
+    }
+
+    #[test]
+    fn test_classify_error() {
+        assert_eq!(classify_error(&rate_limited()), ErrorClass::Retryable { retry_after: None });
+        assert_eq!(
+            classify_error(&AdkError::Model("OpenAI API error: 503 Service Unavailable".into())),
+            ErrorClass::Retryable { retry_after: None }
+        );
+        assert_eq!(
+            classify_error(&AdkError::Model("Anthropic API error: overloaded_error".into())),
+            ErrorClass::Retryable { retry_after: None }
+        );
+        assert_eq!(
+            classify_error(&AdkError::Model("DeepSeek API error (400): invalid model".into())),
+            ErrorClass::Fatal
+        );
+        assert_eq!(classify_error(&AdkError::Config("missing api key".into())), ErrorClass::Fatal);
+    }
+
+    #[test]
+    fn test_classify_error_needs_a_status_not_any_number_or_word() {
+        let classify = |message: &str| classify_error(&AdkError::Model(message.into()));
+
+        // Numbers and words outside a status position are ignored
+        assert_eq!(classify("max_tokens must be at most 500"), ErrorClass::Fatal);
+        assert_eq!(classify("invalid connection_id"), ErrorClass::Fatal);
+        // A status code decides over phrases elsewhere in the message
+        assert_eq!(classify("API error (400): timeout must be positive"), ErrorClass::Fatal);
+        assert_eq!(
+            classify("request failed with status: 502"),
+            ErrorClass::Retryable { retry_after: None }
+        );
+        assert_eq!(
+            classify("Gemini error: RESOURCE_EXHAUSTED, retryDelay: 30s"),
+            ErrorClass::Retryable { retry_after: Some(Duration::from_secs(30)) }
+        );
+
+        let io = |kind| classify_error(&AdkError::Io(std::io::Error::from(kind)));
+        assert_eq!(io(std::io::ErrorKind::TimedOut), ErrorClass::Retryable { retry_after: None });
+        assert_eq!(io(std::io::ErrorKind::NotFound), ErrorClass::Fatal);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let parse = |message: &str| parse_retry_after(&message.to_lowercase());
+
+        assert_eq!(parse("429: Retry-After: 20"), Some(Duration::from_secs(20)));
+        assert_eq!(parse("Please try again in 1.5s."), Some(Duration::from_millis(1500)));
+        assert_eq!(parse("try again in 250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse(r#"{"retryDelay": "30s"}"#), Some(Duration::from_secs(30)));
+        assert_eq!(parse("rate limit exceeded"), None);
+    }
+
+    #[test]
+    fn test_backoff_is_capped_and_jittered() {
+        let policy =
+            RetryPolicy::new().with_backoff(Duration::from_millis(100), Duration::from_millis(300));
+
+        for _ in 0..20 {
+            let first = policy.backoff_for(1);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let capped = policy.backoff_for(10);
+            assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_then_succeeds() {
+        let primary = Arc::new(FlakyLlm::new("primary", vec![rate_limited(), rate_limited()]));
+        let llm = FallbackLlm::new(primary.clone()).with_retry_policy(fast_policy(2));
+
+        let response = answer(&llm).await.unwrap();
+
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(response.model_name.as_deref(), Some("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_when_retries_exhausted() {
+        let primary = Arc::new(FlakyLlm::new("primary", vec![rate_limited(), rate_limited()]));
+        let backup = Arc::new(FlakyLlm::new("backup", vec![]));
+        let llm = FallbackLlm::new(primary.clone())
+            .with_fallback(backup.clone())
+            .with_retry_policy(fast_policy(1));
+
+        let response = answer(&llm).await.unwrap();
+
+        assert_eq!(llm.name(), "primary");
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(response.model_name.as_deref(), Some("backup"));
+        assert_eq!(backup.models_requested.lock().unwrap().as_slice(), ["backup"]);
+    }
+
+    #[tokio::test]
+    async fn test_fatal_errors_are_not_retried() {
+        let primary = Arc::new(FlakyLlm::new(
+            "primary",
+            vec![AdkError::Model("API error (400): bad request".into())],
+        ));
+        let backup = Arc::new(FlakyLlm::new("backup", vec![]));
+        let llm = FallbackLlm::new(primary.clone())
+            .with_fallback(backup.clone())
+            .with_retry_policy(fast_policy(3));
+
+        assert!(answer(&llm).await.is_err());
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_falls_back_immediately() {
+        let primary = Arc::new(FlakyLlm::new(
+            "primary",
+            vec![AdkError::Model("429 rate limited, retry after 60s".into())],
+        ));
+        let backup = Arc::new(FlakyLlm::new("backup", vec![]));
+        let llm = FallbackLlm::new(primary.clone())
+            .with_fallback(backup)
+            .with_retry_policy(fast_policy(3));
+
+        let response = answer(&llm).await.unwrap();
+
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(response.model_name.as_deref(), Some("backup"));
+    }
+
+    #[tokio::test]
+    async fn test_errors_in_first_stream_item_are_retried() {
+        let primary = Arc::new(FlakyLlm::new("primary", vec![rate_limited()]).failing_in_stream());
+        let llm = FallbackLlm::new(primary.clone()).with_retry_policy(fast_policy(1));
+
+        let response = answer(&llm).await.unwrap();
+
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 2);
+        assert_eq!(response.model_name.as_deref(), Some("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_last_error_returned_when_all_models_fail() {
+        let primary = Arc::new(FlakyLlm::new("primary", vec![rate_limited()]));
+        let backup = Arc::new(FlakyLlm::new(
+            "backup",
+            vec![AdkError::Model("503 Service Unavailable".into())],
+        ));
+        let llm = FallbackLlm::new(primary)
+            .with_fallback(backup)
+            .with_error_classifier(|_| ErrorClass::Retryable { retry_after: None })
+            .with_retry_policy(fast_policy(0));
+
+        let error = answer(&llm).await.unwrap_err();
+        assert!(error.to_string().contains("503"));
+    }
+}
@@ -25,10 +25,21 @@ impl GeminiModel {
         if let Some(parts) = resp.candidates.first().and_then(|c| c.content.parts.as_ref()) {
             for p in parts {
                 match p {
-                    adk_gemini::Part::Text { text, .. } => {
+                    adk_gemini::Part::Text { text, thought: Some(true), thought_signature } => {
+                        converted_parts.push(Part::Thinking {
+                            thinking: text.clone(),
+                            signature: thought_signature.clone(),
+                        });
+                    }
+                    adk_gemini::Part::Text { text, thought_signature, .. } => {
+                        push_signature(&mut converted_parts, thought_signature.as_ref());
                         converted_parts.push(Part::Text { text: text.clone() });
                     }
-                    adk_gemini::Part::FunctionCall { function_call, .. } => {
+                    adk_gemini::Part::FunctionCall { function_call, thought_signature } => {
+                        push_signature(
+                            &mut converted_parts,
+                            thought_signature.as_ref().or(function_call.thought_signature.as_ref()),
+                        );
                         converted_parts.push(Part::FunctionCall {
                             name: function_call.name.clone(),
                             args: function_call.args.clone(),
@@ -86,10 +97,15 @@ impl GeminiModel {
             Some(Content { role: "model".to_string(), parts: converted_parts })
         };
 
+        // Gemini counts thinking separately from candidates; fold it into the
+        // completion count so usage means the same thing across providers.
         let usage_metadata = resp.usage_metadata.as_ref().map(|u| UsageMetadata {
             prompt_token_count: u.prompt_token_count.unwrap_or(0),
-            candidates_token_count: u.candidates_token_count.unwrap_or(0),
+            candidates_token_count: u.candidates_token_count.unwrap_or(0)
+                + u.thoughts_token_count.unwrap_or(0),
             total_token_count: u.total_token_count.unwrap_or(0),
+            cached_content_token_count: u.cached_content_token_count,
+            thoughts_token_count: u.thoughts_token_count,
         });
 
         let finish_reason =
@@ -110,10 +126,20 @@ impl GeminiModel {
             interrupted: false,
             error_code: None,
             error_message: None,
+            model_name: None,
         })
     }
 }
 
+/// Gemini attaches thought signatures to the text or function call part that follows
+/// its reasoning. They are carried as a signature-only thinking part placed before
+/// that part, and reattached when the history is sent back.
+fn push_signature(parts: &mut Vec<Part>, signature: Option<&String>) {
+    if let Some(signature) = signature {
+        parts.push(Part::Thinking { thinking: String::new(), signature: Some(signature.clone()) });
+    }
+}
+
 #[async_trait]
 impl Llm for GeminiModel {
     fn name(&self) -> &str {
@@ -177,13 +203,25 @@ impl Llm for GeminiModel {
                 "model" => {
                     // For model messages, build gemini Content
                     let mut gemini_parts = Vec::new();
+                    // A signature-only thinking part belongs to the part that follows it
+                    let mut pending_signature: Option<String> = None;
                     for part in &content.parts {
                         match part {
+                            Part::Thinking { thinking, signature } if thinking.is_empty() => {
+                                pending_signature = signature.clone();
+                            }
+                            Part::Thinking { thinking, signature } => {
+                                gemini_parts.push(adk_gemini::Part::Text {
+                                    text: thinking.clone(),
+                                    thought: Some(true),
+                                    thought_signature: signature.clone(),
+                                });
+                            }
                             Part::Text { text } => {
                                 gemini_parts.push(adk_gemini::Part::Text {
                                     text: text.clone(),
                                     thought: None,
-                                    thought_signature: None,
+                                    thought_signature: pending_signature.take(),
                                 });
                             }
                             Part::FunctionCall { name, args, .. } => {
@@ -193,7 +231,7 @@ impl Llm for GeminiModel {
                                         args: args.clone(),
                                         thought_signature: None,
                                     },
-                                    thought_signature: None,
+                                    thought_signature: pending_signature.take(),
                                 });
                             }
                             _ => {}
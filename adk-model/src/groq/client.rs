@@ -244,6 +244,7 @@ impl Llm for GroqClient {
                                                         prompt_token_count: u.prompt_tokens as i32,
                                                         candidates_token_count: u.completion_tokens as i32,
                                                         total_token_count: u.total_tokens as i32,
+                                                        ..Default::default()
                                                     }
                                                 }),
                                                 finish_reason,
@@ -252,10 +253,32 @@ impl Llm for GroqClient {
                                                 interrupted: false,
                                                 error_code: None,
                                                 error_message: None,
+                                                model_name: None,
                                             };
                                         } else {
-                                            // Emit partial text content
+                                            // Emit partial reasoning and text content
                                             if let Some(delta) = &choice.delta {
+                                                if let Some(reasoning) = &delta.reasoning {
+                                                    if !reasoning.is_empty() {
+                                                        yield LlmResponse {
+                                                            content: Some(adk_core::Content {
+                                                                role: "model".to_string(),
+                                                                parts: vec![Part::Thinking {
+                                                                    thinking: reasoning.clone(),
+                                                                    signature: None,
+                                                                }],
+                                                            }),
+                                                            usage_metadata: None,
+                                                            finish_reason: None,
+                                                            partial: true,
+                                                            turn_complete: false,
+                                                            interrupted: false,
+                                                            error_code: None,
+                                                            error_message: None,
+                                                            model_name: None,
+                                                        };
+                                                    }
+                                                }
                                                 if let Some(text) = &delta.content {
                                                     if !text.is_empty() {
                                                         yield LlmResponse {
@@ -272,6 +295,7 @@ impl Llm for GroqClient {
                                                             interrupted: false,
                                                             error_code: None,
                                                             error_message: None,
+                                                            model_name: None,
                                                         };
                                                     }
                                                 }
@@ -17,6 +17,9 @@ pub struct Message {
     pub tool_calls: Option<Vec<ToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_call_id: Option<String>,
+    /// Reasoning returned by reasoning models. Never sent back in requests.
+    #[serde(default, skip_serializing)]
+    pub reasoning: Option<String>,
 }
 
 /// Tool call in a message.
@@ -104,7 +107,6 @@ pub struct DeltaMessage {
     #[serde(default)]
     pub content: Option<String>,
     #[serde(default)]
-    #[allow(dead_code)]
     pub reasoning: Option<String>,
     #[serde(default)]
     pub tool_calls: Option<Vec<DeltaToolCall>>,
@@ -171,6 +173,8 @@ pub fn content_to_message(content: &Content) -> Message {
                 text_parts
                     .push(serde_json::to_string(&function_response.response).unwrap_or_default());
             }
+            // Reasoning is output only and is not replayed to the model
+            Part::Thinking { .. } => {}
             _ => {}
         }
     }
@@ -183,6 +187,7 @@ pub fn content_to_message(content: &Content) -> Message {
         name: None,
         tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
         tool_call_id,
+        reasoning: None,
     }
 }
 
@@ -226,6 +231,12 @@ pub fn from_response(response: &ChatCompletionResponse) -> LlmResponse {
         if let Some(msg) = &choice.message {
             let mut parts = Vec::new();
 
+            if let Some(reasoning) = &msg.reasoning {
+                if !reasoning.is_empty() {
+                    parts.push(Part::Thinking { thinking: reasoning.clone(), signature: None });
+                }
+            }
+
             if let Some(text) = &msg.content {
                 if !text.is_empty() {
                     parts.push(Part::Text { text: text.clone() });
@@ -263,6 +274,7 @@ pub fn from_response(response: &ChatCompletionResponse) -> LlmResponse {
         prompt_token_count: u.prompt_tokens as i32,
         candidates_token_count: u.completion_tokens as i32,
         total_token_count: u.total_tokens as i32,
+        ..Default::default()
     });
 
     LlmResponse {
@@ -274,6 +286,7 @@ pub fn from_response(response: &ChatCompletionResponse) -> LlmResponse {
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
@@ -296,5 +309,6 @@ pub fn create_tool_call_response(
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
@@ -14,6 +14,7 @@
 //! - `OllamaModel` - Local LLMs via Ollama (LLaMA, Mistral, Qwen, etc.) - requires `ollama` feature
 //! - `GroqClient` - Groq ultra-fast inference (LLaMA, Mixtral, Gemma) - requires `groq` feature
 //! - [`MockLlm`] - Mock LLM for testing
+//! - [`FallbackLlm`] - Retries transient errors and falls back to other models
 //!
 //! ## Quick Start
 //!
@@ -98,6 +99,17 @@
 //! - Generation configuration (temperature, top_p, etc.)
 //! - OpenAI-compatible APIs (Ollama, vLLM, etc.)
 //!
+//! ### Retries and Fallback
+//!
+//! ```rust,ignore
+//! use adk_model::{FallbackLlm, RetryPolicy};
+//!
+//! // Retry rate limits and server errors, then fall back to a second provider
+//! let model = FallbackLlm::new(Arc::new(primary))
+//!     .with_fallback(Arc::new(backup))
+//!     .with_retry_policy(RetryPolicy::new().with_max_retries(2));
+//! ```
+//!
 //! ### Ollama (Local)
 //!
 //! ```rust,ignore
@@ -111,6 +123,7 @@
 pub mod anthropic;
 #[cfg(feature = "deepseek")]
 pub mod deepseek;
+pub mod fallback;
 #[cfg(feature = "gemini")]
 pub mod gemini;
 #[cfg(feature = "groq")]
@@ -125,6 +138,7 @@ pub mod openai;
 pub use anthropic::AnthropicClient;
 #[cfg(feature = "deepseek")]
 pub use deepseek::{DeepSeekClient, DeepSeekConfig};
+pub use fallback::{ErrorClass, FallbackLlm, RetryPolicy};
 #[cfg(feature = "gemini")]
 pub use gemini::GeminiModel;
 #[cfg(feature = "groq")]
@@ -47,6 +47,11 @@ pub fn content_to_chat_message(content: &Content) -> Option<ChatMessage> {
 pub fn chat_response_to_llm_response(response: &ChatMessageResponse, partial: bool) -> LlmResponse {
     let mut parts = Vec::new();
 
+    // Add reasoning from thinking models
+    if let Some(thinking) = response.message.thinking.as_ref().filter(|t| !t.is_empty()) {
+        parts.push(Part::Thinking { thinking: thinking.clone(), signature: None });
+    }
+
     // Add text content
     if !response.message.content.is_empty() {
         parts.push(Part::Text { text: response.message.content.clone() });
@@ -72,6 +77,7 @@ pub fn chat_response_to_llm_response(response: &ChatMessageResponse, partial: bo
         prompt_token_count: data.prompt_eval_count as i32,
         candidates_token_count: data.eval_count as i32,
         total_token_count: (data.prompt_eval_count + data.eval_count) as i32,
+        ..Default::default()
     });
 
     LlmResponse {
@@ -83,6 +89,7 @@ pub fn chat_response_to_llm_response(response: &ChatMessageResponse, partial: bo
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
@@ -100,5 +107,6 @@ pub fn text_delta_response(text: &str) -> LlmResponse {
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
@@ -200,6 +200,7 @@ impl Llm for OpenAIClient {
                                         interrupted: false,
                                         error_code: None,
                                         error_message: None,
+                                        model_name: None,
                                     };
                                     continue;
                                 }
@@ -227,6 +228,7 @@ impl Llm for OpenAIClient {
                                         interrupted: false,
                                         error_code: None,
                                         error_message: None,
+                                        model_name: None,
                                     };
                                 }
                             }
@@ -84,6 +84,8 @@ fn extract_text(parts: &[Part]) -> String {
         .iter()
         .filter_map(|p| match p {
             Part::Text { text } => Some(text.clone()),
+            // Chat Completions has no reasoning channel, so thinking is not sent back
+            Part::Thinking { .. } => None,
             _ => None,
         })
         .collect::<Vec<_>>()
@@ -145,6 +147,8 @@ pub fn from_openai_response(resp: &CreateChatCompletionResponse) -> LlmResponse
     let content = resp.choices.first().map(|choice| {
         let mut parts = Vec::new();
 
+        // Chat Completions keeps reasoning hidden, so no Thinking parts are produced
+
         // Add text content
         if let Some(text) = &choice.message.content {
             parts.push(Part::Text { text: text.clone() });
@@ -170,6 +174,16 @@ pub fn from_openai_response(resp: &CreateChatCompletionResponse) -> LlmResponse
         prompt_token_count: u.prompt_tokens as i32,
         candidates_token_count: u.completion_tokens as i32,
         total_token_count: u.total_tokens as i32,
+        cached_content_token_count: u
+            .prompt_tokens_details
+            .as_ref()
+            .and_then(|d| d.cached_tokens)
+            .map(|n| n as i32),
+        thoughts_token_count: u
+            .completion_tokens_details
+            .as_ref()
+            .and_then(|d| d.reasoning_tokens)
+            .map(|n| n as i32),
     });
 
     let finish_reason = resp.choices.first().and_then(|c| c.finish_reason).map(|fr| match fr {
@@ -189,6 +203,7 @@ pub fn from_openai_response(resp: &CreateChatCompletionResponse) -> LlmResponse
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
@@ -250,6 +265,7 @@ pub fn from_openai_chunk(chunk: &CreateChatCompletionStreamResponse) -> LlmRespo
         interrupted: false,
         error_code: None,
         error_message: None,
+        model_name: None,
     }
 }
 
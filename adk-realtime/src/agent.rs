@@ -57,9 +57,9 @@ use crate::events::{ServerEvent, ToolResponse};
 use crate::model::RealtimeModel;
 use adk_core::{
     AdkError, AfterAgentCallback, AfterToolCallback, Agent, BeforeAgentCallback,
-    BeforeToolCallback, CallbackContext, Content, Event, EventActions, EventStream,
-    GlobalInstructionProvider, InstructionProvider, InvocationContext, MemoryEntry, Part,
-    ReadonlyContext, Result, Tool, ToolContext,
+    BeforeToolCallback, BeforeToolResult, CallbackContext, Content, Event, EventActions,
+    EventStream, GlobalInstructionProvider, InstructionProvider, InvocationContext, MemoryEntry,
+    Part, ReadonlyContext, Result, Tool, ToolContext, ToolExecution,
 };
 use async_stream::stream;
 use async_trait::async_trait;
@@ -258,6 +258,9 @@ impl RealtimeAgentBuilder {
     }
 
     /// Add a tool.
+    ///
+    /// Calls to tools that require confirmation are answered with an error, since a
+    /// realtime session cannot pause for approval.
     pub fn tool(mut self, tool: Arc<dyn Tool>) -> Self {
         self.tools.push(tool);
         self
@@ -451,60 +454,6 @@ impl RealtimeAgent {
 
         Ok(config)
     }
-
-    /// Execute a tool call.
-    #[allow(dead_code)]
-    async fn execute_tool(
-        &self,
-        ctx: &Arc<dyn InvocationContext>,
-        call_id: &str,
-        name: &str,
-        arguments: &str,
-    ) -> (serde_json::Value, EventActions) {
-        // Find the tool
-        let tool = self.tools.iter().find(|t| t.name() == name);
-
-        if let Some(tool) = tool {
-            let args: serde_json::Value =
-                serde_json::from_str(arguments).unwrap_or(serde_json::json!({}));
-
-            // Create tool context
-            let tool_ctx: Arc<dyn ToolContext> =
-                Arc::new(RealtimeToolContext::new(ctx.clone(), call_id.to_string()));
-
-            // Execute before_tool callbacks
-            for callback in self.before_tool_callbacks.as_ref() {
-                if let Err(e) = callback(ctx.clone() as Arc<dyn CallbackContext>).await {
-                    return (
-                        serde_json::json!({ "error": e.to_string() }),
-                        EventActions::default(),
-                    );
-                }
-            }
-
-            // Execute the tool
-            let result = match tool.execute(tool_ctx.clone(), args).await {
-                Ok(result) => result,
-                Err(e) => serde_json::json!({ "error": e.to_string() }),
-            };
-
-            let actions = tool_ctx.actions();
-
-            // Execute after_tool callbacks
-            for callback in self.after_tool_callbacks.as_ref() {
-                if let Err(e) = callback(ctx.clone() as Arc<dyn CallbackContext>).await {
-                    return (serde_json::json!({ "error": e.to_string() }), actions);
-                }
-            }
-
-            (result, actions)
-        } else {
-            (
-                serde_json::json!({ "error": format!("Tool {} not found", name) }),
-                EventActions::default(),
-            )
-        }
-    }
 }
 
 #[async_trait]
@@ -686,34 +635,14 @@ impl Agent for RealtimeAgent {
                                 let (result, actions) = if let Some(tool) = tool {
                                     let args: serde_json::Value = serde_json::from_str(&arguments)
                                         .unwrap_or(serde_json::json!({}));
-
-                                    let tool_ctx: Arc<dyn ToolContext> = Arc::new(
-                                        RealtimeToolContext::new(ctx.clone(), call_id.clone())
-                                    );
-
-                                    // Execute before_tool callbacks
-                                    for callback in before_tool_callbacks.as_ref() {
-                                        if let Err(e) = callback(ctx.clone() as Arc<dyn CallbackContext>).await {
-                                            let error_result = serde_json::json!({ "error": e.to_string() });
-                                            (error_result, EventActions::default())
-                                        } else {
-                                            continue;
-                                        };
-                                    }
-
-                                    let result = match tool.execute(tool_ctx.clone(), args).await {
-                                        Ok(r) => r,
-                                        Err(e) => serde_json::json!({ "error": e.to_string() }),
-                                    };
-
-                                    let actions = tool_ctx.actions();
-
-                                    // Execute after_tool callbacks
-                                    for callback in after_tool_callbacks.as_ref() {
-                                        let _ = callback(ctx.clone() as Arc<dyn CallbackContext>).await;
-                                    }
-
-                                    (result, actions)
+                                    execute_tool_call(
+                                        tool.clone(),
+                                        &before_tool_callbacks,
+                                        &after_tool_callbacks,
+                                        &ctx,
+                                        &call_id,
+                                        args,
+                                    ).await
                                 } else {
                                     (
                                         serde_json::json!({ "error": format!("Tool {} not found", name) }),
@@ -807,6 +736,73 @@ impl Agent for RealtimeAgent {
     }
 }
 
+/// Execute a tool call, running the before/after tool callbacks around it.
+///
+/// Before-tool callbacks may rewrite the arguments or skip the tool with a substitute
+/// result; the first after-tool callback that returns a value replaces the result.
+/// The tool's own policy is applied through
+/// [`ToolExecutionPolicy::run`](adk_core::ToolExecutionPolicy::run), so timeouts reach the
+/// model as the same structured error `LlmAgent` sends; tools without one run once with no
+/// timeout.
+async fn execute_tool_call(
+    tool: Arc<dyn Tool>,
+    before_tool_callbacks: &[BeforeToolCallback],
+    after_tool_callbacks: &[AfterToolCallback],
+    ctx: &Arc<dyn InvocationContext>,
+    call_id: &str,
+    mut args: serde_json::Value,
+) -> (serde_json::Value, EventActions) {
+    // A realtime session cannot pause for approval, so such tools never run
+    if tool.requires_confirmation() {
+        let error = format!(
+            "Tool {} requires confirmation, which realtime sessions do not support",
+            tool.name()
+        );
+        return (serde_json::json!({ "error": error }), EventActions::default());
+    }
+
+    // Execute before_tool callbacks
+    for callback in before_tool_callbacks {
+        match callback(ctx.clone() as Arc<dyn CallbackContext>, tool.clone(), args.clone()).await {
+            Ok(BeforeToolResult::Continue(modified_args)) => args = modified_args,
+            Ok(BeforeToolResult::Skip(result)) => return (result, EventActions::default()),
+            Err(e) => {
+                return (serde_json::json!({ "error": e.to_string() }), EventActions::default());
+            }
+        }
+    }
+
+    let policy = tool.execution_policy().unwrap_or_default();
+    let ToolExecution { result, response, actions, .. } = policy
+        .run(
+            tool.as_ref(),
+            || {
+                Arc::new(RealtimeToolContext::new(ctx.clone(), call_id.to_string()))
+                    as Arc<dyn ToolContext>
+            },
+            &args,
+        )
+        .await;
+
+    // Execute after_tool callbacks
+    for callback in after_tool_callbacks {
+        match callback(
+            ctx.clone() as Arc<dyn CallbackContext>,
+            tool.clone(),
+            args.clone(),
+            result.clone(),
+        )
+        .await
+        {
+            Ok(Some(replacement)) => return (replacement, actions),
+            Ok(None) => continue,
+            Err(e) => return (serde_json::json!({ "error": e.to_string() }), actions),
+        }
+    }
+
+    (response, actions)
+}
+
 /// Tool context for realtime agent tool execution.
 struct RealtimeToolContext {
     parent_ctx: Arc<dyn InvocationContext>,
@@ -879,4 +875,152 @@ impl ToolContext for RealtimeToolContext {
             Ok(vec![])
         }
     }
+
+    async fn save_memory(&self, entry: MemoryEntry) -> Result<String> {
+        match self.parent_ctx.memory() {
+            Some(memory) => memory.add(entry).await,
+            None => Err(AdkError::Memory("memory is not available".into())),
+        }
+    }
+
+    async fn forget_memory(&self, id: &str) -> Result<bool> {
+        match self.parent_ctx.memory() {
+            Some(memory) => memory.delete(id).await,
+            None => Err(AdkError::Memory("memory is not available".into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adk_core::{RunConfig, Session, State};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct PaymentTool {
+        executions: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Tool for PaymentTool {
+        fn name(&self) -> &str {
+            "send_payment"
+        }
+
+        fn description(&self) -> &str {
+            "Sends a payment"
+        }
+
+        fn requires_confirmation(&self) -> bool {
+            true
+        }
+
+        async fn execute(
+            &self,
+            _ctx: Arc<dyn ToolContext>,
+            _args: serde_json::Value,
+        ) -> Result<serde_json::Value> {
+            self.executions.fetch_add(1, Ordering::SeqCst);
+            Ok(serde_json::json!({ "sent": true }))
+        }
+    }
+
+    struct MockContext {
+        content: Content,
+        config: RunConfig,
+    }
+
+    impl Session for MockContext {
+        fn id(&self) -> &str {
+            "session"
+        }
+        fn app_name(&self) -> &str {
+            "app"
+        }
+        fn user_id(&self) -> &str {
+            "user"
+        }
+        fn state(&self) -> &dyn State {
+            self
+        }
+        fn conversation_history(&self) -> Vec<Content> {
+            Vec::new()
+        }
+    }
+
+    impl State for MockContext {
+        fn get(&self, _key: &str) -> Option<serde_json::Value> {
+            None
+        }
+        fn set(&mut self, _key: String, _value: serde_json::Value) {}
+        fn all(&self) -> HashMap<String, serde_json::Value> {
+            HashMap::new()
+        }
+    }
+
+    #[async_trait]
+    impl ReadonlyContext for MockContext {
+        fn invocation_id(&self) -> &str {
+            "invocation"
+        }
+        fn agent_name(&self) -> &str {
+            "voice"
+        }
+        fn user_id(&self) -> &str {
+            "user"
+        }
+        fn app_name(&self) -> &str {
+            "app"
+        }
+        fn session_id(&self) -> &str {
+            "session"
+        }
+        fn branch(&self) -> &str {
+            ""
+        }
+        fn user_content(&self) -> &Content {
+            &self.content
+        }
+    }
+
+    #[async_trait]
+    impl CallbackContext for MockContext {
+        fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
+            None
+        }
+    }
+
+    #[async_trait]
+    impl InvocationContext for MockContext {
+        fn agent(&self) -> Arc<dyn Agent> {
+            unimplemented!()
+        }
+        fn memory(&self) -> Option<Arc<dyn adk_core::Memory>> {
+            None
+        }
+        fn session(&self) -> &dyn Session {
+            self
+        }
+        fn run_config(&self) -> &RunConfig {
+            &self.config
+        }
+        fn end_invocation(&self) {}
+        fn ended(&self) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_requiring_confirmation_is_refused() {
+        let tool = Arc::new(PaymentTool { executions: AtomicU32::new(0) });
+        let ctx: Arc<dyn InvocationContext> =
+            Arc::new(MockContext { content: Content::new("user"), config: RunConfig::default() });
+
+        let (response, _) =
+            execute_tool_call(tool.clone(), &[], &[], &ctx, "call_1", serde_json::json!({})).await;
+
+        assert_eq!(tool.executions.load(Ordering::SeqCst), 0);
+        assert!(response["error"].as_str().unwrap().contains("requires confirmation"));
+    }
 }
@@ -14,6 +14,9 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Handler for tool/function calls from the realtime model.
+///
+/// Handlers run once with no timeout; `adk_core::ToolExecutionPolicy` only applies to
+/// [`Tool`](adk_core::Tool)s registered on a `RealtimeAgent`.
 #[async_trait]
 pub trait ToolHandler: Send + Sync {
     /// Execute a tool call and return the result.
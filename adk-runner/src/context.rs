@@ -1,6 +1,6 @@
 use adk_core::{
-    Agent, Artifacts, CallbackContext, Content, Event, InvocationContext as InvocationContextTrait,
-    Memory, ReadonlyContext, RunConfig,
+    Agent, Artifacts, CallbackContext, CancellationToken, Content, Event,
+    InvocationContext as InvocationContextTrait, Memory, ReadonlyContext, RunConfig,
 };
 use adk_session::Session as AdkSession;
 use async_trait::async_trait;
@@ -101,6 +101,15 @@ impl adk_core::Session for MutableSession {
 
         history
     }
+
+    fn confirmation_request_events(&self) -> Vec<Event> {
+        let events = self.events.read().unwrap();
+        events
+            .iter()
+            .filter(|event| !event.actions.requested_tool_confirmations.is_empty())
+            .cloned()
+            .collect()
+    }
 }
 
 impl adk_core::State for MutableSession {
@@ -132,6 +141,7 @@ pub struct InvocationContext {
     memory: Option<Arc<dyn Memory>>,
     run_config: RunConfig,
     ended: Arc<AtomicBool>,
+    cancellation_token: CancellationToken,
     /// Mutable session that allows state to be updated during execution.
     /// This is shared across all agents in a workflow, enabling state
     /// propagation between sequential/parallel agents.
@@ -160,6 +170,7 @@ impl InvocationContext {
             memory: None,
             run_config: RunConfig::default(),
             ended: Arc::new(AtomicBool::new(false)),
+            cancellation_token: CancellationToken::new(),
             session: Arc::new(MutableSession::new(session)),
         }
     }
@@ -188,6 +199,7 @@ impl InvocationContext {
             memory: None,
             run_config: RunConfig::default(),
             ended: Arc::new(AtomicBool::new(false)),
+            cancellation_token: CancellationToken::new(),
             session,
         }
     }
@@ -212,6 +224,12 @@ impl InvocationContext {
         self
     }
 
+    /// Stop the invocation's agents when `token` is cancelled
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
     /// Get a reference to the mutable session.
     /// This allows the Runner to apply state deltas when events are processed.
     pub fn mutable_session(&self) -> &Arc<MutableSession> {
@@ -282,4 +300,8 @@ impl InvocationContextTrait for InvocationContext {
     fn ended(&self) -> bool {
         self.ended.load(std::sync::atomic::Ordering::SeqCst)
     }
+
+    fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
 }
@@ -32,15 +32,25 @@
 //!
 //! - Automatic session management
 //! - Memory injection
+//! - Automatic ingestion of sessions into long-term memory
 //! - Artifact handling
 //! - Callback hooks at every stage
+//! - Token usage and cost accounting with optional budgets
+//! - Stale-session detection and optional per-session serialization
+//! - Cooperative cancellation of in-flight invocations
 
 mod callbacks;
 mod context;
+pub mod memory_ingestion;
 mod runner;
+mod session_lock;
+mod usage;
 
 pub use callbacks::{
     AfterModelCallback, AfterToolCallback, BeforeModelCallback, BeforeToolCallback, Callbacks,
 };
 pub use context::{InvocationContext, MutableSession};
-pub use runner::{Runner, RunnerConfig};
+pub use memory_ingestion::{IngestTrigger, MemoryIngestion};
+pub use runner::{CANCELLED_ERROR_CODE, RunOptions, Runner, RunnerConfig};
+pub use session_lock::SessionLocks;
+pub use usage::UsageBudget;
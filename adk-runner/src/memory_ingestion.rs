@@ -0,0 +1,308 @@
+//! Automatic ingestion of session history into long-term memory.
+//!
+//! A [`MemoryIngestion`] set with [`Runner::with_memory_ingestion`](crate::Runner::with_memory_ingestion)
+//! pushes a session's events into a [`MemoryService`] when an invocation ends or when
+//! the session is closed with [`Runner::close_session`](crate::Runner::close_session).
+//!
+//! Each ingestion sends the whole session, and memory services replace what they
+//! remembered from a session when it is added again, so ingesting repeatedly never
+//! duplicates memories. Ingestion after an invocation runs in the background once the
+//! event stream has ended.
+//!
+//! ```rust,ignore
+//! use adk_memory::InMemoryMemoryService;
+//! use adk_runner::{IngestTrigger, MemoryIngestion};
+//!
+//! let ingestion = MemoryIngestion::new(Arc::new(InMemoryMemoryService::new()))
+//!     .with_trigger(IngestTrigger::SessionClose)
+//!     .with_distiller(model);
+//! let runner = Runner::new(config)?.with_memory_ingestion(ingestion);
+//! ```
+
+use adk_core::{Content, Event, Llm, LlmRequest, Part, Result};
+use adk_memory::{MemoryEntry, MemoryService};
+use adk_session::{KEY_PREFIX_TEMP, Session, SessionService};
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// Author of the memories produced by distillation.
+pub const DISTILLED_MEMORY_AUTHOR: &str = "memory";
+
+const DEFAULT_DISTILL_INSTRUCTION: &str = "Extract the facts worth remembering about the user \
+from the conversation below: preferences, personal details, decisions and commitments. Write \
+each fact as a short standalone sentence on its own line. Reply with the facts only, or with \
+nothing if there are none.";
+
+/// When the runner ingests a session into memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IngestTrigger {
+    /// After every invocation that completes without error.
+    ///
+    /// With a distiller, every invocation sends the whole session to the distilling
+    /// model; prefer [`SessionClose`](Self::SessionClose) for long sessions.
+    #[default]
+    InvocationEnd,
+    /// Only when the session is closed with [`Runner::close_session`](crate::Runner::close_session).
+    SessionClose,
+}
+
+type EventFilter = dyn Fn(&Event) -> bool + Send + Sync;
+
+/// Policy for turning session events into memories.
+///
+/// By default, text from user and agent messages is stored as one memory per event.
+/// Tool calls and responses, partial streaming chunks, thinking and state changes are
+/// left out. Compaction summaries are never stored.
+pub struct MemoryIngestion {
+    service: Arc<dyn MemoryService>,
+    trigger: IngestTrigger,
+    include_tool_events: bool,
+    include_partial_events: bool,
+    include_state: bool,
+    event_filter: Option<Arc<EventFilter>>,
+    distiller: Option<Arc<dyn Llm>>,
+    distill_instruction: String,
+}
+
+impl MemoryIngestion {
+    /// Ingest into `service` at the end of every invocation.
+    pub fn new(service: Arc<dyn MemoryService>) -> Self {
+        Self {
+            service,
+            trigger: IngestTrigger::default(),
+            include_tool_events: false,
+            include_partial_events: false,
+            include_state: false,
+            event_filter: None,
+            distiller: None,
+            distill_instruction: DEFAULT_DISTILL_INSTRUCTION.to_string(),
+        }
+    }
+
+    pub fn with_trigger(mut self, trigger: IngestTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Also store function calls and responses, rendered as text.
+    pub fn with_tool_events(mut self, include: bool) -> Self {
+        self.include_tool_events = include;
+        self
+    }
+
+    /// Also store partial streaming chunks. The complete response, merged from the
+    /// chunks, is stored either way.
+    pub fn with_partial_events(mut self, include: bool) -> Self {
+        self.include_partial_events = include;
+        self
+    }
+
+    /// Also store state changes as `key: value` memories. `temp:` keys are never stored.
+    pub fn with_state(mut self, include: bool) -> Self {
+        self.include_state = include;
+        self
+    }
+
+    /// Only consider events for which `filter` returns true, on top of the other rules.
+    pub fn with_event_filter(
+        mut self,
+        filter: impl Fn(&Event) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.event_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Distill the session into atomic facts with `model` and store those instead of
+    /// the raw messages.
+    ///
+    /// Each ingestion is one model call over the whole session. Its tokens are not
+    /// counted in the invocation's usage reports or budget.
+    pub fn with_distiller(mut self, model: Arc<dyn Llm>) -> Self {
+        self.distiller = Some(model);
+        self
+    }
+
+    /// Replace the instruction sent to the distilling model.
+    pub fn with_distill_instruction(mut self, instruction: impl Into<String>) -> Self {
+        self.distill_instruction = instruction.into();
+        self
+    }
+
+    pub fn trigger(&self) -> IngestTrigger {
+        self.trigger
+    }
+
+    /// The memories the filtering rules keep from `events`, before any distillation.
+    ///
+    /// Streamed chunks of one response share an event id, and the last chunk only holds
+    /// its own delta, so consecutive events with the same id are merged into one
+    /// response first.
+    pub fn entries(&self, events: &[Event]) -> Vec<MemoryEntry> {
+        let mut entries = Vec::new();
+        for group in events.chunk_by(|a, b| a.id == b.id && a.invocation_id == b.invocation_id) {
+            if let [event] = group {
+                if !event.llm_response.partial || self.include_partial_events {
+                    self.push_entries(event, &mut entries);
+                }
+                continue;
+            }
+
+            if self.include_partial_events {
+                for chunk in group.iter().filter(|event| event.llm_response.partial) {
+                    self.push_entries(chunk, &mut entries);
+                }
+            }
+            self.push_entries(&merge_chunks(group), &mut entries);
+        }
+        entries
+    }
+
+    fn push_entries(&self, event: &Event, entries: &mut Vec<MemoryEntry>) {
+        if event.is_compaction_summary() {
+            return;
+        }
+        if self.event_filter.as_ref().is_some_and(|filter| !filter(event)) {
+            return;
+        }
+
+        if let Some(content) = &event.llm_response.content {
+            let parts: Vec<Part> =
+                content.parts.iter().filter_map(|part| self.memory_part(part)).collect();
+            if !parts.is_empty() {
+                entries.push(
+                    MemoryEntry::new(&event.author, Content { role: content.role.clone(), parts })
+                        .with_timestamp(event.timestamp),
+                );
+            }
+        }
+
+        if self.include_state {
+            let mut keys: Vec<&String> = event
+                .actions
+                .state_delta
+                .keys()
+                .filter(|key| !key.starts_with(KEY_PREFIX_TEMP))
+                .collect();
+            keys.sort();
+            for key in keys {
+                let value = &event.actions.state_delta[key];
+                let value = value.as_str().map(str::to_string).unwrap_or(value.to_string());
+                let content = Content::new("model").with_text(format!("{key}: {value}"));
+                entries
+                    .push(MemoryEntry::new(&event.author, content).with_timestamp(event.timestamp));
+            }
+        }
+    }
+
+    /// Store the memories from `session`, replacing what was remembered from it before.
+    pub async fn ingest(&self, session: &dyn Session) -> Result<()> {
+        let mut entries = self.entries(&session.events().all());
+        if let Some(model) = &self.distiller {
+            entries = self.distill(model.as_ref(), entries).await?;
+        }
+        self.service.add_session(session.app_name(), session.user_id(), session.id(), entries).await
+    }
+
+    /// Load the stored session and ingest it.
+    pub(crate) async fn ingest_stored(
+        &self,
+        sessions: &dyn SessionService,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<()> {
+        let session = sessions
+            .get(adk_session::GetRequest {
+                app_name: app_name.to_string(),
+                user_id: user_id.to_string(),
+                session_id: session_id.to_string(),
+                num_recent_events: None,
+                after: None,
+            })
+            .await?;
+        self.ingest(session.as_ref()).await
+    }
+
+    fn memory_part(&self, part: &Part) -> Option<Part> {
+        match part {
+            Part::Text { text } if !text.trim().is_empty() => Some(part.clone()),
+            Part::FunctionCall { name, args, .. } if self.include_tool_events => {
+                Some(Part::Text { text: format!("Called {name} with {args}") })
+            }
+            Part::FunctionResponse { function_response, .. } if self.include_tool_events => {
+                Some(Part::Text {
+                    text: format!(
+                        "{} returned {}",
+                        function_response.name, function_response.response
+                    ),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    async fn distill(
+        &self,
+        model: &dyn Llm,
+        entries: Vec<MemoryEntry>,
+    ) -> Result<Vec<MemoryEntry>> {
+        let Some(timestamp) = entries.iter().map(|entry| entry.timestamp).max() else {
+            return Ok(Vec::new());
+        };
+
+        let transcript = entries
+            .iter()
+            .map(|entry| format!("{}: {}", entry.author, text_of(&entry.content)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = format!("{}\n\n{}", self.distill_instruction, transcript);
+        let request = LlmRequest::new(model.name(), vec![Content::new("user").with_text(prompt)]);
+        let mut responses = model.generate_content(request, false).await?;
+        let mut reply = String::new();
+        while let Some(response) = responses.next().await {
+            if let Some(content) = response?.content {
+                reply.push_str(&text_of(&content));
+            }
+        }
+
+        Ok(reply
+            .lines()
+            .map(|line| line.trim().trim_start_matches(['-', '*', '•']).trim())
+            .filter(|fact| !fact.is_empty())
+            .map(|fact| {
+                MemoryEntry::new(DISTILLED_MEMORY_AUTHOR, Content::new("model").with_text(fact))
+                    .with_timestamp(timestamp)
+            })
+            .collect())
+    }
+}
+
+/// One event holding the whole response streamed as `chunks`, with adjacent text
+/// joined, the state changes of every chunk and the timestamp of the last.
+fn merge_chunks(chunks: &[Event]) -> Event {
+    let mut merged = chunks[chunks.len() - 1].clone();
+    let mut parts: Vec<Part> = Vec::new();
+    for chunk in chunks {
+        let Some(content) = &chunk.llm_response.content else { continue };
+        for part in &content.parts {
+            match (parts.last_mut(), part) {
+                (Some(Part::Text { text }), Part::Text { text: more }) => text.push_str(more),
+                (Some(Part::Thinking { thinking, .. }), Part::Thinking { thinking: more, .. }) => {
+                    thinking.push_str(more)
+                }
+                _ => parts.push(part.clone()),
+            }
+        }
+    }
+    merged.actions.state_delta =
+        chunks.iter().flat_map(|chunk| chunk.actions.state_delta.clone()).collect();
+    merged.llm_response.partial = false;
+    if let Some(content) = chunks.iter().find_map(|chunk| chunk.llm_response.content.as_ref()) {
+        merged.llm_response.content = Some(Content { role: content.role.clone(), parts });
+    }
+    merged
+}
+
+fn text_of(content: &Content) -> String {
+    content.parts.iter().filter_map(|part| part.text()).collect()
+}
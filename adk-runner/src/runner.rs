@@ -1,11 +1,21 @@
 use crate::InvocationContext;
+use crate::memory_ingestion::{IngestTrigger, MemoryIngestion};
+use crate::session_lock::SessionLocks;
+use crate::usage::{UsageBudget, UsageTracker};
 use adk_artifact::ArtifactService;
-use adk_core::{Agent, Content, EventStream, Memory, Result, RunConfig};
-use adk_session::SessionService;
+use adk_core::{
+    AdkError, Agent, CancellationToken, Content, Event, EventStream, Memory, PriceTable, Result,
+    RunConfig, ToolConfirmation,
+};
+use adk_session::{SessionKey, SessionService};
 use async_stream::stream;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::Instrument;
 
+/// `error_code` of the event recording that an invocation was cancelled
+pub const CANCELLED_ERROR_CODE: &str = "CANCELLED";
+
 pub struct RunnerConfig {
     pub app_name: String,
     pub agent: Arc<dyn Agent>,
@@ -18,6 +28,21 @@ pub struct RunnerConfig {
     pub run_config: Option<RunConfig>,
 }
 
+/// Per-call options for [`Runner::run_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Applied to the session along with the user's message, so the agent sees it
+    /// from the start of the invocation
+    pub state_delta: HashMap<String, serde_json::Value>,
+    /// ID for the invocation, generated when unset. A message resuming a paused
+    /// invocation keeps that invocation's ID.
+    pub invocation_id: Option<String>,
+    /// Stops the invocation when cancelled. The agent is dropped at its next event,
+    /// or as soon as it checks the token, and a cancellation event with
+    /// [`CANCELLED_ERROR_CODE`] is recorded in the session and ends the stream.
+    pub cancellation_token: Option<CancellationToken>,
+}
+
 pub struct Runner {
     app_name: String,
     root_agent: Arc<dyn Agent>,
@@ -25,6 +50,10 @@ pub struct Runner {
     artifact_service: Option<Arc<dyn ArtifactService>>,
     memory_service: Option<Arc<dyn Memory>>,
     run_config: RunConfig,
+    price_table: Option<Arc<PriceTable>>,
+    usage_budget: Option<UsageBudget>,
+    session_locks: Option<SessionLocks>,
+    memory_ingestion: Option<Arc<MemoryIngestion>>,
 }
 
 impl Runner {
@@ -36,23 +65,120 @@ impl Runner {
             artifact_service: config.artifact_service,
             memory_service: config.memory_service,
             run_config: config.run_config.unwrap_or_default(),
+            price_table: None,
+            usage_budget: None,
+            session_locks: None,
+            memory_ingestion: None,
         })
     }
 
+    /// Price token usage with `prices`. Usage reports on final events then include
+    /// costs, and cost is exported through `adk-telemetry` metrics.
+    pub fn with_price_table(mut self, prices: PriceTable) -> Self {
+        self.price_table = Some(Arc::new(prices));
+        self
+    }
+
+    /// Abort invocations whose token usage or cost exceeds `budget`.
+    pub fn with_usage_budget(mut self, budget: UsageBudget) -> Self {
+        self.usage_budget = Some(budget);
+        self
+    }
+
+    /// Run invocations on the same session one at a time. A run on a session that
+    /// already has one in flight waits for it to finish instead of interleaving with it.
+    pub fn with_serialized_sessions(self) -> Self {
+        self.with_session_locks(SessionLocks::new())
+    }
+
+    /// Like [`with_serialized_sessions`](Self::with_serialized_sessions), queueing
+    /// behind runs of every runner that shares `locks`.
+    pub fn with_session_locks(mut self, locks: SessionLocks) -> Self {
+        self.session_locks = Some(locks);
+        self
+    }
+
+    /// Store session history in long-term memory according to `ingestion`.
+    pub fn with_memory_ingestion(mut self, ingestion: MemoryIngestion) -> Self {
+        self.memory_ingestion = Some(Arc::new(ingestion));
+        self
+    }
+
+    /// Mark the end of a conversation. With memory ingestion configured, the session
+    /// is ingested into memory whatever the trigger. The session itself is kept.
+    pub async fn close_session(&self, user_id: &str, session_id: &str) -> Result<()> {
+        let Some(ingestion) = &self.memory_ingestion else {
+            return Ok(());
+        };
+        ingestion
+            .ingest_stored(self.session_service.as_ref(), &self.app_name, user_id, session_id)
+            .await
+    }
+
     pub async fn run(
         &self,
         user_id: String,
         session_id: String,
         user_content: Content,
     ) -> Result<EventStream> {
+        self.run_with_options(user_id, session_id, user_content, RunOptions::default()).await
+    }
+
+    /// Like [`run`](Self::run), applying `state_delta` to the session along with the
+    /// user's message, so the agent sees it from the start of the invocation.
+    pub async fn run_with_state_delta(
+        &self,
+        user_id: String,
+        session_id: String,
+        user_content: Content,
+        state_delta: HashMap<String, serde_json::Value>,
+    ) -> Result<EventStream> {
+        let options = RunOptions { state_delta, ..Default::default() };
+        self.run_with_options(user_id, session_id, user_content, options).await
+    }
+
+    /// Like [`run`](Self::run), with per-call [`RunOptions`]
+    ///
+    /// ```rust,ignore
+    /// let token = CancellationToken::new();
+    /// let options = RunOptions { cancellation_token: Some(token.clone()), ..Default::default() };
+    /// let events = runner.run_with_options(user_id, session_id, content, options).await?;
+    ///
+    /// // Elsewhere
+    /// token.cancel();
+    /// ```
+    pub async fn run_with_options(
+        &self,
+        user_id: String,
+        session_id: String,
+        user_content: Content,
+        options: RunOptions,
+    ) -> Result<EventStream> {
+        let RunOptions { state_delta, invocation_id, cancellation_token } = options;
         let app_name = self.app_name.clone();
         let session_service = self.session_service.clone();
         let root_agent = self.root_agent.clone();
         let artifact_service = self.artifact_service.clone();
         let memory_service = self.memory_service.clone();
         let run_config = self.run_config.clone();
+        let price_table = self.price_table.clone();
+        let usage_budget = self.usage_budget;
+        let session_locks = self.session_locks.clone();
+        let cancellation_token = cancellation_token.unwrap_or_default();
+        let new_invocation_id =
+            invocation_id.unwrap_or_else(|| format!("inv-{}", uuid::Uuid::new_v4()));
+        let memory_ingestion = self
+            .memory_ingestion
+            .clone()
+            .filter(|ingestion| ingestion.trigger() == IngestTrigger::InvocationEnd);
 
         let s = stream! {
+            // In serialized mode, hold the session until this invocation's stream ends
+            let _session_guard = match &session_locks {
+                Some(locks) => Some(locks.lock(&app_name, &user_id, &session_id).await),
+                None => None,
+            };
+
             // Get or create session
             let session = match session_service
                 .get(adk_session::GetRequest {
@@ -74,12 +200,22 @@ impl Runner {
             // Find which agent should handle this request
             let agent_to_run = Self::find_agent_to_run(&root_agent, session.as_ref());
 
+            // Session usage so far seeds the totals reported for this invocation
+            let mut usage = UsageTracker::new(price_table, usage_budget, session.as_ref());
+
+            // Appends are conditional on the version read here, so a concurrent writer
+            // surfaces as a stale session error instead of interleaved events
+            let mut version = session.version();
+            let session_key = SessionKey::new(&app_name, &user_id, &session_id);
+
             // Clone services for potential reuse in transfer
             let artifact_service_clone = artifact_service.clone();
             let memory_service_clone = memory_service.clone();
 
-            // Create invocation context with MutableSession
-            let invocation_id = format!("inv-{}", uuid::Uuid::new_v4());
+            // Create invocation context with MutableSession.
+            // A message answering a tool confirmation resumes the paused invocation.
+            let invocation_id = Self::find_paused_invocation(session.as_ref(), &user_content)
+                .unwrap_or(new_invocation_id);
             let mut ctx = InvocationContext::new(
                 invocation_id.clone(),
                 agent_to_run.clone(),
@@ -107,6 +243,7 @@ impl Runner {
 
             // Apply run config (streaming mode, etc.)
             ctx = ctx.with_run_config(run_config.clone());
+            ctx = ctx.with_cancellation_token(cancellation_token.clone());
 
             let ctx = Arc::new(ctx);
 
@@ -114,14 +251,19 @@ impl Runner {
             let mut user_event = adk_core::Event::new(&invocation_id);
             user_event.author = "user".to_string();
             user_event.llm_response.content = Some(user_content.clone());
+            user_event.actions.state_delta = state_delta;
 
             // Also add to mutable session for immediate visibility
             // Note: adk_session::Event is a re-export of adk_core::Event, so we can use it directly
+            ctx.mutable_session().apply_state_delta(&user_event.actions.state_delta);
             ctx.mutable_session().append_event(user_event.clone());
 
-            if let Err(e) = session_service.append_event(&session_id, user_event).await {
-                yield Err(e);
-                return;
+            match session_service.append_event_if_version(&session_key, user_event, version).await {
+                Ok(v) => version = v,
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
             }
 
             // Run the agent with instrumentation (ADK-Go style attributes)
@@ -129,6 +271,7 @@ impl Runner {
                 "agent.execute",
                 "gcp.vertex.agent.invocation_id" = %invocation_id,
                 "gcp.vertex.agent.session_id" = %session_id,
+                "gcp.vertex.agent.user_id" = %user_id,
                 "gcp.vertex.agent.event_id" = %invocation_id, // Use invocation_id as event_id for agent spans
                 "agent.name" = %agent_to_run.name()
             );
@@ -145,9 +288,19 @@ impl Runner {
             use futures::StreamExt;
             let mut transfer_target: Option<String> = None;
 
-            while let Some(result) = agent_stream.next().await {
+            loop {
+                let result = tokio::select! {
+                    biased;
+                    _ = cancellation_token.cancelled() => break,
+                    result = agent_stream.next() => match result {
+                        Some(result) => result,
+                        None => break,
+                    },
+                };
                 match result {
-                    Ok(event) => {
+                    Ok(mut event) => {
+                        let over_budget = usage.observe(&mut event);
+
                         // Check for transfer action
                         if let Some(target) = &event.actions.transfer_to_agent {
                             transfer_target = Some(target.clone());
@@ -166,11 +319,20 @@ impl Runner {
                         ctx.mutable_session().append_event(event.clone());
 
                         // Append event to session service (persistent storage)
-                        if let Err(e) = session_service.append_event(&session_id, event.clone()).await {
-                            yield Err(e);
-                            return;
+                        match session_service.append_event_if_version(&session_key, event.clone(), version).await {
+                            Ok(v) => version = v,
+                            Err(e) => {
+                                yield Err(e);
+                                return;
+                            }
                         }
                         yield Ok(event);
+
+                        if let Some(reason) = over_budget {
+                            tracing::warn!(invocation_id = %invocation_id, "{reason}");
+                            yield Err(AdkError::Agent(reason));
+                            return;
+                        }
                     }
                     Err(e) => {
                         yield Err(e);
@@ -179,6 +341,17 @@ impl Runner {
                 }
             }
 
+            if cancellation_token.is_cancelled() {
+                drop(agent_stream);
+                let event = Self::cancellation_event(&invocation_id, agent_to_run.name());
+                ctx.mutable_session().append_event(event.clone());
+                match session_service.append_event_if_version(&session_key, event.clone(), version).await {
+                    Ok(_) => yield Ok(event),
+                    Err(e) => yield Err(e),
+                }
+                return;
+            }
+
             // If a transfer was requested, automatically invoke the target agent
             if let Some(target_name) = transfer_target {
                 if let Some(target_agent) = Self::find_agent(&root_agent, &target_name) {
@@ -206,6 +379,7 @@ impl Runner {
                     if let Some(memory) = memory_service_clone {
                         transfer_ctx = transfer_ctx.with_memory(memory);
                     }
+                    transfer_ctx = transfer_ctx.with_cancellation_token(cancellation_token.clone());
 
                     let transfer_ctx = Arc::new(transfer_ctx);
 
@@ -219,9 +393,19 @@ impl Runner {
                     };
 
                     // Stream events from the transferred agent
-                    while let Some(result) = transfer_stream.next().await {
+                    loop {
+                        let result = tokio::select! {
+                            biased;
+                            _ = cancellation_token.cancelled() => break,
+                            result = transfer_stream.next() => match result {
+                                Some(result) => result,
+                                None => break,
+                            },
+                        };
                         match result {
-                            Ok(event) => {
+                            Ok(mut event) => {
+                                let over_budget = usage.observe(&mut event);
+
                                 // Apply state delta for transferred agent too
                                 if !event.actions.state_delta.is_empty() {
                                     transfer_ctx.mutable_session().apply_state_delta(&event.actions.state_delta);
@@ -230,11 +414,20 @@ impl Runner {
                                 // Add to mutable session
                                 transfer_ctx.mutable_session().append_event(event.clone());
 
-                                if let Err(e) = session_service.append_event(&session_id, event.clone()).await {
-                                    yield Err(e);
-                                    return;
+                                match session_service.append_event_if_version(&session_key, event.clone(), version).await {
+                                    Ok(v) => version = v,
+                                    Err(e) => {
+                                        yield Err(e);
+                                        return;
+                                    }
                                 }
                                 yield Ok(event);
+
+                                if let Some(reason) = over_budget {
+                                    tracing::warn!(invocation_id = %transfer_invocation_id, "{reason}");
+                                    yield Err(AdkError::Agent(reason));
+                                    return;
+                                }
                             }
                             Err(e) => {
                                 yield Err(e);
@@ -242,13 +435,49 @@ impl Runner {
                             }
                         }
                     }
+
+                    if cancellation_token.is_cancelled() {
+                        drop(transfer_stream);
+                        let event = Self::cancellation_event(&transfer_invocation_id, target_agent.name());
+                        transfer_ctx.mutable_session().append_event(event.clone());
+                        match session_service.append_event_if_version(&session_key, event.clone(), version).await {
+                            Ok(_) => yield Ok(event),
+                            Err(e) => yield Err(e),
+                        }
+                        return;
+                    }
                 }
             }
+
+            // Memory is a side effect of a completed invocation: it runs in the background
+            // so the stream ends (and the session is released) without waiting for it,
+            // and a failure to ingest is logged rather than reported on the stream
+            if let Some(ingestion) = memory_ingestion {
+                tokio::spawn(async move {
+                    if let Err(e) = ingestion
+                        .ingest_stored(session_service.as_ref(), &app_name, &user_id, &session_id)
+                        .await
+                    {
+                        tracing::warn!(session_id = %session_id, "memory ingestion failed: {e}");
+                    }
+                });
+            }
         };
 
         Ok(Box::pin(s))
     }
 
+    /// The event recording that `author` was stopped before finishing `invocation_id`
+    fn cancellation_event(invocation_id: &str, author: &str) -> Event {
+        let mut event = Event::new(invocation_id);
+        event.author = author.to_string();
+        event.llm_response.interrupted = true;
+        event.llm_response.turn_complete = true;
+        event.llm_response.error_code = Some(CANCELLED_ERROR_CODE.to_string());
+        event.llm_response.error_message = Some("Invocation cancelled".to_string());
+        event
+    }
+
     /// Find which agent should handle the request based on session history
     pub fn find_agent_to_run(
         root_agent: &Arc<dyn Agent>,
@@ -283,6 +512,35 @@ impl Runner {
         root_agent.clone()
     }
 
+    /// Find the invocation paused on the tool confirmation requests that `content` answers.
+    /// Returns the id of that invocation so the resumed run continues it.
+    pub fn find_paused_invocation(
+        session: &dyn adk_session::Session,
+        content: &Content,
+    ) -> Option<String> {
+        let confirmation_ids: Vec<String> = content
+            .parts
+            .iter()
+            .filter_map(ToolConfirmation::from_part)
+            .map(|(id, _)| id)
+            .collect();
+        if confirmation_ids.is_empty() {
+            return None;
+        }
+
+        let events = session.events();
+        for i in (0..events.len()).rev() {
+            if let Some(event) = events.at(i) {
+                let requested = &event.actions.requested_tool_confirmations;
+                if confirmation_ids.iter().any(|id| requested.contains_key(id)) {
+                    return Some(event.invocation_id.clone());
+                }
+            }
+        }
+
+        None
+    }
+
     /// Check if agent and its parent chain allow transfer up the tree
     fn is_transferable(root_agent: &Arc<dyn Agent>, agent: &Arc<dyn Agent>) -> bool {
         // For now, always allow transfer
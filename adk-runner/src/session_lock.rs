@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::OwnedMutexGuard;
+
+type SessionKey = (String, String, String);
+
+/// Per-session locks that make concurrent runs on one session queue instead of racing.
+///
+/// Clones share the same locks, so runners built per request (as `adk-server` does)
+/// serialize against each other when given clones of one `SessionLocks`. Locks only
+/// cover runners in this process; writers elsewhere are still caught by the session
+/// version check and fail with [`AdkError::StaleSession`](adk_core::AdkError::StaleSession).
+#[derive(Clone, Default)]
+pub struct SessionLocks {
+    locks: Arc<Mutex<HashMap<SessionKey, Weak<tokio::sync::Mutex<()>>>>>,
+}
+
+impl SessionLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no other run holds the session, then hold it until the guard drops.
+    pub(crate) async fn lock(
+        &self,
+        app_name: &str,
+        user_id: &str,
+        session_id: &str,
+    ) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Entries die with their last guard or waiter; drop them as we go
+            locks.retain(|_, lock| lock.strong_count() > 0);
+
+            let key = (app_name.to_string(), user_id.to_string(), session_id.to_string());
+            match locks.get(&key).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(tokio::sync::Mutex::new(()));
+                    locks.insert(key, Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+
+    /// Number of sessions currently held or waited on.
+    pub fn active(&self) -> usize {
+        self.locks.lock().unwrap().values().filter(|lock| lock.strong_count() > 0).count()
+    }
+}
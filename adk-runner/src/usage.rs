@@ -0,0 +1,197 @@
+use adk_core::{Event, PriceTable, TokenUsage, UsageReport, UsageTotals};
+use adk_telemetry::TokenCounts;
+use std::sync::Arc;
+
+/// Model name recorded for responses that do not say which model produced them.
+const UNKNOWN_MODEL: &str = "unknown";
+
+/// Limits on the usage of a single invocation.
+///
+/// When an event pushes the invocation past a limit, the runner yields that event
+/// with its usage report attached, then an error, and stops the invocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UsageBudget {
+    /// Maximum total tokens across all model calls.
+    pub max_total_tokens: Option<u64>,
+    /// Maximum cost, in the price table's currency. Only priced models count.
+    pub max_cost: Option<f64>,
+}
+
+impl UsageBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_total_tokens(mut self, max_total_tokens: u64) -> Self {
+        self.max_total_tokens = Some(max_total_tokens);
+        self
+    }
+
+    pub fn with_max_cost(mut self, max_cost: f64) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    /// Describe the exceeded limit, if `totals` is over budget.
+    pub fn check(&self, totals: &UsageTotals) -> Option<String> {
+        if let Some(max) = self.max_total_tokens {
+            if totals.tokens.total_tokens > max {
+                return Some(format!(
+                    "usage budget exceeded: {} tokens used, limit is {}",
+                    totals.tokens.total_tokens, max
+                ));
+            }
+        }
+        if let (Some(max), Some(cost)) = (self.max_cost, totals.cost) {
+            if cost > max {
+                return Some(format!("usage budget exceeded: cost {cost:.6}, limit is {max:.6}"));
+            }
+        }
+        None
+    }
+}
+
+/// Accumulates token usage and cost for one invocation and its session.
+pub(crate) struct UsageTracker {
+    prices: Option<Arc<PriceTable>>,
+    budget: Option<UsageBudget>,
+    report: UsageReport,
+    /// Event id and usage already counted for the latest model response. Chunks of
+    /// one streamed response share an event id and may each report running totals.
+    counted: Option<(String, TokenUsage)>,
+}
+
+impl UsageTracker {
+    /// Create a tracker whose session totals start from the usage recorded in
+    /// `session`'s existing events.
+    pub(crate) fn new(
+        prices: Option<Arc<PriceTable>>,
+        budget: Option<UsageBudget>,
+        session: &dyn adk_session::Session,
+    ) -> Self {
+        let mut tracker = Self { prices, budget, report: UsageReport::default(), counted: None };
+
+        let events = session.events();
+        for i in 0..events.len() {
+            if let Some(event) = events.at(i) {
+                if let Some(usage) = tracker.new_usage(event) {
+                    let cost = tracker.cost(event, &usage);
+                    tracker.report.session.add(usage, cost);
+                }
+            }
+        }
+        tracker.counted = None;
+        tracker
+    }
+
+    /// Count the usage carried by `event`, attach the report to final responses,
+    /// and return a description of the exceeded limit if the budget ran out.
+    /// Events that exceed the budget also carry the report.
+    pub(crate) fn observe(&mut self, event: &mut Event) -> Option<String> {
+        if let Some(usage) = self.new_usage(event) {
+            let model = event.llm_response.model_name.as_deref().unwrap_or(UNKNOWN_MODEL);
+            let cost = self.cost(event, &usage);
+
+            self.report.invocation.add(usage, cost);
+            self.report.session.add(usage, cost);
+            self.report.agents.entry(event.author.clone()).or_default().add(usage, cost);
+            self.report.models.entry(model.to_string()).or_default().add(usage, cost);
+
+            adk_telemetry::record_llm_usage(
+                &event.author,
+                model,
+                TokenCounts {
+                    prompt: usage.prompt_tokens,
+                    completion: usage.completion_tokens,
+                    cached: usage.cached_tokens,
+                    reasoning: usage.reasoning_tokens,
+                },
+                cost,
+            );
+        }
+
+        let exceeded = self.budget.and_then(|budget| budget.check(&self.report.invocation));
+        if exceeded.is_some() || event.is_final_response() {
+            event.usage_report = Some(self.report.clone());
+        }
+        exceeded
+    }
+
+    /// Usage in `event` not yet counted for the same response.
+    fn new_usage(&mut self, event: &Event) -> Option<TokenUsage> {
+        let reported = TokenUsage::from(event.llm_response.usage_metadata.as_ref()?);
+        let counted = match &self.counted {
+            Some((id, counted)) if *id == event.id => *counted,
+            _ => TokenUsage::default(),
+        };
+
+        let merged = counted.max(&reported);
+        let usage = merged.saturating_sub(&counted);
+        self.counted = Some((event.id.clone(), merged));
+        (!usage.is_empty()).then_some(usage)
+    }
+
+    fn cost(&self, event: &Event, usage: &TokenUsage) -> Option<f64> {
+        let model = event.llm_response.model_name.as_deref()?;
+        self.prices.as_ref()?.cost(model, usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adk_core::{ModelPricing, UsageMetadata};
+
+    fn usage_event(id: &str, prompt: i32, completion: i32) -> Event {
+        let mut event = Event::with_id(id, "inv-1");
+        event.author = "agent".to_string();
+        event.llm_response.partial = true;
+        event.llm_response.model_name = Some("test-model".to_string());
+        event.llm_response.usage_metadata = Some(UsageMetadata {
+            prompt_token_count: prompt,
+            candidates_token_count: completion,
+            total_token_count: prompt + completion,
+            ..Default::default()
+        });
+        event
+    }
+
+    fn tracker(budget: Option<UsageBudget>) -> UsageTracker {
+        let prices = PriceTable::new().with_model("test-model", ModelPricing::new(1.0, 2.0));
+        UsageTracker {
+            prices: Some(Arc::new(prices)),
+            budget,
+            report: UsageReport::default(),
+            counted: None,
+        }
+    }
+
+    #[test]
+    fn test_running_totals_in_one_response_count_once() {
+        let mut tracker = tracker(None);
+        tracker.observe(&mut usage_event("resp-1", 100, 10));
+        tracker.observe(&mut usage_event("resp-1", 100, 30));
+        tracker.observe(&mut usage_event("resp-2", 50, 5));
+
+        let totals = tracker.report.invocation;
+        assert_eq!(totals.tokens.prompt_tokens, 150);
+        assert_eq!(totals.tokens.completion_tokens, 35);
+        assert_eq!(totals.tokens.total_tokens, 185);
+        assert!((totals.cost.unwrap() - 220.0 / 1_000_000.0).abs() < 1e-12);
+        assert_eq!(tracker.report.agents["agent"], totals);
+        assert_eq!(tracker.report.models["test-model"], totals);
+    }
+
+    #[test]
+    fn test_budget_reports_exceeded_limit() {
+        let mut tracker = tracker(Some(UsageBudget::new().with_max_total_tokens(100)));
+        let mut first = usage_event("resp-1", 60, 10);
+        assert!(tracker.observe(&mut first).is_none());
+        assert!(first.usage_report.is_none());
+
+        let mut second = usage_event("resp-2", 30, 10);
+        let exceeded = tracker.observe(&mut second).unwrap();
+        assert!(exceeded.contains("110 tokens"));
+        assert_eq!(second.usage_report.unwrap().invocation.tokens.total_tokens, 110);
+    }
+}
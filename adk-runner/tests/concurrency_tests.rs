@@ -0,0 +1,194 @@
+use adk_core::{AdkError, Agent, Content, Event, EventStream, InvocationContext, Result};
+use adk_runner::{Runner, RunnerConfig, SessionLocks};
+use adk_session::{CreateRequest, GetRequest, InMemorySessionService, SessionService};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Replies with two events tagged `tag`. With a gate, waits for it between the two.
+struct TwoStepAgent {
+    tag: &'static str,
+    gate: Option<Arc<Notify>>,
+}
+
+#[async_trait]
+impl Agent for TwoStepAgent {
+    fn name(&self) -> &str {
+        "two_step"
+    }
+
+    fn description(&self) -> &str {
+        "Replies in two steps"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
+        let invocation_id = ctx.invocation_id().to_string();
+        let tag = self.tag;
+        let gate = self.gate.clone();
+
+        let s = async_stream::stream! {
+            for step in 1..=2 {
+                if step == 2 {
+                    if let Some(gate) = &gate {
+                        gate.notified().await;
+                    }
+                }
+                let mut event = Event::new(&invocation_id);
+                event.author = "two_step".to_string();
+                event.llm_response.content =
+                    Some(Content::new("model").with_text(format!("{tag}{step}")));
+                yield Ok(event);
+            }
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+async fn session_service() -> Arc<InMemorySessionService> {
+    let service = Arc::new(InMemorySessionService::new());
+    service
+        .create(CreateRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user".to_string(),
+            session_id: Some("session".to_string()),
+            state: HashMap::new(),
+        })
+        .await
+        .unwrap();
+    service
+}
+
+fn runner(service: Arc<InMemorySessionService>, agent: TwoStepAgent) -> Runner {
+    Runner::new(RunnerConfig {
+        app_name: "test_app".to_string(),
+        agent: Arc::new(agent),
+        session_service: service,
+        artifact_service: None,
+        memory_service: None,
+        run_config: None,
+    })
+    .unwrap()
+}
+
+async fn start(runner: &Runner, text: &str) -> EventStream {
+    runner
+        .run("user".to_string(), "session".to_string(), Content::new("user").with_text(text))
+        .await
+        .unwrap()
+}
+
+async fn session_texts(service: &InMemorySessionService) -> Vec<String> {
+    let session = service
+        .get(GetRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user".to_string(),
+            session_id: "session".to_string(),
+            num_recent_events: None,
+            after: None,
+        })
+        .await
+        .unwrap();
+    session
+        .events()
+        .all()
+        .iter()
+        .filter_map(|e| e.llm_response.content.as_ref()?.parts.first()?.text().map(String::from))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_concurrent_run_makes_other_run_stale() {
+    let service = session_service().await;
+    let gate = Arc::new(Notify::new());
+    let slow = runner(service.clone(), TwoStepAgent { tag: "a", gate: Some(gate.clone()) });
+    let fast = runner(service.clone(), TwoStepAgent { tag: "b", gate: None });
+
+    let mut slow_stream = start(&slow, "first").await;
+    assert!(slow_stream.next().await.unwrap().is_ok());
+
+    // A second run on the same session completes while the first is mid-invocation
+    let fast_events: Vec<_> = start(&fast, "second").await.collect().await;
+    assert!(fast_events.iter().all(|e| e.is_ok()));
+
+    gate.notify_one();
+    match slow_stream.next().await.unwrap() {
+        Err(AdkError::StaleSession { session_id, expected, actual }) => {
+            assert_eq!(session_id, "session");
+            assert_eq!(expected, 2);
+            assert_eq!(actual, 5);
+        }
+        other => panic!("expected StaleSession, got {other:?}"),
+    }
+    assert!(slow_stream.next().await.is_none());
+
+    assert_eq!(session_texts(&service).await, ["first", "a1", "second", "b1", "b2"]);
+}
+
+#[tokio::test]
+async fn test_serialized_runs_queue_per_session() {
+    let service = session_service().await;
+    let locks = SessionLocks::new();
+    let gate = Arc::new(Notify::new());
+    let slow = runner(service.clone(), TwoStepAgent { tag: "a", gate: Some(gate.clone()) })
+        .with_session_locks(locks.clone());
+    let queued = runner(service.clone(), TwoStepAgent { tag: "b", gate: None })
+        .with_session_locks(locks.clone());
+
+    let mut slow_stream = start(&slow, "first").await;
+    assert!(slow_stream.next().await.unwrap().is_ok());
+
+    let queued_stream = start(&queued, "second").await;
+    let queued_run = tokio::spawn(queued_stream.collect::<Vec<_>>());
+
+    // The queued run waits for the session instead of appending
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!queued_run.is_finished());
+    assert_eq!(session_texts(&service).await, ["first", "a1"]);
+
+    gate.notify_one();
+    let slow_events: Vec<_> = slow_stream.collect().await;
+    assert!(slow_events.iter().all(|e| e.is_ok()));
+
+    let queued_events = queued_run.await.unwrap();
+    assert!(queued_events.iter().all(|e| e.is_ok()));
+    assert_eq!(session_texts(&service).await, ["first", "a1", "a2", "second", "b1", "b2"]);
+    assert_eq!(locks.active(), 0);
+}
+
+#[tokio::test]
+async fn test_serialized_runs_on_different_sessions_do_not_wait() {
+    let service = session_service().await;
+    service
+        .create(CreateRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user".to_string(),
+            session_id: Some("other".to_string()),
+            state: HashMap::new(),
+        })
+        .await
+        .unwrap();
+    let gate = Arc::new(Notify::new());
+    let slow = runner(service.clone(), TwoStepAgent { tag: "a", gate: Some(gate.clone()) })
+        .with_serialized_sessions();
+
+    let mut slow_stream = start(&slow, "first").await;
+    assert!(slow_stream.next().await.unwrap().is_ok());
+
+    // The first run holds "session" only, so a run on "other" proceeds
+    let mut other_stream = slow
+        .run("user".to_string(), "other".to_string(), Content::new("user").with_text("hi"))
+        .await
+        .unwrap();
+    assert!(other_stream.next().await.unwrap().is_ok());
+    drop(other_stream);
+
+    gate.notify_one();
+    assert!(slow_stream.next().await.unwrap().is_ok());
+}
@@ -0,0 +1,361 @@
+use adk_agent::LlmAgentBuilder;
+use adk_core::{
+    Agent, Content, Event, EventStream, InvocationContext, Llm, LlmRequest, LlmResponse,
+    LlmResponseStream, Part, Result, RunConfig, StreamingMode,
+};
+use adk_memory::{MemoryEntry, MemoryService, SearchRequest, SearchResponse};
+use adk_runner::{IngestTrigger, MemoryIngestion, Runner, RunnerConfig};
+use adk_session::{CreateRequest, InMemorySessionService, SessionService};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Replies with a tool call, its response, a partial chunk, a final answer and state.
+struct ChattyAgent;
+
+#[async_trait]
+impl Agent for ChattyAgent {
+    fn name(&self) -> &str {
+        "assistant"
+    }
+
+    fn description(&self) -> &str {
+        "Talks a lot"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
+        let invocation_id = ctx.invocation_id().to_string();
+        let event = move |parts: Vec<Part>| {
+            let mut event = Event::new(&invocation_id);
+            event.author = "assistant".to_string();
+            event.llm_response.content = Some(Content { role: "model".to_string(), parts });
+            event
+        };
+
+        let call = event(vec![Part::FunctionCall {
+            name: "get_weather".to_string(),
+            args: serde_json::json!({"city": "Paris"}),
+            id: Some("call-1".to_string()),
+        }]);
+        let mut response = event(vec![Part::FunctionResponse {
+            function_response: adk_core::FunctionResponseData {
+                name: "get_weather".to_string(),
+                response: serde_json::json!({"forecast": "sunny"}),
+            },
+            id: Some("call-1".to_string()),
+        }]);
+        response.author = "get_weather".to_string();
+        let mut partial = event(vec![Part::Text { text: "It is sun".to_string() }]);
+        partial.llm_response.partial = true;
+        let mut answer = event(vec![Part::Text { text: "It is sunny in Paris".to_string() }]);
+        answer.actions.state_delta.insert("city".to_string(), serde_json::json!("Paris"));
+        answer.actions.state_delta.insert("temp:scratch".to_string(), serde_json::json!(1));
+
+        let s = async_stream::stream! {
+            for event in [call, response, partial, answer] {
+                yield Ok(event);
+            }
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+#[derive(Default)]
+struct RecordingMemory {
+    added: Mutex<Vec<(String, Vec<MemoryEntry>)>>,
+}
+
+impl RecordingMemory {
+    fn texts(&self) -> Vec<Vec<String>> {
+        self.added
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, entries)| {
+                entries
+                    .iter()
+                    .map(|e| e.content.parts.iter().filter_map(|p| p.text()).collect())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Ingestion runs in the background after the stream ends, so wait for it.
+    async fn wait_for_ingestions(&self, count: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while self.added.lock().unwrap().len() < count {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("memory ingestion did not run");
+    }
+}
+
+#[async_trait]
+impl MemoryService for RecordingMemory {
+    async fn add_session(
+        &self,
+        _app_name: &str,
+        _user_id: &str,
+        session_id: &str,
+        entries: Vec<MemoryEntry>,
+    ) -> Result<()> {
+        self.added.lock().unwrap().push((session_id.to_string(), entries));
+        Ok(())
+    }
+
+    async fn search(&self, _req: SearchRequest) -> Result<SearchResponse> {
+        Ok(SearchResponse::default())
+    }
+}
+
+/// Never finishes storing a session.
+struct StalledMemory;
+
+#[async_trait]
+impl MemoryService for StalledMemory {
+    async fn add_session(
+        &self,
+        _app_name: &str,
+        _user_id: &str,
+        _session_id: &str,
+        _entries: Vec<MemoryEntry>,
+    ) -> Result<()> {
+        std::future::pending().await
+    }
+
+    async fn search(&self, _req: SearchRequest) -> Result<SearchResponse> {
+        Ok(SearchResponse::default())
+    }
+}
+
+struct FactModel {
+    prompts: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl Llm for FactModel {
+    fn name(&self) -> &str {
+        "fact-model"
+    }
+
+    async fn generate_content(&self, req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        let prompt = req.contents[0].parts[0].text().unwrap_or_default().to_string();
+        self.prompts.lock().unwrap().push(prompt);
+        let response = LlmResponse {
+            content: Some(
+                Content::new("model").with_text("- The user asked about Paris\n\n- Paris is sunny"),
+            ),
+            ..Default::default()
+        };
+        let s = async_stream::stream! {
+            yield Ok(response);
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+/// Streams its answer in three chunks; only the last one is not partial.
+struct ChunkedModel;
+
+#[async_trait]
+impl Llm for ChunkedModel {
+    fn name(&self) -> &str {
+        "chunked-model"
+    }
+
+    async fn generate_content(&self, _req: LlmRequest, _stream: bool) -> Result<LlmResponseStream> {
+        let chunks = ["It is ", "sunny ", "in Paris"].map(|text| {
+            let last = text == "in Paris";
+            LlmResponse {
+                content: Some(Content::new("model").with_text(text)),
+                partial: !last,
+                turn_complete: last,
+                ..Default::default()
+            }
+        });
+        Ok(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok))))
+    }
+}
+
+async fn runner(ingestion: MemoryIngestion) -> Runner {
+    runner_with_agent(Arc::new(ChattyAgent), None, ingestion).await
+}
+
+async fn runner_with_agent(
+    agent: Arc<dyn Agent>,
+    run_config: Option<RunConfig>,
+    ingestion: MemoryIngestion,
+) -> Runner {
+    let sessions = Arc::new(InMemorySessionService::new());
+    sessions
+        .create(CreateRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user".to_string(),
+            session_id: Some("session".to_string()),
+            state: HashMap::new(),
+        })
+        .await
+        .unwrap();
+    Runner::new(RunnerConfig {
+        app_name: "test_app".to_string(),
+        agent,
+        session_service: sessions,
+        artifact_service: None,
+        memory_service: None,
+        run_config,
+    })
+    .unwrap()
+    .with_memory_ingestion(ingestion)
+}
+
+async fn run(runner: &Runner) {
+    let events: Vec<_> = runner
+        .run(
+            "user".to_string(),
+            "session".to_string(),
+            Content::new("user").with_text("Weather in Paris?"),
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(events.iter().all(|e| e.is_ok()));
+}
+
+#[tokio::test]
+async fn test_invocation_end_ingests_messages_only() {
+    let memory = Arc::new(RecordingMemory::default());
+    let runner = runner(MemoryIngestion::new(memory.clone())).await;
+
+    run(&runner).await;
+    memory.wait_for_ingestions(1).await;
+
+    assert_eq!(memory.texts(), [["Weather in Paris?", "It is sunny in Paris"]]);
+    assert_eq!(memory.added.lock().unwrap()[0].0, "session");
+
+    // The next invocation sends the whole session again
+    run(&runner).await;
+    memory.wait_for_ingestions(2).await;
+    assert_eq!(memory.texts()[1].len(), 4);
+}
+
+#[tokio::test]
+async fn test_streamed_responses_are_ingested_whole() {
+    let memory = Arc::new(RecordingMemory::default());
+    let agent = LlmAgentBuilder::new("assistant").model(Arc::new(ChunkedModel)).build().unwrap();
+    let runner = runner_with_agent(
+        Arc::new(agent),
+        Some(RunConfig { streaming_mode: StreamingMode::SSE }),
+        MemoryIngestion::new(memory.clone()),
+    )
+    .await;
+
+    run(&runner).await;
+    memory.wait_for_ingestions(1).await;
+
+    assert_eq!(memory.texts(), [["Weather in Paris?", "It is sunny in Paris"]]);
+}
+
+#[tokio::test]
+async fn test_filters_can_include_tools_partials_and_state() {
+    let memory = Arc::new(RecordingMemory::default());
+    let ingestion = MemoryIngestion::new(memory.clone())
+        .with_tool_events(true)
+        .with_partial_events(true)
+        .with_state(true)
+        .with_event_filter(|event| event.author != "user");
+    let runner = runner(ingestion).await;
+
+    run(&runner).await;
+    memory.wait_for_ingestions(1).await;
+
+    assert_eq!(
+        memory.texts(),
+        [[
+            r#"Called get_weather with {"city":"Paris"}"#,
+            r#"get_weather returned {"forecast":"sunny"}"#,
+            "It is sun",
+            "It is sunny in Paris",
+            "city: Paris",
+        ]]
+    );
+}
+
+#[test]
+fn test_compaction_summaries_are_not_ingested() {
+    let ingestion =
+        MemoryIngestion::new(Arc::new(RecordingMemory::default())).with_tool_events(true);
+    let mut summary = Event::new("inv");
+    summary.author = "assistant".to_string();
+    summary.llm_response.content = Some(Content {
+        role: "model".to_string(),
+        parts: vec![Part::FunctionResponse {
+            function_response: adk_core::FunctionResponseData {
+                name: adk_core::COMPACTION_SUMMARY_FUNCTION_NAME.to_string(),
+                response: serde_json::json!({"summary": "earlier turns", "retained_turns": 1}),
+            },
+            id: None,
+        }],
+    });
+
+    assert!(ingestion.entries(&[summary]).is_empty());
+}
+
+#[tokio::test]
+async fn test_stream_ends_without_waiting_for_ingestion() {
+    let runner = runner(MemoryIngestion::new(Arc::new(StalledMemory))).await;
+
+    tokio::time::timeout(Duration::from_secs(5), run(&runner))
+        .await
+        .expect("stream waited for memory ingestion");
+}
+
+#[tokio::test]
+async fn test_session_close_trigger() {
+    let memory = Arc::new(RecordingMemory::default());
+    let runner =
+        runner(MemoryIngestion::new(memory.clone()).with_trigger(IngestTrigger::SessionClose))
+            .await;
+
+    run(&runner).await;
+    assert!(memory.texts().is_empty());
+
+    runner.close_session("user", "session").await.unwrap();
+    assert_eq!(memory.texts(), [["Weather in Paris?", "It is sunny in Paris"]]);
+}
+
+#[tokio::test]
+async fn test_distillation_stores_facts() {
+    let memory = Arc::new(RecordingMemory::default());
+    let model = Arc::new(FactModel { prompts: Mutex::new(Vec::new()) });
+    let runner = runner(
+        MemoryIngestion::new(memory.clone())
+            .with_distiller(model.clone())
+            .with_distill_instruction("List the facts."),
+    )
+    .await;
+
+    run(&runner).await;
+    memory.wait_for_ingestions(1).await;
+
+    assert_eq!(memory.texts(), [["The user asked about Paris", "Paris is sunny"]]);
+    let added = memory.added.lock().unwrap();
+    assert!(
+        added[0]
+            .1
+            .iter()
+            .all(|e| e.author == adk_runner::memory_ingestion::DISTILLED_MEMORY_AUTHOR)
+    );
+    assert_eq!(
+        model.prompts.lock().unwrap()[0],
+        "List the facts.\n\nuser: Weather in Paris?\nassistant: It is sunny in Paris"
+    );
+}
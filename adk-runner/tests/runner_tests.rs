@@ -182,6 +182,192 @@ async fn test_runner_run() {
     assert!(result.is_ok());
 }
 
+/// Answers with the session's `topic` state
+struct StateEchoAgent;
+
+#[async_trait]
+impl Agent for StateEchoAgent {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn description(&self) -> &str {
+        "Echoes session state"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
+        let topic = ctx.session().state().get("topic").unwrap_or_default();
+        let mut event = Event::new(ctx.invocation_id());
+        event.author = "echo".to_string();
+        event.llm_response.content = Some(Content::new("model").with_text(topic.to_string()));
+        Ok(Box::pin(futures::stream::iter([Ok(event)])))
+    }
+}
+
+#[tokio::test]
+async fn test_runner_run_with_state_delta() {
+    use futures::StreamExt;
+
+    let session_service = Arc::new(adk_session::InMemorySessionService::new());
+    session_service
+        .create(adk_session::CreateRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user123".to_string(),
+            session_id: Some("session456".to_string()),
+            state: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    let runner = Runner::new(RunnerConfig {
+        app_name: "test_app".to_string(),
+        agent: Arc::new(StateEchoAgent),
+        session_service: session_service.clone(),
+        artifact_service: None,
+        memory_service: None,
+        run_config: None,
+    })
+    .unwrap();
+
+    let delta = std::collections::HashMap::from([("topic".to_string(), serde_json::json!("rust"))]);
+    let events: Vec<_> = runner
+        .run_with_state_delta(
+            "user123".to_string(),
+            "session456".to_string(),
+            Content::new("user").with_text("Hi"),
+            delta,
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    // The agent sees the delta, and it is saved with the user's message
+    let reply = events[0].as_ref().unwrap().llm_response.content.clone().unwrap();
+    assert_eq!(reply.parts[0], Part::Text { text: "\"rust\"".to_string() });
+
+    let session = session_service
+        .get(GetRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user123".to_string(),
+            session_id: "session456".to_string(),
+            num_recent_events: None,
+            after: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(session.state().get("topic"), Some(serde_json::json!("rust")));
+    let user_event = session.events().at(0).unwrap().clone();
+    assert_eq!(user_event.author, "user");
+    assert_eq!(user_event.actions.state_delta["topic"], "rust");
+}
+
+/// Answers once, then hangs until the invocation is cancelled
+struct HangingAgent {
+    token: std::sync::Mutex<Option<adk_core::CancellationToken>>,
+}
+
+#[async_trait]
+impl Agent for HangingAgent {
+    fn name(&self) -> &str {
+        "hanging"
+    }
+
+    fn description(&self) -> &str {
+        "Never finishes"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
+        *self.token.lock().unwrap() = Some(ctx.cancellation_token());
+        let mut event = adk_core::Event::new(ctx.invocation_id());
+        event.author = "hanging".to_string();
+        event.llm_response.content = Some(Content::new("model").with_text("Working on it"));
+        Ok(Box::pin(futures::StreamExt::chain(
+            futures::stream::iter([Ok(event)]),
+            futures::stream::pending(),
+        )))
+    }
+}
+
+#[tokio::test]
+async fn test_runner_cancellation_records_event() {
+    use futures::StreamExt;
+
+    let session_service = Arc::new(adk_session::InMemorySessionService::new());
+    session_service
+        .create(adk_session::CreateRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user123".to_string(),
+            session_id: Some("session456".to_string()),
+            state: std::collections::HashMap::new(),
+        })
+        .await
+        .unwrap();
+    let agent = Arc::new(HangingAgent { token: std::sync::Mutex::new(None) });
+    let token = adk_core::CancellationToken::new();
+    let runner = Runner::new(RunnerConfig {
+        app_name: "test_app".to_string(),
+        agent: agent.clone(),
+        session_service: session_service.clone(),
+        artifact_service: None,
+        memory_service: None,
+        run_config: None,
+    })
+    .unwrap();
+
+    let options = adk_runner::RunOptions {
+        invocation_id: Some("inv-chosen".to_string()),
+        cancellation_token: Some(token.clone()),
+        ..Default::default()
+    };
+    let mut events = runner
+        .run_with_options(
+            "user123".to_string(),
+            "session456".to_string(),
+            Content::new("user").with_text("Hi"),
+            options,
+        )
+        .await
+        .unwrap();
+    let first = events.next().await.unwrap().unwrap();
+    assert_eq!(first.invocation_id, "inv-chosen");
+
+    // The agent sees the runner's token
+    token.cancel();
+    assert!(agent.token.lock().unwrap().as_ref().unwrap().is_cancelled());
+
+    let cancelled = events.next().await.unwrap().unwrap();
+    assert_eq!(cancelled.invocation_id, first.invocation_id);
+    assert_eq!(cancelled.author, "hanging");
+    assert!(cancelled.llm_response.interrupted);
+    assert_eq!(
+        cancelled.llm_response.error_code.as_deref(),
+        Some(adk_runner::CANCELLED_ERROR_CODE)
+    );
+    assert!(events.next().await.is_none());
+
+    let session = session_service
+        .get(GetRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user123".to_string(),
+            session_id: "session456".to_string(),
+            num_recent_events: None,
+            after: None,
+        })
+        .await
+        .unwrap();
+    let recorded = session.events().all();
+    assert_eq!(recorded.len(), 3);
+    assert_eq!(recorded[2].id, cancelled.id);
+}
+
 #[test]
 fn test_find_agent_in_tree() {
     let sub_agent: Arc<dyn Agent> = Arc::new(MockAgent { name: "sub_agent".to_string() });
@@ -272,6 +458,40 @@ async fn test_find_agent_to_run_skips_user_events() {
     assert_eq!(agent.name(), "root");
 }
 
+#[test]
+fn test_find_paused_invocation() {
+    let mut paused = adk_session::Event::new("inv-paused");
+    paused.author = "assistant".to_string();
+    paused.actions.requested_tool_confirmations.insert(
+        "confirm_call_1".to_string(),
+        adk_core::ToolConfirmationRequest {
+            tool_name: "send_payment".to_string(),
+            args: serde_json::json!({"amount": 10}),
+            function_call_id: Some("call_1".to_string()),
+            hint: None,
+        },
+    );
+
+    let session = MockSession {
+        id: "session1".to_string(),
+        app_name: "test".to_string(),
+        user_id: "user1".to_string(),
+        events: MockEvents { events: vec![paused] },
+        state: MockState,
+    };
+
+    let approval = Content::new("user")
+        .with_tool_confirmation("confirm_call_1", adk_core::ToolConfirmation::approve());
+    assert_eq!(Runner::find_paused_invocation(&session, &approval), Some("inv-paused".to_string()));
+
+    let unrelated = Content::new("user")
+        .with_tool_confirmation("confirm_other", adk_core::ToolConfirmation::approve());
+    assert_eq!(Runner::find_paused_invocation(&session, &unrelated), None);
+
+    let text = Content::new("user").with_text("hello");
+    assert_eq!(Runner::find_paused_invocation(&session, &text), None);
+}
+
 // Mock agent with sub-agents
 struct MockAgentWithSubs {
     name: String,
@@ -0,0 +1,146 @@
+use adk_core::{
+    Agent, Content, Event, EventStream, InvocationContext, ModelPricing, Part, PriceTable, Result,
+    UsageMetadata,
+};
+use adk_runner::{Runner, RunnerConfig, UsageBudget};
+use adk_session::{CreateRequest, InMemorySessionService, SessionService};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Emits one model response per entry in `responses`, each reporting
+/// `(prompt, completion)` tokens. The last response is the final answer.
+struct UsageAgent {
+    responses: Vec<(i32, i32)>,
+    emitted: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Agent for UsageAgent {
+    fn name(&self) -> &str {
+        "usage_agent"
+    }
+
+    fn description(&self) -> &str {
+        "Reports token usage"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> Result<EventStream> {
+        let invocation_id = ctx.invocation_id().to_string();
+        let responses = self.responses.clone();
+        let emitted = self.emitted.clone();
+        let last = responses.len() - 1;
+
+        let s = async_stream::stream! {
+            for (i, (prompt, completion)) in responses.into_iter().enumerate() {
+                emitted.fetch_add(1, Ordering::SeqCst);
+                let mut event = Event::new(&invocation_id);
+                event.author = "usage_agent".to_string();
+                event.llm_response.model_name = Some("test-model-001".to_string());
+                event.llm_response.usage_metadata = Some(UsageMetadata {
+                    prompt_token_count: prompt,
+                    candidates_token_count: completion,
+                    total_token_count: prompt + completion,
+                    ..Default::default()
+                });
+                let content = if i == last {
+                    Content::new("model").with_text("done")
+                } else {
+                    Content {
+                        role: "model".to_string(),
+                        parts: vec![Part::FunctionCall {
+                            name: "lookup".to_string(),
+                            args: serde_json::json!({}),
+                            id: None,
+                        }],
+                    }
+                };
+                event.llm_response.content = Some(content);
+                yield Ok(event);
+            }
+        };
+        Ok(Box::pin(s))
+    }
+}
+
+async fn runner(agent: UsageAgent, budget: Option<UsageBudget>) -> Runner {
+    let session_service = Arc::new(InMemorySessionService::new());
+    session_service
+        .create(CreateRequest {
+            app_name: "test_app".to_string(),
+            user_id: "user".to_string(),
+            session_id: Some("session".to_string()),
+            state: HashMap::new(),
+        })
+        .await
+        .unwrap();
+
+    let mut runner = Runner::new(RunnerConfig {
+        app_name: "test_app".to_string(),
+        agent: Arc::new(agent),
+        session_service,
+        artifact_service: None,
+        memory_service: None,
+        run_config: None,
+    })
+    .unwrap()
+    .with_price_table(PriceTable::new().with_model("test-model", ModelPricing::new(1.0, 4.0)));
+    if let Some(budget) = budget {
+        runner = runner.with_usage_budget(budget);
+    }
+    runner
+}
+
+async fn run(runner: &Runner) -> Vec<Result<Event>> {
+    let stream = runner
+        .run("user".to_string(), "session".to_string(), Content::new("user").with_text("hi"))
+        .await
+        .unwrap();
+    stream.collect().await
+}
+
+#[tokio::test]
+async fn test_final_event_reports_invocation_and_session_usage() {
+    let agent = UsageAgent { responses: vec![(100, 20), (150, 30)], emitted: Default::default() };
+    let runner = runner(agent, None).await;
+
+    let events: Vec<Event> = run(&runner).await.into_iter().map(|e| e.unwrap()).collect();
+    assert!(events[0].usage_report.is_none());
+    let report = events[1].usage_report.clone().unwrap();
+    assert_eq!(report.invocation.tokens.prompt_tokens, 250);
+    assert_eq!(report.invocation.tokens.completion_tokens, 50);
+    assert_eq!(report.invocation.tokens.total_tokens, 300);
+    // 250 prompt tokens at 1.0 and 50 completion tokens at 4.0 per million
+    assert!((report.invocation.cost.unwrap() - 450.0 / 1_000_000.0).abs() < 1e-12);
+    assert_eq!(report.agents["usage_agent"], report.invocation);
+    assert_eq!(report.models["test-model-001"], report.invocation);
+    assert_eq!(report.session, report.invocation);
+
+    // A second invocation starts from zero, while the session keeps counting
+    let events = run(&runner).await;
+    let report = events.last().unwrap().as_ref().unwrap().usage_report.clone().unwrap();
+    assert_eq!(report.invocation.tokens.total_tokens, 300);
+    assert_eq!(report.session.tokens.total_tokens, 600);
+}
+
+#[tokio::test]
+async fn test_usage_budget_aborts_invocation() {
+    let emitted = Arc::new(AtomicU32::new(0));
+    let agent =
+        UsageAgent { responses: vec![(100, 20), (150, 30), (10, 10)], emitted: emitted.clone() };
+    let runner = runner(agent, Some(UsageBudget::new().with_max_total_tokens(200))).await;
+
+    let events = run(&runner).await;
+    assert_eq!(events.len(), 3);
+    let over = events[1].as_ref().unwrap();
+    assert_eq!(over.usage_report.as_ref().unwrap().invocation.tokens.total_tokens, 300);
+    let err = events[2].as_ref().unwrap_err();
+    assert!(err.to_string().contains("usage budget exceeded"));
+    assert_eq!(emitted.load(Ordering::SeqCst), 2);
+}
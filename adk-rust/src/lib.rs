@@ -183,6 +183,7 @@
 //! - [`GoogleSearchTool`](tool::GoogleSearchTool) - Web search via Google
 //! - [`ExitLoopTool`](tool::ExitLoopTool) - Control loop termination
 //! - [`LoadArtifactsTool`](tool::LoadArtifactsTool) - Access stored artifacts
+//! - [`ManageMemoryTool`](tool::ManageMemoryTool) - Search, save and forget user memories
 //!
 //! ### MCP Tools - External Integrations
 //!
@@ -260,10 +261,10 @@
 //!         })
 //!     }))
 //!     // Track tool usage
-//!     .before_tool_callback(Box::new(|_ctx| {
+//!     .before_tool_callback(Box::new(|_ctx, tool, args| {
 //!         Box::pin(async move {
-//!             println!("Tool about to be called");
-//!             Ok(None) // Continue execution
+//!             println!("Calling tool {} with {}", tool.name(), args);
+//!             Ok(BeforeToolResult::Continue(args)) // Or Skip(result) to bypass the tool
 //!         })
 //!     }))
 //!     .build()?;
@@ -542,8 +543,9 @@ pub mod artifact {
 ///
 /// Long-term memory for agents:
 /// - [`InMemoryMemoryService`](memory::InMemoryMemoryService) - In-memory storage
-/// - Semantic search capabilities
-/// - Memory retrieval and updates
+/// - [`SemanticMemoryService`](memory::SemanticMemoryService) - Embedding-based search
+/// - [`ScopedMemory`](memory::ScopedMemory) - Per-user memory for agents and tools
+/// - Listing, updating, deleting and expiring memories
 ///
 /// Available with feature: `memory`
 #[cfg(feature = "memory")]
@@ -677,9 +679,9 @@ pub use adk_cli::{Launcher, SingleAgentLoader};
 pub mod prelude {
     // Core types (always available)
     pub use crate::{
-        AdkError, Agent, BeforeModelResult, Content, Event, EventStream, InvocationContext, Llm,
-        LlmRequest, LlmResponse, Part, Result, RunConfig, Session, State, Tool, ToolContext,
-        Toolset,
+        AdkError, Agent, BeforeModelResult, BeforeToolResult, Content, Event, EventStream,
+        InvocationContext, Llm, LlmRequest, LlmResponse, Part, Result, RunConfig, Session, State,
+        Tool, ToolContext, Toolset,
     };
 
     // Agents
@@ -712,7 +714,8 @@ pub mod prelude {
     // Tools
     #[cfg(feature = "tools")]
     pub use crate::tool::{
-        BasicToolset, ExitLoopTool, FunctionTool, GoogleSearchTool, LoadArtifactsTool, McpToolset,
+        BasicToolset, ExitLoopTool, FunctionTool, GoogleSearchTool, LoadArtifactsTool,
+        ManageMemoryTool, McpToolset,
     };
 
     // Sessions
@@ -725,7 +728,7 @@ pub mod prelude {
 
     // Memory
     #[cfg(feature = "memory")]
-    pub use crate::memory::InMemoryMemoryService;
+    pub use crate::memory::{InMemoryMemoryService, ScopedMemory};
 
     // Runner
     #[cfg(feature = "runner")]
@@ -11,7 +11,7 @@ pub fn event_to_message(event: &Event) -> Result<Message> {
         .as_ref()
         .ok_or_else(|| adk_core::AdkError::Agent("Event has no content".to_string()))?;
 
-    let a2a_parts = parts::adk_parts_to_a2a(&content.parts, &[])?;
+    let a2a_parts = parts::adk_parts_to_a2a(&content.parts, &event.long_running_tool_ids)?;
 
     let mut metadata = Map::new();
     if event.actions.escalate {
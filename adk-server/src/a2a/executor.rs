@@ -2,33 +2,120 @@ use crate::a2a::{
     Message, TaskState, TaskStatus, TaskStatusUpdateEvent, UpdateEvent, events::message_to_event,
     metadata::to_invocation_meta, processor::EventProcessor,
 };
-use adk_core::Result;
+use adk_core::{EventStream, Result};
+use adk_memory::{MemoryService, ScopedMemory};
 use adk_runner::{Runner, RunnerConfig};
 use adk_session::{CreateRequest, GetRequest};
 use futures::StreamExt;
+use futures::stream::BoxStream;
 use std::sync::Arc;
 
+#[derive(Clone)]
 pub struct ExecutorConfig {
     pub app_name: String,
     pub runner_config: Arc<RunnerConfig>,
 }
 
+#[derive(Clone)]
 pub struct Executor {
     config: ExecutorConfig,
+    user_id: Option<String>,
+    memory_service: Option<Arc<dyn MemoryService>>,
 }
 
 impl Executor {
     pub fn new(config: ExecutorConfig) -> Self {
-        Self { config }
+        Self { config, user_id: None, memory_service: None }
     }
 
+    /// Run as `user_id` instead of a user derived from the context ID
+    pub fn for_user(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Give runs the memories of the user they run as, in place of the runner
+    /// config's memory service
+    pub fn with_memory_service(mut self, memory_service: Arc<dyn MemoryService>) -> Self {
+        self.memory_service = Some(memory_service);
+        self
+    }
+
+    /// Run the agent on `message`, yielding status and artifact updates as the runner
+    /// produces them
+    ///
+    /// A failure ends the stream with a final `Failed` status. Dropping the stream stops
+    /// the agent.
+    pub fn execute_stream(
+        &self,
+        context_id: &str,
+        task_id: &str,
+        message: &Message,
+    ) -> BoxStream<'static, UpdateEvent> {
+        let executor = self.clone();
+        let context_id = context_id.to_string();
+        let task_id = task_id.to_string();
+        let message = message.clone();
+
+        Box::pin(async_stream::stream! {
+            let status = |state: TaskState, message: Option<String>, final_update: bool| {
+                UpdateEvent::TaskStatusUpdate(TaskStatusUpdateEvent {
+                    task_id: task_id.clone(),
+                    context_id: Some(context_id.clone()),
+                    status: TaskStatus { state, message },
+                    final_update,
+                })
+            };
+
+            yield status(TaskState::Submitted, None, false);
+            yield status(TaskState::Working, None, false);
+
+            let (mut processor, mut event_stream) =
+                match executor.start(&context_id, &task_id, &message).await {
+                    Ok(started) => started,
+                    Err(e) => {
+                        yield status(TaskState::Failed, Some(e.to_string()), true);
+                        return;
+                    }
+                };
+
+            while let Some(result) = event_stream.next().await {
+                match result.and_then(|adk_event| processor.process(&adk_event)) {
+                    Ok(Some(artifact_event)) => {
+                        yield UpdateEvent::TaskArtifactUpdate(artifact_event);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        yield status(TaskState::Failed, Some(e.to_string()), true);
+                        return;
+                    }
+                }
+            }
+
+            for terminal_event in processor.make_terminal_events() {
+                yield UpdateEvent::TaskStatusUpdate(terminal_event);
+            }
+        })
+    }
+
+    /// Run the agent on `message` to completion, collecting its updates
     pub async fn execute(
         &self,
         context_id: &str,
         task_id: &str,
         message: &Message,
     ) -> Result<Vec<UpdateEvent>> {
-        let meta = to_invocation_meta(&self.config.app_name, context_id, None);
+        Ok(self.execute_stream(context_id, task_id, message).collect().await)
+    }
+
+    /// Prepare the session and start the runner
+    async fn start(
+        &self,
+        context_id: &str,
+        task_id: &str,
+        message: &Message,
+    ) -> Result<(EventProcessor, EventStream)> {
+        let meta = to_invocation_meta(&self.config.app_name, context_id, self.user_id.as_deref());
 
         // Prepare session
         self.prepare_session(&meta.user_id, &meta.session_id).await?;
@@ -37,86 +124,35 @@ impl Executor {
         let invocation_id = uuid::Uuid::new_v4().to_string();
         let event = message_to_event(message, invocation_id)?;
 
+        let memory_service = match &self.memory_service {
+            Some(service) => Some(Arc::new(ScopedMemory::new(
+                service.clone(),
+                &self.config.runner_config.app_name,
+                &meta.user_id,
+            )) as Arc<dyn adk_core::Memory>),
+            None => self.config.runner_config.memory_service.clone(),
+        };
+
         // Create runner
         let runner = Runner::new(RunnerConfig {
             app_name: self.config.runner_config.app_name.clone(),
             agent: self.config.runner_config.agent.clone(),
             session_service: self.config.runner_config.session_service.clone(),
             artifact_service: self.config.runner_config.artifact_service.clone(),
-            memory_service: self.config.runner_config.memory_service.clone(),
+            memory_service,
             run_config: None,
         })?;
 
-        // Create processor
-        let mut processor =
-            EventProcessor::new(context_id.to_string(), task_id.to_string(), meta.clone());
-
-        let mut results = vec![];
-
-        // Send submitted event
-        results.push(UpdateEvent::TaskStatusUpdate(TaskStatusUpdateEvent {
-            task_id: task_id.to_string(),
-            context_id: Some(context_id.to_string()),
-            status: TaskStatus { state: TaskState::Submitted, message: None },
-            final_update: false,
-        }));
-
-        // Send working event
-        results.push(UpdateEvent::TaskStatusUpdate(TaskStatusUpdateEvent {
-            task_id: task_id.to_string(),
-            context_id: Some(context_id.to_string()),
-            status: TaskStatus { state: TaskState::Working, message: None },
-            final_update: false,
-        }));
-
-        // Run agent
         let content = event
             .llm_response
             .content
             .ok_or_else(|| adk_core::AdkError::Agent("Event has no content".to_string()))?;
-
-        let mut event_stream =
+        let event_stream =
             runner.run(meta.user_id.clone(), meta.session_id.clone(), content).await?;
 
-        // Process events
-        while let Some(result) = event_stream.next().await {
-            match result {
-                Ok(adk_event) => {
-                    if let Some(artifact_event) = processor.process(&adk_event)? {
-                        results.push(UpdateEvent::TaskArtifactUpdate(artifact_event));
-                    }
-                }
-                Err(e) => {
-                    // Send failed event
-                    results.push(UpdateEvent::TaskStatusUpdate(TaskStatusUpdateEvent {
-                        task_id: task_id.to_string(),
-                        context_id: Some(context_id.to_string()),
-                        status: TaskStatus {
-                            state: TaskState::Failed,
-                            message: Some(e.to_string()),
-                        },
-                        final_update: true,
-                    }));
-                    return Ok(results);
-                }
-            }
-        }
-
-        // Send terminal events
-        for terminal_event in processor.make_terminal_events() {
-            results.push(UpdateEvent::TaskStatusUpdate(terminal_event));
-        }
-
-        Ok(results)
-    }
-
-    pub async fn cancel(&self, context_id: &str, task_id: &str) -> Result<TaskStatusUpdateEvent> {
-        Ok(TaskStatusUpdateEvent {
-            task_id: task_id.to_string(),
-            context_id: Some(context_id.to_string()),
-            status: TaskStatus { state: TaskState::Canceled, message: None },
-            final_update: true,
-        })
+        let processor =
+            EventProcessor::new(context_id.to_string(), task_id.to_string(), meta.clone());
+        Ok((processor, event_stream))
     }
 
     async fn prepare_session(&self, user_id: &str, session_id: &str) -> Result<()> {
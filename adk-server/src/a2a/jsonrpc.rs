@@ -62,6 +62,22 @@ impl JsonRpcError {
         Self { code: -32603, message: message.into(), data: None }
     }
 
+    pub fn task_not_found(task_id: &str) -> Self {
+        Self { code: -32001, message: format!("Task not found: {}", task_id), data: None }
+    }
+
+    pub fn task_not_cancelable(task_id: &str) -> Self {
+        Self { code: -32002, message: format!("Task cannot be canceled: {}", task_id), data: None }
+    }
+
+    pub fn push_notification_not_supported() -> Self {
+        Self {
+            code: -32003,
+            message: "Push notifications are not supported".to_string(),
+            data: None,
+        }
+    }
+
     /// Create an internal error with sanitized message for production.
     /// Logs the detailed error but returns a generic message to the client.
     pub fn internal_error_sanitized(error: &dyn std::fmt::Display, expose_details: bool) -> Self {
@@ -81,6 +97,9 @@ pub mod methods {
     pub const MESSAGE_SEND_STREAM: &str = "message/stream";
     pub const TASKS_GET: &str = "tasks/get";
     pub const TASKS_CANCEL: &str = "tasks/cancel";
+    pub const TASKS_RESUBSCRIBE: &str = "tasks/resubscribe";
+    pub const TASKS_PUSH_NOTIFICATION_CONFIG_SET: &str = "tasks/pushNotificationConfig/set";
+    pub const TASKS_PUSH_NOTIFICATION_CONFIG_GET: &str = "tasks/pushNotificationConfig/get";
 }
 
 /// Parameters for message/send method
@@ -100,6 +119,9 @@ pub struct MessageSendConfig {
     pub blocking: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "historyLength")]
     pub history_length: Option<u32>,
+    /// Webhook to notify of the task's updates
+    #[serde(skip_serializing_if = "Option::is_none", rename = "pushNotificationConfig")]
+    pub push_notification_config: Option<PushNotificationConfig>,
 }
 
 /// Parameters for tasks/get method
@@ -118,6 +140,53 @@ pub struct TasksCancelParams {
     pub task_id: String,
 }
 
+/// Parameters for tasks/resubscribe method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksResubscribeParams {
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+}
+
+/// Where to send a task's push notifications
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushNotificationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Webhook URL the task's updates are POSTed to
+    pub url: String,
+    /// Echoed in the `X-A2A-Notification-Token` header, so the receiver can check the
+    /// notification is for a task it asked about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authentication: Option<PushNotificationAuthenticationInfo>,
+}
+
+/// Credentials the server presents to the webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushNotificationAuthenticationInfo {
+    /// Supported schemes; `Bearer` sends the credentials as a bearer token
+    pub schemes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+}
+
+/// Parameters and result of tasks/pushNotificationConfig/set, and result of get
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskPushNotificationConfig {
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+    #[serde(rename = "pushNotificationConfig")]
+    pub push_notification_config: PushNotificationConfig,
+}
+
+/// Parameters for tasks/pushNotificationConfig/get method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksPushNotificationConfigGetParams {
+    #[serde(rename = "taskId")]
+    pub task_id: String,
+}
+
 /// Task representation returned by A2A
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
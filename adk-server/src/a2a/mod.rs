@@ -6,7 +6,10 @@ pub mod jsonrpc;
 pub mod metadata;
 pub mod parts;
 pub mod processor;
+pub mod push;
 pub mod remote_agent;
+pub mod task_manager;
+pub mod task_store;
 pub mod types;
 
 pub use agent_card::{build_agent_card, build_agent_skills};
@@ -14,10 +17,15 @@ pub use client::A2aClient;
 pub use events::{event_to_message, message_to_event};
 pub use executor::{Executor, ExecutorConfig};
 pub use jsonrpc::{
-    JsonRpcError, JsonRpcRequest, JsonRpcResponse, MessageSendConfig, MessageSendParams, Task,
-    TasksCancelParams, TasksGetParams,
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, MessageSendConfig, MessageSendParams,
+    PushNotificationAuthenticationInfo, PushNotificationConfig, Task, TaskPushNotificationConfig,
+    TasksCancelParams, TasksGetParams, TasksPushNotificationConfigGetParams,
+    TasksResubscribeParams,
 };
 pub use metadata::{InvocationMeta, to_invocation_meta};
 pub use parts::{a2a_parts_to_adk, adk_parts_to_a2a};
+pub use push::{PushError, PushNotifier, verify_signature};
 pub use remote_agent::{RemoteA2aAgent, RemoteA2aAgentBuilder, RemoteA2aConfig};
+pub use task_manager::TaskManager;
+pub use task_store::{InMemoryTaskStore, SessionTaskStore, TaskStore};
 pub use types::*;
@@ -10,6 +10,15 @@ pub fn adk_parts_to_a2a(
         .iter()
         .map(|part| match part {
             Part::Text { text } => Ok(crate::a2a::Part::text(text.clone())),
+            Part::Thinking { thinking, signature } => {
+                let mut metadata = Map::new();
+                metadata.insert("thought".to_string(), Value::Bool(true));
+                if let Some(signature) = signature {
+                    metadata
+                        .insert("thought_signature".to_string(), Value::String(signature.clone()));
+                }
+                Ok(crate::a2a::Part::Text { text: thinking.clone(), metadata: Some(metadata) })
+            }
             Part::InlineData { mime_type, data } => {
                 let encoded = general_purpose::STANDARD.encode(data);
                 Ok(crate::a2a::Part::file(crate::a2a::FileContent {
@@ -29,7 +38,9 @@ pub fn adk_parts_to_a2a(
                 }))
             }
             Part::FunctionCall { name, args, id } => {
-                let is_long_running = long_running_ids.contains(name);
+                // Long-running tools are listed by name, confirmation requests by call ID
+                let is_long_running = long_running_ids.contains(name)
+                    || id.as_ref().is_some_and(|id| long_running_ids.contains(id));
                 let mut data = Map::new();
                 let mut call_data = Map::new();
                 call_data.insert("name".to_string(), Value::String(name.clone()));
@@ -64,7 +75,20 @@ pub fn a2a_parts_to_adk(parts: &[crate::a2a::Part]) -> Result<Vec<Part>> {
     parts
         .iter()
         .map(|part| match part {
-            crate::a2a::Part::Text { text, .. } => Ok(Part::Text { text: text.clone() }),
+            crate::a2a::Part::Text { text, metadata } => {
+                let thought =
+                    metadata.as_ref().filter(|m| m.get("thought") == Some(&Value::Bool(true)));
+                match thought {
+                    Some(metadata) => Ok(Part::Thinking {
+                        thinking: text.clone(),
+                        signature: metadata
+                            .get("thought_signature")
+                            .and_then(|v| v.as_str())
+                            .map(String::from),
+                    }),
+                    None => Ok(Part::Text { text: text.clone() }),
+                }
+            }
             crate::a2a::Part::File { file, .. } => {
                 if let Some(bytes) = &file.bytes {
                     let data = general_purpose::STANDARD.decode(bytes).map_err(|e| {
@@ -128,6 +152,24 @@ mod tests {
         assert_eq!(back.len(), 1);
     }
 
+    #[test]
+    fn test_thinking_conversion() {
+        let adk_parts = vec![Part::Thinking {
+            thinking: "Considering options".to_string(),
+            signature: Some("sig".to_string()),
+        }];
+        let a2a_parts = adk_parts_to_a2a(&adk_parts, &[]).unwrap();
+        match &a2a_parts[0] {
+            crate::a2a::Part::Text { metadata, .. } => {
+                assert_eq!(metadata.as_ref().unwrap()["thought"], json!(true));
+            }
+            other => panic!("expected text part, got {:?}", other),
+        }
+
+        let back = a2a_parts_to_adk(&a2a_parts).unwrap();
+        assert_eq!(back, adk_parts);
+    }
+
     #[test]
     fn test_function_call_conversion() {
         let adk_parts = vec![Part::FunctionCall {
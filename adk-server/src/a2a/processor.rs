@@ -12,6 +12,8 @@ pub struct EventProcessor {
     terminal_actions: EventActions,
     response_id: Option<String>,
     terminal_state: Option<TaskState>,
+    /// Long-running calls, including confirmation requests, the agent is waiting on
+    pending_input: Vec<String>,
     has_artifacts: bool,
 }
 
@@ -24,6 +26,7 @@ impl EventProcessor {
             terminal_actions: EventActions::default(),
             response_id: None,
             terminal_state: None,
+            pending_input: Vec::new(),
             has_artifacts: false,
         }
     }
@@ -31,6 +34,17 @@ impl EventProcessor {
     pub fn process(&mut self, event: &Event) -> Result<Option<TaskArtifactUpdateEvent>> {
         self.update_terminal_actions(event);
 
+        // The agent paused on a long-running tool or a confirmation request; the client
+        // answers with a function response in a follow-up message on the same task
+        if !event.long_running_tool_ids.is_empty() {
+            self.terminal_state = Some(TaskState::InputRequired);
+            for id in &event.long_running_tool_ids {
+                if !self.pending_input.contains(id) {
+                    self.pending_input.push(id.clone());
+                }
+            }
+        }
+
         let event_meta = to_event_meta(&self.meta, event);
         let event_meta_map: serde_json::Map<String, serde_json::Value> =
             event_meta.into_iter().collect();
@@ -46,7 +60,7 @@ impl EventProcessor {
         }
 
         // Convert parts
-        let parts = adk_parts_to_a2a(&content.parts, &[])?;
+        let parts = adk_parts_to_a2a(&content.parts, &event.long_running_tool_ids)?;
 
         if parts.is_empty() {
             return Ok(None);
@@ -97,11 +111,13 @@ impl EventProcessor {
 
         // Terminal status
         let state = self.terminal_state.clone().unwrap_or(TaskState::Completed);
+        let message = (state == TaskState::InputRequired)
+            .then(|| format!("Waiting for input on: {}", self.pending_input.join(", ")));
 
         events.push(TaskStatusUpdateEvent {
             task_id: self.task_id.clone(),
             context_id: Some(self.context_id.clone()),
-            status: TaskStatus { state, message: None },
+            status: TaskStatus { state, message },
             final_update: true,
         });
 
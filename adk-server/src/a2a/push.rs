@@ -0,0 +1,379 @@
+//! Push notifications: POSTing a task to the client's webhook when its status changes
+//!
+//! Each notification's body is the task as JSON. When the [`PushNotifier`] has a signing
+//! secret, the `X-A2A-Signature` header carries `sha256=<hex>`, the HMAC-SHA256 of
+//! `<timestamp>.<body>` with the timestamp from the `X-A2A-Timestamp` header. Receivers
+//! check it with [`verify_signature`].
+//!
+//! By default webhooks must be public: URLs naming loopback, private or link-local
+//! addresses are refused, hostnames only connect to the public addresses they resolve
+//! to, and redirects are not followed. Replace the check with
+//! [`PushNotifier::with_url_policy`], for example to allow only known hosts.
+//!
+//! ```rust,ignore
+//! use adk_server::a2a::PushNotifier;
+//!
+//! let notifier = PushNotifier::new()
+//!     .with_signing_secret(std::env::var("A2A_WEBHOOK_SECRET")?)
+//!     .with_retry(5, Duration::from_secs(1));
+//! let config = ServerConfig::new(agent_loader, session_service).with_push_notifications(notifier);
+//! ```
+
+use crate::a2a::task_manager::is_terminal;
+use crate::a2a::{PushNotificationConfig, Task};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+/// Header carrying the payload's signature, `sha256=<hex>`
+pub const SIGNATURE_HEADER: &str = "x-a2a-signature";
+/// Header carrying the Unix time, in seconds, the payload was signed at
+pub const TIMESTAMP_HEADER: &str = "x-a2a-timestamp";
+/// Header echoing the `token` of the task's [`PushNotificationConfig`]
+pub const TOKEN_HEADER: &str = "x-a2a-notification-token";
+
+/// Why a notification could not be delivered
+#[derive(Debug, thiserror::Error)]
+pub enum PushError {
+    #[error("Invalid webhook URL '{0}'")]
+    InvalidUrl(String),
+    #[error("Webhook URL '{0}' is not allowed")]
+    UrlNotAllowed(String),
+    #[error("Webhook rejected the notification with status {0}")]
+    Rejected(reqwest::StatusCode),
+    #[error("Webhook unreachable: {0}")]
+    Unreachable(#[from] reqwest::Error),
+}
+
+impl PushError {
+    /// Whether a later attempt might succeed
+    fn is_transient(&self) -> bool {
+        match self {
+            PushError::InvalidUrl(_) | PushError::UrlNotAllowed(_) => false,
+            PushError::Rejected(status) => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+            PushError::Unreachable(_) => true,
+        }
+    }
+}
+
+/// Decides whether notifications may be sent to a webhook URL
+pub type UrlPolicy = Arc<dyn Fn(&Url) -> bool + Send + Sync>;
+
+/// Sends tasks' push notifications to the webhooks clients register for them
+///
+/// Webhooks are kept in memory, so they are forgotten on restart, and a task's
+/// webhook is dropped once its final status has been sent.
+#[derive(Clone)]
+pub struct PushNotifier {
+    client: reqwest::Client,
+    url_policy: UrlPolicy,
+    signing_secret: Option<Arc<[u8]>>,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    configs: Arc<RwLock<HashMap<String, PushNotificationConfig>>>,
+}
+
+impl Default for PushNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PushNotifier {
+    pub fn new() -> Self {
+        Self {
+            client: client(Some(Arc::new(PublicResolver))),
+            url_policy: Arc::new(is_public_url),
+            signing_secret: None,
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            configs: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Only accept webhooks whose URL passes `policy`, instead of requiring public addresses
+    ///
+    /// Hostnames are then connected to whatever they resolve to, so an allowlist should
+    /// name hosts the deployment trusts.
+    ///
+    /// ```rust,ignore
+    /// let notifier = PushNotifier::new()
+    ///     .with_url_policy(|url| url.host_str() == Some("hooks.example.com"));
+    /// ```
+    pub fn with_url_policy(
+        mut self,
+        policy: impl Fn(&Url) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.client = client(None);
+        self.url_policy = Arc::new(policy);
+        self
+    }
+
+    /// Sign every notification with `secret`
+    pub fn with_signing_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.signing_secret = Some(Arc::from(secret.as_ref()));
+        self
+    }
+
+    /// Try each notification up to `max_attempts` times, doubling the wait after
+    /// `initial_backoff` between attempts. Defaults to 3 attempts from 500ms.
+    pub fn with_retry(mut self, max_attempts: u32, initial_backoff: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Send `task_id`'s notifications to `config`'s webhook, replacing any earlier one
+    pub async fn set_config(
+        &self,
+        task_id: &str,
+        config: PushNotificationConfig,
+    ) -> Result<(), PushError> {
+        let url = match Url::parse(&config.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            _ => return Err(PushError::InvalidUrl(config.url)),
+        };
+        if !(self.url_policy)(&url) {
+            return Err(PushError::UrlNotAllowed(config.url));
+        }
+        self.configs.write().await.insert(task_id.to_string(), config);
+        Ok(())
+    }
+
+    /// The webhook `task_id`'s notifications go to
+    pub async fn config(&self, task_id: &str) -> Option<PushNotificationConfig> {
+        self.configs.read().await.get(task_id).cloned()
+    }
+
+    /// POST `task` to its webhook, if it has one, retrying transient failures
+    ///
+    /// After a task's final status the webhook is forgotten, whether or not it was delivered.
+    pub async fn notify(&self, task: &Task) -> Result<(), PushError> {
+        let Some(config) = self.config(&task.id).await else {
+            return Ok(());
+        };
+        let result = self.send(task, &config).await;
+        if is_terminal(&task.status.state) {
+            self.configs.write().await.remove(&task.id);
+        }
+        result
+    }
+
+    async fn send(&self, task: &Task, config: &PushNotificationConfig) -> Result<(), PushError> {
+        let body = serde_json::to_vec(task).unwrap_or_default();
+
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.deliver(config, &body).await {
+                Ok(()) => return Ok(()),
+                Err(e) if e.is_transient() && attempt < self.max_attempts => {
+                    tracing::debug!(task_id = %task.id, attempt, error = %e, "retrying push notification");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn deliver(&self, config: &PushNotificationConfig, body: &[u8]) -> Result<(), PushError> {
+        let mut request = self
+            .client
+            .post(&config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_vec());
+
+        if let Some(secret) = &self.signing_secret {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default()
+                .to_string();
+            request = request
+                .header(SIGNATURE_HEADER, sign(secret, &timestamp, body))
+                .header(TIMESTAMP_HEADER, timestamp);
+        }
+        if let Some(token) = &config.token {
+            request = request.header(TOKEN_HEADER, token);
+        }
+        if let Some(auth) = &config.authentication {
+            let bearer = auth.schemes.iter().any(|scheme| scheme.eq_ignore_ascii_case("bearer"));
+            if let (true, Some(credentials)) = (bearer, &auth.credentials) {
+                request = request.bearer_auth(credentials);
+            }
+        }
+
+        let response = request.send().await?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(PushError::Rejected(response.status()))
+        }
+    }
+}
+
+/// The notification client, which never follows redirects so webhooks cannot bounce
+/// requests past the URL policy
+fn client(resolver: Option<Arc<PublicResolver>>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .redirect(reqwest::redirect::Policy::none());
+    if let Some(resolver) = resolver {
+        builder = builder.dns_resolver(resolver);
+    }
+    builder.build().unwrap_or_default()
+}
+
+/// The default [`UrlPolicy`]: no `localhost`, and no IP literals outside the public internet
+fn is_public_url(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => {
+            let domain = host.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+    }
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64;
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(ip.into()),
+            None => {
+                let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+                let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+                !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+            }
+        },
+    }
+}
+
+/// Resolves webhook hosts to their public addresses only, so a hostname pointing at
+/// an internal address cannot get past [`is_public_url`]
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("'{host}' has no public address").into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn mac(secret: &[u8], timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// The `X-A2A-Signature` value for `body` sent at `timestamp`
+pub fn sign(secret: &[u8], timestamp: &str, body: &[u8]) -> String {
+    format!("sha256={}", hex::encode(mac(secret, timestamp, body).finalize().into_bytes()))
+}
+
+/// Whether `signature` is the `X-A2A-Signature` of `body` sent at `timestamp`
+pub fn verify_signature(secret: &[u8], timestamp: &str, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=").and_then(|hex| hex::decode(hex).ok())
+    else {
+        return false;
+    };
+    mac(secret, timestamp, body).verify_slice(&expected).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign(b"secret", "1700000000", b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert!(verify_signature(b"secret", "1700000000", b"{}", &signature));
+        assert!(!verify_signature(b"other", "1700000000", b"{}", &signature));
+        assert!(!verify_signature(b"secret", "1700000001", b"{}", &signature));
+        assert!(!verify_signature(b"secret", "1700000000", b"[]", &signature));
+        assert!(!verify_signature(b"secret", "1700000000", b"{}", "sha256=zz"));
+    }
+
+    #[tokio::test]
+    async fn test_set_config_rejects_non_http_urls() {
+        let notifier = PushNotifier::new();
+        let config = |url: &str| PushNotificationConfig {
+            id: None,
+            url: url.to_string(),
+            token: None,
+            authentication: None,
+        };
+        assert!(notifier.set_config("task-1", config("file:///etc/passwd")).await.is_err());
+        assert!(notifier.set_config("task-1", config("not a url")).await.is_err());
+        assert!(notifier.config("task-1").await.is_none());
+
+        notifier.set_config("task-1", config("https://example.com/hook")).await.unwrap();
+        assert_eq!(notifier.config("task-1").await.unwrap().url, "https://example.com/hook");
+    }
+
+    #[tokio::test]
+    async fn test_set_config_rejects_internal_addresses() {
+        let notifier = PushNotifier::new();
+        let config = |url: &str| PushNotificationConfig {
+            id: None,
+            url: url.to_string(),
+            token: None,
+            authentication: None,
+        };
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "http://0.0.0.0/hook",
+        ] {
+            let result = notifier.set_config("task-1", config(url)).await;
+            assert!(matches!(result, Err(PushError::UrlNotAllowed(_))), "{url} was allowed");
+        }
+        assert!(notifier.config("task-1").await.is_none());
+
+        let allowlisted =
+            PushNotifier::new().with_url_policy(|url| url.host_str() == Some("127.0.0.1"));
+        allowlisted.set_config("task-1", config("http://127.0.0.1:8080/hook")).await.unwrap();
+        assert!(
+            allowlisted.set_config("task-1", config("https://example.com/hook")).await.is_err()
+        );
+    }
+}
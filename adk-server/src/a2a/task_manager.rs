@@ -0,0 +1,329 @@
+//! Running A2A tasks in the background, with live updates and cancellation
+//!
+//! Each task runs on its own tokio task, so it outlives the request that started it.
+//! Its updates are broadcast to every subscriber, applied to the task and saved to the
+//! [`TaskStore`] on each status change, which is also pushed to the task's webhook when
+//! a [`PushNotifier`] is set.
+
+use crate::a2a::{
+    Executor, Message, PushNotifier, Task, TaskState, TaskStatus, TaskStatusUpdateEvent, TaskStore,
+    UpdateEvent,
+};
+use adk_core::Result;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+/// Updates buffered per subscriber before a slow one starts missing them
+const UPDATE_BUFFER: usize = 256;
+
+/// Why a message could not start a task
+#[derive(Debug, thiserror::Error)]
+pub enum StartError {
+    #[error("Task {0} is still running")]
+    Running(String),
+    #[error("Task {0} has already finished")]
+    Finished(String),
+    #[error(transparent)]
+    Store(#[from] adk_core::AdkError),
+}
+
+/// A running task's latest snapshot and the channel its updates go out on
+struct Live {
+    task: Task,
+    updates: broadcast::Sender<UpdateEvent>,
+    /// Status changes queued for the webhook, delivered in order
+    notifications: Option<mpsc::UnboundedSender<Task>>,
+}
+
+struct Running {
+    live: Arc<Mutex<Live>>,
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+/// Starts, tracks and cancels the tasks of one A2A server
+pub struct TaskManager {
+    store: Arc<dyn TaskStore>,
+    notifier: Option<PushNotifier>,
+    running: Arc<Mutex<HashMap<String, Running>>>,
+}
+
+impl TaskManager {
+    pub fn new(store: Arc<dyn TaskStore>) -> Self {
+        Self { store, notifier: None, running: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Push every status change to the task's webhook, if it has one
+    pub fn with_notifier(mut self, notifier: PushNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Start a task for `message`, or continue the task it names, e.g. one waiting for
+    /// input
+    ///
+    /// Returns the task as submitted and a receiver of its updates, which ends after
+    /// the final one.
+    pub async fn start(
+        &self,
+        executor: Executor,
+        message: Message,
+    ) -> std::result::Result<(Task, broadcast::Receiver<UpdateEvent>), StartError> {
+        let task_id = message.task_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        if self.running.lock().unwrap().contains_key(&task_id) {
+            return Err(StartError::Running(task_id));
+        }
+
+        let mut task = match self.store.get(&task_id).await? {
+            Some(task) if is_terminal(&task.status.state) => {
+                return Err(StartError::Finished(task_id));
+            }
+            Some(task) => task,
+            None => Task {
+                id: task_id.clone(),
+                context_id: message.context_id.clone(),
+                status: TaskStatus { state: TaskState::Submitted, message: None },
+                artifacts: Some(vec![]),
+                history: Some(vec![]),
+            },
+        };
+        let context_id = task
+            .context_id
+            .clone()
+            .or_else(|| message.context_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        task.context_id = Some(context_id.clone());
+        task.status = TaskStatus { state: TaskState::Submitted, message: None };
+        let mut message = message;
+        message.task_id = Some(task_id.clone());
+        message.context_id = Some(context_id.clone());
+        task.history.get_or_insert_with(Vec::new).push(message.clone());
+
+        let (sender, receiver) = broadcast::channel(UPDATE_BUFFER);
+        let (cancel, cancelled) = oneshot::channel();
+        let notifications = self.notifier.clone().map(|notifier| {
+            let (sender, mut queue) = mpsc::unbounded_channel::<Task>();
+            tokio::spawn(async move {
+                while let Some(task) = queue.recv().await {
+                    if let Err(e) = notifier.notify(&task).await {
+                        tracing::warn!(task_id = %task.id, error = %e, "failed to push A2A task");
+                    }
+                }
+            });
+            sender
+        });
+        let live =
+            Arc::new(Mutex::new(Live { task: task.clone(), updates: sender, notifications }));
+        {
+            let mut running = self.running.lock().unwrap();
+            if running.contains_key(&task_id) {
+                return Err(StartError::Running(task_id));
+            }
+            running.insert(task_id.clone(), Running { live: live.clone(), cancel: Some(cancel) });
+        }
+
+        let updates = executor.execute_stream(&context_id, &task_id, &message);
+        let store = self.store.clone();
+        let running = self.running.clone();
+        tokio::spawn(async move {
+            drive(updates, cancelled, &live, store.as_ref()).await;
+            running.lock().unwrap().remove(&task_id);
+        });
+
+        Ok((task, receiver))
+    }
+
+    /// A task's latest state, running or stored
+    pub async fn get(&self, task_id: &str) -> Result<Option<Task>> {
+        let live = self.running.lock().unwrap().get(task_id).map(|r| r.live.clone());
+        match live {
+            Some(live) => Ok(Some(live.lock().unwrap().task.clone())),
+            None => self.store.get(task_id).await,
+        }
+    }
+
+    /// A running task's current state and a receiver of its further updates
+    pub fn subscribe(&self, task_id: &str) -> Option<(Task, broadcast::Receiver<UpdateEvent>)> {
+        let running = self.running.lock().unwrap();
+        let live = running.get(task_id)?.live.lock().unwrap();
+        Some((live.task.clone(), live.updates.subscribe()))
+    }
+
+    /// Stop a running task's agent, or a task waiting for input, and mark it canceled
+    ///
+    /// Returns the task once it has stopped, or `None` when there is no such task.
+    pub async fn cancel(&self, task_id: &str) -> Option<Task> {
+        let running = {
+            let mut running = self.running.lock().unwrap();
+            match running.get_mut(task_id) {
+                Some(entry) => {
+                    let live = entry.live.lock().unwrap();
+                    if is_terminal(&live.task.status.state) {
+                        return Some(live.task.clone());
+                    }
+                    let updates = live.updates.subscribe();
+                    drop(live);
+                    Some((updates, entry.cancel.take()))
+                }
+                None => None,
+            }
+        };
+        let Some((mut updates, cancel)) = running else {
+            return self.cancel_stored(task_id).await;
+        };
+        if let Some(cancel) = cancel {
+            let _ = cancel.send(());
+        }
+
+        loop {
+            match updates.recv().await {
+                Ok(UpdateEvent::TaskStatusUpdate(status)) if status.final_update => break,
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+        self.get(task_id).await.ok().flatten()
+    }
+
+    /// Cancel a task that is not running, unless it has already finished
+    async fn cancel_stored(&self, task_id: &str) -> Option<Task> {
+        let mut task = self.store.get(task_id).await.ok().flatten()?;
+        if is_terminal(&task.status.state) {
+            return Some(task);
+        }
+        task.status = TaskStatus { state: TaskState::Canceled, message: None };
+        if let Err(e) = self.store.save(&task).await {
+            tracing::warn!(task_id = %task.id, error = %e, "failed to save A2A task");
+        }
+        if let Some(notifier) = self.notifier.clone() {
+            let task = task.clone();
+            tokio::spawn(async move {
+                if let Err(e) = notifier.notify(&task).await {
+                    tracing::warn!(task_id = %task.id, error = %e, "failed to push A2A task");
+                }
+            });
+        }
+        Some(task)
+    }
+}
+
+/// Whether a task in `state` has finished for good
+pub fn is_terminal(state: &TaskState) -> bool {
+    matches!(state, TaskState::Completed | TaskState::Failed | TaskState::Canceled)
+}
+
+/// Run a task's updates until they end or it is canceled
+async fn drive(
+    mut updates: BoxStream<'static, UpdateEvent>,
+    mut cancelled: oneshot::Receiver<()>,
+    live: &Arc<Mutex<Live>>,
+    store: &dyn TaskStore,
+) {
+    let cancelled = loop {
+        tokio::select! {
+            update = updates.next() => match update {
+                Some(update) => publish(live, store, update).await,
+                None => break false,
+            },
+            _ = &mut cancelled => break true,
+        }
+    };
+    if !cancelled {
+        return;
+    }
+
+    // Dropping the updates stops the agent
+    drop(updates);
+    let update = {
+        let live = live.lock().unwrap();
+        UpdateEvent::TaskStatusUpdate(TaskStatusUpdateEvent {
+            task_id: live.task.id.clone(),
+            context_id: live.task.context_id.clone(),
+            status: TaskStatus { state: TaskState::Canceled, message: None },
+            final_update: true,
+        })
+    };
+    publish(live, store, update).await;
+}
+
+/// Apply an update to the task and send it to subscribers, saving status changes
+async fn publish(live: &Arc<Mutex<Live>>, store: &dyn TaskStore, update: UpdateEvent) {
+    let task = {
+        let mut live = live.lock().unwrap();
+        let is_status = apply(&mut live.task, &update);
+        // No subscribers is fine; the task is still saved
+        let _ = live.updates.send(update);
+        if let (true, Some(notifications)) = (is_status, &live.notifications) {
+            let _ = notifications.send(live.task.clone());
+        }
+        is_status.then(|| live.task.clone())
+    };
+    if let Some(task) = task {
+        if let Err(e) = store.save(&task).await {
+            tracing::warn!(task_id = %task.id, error = %e, "failed to save A2A task");
+        }
+    }
+}
+
+/// Apply an update to `task`, returning whether it changed the status
+fn apply(task: &mut Task, update: &UpdateEvent) -> bool {
+    match update {
+        UpdateEvent::TaskStatusUpdate(status) => {
+            task.status = status.status.clone();
+            true
+        }
+        UpdateEvent::TaskArtifactUpdate(update) => {
+            let artifacts = task.artifacts.get_or_insert_with(Vec::new);
+            match artifacts.iter_mut().find(|a| a.artifact_id == update.artifact.artifact_id) {
+                Some(artifact) if update.append => {
+                    artifact.parts.extend(update.artifact.parts.iter().cloned());
+                }
+                Some(artifact) => *artifact = update.artifact.clone(),
+                None => artifacts.push(update.artifact.clone()),
+            }
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::{Artifact, Part, TaskArtifactUpdateEvent};
+
+    fn chunk(text: &str) -> UpdateEvent {
+        UpdateEvent::TaskArtifactUpdate(TaskArtifactUpdateEvent {
+            task_id: "task-1".to_string(),
+            context_id: None,
+            artifact: Artifact {
+                artifact_id: "response".to_string(),
+                name: None,
+                description: None,
+                parts: vec![Part::text(text.to_string())],
+                metadata: None,
+                extensions: None,
+            },
+            append: true,
+            last_chunk: false,
+        })
+    }
+
+    #[test]
+    fn test_apply_appends_artifact_chunks() {
+        let mut task = Task {
+            id: "task-1".to_string(),
+            context_id: None,
+            status: TaskStatus { state: TaskState::Working, message: None },
+            artifacts: None,
+            history: None,
+        };
+        assert!(!apply(&mut task, &chunk("Hello")));
+        assert!(!apply(&mut task, &chunk(" world")));
+
+        let artifacts = task.artifacts.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].parts.len(), 2);
+    }
+}
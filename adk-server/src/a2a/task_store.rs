@@ -0,0 +1,165 @@
+//! Storage for A2A tasks, their history and artifacts
+
+use crate::a2a::Task;
+use adk_core::{AdkError, Result};
+use adk_session::{CreateRequest, DeleteRequest, GetRequest, SessionService};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Where A2A tasks are kept once started, so `tasks/get` can find them
+#[async_trait]
+pub trait TaskStore: Send + Sync {
+    /// Insert or replace a task
+    async fn save(&self, task: &Task) -> Result<()>;
+    async fn get(&self, task_id: &str) -> Result<Option<Task>>;
+    async fn delete(&self, task_id: &str) -> Result<()>;
+}
+
+/// Tasks kept in memory, lost on restart
+#[derive(Default)]
+pub struct InMemoryTaskStore {
+    tasks: RwLock<HashMap<String, Task>>,
+}
+
+impl InMemoryTaskStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TaskStore for InMemoryTaskStore {
+    async fn save(&self, task: &Task) -> Result<()> {
+        self.tasks.write().await.insert(task.id.clone(), task.clone());
+        Ok(())
+    }
+
+    async fn get(&self, task_id: &str) -> Result<Option<Task>> {
+        Ok(self.tasks.read().await.get(task_id).cloned())
+    }
+
+    async fn delete(&self, task_id: &str) -> Result<()> {
+        self.tasks.write().await.remove(task_id);
+        Ok(())
+    }
+}
+
+/// User the sessions holding tasks belong to
+const TASKS_USER_ID: &str = "a2a_tasks";
+/// Session state key holding the task
+const TASK_KEY: &str = "a2a_task";
+
+/// Tasks kept in a session database, one session per task
+///
+/// Each task is a session of `app_name` owned by the reserved `a2a_tasks` user, so any
+/// [`SessionService`] backend, e.g. PostgreSQL, can persist tasks across restarts and
+/// share them between replicas.
+pub struct SessionTaskStore {
+    session_service: Arc<dyn SessionService>,
+    app_name: String,
+}
+
+impl SessionTaskStore {
+    pub fn new(session_service: Arc<dyn SessionService>, app_name: impl Into<String>) -> Self {
+        Self { session_service, app_name: app_name.into() }
+    }
+
+    fn session_id(task_id: &str) -> String {
+        format!("a2a-task-{task_id}")
+    }
+
+    fn get_request(&self, task_id: &str) -> GetRequest {
+        GetRequest {
+            app_name: self.app_name.clone(),
+            user_id: TASKS_USER_ID.to_string(),
+            session_id: Self::session_id(task_id),
+            num_recent_events: Some(0),
+            after: None,
+        }
+    }
+}
+
+#[async_trait]
+impl TaskStore for SessionTaskStore {
+    async fn save(&self, task: &Task) -> Result<()> {
+        let value = serde_json::to_value(task)?;
+        let session_id = Self::session_id(&task.id);
+
+        if self.session_service.get(self.get_request(&task.id)).await.is_err() {
+            self.session_service
+                .create(CreateRequest {
+                    app_name: self.app_name.clone(),
+                    user_id: TASKS_USER_ID.to_string(),
+                    session_id: Some(session_id),
+                    state: HashMap::from([(TASK_KEY.to_string(), value)]),
+                })
+                .await?;
+            return Ok(());
+        }
+
+        // Later versions replace the task through a state delta
+        let mut event = adk_session::Event::new(&task.id);
+        event.author = "a2a".to_string();
+        event.actions.state_delta.insert(TASK_KEY.to_string(), value);
+        self.session_service.append_event(&session_id, event).await
+    }
+
+    async fn get(&self, task_id: &str) -> Result<Option<Task>> {
+        let Ok(session) = self.session_service.get(self.get_request(task_id)).await else {
+            return Ok(None);
+        };
+        let Some(value) = session.state().get(TASK_KEY) else {
+            return Ok(None);
+        };
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| AdkError::Session(format!("invalid stored task '{task_id}': {e}")))
+    }
+
+    async fn delete(&self, task_id: &str) -> Result<()> {
+        self.session_service
+            .delete(DeleteRequest {
+                app_name: self.app_name.clone(),
+                user_id: TASKS_USER_ID.to_string(),
+                session_id: Self::session_id(task_id),
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::{TaskState, TaskStatus};
+    use adk_session::InMemorySessionService;
+
+    fn task(state: TaskState) -> Task {
+        Task {
+            id: "task-1".to_string(),
+            context_id: Some("ctx-1".to_string()),
+            status: TaskStatus { state, message: None },
+            artifacts: Some(vec![]),
+            history: Some(vec![]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_task_store_round_trip() {
+        let store = SessionTaskStore::new(Arc::new(InMemorySessionService::new()), "app");
+        assert!(store.get("task-1").await.unwrap().is_none());
+
+        store.save(&task(TaskState::Working)).await.unwrap();
+        let saved = store.get("task-1").await.unwrap().unwrap();
+        assert_eq!(saved.status.state, TaskState::Working);
+        assert_eq!(saved.context_id.as_deref(), Some("ctx-1"));
+
+        store.save(&task(TaskState::Completed)).await.unwrap();
+        let saved = store.get("task-1").await.unwrap().unwrap();
+        assert_eq!(saved.status.state, TaskState::Completed);
+
+        store.delete("task-1").await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,338 @@
+//! Authentication and per-user authorization for the REST and A2A routes
+//!
+//! With an [`AuthConfig`] set on [`ServerConfig`](crate::ServerConfig), every route
+//! except `/api/health` and the agent card requires an API key or a bearer JWT:
+//!
+//! - The caller becomes a [`Principal`], added to the request's extensions
+//! - A `user_id` in the path, or a `userId` in a JSON body, must be the caller's own
+//! - Running or reading an app's sessions needs [`Permission::agent`] for the app when
+//!   an [`AccessControl`] is configured
+//! - Every access decision is sent to the [`AuditSink`], if one is configured
+//!
+//! ```rust,ignore
+//! use adk_auth::sso::{ClaimsMapper, GoogleProvider};
+//! use adk_auth::{AccessControl, FileAuditSink, Permission, Role};
+//! use adk_server::auth::AuthConfig;
+//!
+//! let auth = AuthConfig::new()
+//!     .with_api_key(std::env::var("OPS_API_KEY")?, "ops-bot")
+//!     .with_token_validator(GoogleProvider::new("client-id"))
+//!     .with_claims_mapper(ClaimsMapper::builder().user_id_from_email().default_role("user").build())
+//!     .with_access_control(
+//!         AccessControl::builder()
+//!             .role(Role::new("user").allow(Permission::agent("assistant")))
+//!             .role(Role::new("ops").allow(Permission::AllAgents))
+//!             .assign("ops-bot", "ops")
+//!             .build()?,
+//!     )
+//!     .with_audit_sink(FileAuditSink::new("audit.jsonl")?);
+//!
+//! let config = ServerConfig::new(agent_loader, session_service).with_auth(auth);
+//! ```
+
+use adk_auth::sso::{ClaimsMapper, TokenValidator};
+use adk_auth::{AccessControl, AuditEvent, AuditEventType, AuditOutcome, AuditSink, Permission};
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, RawPathParams, Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Header carrying an API key, as an alternative to `Authorization: Bearer`
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The authenticated caller
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Principal {
+    /// The only user ID the caller may act as
+    pub user_id: String,
+    /// Roles mapped from the caller's token claims; empty for API keys, whose roles are
+    /// assigned in the [`AccessControl`]
+    pub roles: Vec<String>,
+}
+
+/// How callers authenticate and what they may access
+#[derive(Clone, Default)]
+pub struct AuthConfig {
+    /// API keys and the user each one authenticates as
+    api_keys: Vec<(String, String)>,
+    validator: Option<Arc<dyn TokenValidator>>,
+    mapper: Option<Arc<ClaimsMapper>>,
+    access_control: Option<Arc<AccessControl>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+}
+
+impl AuthConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `key` as `Authorization: Bearer` or `X-API-Key`, authenticating as `user_id`
+    pub fn with_api_key(mut self, key: impl Into<String>, user_id: impl Into<String>) -> Self {
+        self.api_keys.push((key.into(), user_id.into()));
+        self
+    }
+
+    /// Accept bearer JWTs that `validator` accepts
+    pub fn with_token_validator(mut self, validator: impl TokenValidator + 'static) -> Self {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Map token claims to the user ID and roles; by default the `sub` claim is the
+    /// user ID and no roles are mapped
+    pub fn with_claims_mapper(mut self, mapper: ClaimsMapper) -> Self {
+        self.mapper = Some(Arc::new(mapper));
+        self
+    }
+
+    /// Require [`Permission::agent`] for the app a request runs or reads. Without one,
+    /// any authenticated caller may use every app as themselves.
+    pub fn with_access_control(mut self, access_control: AccessControl) -> Self {
+        self.access_control = Some(Arc::new(access_control));
+        self
+    }
+
+    /// Send every access decision to `sink`
+    pub fn with_audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Authenticate a request from its `Authorization` or `X-API-Key` header
+    pub async fn authenticate(&self, headers: &HeaderMap) -> Option<Principal> {
+        if let Some(key) = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+            return self.api_key_principal(key);
+        }
+
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))?
+            .trim();
+        if let Some(principal) = self.api_key_principal(token) {
+            return Some(principal);
+        }
+
+        let claims = match self.validator.as_ref()?.validate(token).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                debug!(error = %e, "rejected bearer token");
+                return None;
+            }
+        };
+        Some(match &self.mapper {
+            Some(mapper) => Principal {
+                user_id: mapper.get_user_id(&claims),
+                roles: mapper.map_to_roles(&claims),
+            },
+            None => Principal { user_id: claims.sub, roles: Vec::new() },
+        })
+    }
+
+    /// Whether `principal` may use the agent serving `app_name`, audited either way
+    pub async fn authorize_agent(
+        &self,
+        principal: &Principal,
+        app_name: &str,
+        session_id: Option<&str>,
+    ) -> bool {
+        let allowed = match &self.access_control {
+            Some(ac) => {
+                let permission = Permission::agent(app_name);
+                ac.check(&principal.user_id, &permission).is_ok()
+                    || principal.roles.iter().any(|role| {
+                        ac.get_role(role).is_some_and(|role| role.can_access(&permission))
+                    })
+            }
+            None => true,
+        };
+
+        let mut event =
+            AuditEvent::agent_access(&principal.user_id, app_name, audit_outcome(allowed));
+        if let Some(session_id) = session_id {
+            event = event.with_session(session_id);
+        }
+        self.audit(event).await;
+        allowed
+    }
+
+    /// Whether `principal` may act as `user_id`, audited when it may not
+    pub async fn authorize_user(&self, principal: &Principal, user_id: &str) -> bool {
+        if principal.user_id == user_id {
+            return true;
+        }
+        let mut event = AuditEvent::agent_access(&principal.user_id, "", AuditOutcome::Denied);
+        event.event_type = AuditEventType::PermissionCheck;
+        event.resource = format!("user:{user_id}");
+        self.audit(event).await;
+        false
+    }
+
+    fn api_key_principal(&self, key: &str) -> Option<Principal> {
+        // Compare every key in full, so timing does not reveal how much of one matched
+        let mut user_id = None;
+        for (candidate, user) in &self.api_keys {
+            if constant_time_eq(candidate.as_bytes(), key.as_bytes()) {
+                user_id = Some(user);
+            }
+        }
+        user_id.map(|user_id| Principal { user_id: user_id.clone(), roles: Vec::new() })
+    }
+
+    async fn audit(&self, event: AuditEvent) {
+        if let Some(sink) = &self.audit_sink {
+            if let Err(e) = sink.log(event).await {
+                warn!(error = %e, "failed to write audit event");
+            }
+        }
+    }
+}
+
+fn audit_outcome(allowed: bool) -> AuditOutcome {
+    if allowed { AuditOutcome::Allowed } else { AuditOutcome::Denied }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// State of the route layer that enforces an [`AuthConfig`]
+#[derive(Clone)]
+pub(crate) struct AuthLayer {
+    auth: AuthConfig,
+    max_body_size: usize,
+    /// App the routes serve when neither the path nor the body names one
+    default_app: Option<String>,
+}
+
+impl AuthLayer {
+    pub(crate) fn new(auth: AuthConfig, max_body_size: usize) -> Self {
+        Self { auth, max_body_size, default_app: None }
+    }
+
+    pub(crate) fn with_default_app(mut self, app_name: impl Into<String>) -> Self {
+        self.default_app = Some(app_name.into());
+        self
+    }
+}
+
+/// The user, app and session a request names in its path or JSON body
+#[derive(Default)]
+struct Target {
+    user_id: Option<String>,
+    app_name: Option<String>,
+    session_id: Option<String>,
+}
+
+impl Target {
+    fn from_path(params: &RawPathParams) -> Self {
+        let mut target = Self::default();
+        for (key, value) in params {
+            match key {
+                "user_id" => target.user_id = Some(value.to_string()),
+                "app_name" => target.app_name = Some(value.to_string()),
+                "session_id" => target.session_id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        target
+    }
+
+    /// Fill in what the path left out from the body's `userId`, `appName` and `sessionId`
+    fn merge_body(&mut self, body: &serde_json::Value) {
+        let field = |name: &str| body.get(name).and_then(|v| v.as_str()).map(String::from);
+        self.user_id = self.user_id.take().or_else(|| field("userId"));
+        self.app_name = self.app_name.take().or_else(|| field("appName"));
+        self.session_id = self.session_id.take().or_else(|| field("sessionId"));
+    }
+}
+
+fn unauthorized() -> Response {
+    let mut response = StatusCode::UNAUTHORIZED.into_response();
+    response.headers_mut().insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+/// Authenticate the caller and check the user and app the request targets
+pub(crate) async fn require_auth(
+    State(layer): State<AuthLayer>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+
+    let Some(principal) = layer.auth.authenticate(&parts.headers).await else {
+        return unauthorized();
+    };
+
+    let mut target = match RawPathParams::from_request_parts(&mut parts, &()).await {
+        Ok(params) => Target::from_path(&params),
+        Err(_) => Target::default(),
+    };
+
+    // Identity fields can also come in the body, e.g. POST /sessions and /run_sse
+    let body = if matches!(parts.method, Method::POST | Method::PUT) {
+        let bytes = match axum::body::to_bytes(body, layer.max_body_size).await {
+            Ok(bytes) => bytes,
+            Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+        };
+        if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&bytes) {
+            target.merge_body(&json);
+        }
+        Body::from(bytes)
+    } else {
+        body
+    };
+
+    if let Some(user_id) = &target.user_id {
+        if !layer.auth.authorize_user(&principal, user_id).await {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+    if let Some(app_name) = target.app_name.as_ref().or(layer.default_app.as_ref()) {
+        let session_id = target.session_id.as_deref();
+        if !layer.auth.authorize_agent(&principal, app_name, session_id).await {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+
+    parts.extensions.insert(principal);
+    next.run(Request::from_parts(parts, body)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"x"));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_headers() {
+        let auth = AuthConfig::new().with_api_key("key-1", "alice");
+        let alice = Principal { user_id: "alice".to_string(), roles: vec![] };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("key-1"));
+        assert_eq!(auth.authenticate(&headers).await, Some(alice.clone()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer key-1"));
+        assert_eq!(auth.authenticate(&headers).await, Some(alice));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer key-2"));
+        assert_eq!(auth.authenticate(&headers).await, None);
+        assert_eq!(auth.authenticate(&HeaderMap::new()).await, None);
+    }
+}
@@ -1,4 +1,5 @@
-use std::{sync::Arc, time::Duration};
+use crate::auth::AuthConfig;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 /// Security configuration for the ADK server.
 #[derive(Clone, Debug)]
@@ -52,9 +53,27 @@ pub struct ServerConfig {
     pub agent_loader: Arc<dyn adk_core::AgentLoader>,
     pub session_service: Arc<dyn adk_session::SessionService>,
     pub artifact_service: Option<Arc<dyn adk_artifact::ArtifactService>>,
+    /// Long-term memory for runs, scoped to each run's app and user. See
+    /// [`with_memory_service`](Self::with_memory_service).
+    pub memory_service: Option<Arc<dyn adk_memory::MemoryService>>,
     pub span_exporter: Option<Arc<adk_telemetry::AdkSpanExporter>>,
     pub backend_url: Option<String>,
     pub security: SecurityConfig,
+    /// When set, runs on one session queue instead of racing. See
+    /// [`with_serialized_sessions`](Self::with_serialized_sessions).
+    pub session_locks: Option<adk_runner::SessionLocks>,
+    /// Graphs whose checkpoint history is served under `/api/apps/{app_name}/graph`,
+    /// by app name. See [`with_graph`](Self::with_graph).
+    pub graphs: HashMap<String, Arc<adk_graph::CompiledGraph>>,
+    /// When set, requests must authenticate and may only act as their own user.
+    /// See [`with_auth`](Self::with_auth).
+    pub auth: Option<AuthConfig>,
+    /// Where A2A tasks are kept; in memory when unset. See
+    /// [`with_task_store`](Self::with_task_store).
+    pub task_store: Option<Arc<dyn crate::a2a::TaskStore>>,
+    /// When set, A2A clients may register webhooks for their tasks' updates. See
+    /// [`with_push_notifications`](Self::with_push_notifications).
+    pub push_notifier: Option<crate::a2a::PushNotifier>,
 }
 
 impl ServerConfig {
@@ -66,9 +85,15 @@ impl ServerConfig {
             agent_loader,
             session_service,
             artifact_service: None,
+            memory_service: None,
             span_exporter: None,
             backend_url: None,
             security: SecurityConfig::default(),
+            session_locks: None,
+            graphs: HashMap::new(),
+            auth: None,
+            task_store: None,
+            push_notifier: None,
         }
     }
 
@@ -88,6 +113,29 @@ impl ServerConfig {
         self
     }
 
+    /// Give agents run by the server, over REST or A2A, access to `memory_service`
+    ///
+    /// Each run only sees the memories of its own app and user.
+    pub fn with_memory_service(
+        mut self,
+        memory_service: Arc<dyn adk_memory::MemoryService>,
+    ) -> Self {
+        self.memory_service = Some(memory_service);
+        self
+    }
+
+    /// The memory service, scoped to `user_id` in `app_name`
+    pub(crate) fn memory_for(
+        &self,
+        app_name: &str,
+        user_id: &str,
+    ) -> Option<Arc<dyn adk_core::Memory>> {
+        self.memory_service.clone().map(|service| {
+            Arc::new(adk_memory::ScopedMemory::new(service, app_name, user_id))
+                as Arc<dyn adk_core::Memory>
+        })
+    }
+
     pub fn with_backend_url(mut self, backend_url: impl Into<String>) -> Self {
         self.backend_url = Some(backend_url.into());
         self
@@ -106,6 +154,47 @@ impl ServerConfig {
         self
     }
 
+    /// Queue concurrent runs on the same session instead of letting them race.
+    /// Without this, the later of two overlapping runs fails with a stale session error.
+    pub fn with_serialized_sessions(mut self) -> Self {
+        self.session_locks = Some(adk_runner::SessionLocks::new());
+        self
+    }
+
+    /// Serve the checkpoint history of an app's graph, for time-travel debugging.
+    /// A `GraphAgent` run uses the session ID as its thread ID, so the graph's threads
+    /// are the app's sessions; use [`GraphAgent::graph_arc`](adk_graph::GraphAgent::graph_arc).
+    pub fn with_graph(
+        mut self,
+        app_name: impl Into<String>,
+        graph: Arc<adk_graph::CompiledGraph>,
+    ) -> Self {
+        self.graphs.insert(app_name.into(), graph);
+        self
+    }
+
+    /// Require an API key or bearer JWT on every route but the health check and agent
+    /// card, and restrict callers to their own user's sessions
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Keep A2A tasks, with their history and artifacts, in `task_store`, e.g. a
+    /// [`SessionTaskStore`](crate::a2a::SessionTaskStore) to persist them in the session
+    /// database
+    pub fn with_task_store(mut self, task_store: Arc<dyn crate::a2a::TaskStore>) -> Self {
+        self.task_store = Some(task_store);
+        self
+    }
+
+    /// Let A2A clients register webhooks, with `tasks/pushNotificationConfig/set` or
+    /// in `message/send`, that `notifier` POSTs their tasks to on every status change
+    pub fn with_push_notifications(mut self, notifier: crate::a2a::PushNotifier) -> Self {
+        self.push_notifier = Some(notifier);
+        self
+    }
+
     /// Configure allowed CORS origins
     pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
         self.security.allowed_origins = origins;
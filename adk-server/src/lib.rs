@@ -10,6 +10,7 @@
 //! - [`create_app_with_a2a`] - Add A2A protocol support
 //! - [`RemoteA2aAgent`] - Connect to remote A2A agents
 //! - [`ServerConfig`] - Server configuration
+//! - [`AuthConfig`] - API key and JWT authentication
 //!
 //! ## Quick Start
 //!
@@ -32,14 +33,17 @@
 //! - `POST /a2a/stream` - SSE streaming
 
 pub mod a2a;
+pub mod auth;
 pub mod config;
 pub mod rest;
 pub mod web_ui;
 
 pub use a2a::{
-    A2aClient, Executor, ExecutorConfig, RemoteA2aAgent, RemoteA2aAgentBuilder, RemoteA2aConfig,
-    build_agent_card, build_agent_skills,
+    A2aClient, Executor, ExecutorConfig, InMemoryTaskStore, PushNotifier, RemoteA2aAgent,
+    RemoteA2aAgentBuilder, RemoteA2aConfig, SessionTaskStore, TaskStore, build_agent_card,
+    build_agent_skills,
 };
+pub use auth::{AuthConfig, Principal};
 pub use config::{SecurityConfig, ServerConfig};
 pub use rest::{
     A2aController, RuntimeController, SessionController, create_app, create_app_with_a2a,
@@ -1,11 +1,16 @@
 use crate::ServerConfig;
 use crate::a2a::{
-    AgentCard, Executor, ExecutorConfig, JsonRpcError, JsonRpcRequest, JsonRpcResponse,
-    MessageSendParams, Task, TaskState, TaskStatus, TasksCancelParams, TasksGetParams, UpdateEvent,
-    build_agent_card, jsonrpc,
+    AgentCard, Executor, ExecutorConfig, InMemoryTaskStore, JsonRpcError, JsonRpcRequest,
+    JsonRpcResponse, MessageSendParams, PushNotifier, Task, TaskManager,
+    TaskPushNotificationConfig, TaskState, TasksCancelParams, TasksGetParams,
+    TasksPushNotificationConfigGetParams, TasksResubscribeParams, UpdateEvent, build_agent_card,
+    jsonrpc,
+    task_manager::{StartError, is_terminal},
 };
+use crate::auth::Principal;
 use adk_runner::RunnerConfig;
 use axum::{
+    Extension,
     extract::State,
     http::StatusCode,
     response::{
@@ -14,49 +19,166 @@ use axum::{
     },
 };
 use futures::stream::Stream;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use std::{collections::HashMap, convert::Infallible, sync::Arc, time::Duration};
-use tokio::sync::RwLock;
+use std::{convert::Infallible, sync::Arc, time::Duration};
+use tokio::sync::broadcast;
 
-/// In-memory task storage
-#[derive(Default)]
-pub struct TaskStore {
-    tasks: RwLock<HashMap<String, Task>>,
+/// Controller for A2A protocol endpoints
+#[derive(Clone)]
+pub struct A2aController {
+    config: ServerConfig,
+    agent_card: AgentCard,
+    tasks: Arc<TaskManager>,
 }
 
-impl TaskStore {
-    pub fn new() -> Self {
-        Self::default()
+impl A2aController {
+    pub fn new(config: ServerConfig, base_url: &str) -> Self {
+        let root_agent = config.agent_loader.root_agent();
+        let invoke_url = format!("{}/a2a", base_url.trim_end_matches('/'));
+        let mut agent_card = build_agent_card(root_agent.as_ref(), &invoke_url);
+        agent_card.capabilities.push_notifications = config.push_notifier.is_some();
+
+        let store = config.task_store.clone().unwrap_or_else(|| Arc::new(InMemoryTaskStore::new()));
+        let mut tasks = TaskManager::new(store);
+        if let Some(notifier) = &config.push_notifier {
+            tasks = tasks.with_notifier(notifier.clone());
+        }
+
+        Self { config, agent_card, tasks: Arc::new(tasks) }
     }
 
-    pub async fn store(&self, task: Task) {
-        self.tasks.write().await.insert(task.id.clone(), task);
+    fn notifier(&self) -> Result<&PushNotifier, JsonRpcError> {
+        self.config.push_notifier.as_ref().ok_or_else(JsonRpcError::push_notification_not_supported)
+    }
+
+    /// Executor for the root agent, running as the caller when authenticated
+    fn executor(&self, principal: Option<&Principal>) -> Executor {
+        let root_agent = self.config.agent_loader.root_agent();
+        let executor = Executor::new(ExecutorConfig {
+            app_name: root_agent.name().to_string(),
+            runner_config: Arc::new(RunnerConfig {
+                app_name: root_agent.name().to_string(),
+                agent: root_agent,
+                session_service: self.config.session_service.clone(),
+                artifact_service: self.config.artifact_service.clone(),
+                memory_service: None,
+                run_config: None,
+            }),
+        });
+        let executor = match &self.config.memory_service {
+            Some(memory_service) => executor.with_memory_service(memory_service.clone()),
+            None => executor,
+        };
+        match principal {
+            Some(principal) => executor.for_user(&principal.user_id),
+            None => executor,
+        }
     }
 
-    pub async fn get(&self, task_id: &str) -> Option<Task> {
-        self.tasks.read().await.get(task_id).cloned()
+    /// Whether the caller may see `task`: with auth, its context must be one of the
+    /// caller's sessions
+    async fn owns_task(&self, principal: Option<&Principal>, task: &Task) -> bool {
+        let Some(principal) = principal else {
+            return true;
+        };
+        let Some(context_id) = &task.context_id else {
+            return false;
+        };
+        self.config
+            .session_service
+            .get(adk_session::GetRequest {
+                app_name: self.config.agent_loader.root_agent().name().to_string(),
+                user_id: principal.user_id.clone(),
+                session_id: context_id.clone(),
+                num_recent_events: Some(0),
+                after: None,
+            })
+            .await
+            .is_ok()
     }
 
-    pub async fn remove(&self, task_id: &str) -> Option<Task> {
-        self.tasks.write().await.remove(task_id)
+    /// Look up a task the caller may see
+    async fn find_task(
+        &self,
+        principal: Option<&Principal>,
+        task_id: &str,
+    ) -> Result<Task, JsonRpcError> {
+        match self.tasks.get(task_id).await {
+            Ok(Some(task)) if self.owns_task(principal, &task).await => Ok(task),
+            Ok(_) => Err(JsonRpcError::task_not_found(task_id)),
+            Err(e) => Err(self.internal_error(&e)),
+        }
+    }
+
+    /// Start the task `params` names, or a new one
+    async fn start_task(
+        &self,
+        principal: Option<&Principal>,
+        mut params: MessageSendParams,
+    ) -> Result<(Task, broadcast::Receiver<UpdateEvent>), JsonRpcError> {
+        // Continuing a task requires being allowed to see it
+        if let Some(task_id) = &params.message.task_id {
+            if let Ok(Some(task)) = self.tasks.get(task_id).await {
+                if !self.owns_task(principal, &task).await {
+                    return Err(JsonRpcError::task_not_found(task_id));
+                }
+            }
+        }
+
+        // Register the webhook first, so it hears about the task from the start
+        let push_config = params.config.as_mut().and_then(|c| c.push_notification_config.take());
+        if let Some(push_config) = push_config {
+            let task_id = params
+                .message
+                .task_id
+                .get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+                .clone();
+            self.notifier()?
+                .set_config(&task_id, push_config)
+                .await
+                .map_err(|e| JsonRpcError::invalid_params(e.to_string()))?;
+        }
+
+        self.tasks.start(self.executor(principal), params.message).await.map_err(|e| match e {
+            StartError::Store(e) => self.internal_error(&e),
+            e => JsonRpcError::invalid_params(e.to_string()),
+        })
+    }
+
+    fn internal_error(&self, error: &dyn std::fmt::Display) -> JsonRpcError {
+        JsonRpcError::internal_error_sanitized(error, self.config.security.expose_error_details)
     }
 }
 
-/// Controller for A2A protocol endpoints
-#[derive(Clone)]
-pub struct A2aController {
-    config: ServerConfig,
-    agent_card: AgentCard,
-    task_store: Arc<TaskStore>,
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, JsonRpcError> {
+    match params {
+        Some(p) => {
+            serde_json::from_value(p).map_err(|e| JsonRpcError::invalid_params(e.to_string()))
+        }
+        None => Err(JsonRpcError::invalid_params("Missing params")),
+    }
 }
 
-impl A2aController {
-    pub fn new(config: ServerConfig, base_url: &str) -> Self {
-        let root_agent = config.agent_loader.root_agent();
-        let invoke_url = format!("{}/a2a", base_url.trim_end_matches('/'));
-        let agent_card = build_agent_card(root_agent.as_ref(), &invoke_url);
+fn respond(id: Option<Value>, result: Result<Value, JsonRpcError>) -> Json<JsonRpcResponse> {
+    Json(match result {
+        Ok(result) => JsonRpcResponse::success(id, result),
+        Err(error) => JsonRpcResponse::error(id, error),
+    })
+}
+
+fn is_final(update: &UpdateEvent) -> bool {
+    matches!(update, UpdateEvent::TaskStatusUpdate(status) if status.final_update)
+}
 
-        Self { config, agent_card, task_store: Arc::new(TaskStore::new()) }
+/// Wait until a task's final update
+async fn wait_for_final(updates: &mut broadcast::Receiver<UpdateEvent>) {
+    loop {
+        match updates.recv().await {
+            Ok(update) if is_final(&update) => return,
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
     }
 }
 
@@ -68,8 +190,12 @@ pub async fn get_agent_card(State(controller): State<A2aController>) -> impl Int
 /// POST /a2a - JSON-RPC endpoint for A2A protocol
 pub async fn handle_jsonrpc(
     State(controller): State<A2aController>,
+    principal: Option<Extension<Principal>>,
     Json(request): Json<JsonRpcRequest>,
 ) -> impl IntoResponse {
+    let principal = principal.map(|Extension(principal)| principal);
+    let principal = principal.as_ref();
+
     if request.jsonrpc != "2.0" {
         return Json(JsonRpcResponse::error(
             request.id,
@@ -79,13 +205,19 @@ pub async fn handle_jsonrpc(
 
     match request.method.as_str() {
         jsonrpc::methods::MESSAGE_SEND => {
-            handle_message_send(&controller, request.params, request.id).await
+            handle_message_send(&controller, principal, request.params, request.id).await
         }
         jsonrpc::methods::TASKS_GET => {
-            handle_tasks_get(&controller, request.params, request.id).await
+            handle_tasks_get(&controller, principal, request.params, request.id).await
         }
         jsonrpc::methods::TASKS_CANCEL => {
-            handle_tasks_cancel(&controller, request.params, request.id).await
+            handle_tasks_cancel(&controller, principal, request.params, request.id).await
+        }
+        jsonrpc::methods::TASKS_PUSH_NOTIFICATION_CONFIG_SET => {
+            handle_push_config_set(&controller, principal, request.params, request.id).await
+        }
+        jsonrpc::methods::TASKS_PUSH_NOTIFICATION_CONFIG_GET => {
+            handle_push_config_get(&controller, principal, request.params, request.id).await
         }
         _ => Json(JsonRpcResponse::error(
             request.id,
@@ -95,126 +227,93 @@ pub async fn handle_jsonrpc(
 }
 
 /// POST /a2a/stream - SSE streaming endpoint for A2A protocol
+///
+/// Streams a new task's updates as the agent produces them (`message/stream`), or
+/// reconnects to a running task (`tasks/resubscribe`). Disconnecting does not stop
+/// the task.
 pub async fn handle_jsonrpc_stream(
     State(controller): State<A2aController>,
+    principal: Option<Extension<Principal>>,
     Json(request): Json<JsonRpcRequest>,
 ) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<JsonRpcResponse>)>
 {
-    if request.jsonrpc != "2.0" {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(JsonRpcResponse::error(
-                request.id.clone(),
-                JsonRpcError::invalid_request("Invalid JSON-RPC version"),
-            )),
-        ));
-    }
+    let principal = principal.map(|Extension(principal)| principal);
+    let principal = principal.as_ref();
+    let bad_request = |error: JsonRpcError| {
+        (StatusCode::BAD_REQUEST, Json(JsonRpcResponse::error(request.id.clone(), error)))
+    };
 
-    if request.method != jsonrpc::methods::MESSAGE_SEND_STREAM
-        && request.method != jsonrpc::methods::MESSAGE_SEND
-    {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(JsonRpcResponse::error(
-                request.id.clone(),
-                JsonRpcError::method_not_found(&request.method),
-            )),
-        ));
+    if request.jsonrpc != "2.0" {
+        return Err(bad_request(JsonRpcError::invalid_request("Invalid JSON-RPC version")));
     }
 
-    let params: MessageSendParams = match request.params {
-        Some(p) => serde_json::from_value(p).map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(JsonRpcResponse::error(
-                    request.id.clone(),
-                    JsonRpcError::invalid_params(e.to_string()),
-                )),
-            )
-        })?,
-        None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(JsonRpcResponse::error(
-                    request.id.clone(),
-                    JsonRpcError::invalid_params("Missing params"),
-                )),
-            ));
+    let (initial, updates) = match request.method.as_str() {
+        jsonrpc::methods::MESSAGE_SEND_STREAM | jsonrpc::methods::MESSAGE_SEND => {
+            let params: MessageSendParams =
+                parse_params(request.params.clone()).map_err(bad_request)?;
+            match controller.start_task(principal, params).await {
+                Ok((_, updates)) => (None, Some(updates)),
+                Err(error) => (Some(Err(error)), None),
+            }
+        }
+        jsonrpc::methods::TASKS_RESUBSCRIBE => {
+            let params: TasksResubscribeParams =
+                parse_params(request.params.clone()).map_err(bad_request)?;
+            match controller.find_task(principal, &params.task_id).await {
+                // Subscribing after the check can only miss the task finishing, in which
+                // case the stored task is the whole story
+                Ok(task) => match controller.tasks.subscribe(&params.task_id) {
+                    Some((task, updates)) => (Some(Ok(task)), Some(updates)),
+                    None => (Some(Ok(task)), None),
+                },
+                Err(error) => (Some(Err(error)), None),
+            }
         }
+        method => return Err(bad_request(JsonRpcError::method_not_found(method))),
     };
 
-    let request_id = request.id.clone();
-    let stream = create_message_stream(controller, params, request_id);
-
+    let stream = task_event_stream(request.id, initial, updates);
     Ok(Sse::new(stream).keep_alive(
         axum::response::sse::KeepAlive::new().interval(Duration::from_secs(15)).text("ping"),
     ))
 }
 
-fn create_message_stream(
-    controller: A2aController,
-    params: MessageSendParams,
+/// SSE events for a task: its current state or an error, then its updates until the
+/// final one
+fn task_event_stream(
     request_id: Option<Value>,
+    initial: Option<Result<Task, JsonRpcError>>,
+    updates: Option<broadcast::Receiver<UpdateEvent>>,
 ) -> impl Stream<Item = Result<Event, Infallible>> {
     async_stream::stream! {
-        let context_id = params
-            .message
-            .context_id
-            .clone()
-            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-        let task_id = params
-            .message
-            .task_id
-            .clone()
-            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-
-        let root_agent = controller.config.agent_loader.root_agent();
-
-        let executor = Executor::new(ExecutorConfig {
-            app_name: root_agent.name().to_string(),
-            runner_config: Arc::new(RunnerConfig {
-                app_name: root_agent.name().to_string(),
-                agent: root_agent,
-                session_service: controller.config.session_service.clone(),
-                artifact_service: controller.config.artifact_service.clone(),
-                memory_service: None,
-                run_config: None,
-            }),
-        });
+        let event = |response: JsonRpcResponse| {
+            Event::default().data(serde_json::to_string(&response).unwrap_or_default())
+        };
+
+        match initial {
+            Some(Ok(task)) => {
+                let task = serde_json::to_value(task).unwrap_or_default();
+                yield Ok(event(JsonRpcResponse::success(request_id.clone(), task)));
+            }
+            Some(Err(error)) => yield Ok(event(JsonRpcResponse::error(request_id.clone(), error))),
+            None => {}
+        }
 
-        match executor.execute(&context_id, &task_id, &params.message).await {
-            Ok(events) => {
-                for event in events {
-                    let event_data = match &event {
-                        UpdateEvent::TaskStatusUpdate(status) => {
-                            serde_json::to_string(&JsonRpcResponse::success(
-                                request_id.clone(),
-                                serde_json::to_value(status).unwrap_or_default(),
-                            ))
-                        }
-                        UpdateEvent::TaskArtifactUpdate(artifact) => {
-                            serde_json::to_string(&JsonRpcResponse::success(
-                                request_id.clone(),
-                                serde_json::to_value(artifact).unwrap_or_default(),
-                            ))
+        if let Some(mut updates) = updates {
+            loop {
+                match updates.recv().await {
+                    Ok(update) => {
+                        let last = is_final(&update);
+                        let update = serde_json::to_value(&update).unwrap_or_default();
+                        yield Ok(event(JsonRpcResponse::success(request_id.clone(), update)));
+                        if last {
+                            break;
                         }
-                    };
-
-                    if let Ok(data) = event_data {
-                        yield Ok(Event::default().data(data));
                     }
-                }
-            }
-            Err(e) => {
-                let error_response = JsonRpcResponse::error(
-                    request_id.clone(),
-                    JsonRpcError::internal_error_sanitized(
-                        &e,
-                        controller.config.security.expose_error_details,
-                    ),
-                );
-                if let Ok(data) = serde_json::to_string(&error_response) {
-                    yield Ok(Event::default().data(data));
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "A2A stream fell behind, skipping updates");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
                 }
             }
         }
@@ -224,171 +323,110 @@ fn create_message_stream(
     }
 }
 
+/// Start a task and, unless the request asks not to block, wait for it to finish
 async fn handle_message_send(
     controller: &A2aController,
+    principal: Option<&Principal>,
     params: Option<Value>,
     id: Option<Value>,
 ) -> Json<JsonRpcResponse> {
-    let params: MessageSendParams = match params {
-        Some(p) => match serde_json::from_value(p) {
-            Ok(p) => p,
-            Err(e) => {
-                return Json(JsonRpcResponse::error(
-                    id,
-                    JsonRpcError::invalid_params(e.to_string()),
-                ));
-            }
-        },
-        None => {
-            return Json(JsonRpcResponse::error(
-                id,
-                JsonRpcError::invalid_params("Missing params"),
-            ));
-        }
-    };
-
-    let context_id =
-        params.message.context_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-    let task_id =
-        params.message.task_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let result = async {
+        let params: MessageSendParams = parse_params(params)?;
+        let blocking = params.config.as_ref().and_then(|config| config.blocking).unwrap_or(true);
 
-    let root_agent = controller.config.agent_loader.root_agent();
-
-    let executor = Executor::new(ExecutorConfig {
-        app_name: root_agent.name().to_string(),
-        runner_config: Arc::new(RunnerConfig {
-            app_name: root_agent.name().to_string(),
-            agent: root_agent,
-            session_service: controller.config.session_service.clone(),
-            artifact_service: controller.config.artifact_service.clone(),
-            memory_service: None,
-            run_config: None,
-        }),
-    });
-
-    match executor.execute(&context_id, &task_id, &params.message).await {
-        Ok(events) => {
-            // Build task from events
-            let mut task = Task {
-                id: task_id,
-                context_id: Some(context_id),
-                status: TaskStatus { state: TaskState::Completed, message: None },
-                artifacts: Some(vec![]),
-                history: None,
-            };
-
-            for event in events {
-                match event {
-                    UpdateEvent::TaskStatusUpdate(status) => {
-                        task.status = status.status;
-                    }
-                    UpdateEvent::TaskArtifactUpdate(artifact) => {
-                        if let Some(ref mut artifacts) = task.artifacts {
-                            artifacts.push(artifact.artifact);
-                        }
-                    }
-                }
-            }
-
-            // Store task for later retrieval
-            controller.task_store.store(task.clone()).await;
-
-            Json(JsonRpcResponse::success(id, serde_json::to_value(task).unwrap_or_default()))
+        let (task, mut updates) = controller.start_task(principal, params).await?;
+        if blocking {
+            wait_for_final(&mut updates).await;
         }
-        Err(e) => Json(JsonRpcResponse::error(
-            id,
-            JsonRpcError::internal_error_sanitized(
-                &e,
-                controller.config.security.expose_error_details,
-            ),
-        )),
-    }
+        let task = controller.tasks.get(&task.id).await.ok().flatten().unwrap_or(task);
+        Ok(serde_json::to_value(task).unwrap_or_default())
+    };
+    respond(id, result.await)
 }
 
 async fn handle_tasks_get(
     controller: &A2aController,
+    principal: Option<&Principal>,
     params: Option<Value>,
     id: Option<Value>,
 ) -> Json<JsonRpcResponse> {
-    let params: TasksGetParams = match params {
-        Some(p) => match serde_json::from_value(p) {
-            Ok(p) => p,
-            Err(e) => {
-                return Json(JsonRpcResponse::error(
-                    id,
-                    JsonRpcError::invalid_params(e.to_string()),
-                ));
-            }
-        },
-        None => {
-            return Json(JsonRpcResponse::error(
-                id,
-                JsonRpcError::invalid_params("Missing params"),
-            ));
+    let result = async {
+        let params: TasksGetParams = parse_params(params)?;
+        let mut task = controller.find_task(principal, &params.task_id).await?;
+        if let (Some(history), Some(length)) = (&mut task.history, params.history_length) {
+            let skip = history.len().saturating_sub(length as usize);
+            history.drain(..skip);
         }
+        Ok(serde_json::to_value(task).unwrap_or_default())
     };
-
-    match controller.task_store.get(&params.task_id).await {
-        Some(task) => {
-            Json(JsonRpcResponse::success(id, serde_json::to_value(task).unwrap_or_default()))
-        }
-        None => Json(JsonRpcResponse::error(
-            id,
-            JsonRpcError::internal_error(format!("Task not found: {}", params.task_id)),
-        )),
-    }
+    respond(id, result.await)
 }
 
+/// Stop a running task's agent, or a task waiting for input, returning the canceled task
 async fn handle_tasks_cancel(
     controller: &A2aController,
+    principal: Option<&Principal>,
     params: Option<Value>,
     id: Option<Value>,
 ) -> Json<JsonRpcResponse> {
-    let params: TasksCancelParams = match params {
-        Some(p) => match serde_json::from_value(p) {
-            Ok(p) => p,
-            Err(e) => {
-                return Json(JsonRpcResponse::error(
-                    id,
-                    JsonRpcError::invalid_params(e.to_string()),
-                ));
+    let result = async {
+        let params: TasksCancelParams = parse_params(params)?;
+        let task = controller.find_task(principal, &params.task_id).await?;
+        if is_terminal(&task.status.state) {
+            return Err(JsonRpcError::task_not_cancelable(&params.task_id));
+        }
+        match controller.tasks.cancel(&params.task_id).await {
+            Some(task) if task.status.state == TaskState::Canceled => {
+                Ok(serde_json::to_value(task).unwrap_or_default())
             }
-        },
-        None => {
-            return Json(JsonRpcResponse::error(
-                id,
-                JsonRpcError::invalid_params("Missing params"),
-            ));
+            // Finished before it could be canceled
+            _ => Err(JsonRpcError::task_not_cancelable(&params.task_id)),
         }
     };
+    respond(id, result.await)
+}
 
-    let root_agent = controller.config.agent_loader.root_agent();
+/// Register the webhook a task's updates are pushed to
+async fn handle_push_config_set(
+    controller: &A2aController,
+    principal: Option<&Principal>,
+    params: Option<Value>,
+    id: Option<Value>,
+) -> Json<JsonRpcResponse> {
+    let result = async {
+        let params: TaskPushNotificationConfig = parse_params(params)?;
+        let notifier = controller.notifier()?;
+        controller.find_task(principal, &params.task_id).await?;
+        notifier
+            .set_config(&params.task_id, params.push_notification_config.clone())
+            .await
+            .map_err(|e| JsonRpcError::invalid_params(e.to_string()))?;
+        Ok(serde_json::to_value(params).unwrap_or_default())
+    };
+    respond(id, result.await)
+}
 
-    let executor = Executor::new(ExecutorConfig {
-        app_name: root_agent.name().to_string(),
-        runner_config: Arc::new(RunnerConfig {
-            app_name: root_agent.name().to_string(),
-            agent: root_agent,
-            session_service: controller.config.session_service.clone(),
-            artifact_service: controller.config.artifact_service.clone(),
-            memory_service: None,
-            run_config: None,
-        }),
-    });
-
-    // Use a default context_id for cancel
-    let context_id = uuid::Uuid::new_v4().to_string();
-
-    match executor.cancel(&context_id, &params.task_id).await {
-        Ok(status) => {
-            Json(JsonRpcResponse::success(id, serde_json::to_value(status).unwrap_or_default()))
-        }
-        Err(e) => Json(JsonRpcResponse::error(
-            id,
-            JsonRpcError::internal_error_sanitized(
-                &e,
-                controller.config.security.expose_error_details,
-            ),
-        )),
-    }
+async fn handle_push_config_get(
+    controller: &A2aController,
+    principal: Option<&Principal>,
+    params: Option<Value>,
+    id: Option<Value>,
+) -> Json<JsonRpcResponse> {
+    let result = async {
+        let params: TasksPushNotificationConfigGetParams = parse_params(params)?;
+        let notifier = controller.notifier()?;
+        controller.find_task(principal, &params.task_id).await?;
+        let config = notifier.config(&params.task_id).await.ok_or_else(|| {
+            JsonRpcError::invalid_params(format!(
+                "No push notification config for task: {}",
+                params.task_id
+            ))
+        })?;
+        let config = TaskPushNotificationConfig {
+            task_id: params.task_id,
+            push_notification_config: config,
+        };
+        Ok(serde_json::to_value(config).unwrap_or_default())
+    };
+    respond(id, result.await)
 }
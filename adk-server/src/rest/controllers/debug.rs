@@ -1,12 +1,16 @@
 use crate::ServerConfig;
+use crate::auth::Principal;
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, State},
     http::StatusCode,
 };
 use serde::Serialize;
 use std::collections::HashMap;
 
+/// Span attribute naming the user whose session produced the span
+const USER_ID_ATTRIBUTE: &str = "gcp.vertex.agent.user_id";
+
 #[derive(Clone)]
 pub struct DebugController {
     config: ServerConfig,
@@ -16,6 +20,25 @@ impl DebugController {
     pub fn new(config: ServerConfig) -> Self {
         Self { config }
     }
+
+    /// With auth, a span is only visible to the user whose session produced it
+    async fn check_owner(
+        &self,
+        principal: Option<&Principal>,
+        attributes: &HashMap<String, String>,
+    ) -> Result<(), StatusCode> {
+        let (Some(auth), Some(principal)) = (&self.config.auth, principal) else {
+            return Ok(());
+        };
+        match attributes.get(USER_ID_ATTRIBUTE) {
+            Some(owner) if auth.authorize_user(principal, owner).await => Ok(()),
+            _ => Err(StatusCode::FORBIDDEN),
+        }
+    }
+}
+
+fn principal(extension: &Option<Extension<Principal>>) -> Option<&Principal> {
+    extension.as_ref().map(|Extension(principal)| principal)
 }
 
 #[derive(Serialize)]
@@ -27,25 +50,27 @@ pub struct GraphResponse {
 // ADK-Go compatible trace response (attributes map)
 pub async fn get_trace_by_event_id(
     State(controller): State<DebugController>,
+    caller: Option<Extension<Principal>>,
     Path(event_id): Path<String>,
 ) -> Result<Json<HashMap<String, String>>, StatusCode> {
-    if let Some(exporter) = &controller.config.span_exporter {
-        // First try direct lookup by event_id
-        if let Some(attributes) = exporter.get_trace_by_event_id(&event_id) {
-            return Ok(Json(attributes));
-        }
-
-        // If not found, search through all spans for matching session event ID
-        let trace_dict = exporter.get_trace_dict();
-        for (_, attributes) in trace_dict.iter() {
-            // Check if any span has this event_id in its attributes
-            if attributes.values().any(|v| v == &event_id) {
-                return Ok(Json(attributes.clone()));
-            }
-        }
-    }
+    let Some(exporter) = &controller.config.span_exporter else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    // First try direct lookup by event_id, then search through all spans for
+    // one that has this event_id in its attributes
+    let attributes = exporter.get_trace_by_event_id(&event_id).or_else(|| {
+        exporter
+            .get_trace_dict()
+            .into_values()
+            .find(|attributes| attributes.values().any(|v| v == &event_id))
+    });
+    let Some(attributes) = attributes else {
+        return Err(StatusCode::NOT_FOUND);
+    };
 
-    Err(StatusCode::NOT_FOUND)
+    controller.check_owner(principal(&caller), &attributes).await?;
+    Ok(Json(attributes))
 }
 
 // Convert ADK exporter format to UI-compatible SpanData format
@@ -79,10 +104,14 @@ fn convert_to_span_data(attributes: &HashMap<String, String>) -> serde_json::Val
 // Get all spans for a session (UI-compatible format)
 pub async fn get_session_traces(
     State(controller): State<DebugController>,
+    caller: Option<Extension<Principal>>,
     Path(session_id): Path<String>,
 ) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
     if let Some(exporter) = &controller.config.span_exporter {
         let traces = exporter.get_session_trace(&session_id);
+        for attributes in &traces {
+            controller.check_owner(principal(&caller), attributes).await?;
+        }
         let span_data: Vec<serde_json::Value> = traces.iter().map(convert_to_span_data).collect();
         return Ok(Json(span_data));
     }
@@ -111,14 +140,17 @@ pub async fn get_eval_sets(
 /// Get event data by event_id - returns event with invocationId for trace linking
 pub async fn get_event(
     State(controller): State<DebugController>,
-    Path((app_name, _user_id, session_id, event_id)): Path<(String, String, String, String)>,
+    Path((app_name, user_id, session_id, event_id)): Path<(String, String, String, String)>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Try to find trace data for this event_id
     if let Some(exporter) = &controller.config.span_exporter {
         let traces = exporter.get_session_trace(&session_id);
 
-        // Find a trace with matching event_id
+        // Find a trace with matching event_id from this user's session
         for attrs in traces {
+            if attrs.get(USER_ID_ATTRIBUTE) != Some(&user_id) {
+                continue;
+            }
             if let Some(stored_event_id) = attrs.get("gcp.vertex.agent.event_id") {
                 if stored_event_id == &event_id {
                     // Found matching trace - return event-like structure
@@ -0,0 +1,269 @@
+use crate::ServerConfig;
+use crate::auth::Principal;
+use adk_graph::{Checkpoint, CompiledGraph, ExecutionConfig, GraphError, StateDiff};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Checkpoint history, forks and state edits for the graphs registered with
+/// [`ServerConfig::with_graph`]
+#[derive(Clone)]
+pub struct GraphController {
+    config: ServerConfig,
+}
+
+impl GraphController {
+    pub fn new(config: ServerConfig) -> Self {
+        Self { config }
+    }
+
+    fn graph(&self, app_name: &str) -> Result<&Arc<CompiledGraph>, StatusCode> {
+        self.config.graphs.get(app_name).ok_or(StatusCode::NOT_FOUND)
+    }
+
+    /// With auth, a thread is only visible to the user whose session it is
+    async fn check_thread(
+        &self,
+        principal: Option<&Principal>,
+        app_name: &str,
+        thread_id: &str,
+    ) -> Result<(), StatusCode> {
+        let Some(principal) = principal else {
+            return Ok(());
+        };
+        self.config
+            .session_service
+            .get(adk_session::GetRequest {
+                app_name: app_name.to_string(),
+                user_id: principal.user_id.clone(),
+                session_id: thread_id.to_string(),
+                num_recent_events: Some(0),
+                after: None,
+            })
+            .await
+            .map(|_| ())
+            .map_err(|_| StatusCode::NOT_FOUND)
+    }
+
+    /// Load a checkpoint, if its thread is visible to the caller
+    async fn checkpoint(
+        &self,
+        principal: Option<&Principal>,
+        app_name: &str,
+        checkpoint_id: &str,
+    ) -> Result<Checkpoint, StatusCode> {
+        let graph = self.graph(app_name)?;
+        let checkpoint = graph.get_checkpoint(checkpoint_id).await.map_err(status_for)?;
+        self.check_thread(principal, app_name, &checkpoint.thread_id).await?;
+        Ok(checkpoint)
+    }
+}
+
+fn principal(extension: &Option<Extension<Principal>>) -> Option<&Principal> {
+    extension.as_ref().map(|Extension(principal)| principal)
+}
+
+fn status_for(error: GraphError) -> StatusCode {
+    match error {
+        GraphError::CheckpointNotFound(_) => StatusCode::NOT_FOUND,
+        GraphError::NodeNotFound(_) => StatusCode::BAD_REQUEST,
+        error => {
+            warn!(%error, "graph history request failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointResponse {
+    pub checkpoint_id: String,
+    pub thread_id: String,
+    /// Checkpoint this one follows, or was forked or edited from
+    pub parent_id: Option<String>,
+    pub step: usize,
+    /// Nodes that run next; empty once the run finished
+    pub pending_nodes: Vec<String>,
+    /// Fan-out invocations that run next
+    pub pending_sends: Vec<serde_json::Value>,
+    pub state: HashMap<String, serde_json::Value>,
+    pub metadata: HashMap<String, serde_json::Value>,
+    pub created_at: String,
+}
+
+impl From<Checkpoint> for CheckpointResponse {
+    fn from(checkpoint: Checkpoint) -> Self {
+        Self {
+            parent_id: checkpoint.parent_id().map(String::from),
+            pending_sends: checkpoint
+                .pending_sends
+                .iter()
+                .map(|send| serde_json::to_value(send).unwrap_or(serde_json::Value::Null))
+                .collect(),
+            checkpoint_id: checkpoint.checkpoint_id,
+            thread_id: checkpoint.thread_id,
+            step: checkpoint.step,
+            pending_nodes: checkpoint.pending_nodes,
+            state: checkpoint.state,
+            metadata: checkpoint.metadata,
+            created_at: checkpoint.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkRequest {
+    /// Thread to fork to; a new ID is generated when omitted
+    #[serde(default)]
+    pub thread_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStateRequest {
+    pub values: HashMap<String, serde_json::Value>,
+    /// Apply the values as if this node returned them, scheduling the nodes after it
+    #[serde(default)]
+    pub as_node: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayRequest {
+    pub checkpoint_id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResponse {
+    pub thread_id: String,
+    pub state: HashMap<String, serde_json::Value>,
+    /// Set when the run paused at an interrupt
+    pub interrupt: Option<serde_json::Value>,
+    /// Checkpoint to resume from when interrupted
+    pub checkpoint_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+/// List a thread's checkpoints, newest first
+pub async fn list_checkpoints(
+    State(controller): State<GraphController>,
+    Path((app_name, thread_id)): Path<(String, String)>,
+    caller: Option<Extension<Principal>>,
+) -> Result<Json<Vec<CheckpointResponse>>, StatusCode> {
+    let graph = controller.graph(&app_name)?;
+    controller.check_thread(principal(&caller), &app_name, &thread_id).await?;
+    let history = graph.get_state_history(&thread_id).await.map_err(status_for)?;
+    Ok(Json(history.into_iter().map(CheckpointResponse::from).collect()))
+}
+
+pub async fn get_checkpoint(
+    State(controller): State<GraphController>,
+    Path((app_name, checkpoint_id)): Path<(String, String)>,
+    caller: Option<Extension<Principal>>,
+) -> Result<Json<CheckpointResponse>, StatusCode> {
+    let checkpoint = controller.checkpoint(principal(&caller), &app_name, &checkpoint_id).await?;
+    Ok(Json(checkpoint.into()))
+}
+
+/// Copy a checkpoint to another thread. Fork to a session's ID to continue the branch
+/// by running that session; with auth, the target must be one of the caller's sessions.
+pub async fn fork_checkpoint(
+    State(controller): State<GraphController>,
+    Path((app_name, checkpoint_id)): Path<(String, String)>,
+    caller: Option<Extension<Principal>>,
+    Json(req): Json<ForkRequest>,
+) -> Result<Json<CheckpointResponse>, StatusCode> {
+    let graph = controller.graph(&app_name)?;
+    let caller = principal(&caller);
+    controller.checkpoint(caller, &app_name, &checkpoint_id).await?;
+    let thread_id = match (req.thread_id, caller) {
+        (Some(thread_id), _) => thread_id,
+        (None, None) => uuid::Uuid::new_v4().to_string(),
+        (None, Some(_)) => return Err(StatusCode::BAD_REQUEST),
+    };
+    controller.check_thread(caller, &app_name, &thread_id).await?;
+    let fork = graph.fork(&checkpoint_id, &thread_id).await.map_err(status_for)?;
+    Ok(Json(fork.into()))
+}
+
+/// Edit a thread's latest state, returning the checkpoint that records the edit
+pub async fn update_state(
+    State(controller): State<GraphController>,
+    Path((app_name, thread_id)): Path<(String, String)>,
+    caller: Option<Extension<Principal>>,
+    Json(req): Json<UpdateStateRequest>,
+) -> Result<Json<CheckpointResponse>, StatusCode> {
+    let graph = controller.graph(&app_name)?;
+    controller.check_thread(principal(&caller), &app_name, &thread_id).await?;
+    let checkpoint = match req.as_node {
+        Some(as_node) => {
+            graph.update_state_as(&thread_id, &as_node, req.values).await.map_err(status_for)?
+        }
+        None => {
+            graph.update_state(&thread_id, req.values).await.map_err(status_for)?;
+            graph
+                .get_state_history(&thread_id)
+                .await
+                .map_err(status_for)?
+                .into_iter()
+                .next()
+                .ok_or(StatusCode::NOT_FOUND)?
+        }
+    };
+    Ok(Json(checkpoint.into()))
+}
+
+/// Run the graph from a checkpoint on the given thread
+///
+/// Runs the compiled graph directly, outside any session; nodes that need an
+/// invocation context should be replayed by forking to a session and running it.
+pub async fn replay(
+    State(controller): State<GraphController>,
+    Path((app_name, thread_id)): Path<(String, String)>,
+    caller: Option<Extension<Principal>>,
+    Json(req): Json<ReplayRequest>,
+) -> Result<Json<ReplayResponse>, StatusCode> {
+    let graph = controller.graph(&app_name)?;
+    let caller = principal(&caller);
+    controller.check_thread(caller, &app_name, &thread_id).await?;
+    controller.checkpoint(caller, &app_name, &req.checkpoint_id).await?;
+    let config = ExecutionConfig::new(&thread_id);
+    match graph.replay(&req.checkpoint_id, config).await {
+        Ok(state) => {
+            Ok(Json(ReplayResponse { thread_id, state, interrupt: None, checkpoint_id: None }))
+        }
+        Err(GraphError::Interrupted(interrupted)) => Ok(Json(ReplayResponse {
+            thread_id,
+            interrupt: serde_json::to_value(&interrupted.interrupt).ok(),
+            checkpoint_id: Some(interrupted.checkpoint_id),
+            state: interrupted.state,
+        })),
+        Err(e) => Err(status_for(e)),
+    }
+}
+
+/// Compare the states of two checkpoints
+pub async fn diff_checkpoints(
+    State(controller): State<GraphController>,
+    Path(app_name): Path<String>,
+    Query(query): Query<DiffQuery>,
+    caller: Option<Extension<Principal>>,
+) -> Result<Json<StateDiff>, StatusCode> {
+    let caller = principal(&caller);
+    let from = controller.checkpoint(caller, &app_name, &query.from).await?;
+    let to = controller.checkpoint(caller, &app_name, &query.to).await?;
+    Ok(Json(StateDiff::between(&from.state, &to.state)))
+}
@@ -2,6 +2,7 @@ pub mod a2a;
 pub mod apps;
 pub mod artifacts;
 pub mod debug;
+pub mod graph;
 pub mod runtime;
 pub mod session;
 
@@ -9,5 +10,6 @@ pub use a2a::A2aController;
 pub use apps::AppsController;
 pub use artifacts::ArtifactsController;
 pub use debug::DebugController;
+pub use graph::GraphController;
 pub use runtime::RuntimeController;
 pub use session::SessionController;
@@ -1,33 +1,73 @@
 use crate::ServerConfig;
+use adk_core::{CancellationToken, Content, FunctionResponseData, Part, RunConfig, StreamingMode};
 use axum::{
     Json,
     extract::{Path, State},
     http::StatusCode,
-    response::sse::{Event, KeepAlive, Sse},
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
-use futures::stream::{self, Stream};
+use base64::{Engine as _, engine::general_purpose};
+use futures::stream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::convert::Infallible;
-use tracing::{Instrument, info};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, watch};
+use tracing::{Instrument, debug, info};
+
+/// Response header carrying the ID of the invocation a run starts, which can be
+/// cancelled before its first event arrives
+pub const INVOCATION_ID_HEADER: &str = "x-invocation-id";
 
 fn default_streaming_true() -> bool {
     true
 }
 
+/// An invocation in flight on this server
+struct RunningInvocation {
+    app_name: String,
+    user_id: String,
+    session_id: String,
+    cancellation_token: CancellationToken,
+    /// Closed once the run's events have all been recorded
+    finished: watch::Receiver<()>,
+}
+
+type RunningInvocations = Arc<Mutex<HashMap<String, RunningInvocation>>>;
+
 #[derive(Clone)]
 pub struct RuntimeController {
     config: ServerConfig,
+    running: RunningInvocations,
 }
 
 impl RuntimeController {
     pub fn new(config: ServerConfig) -> Self {
-        Self { config }
+        Self { config, running: Arc::new(Mutex::new(HashMap::new())) }
     }
 }
 
-#[derive(Serialize, Deserialize)]
+/// Request body for `/run/{app_name}/{user_id}/{session_id}`
+#[derive(Serialize, Deserialize, Debug)]
 pub struct RunRequest {
-    pub new_message: String,
+    pub new_message: RunMessage,
+    /// Stream partial responses, the default, or send each response whole
+    #[serde(default = "default_streaming_true")]
+    pub streaming: bool,
+    /// State to apply to the session along with the message
+    #[serde(default)]
+    pub state_delta: Option<serde_json::Value>,
+}
+
+/// A message to run: plain text, or content with any number of parts
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum RunMessage {
+    Text(String),
+    Content(NewMessage),
 }
 
 /// Request format for /run_sse (adk-go compatible)
@@ -44,20 +84,36 @@ pub struct RunSseRequest {
     pub state_delta: Option<serde_json::Value>,
 }
 
+fn default_user_role() -> String {
+    "user".to_string()
+}
+
+/// Message content in the Gemini API's JSON format
 #[derive(Serialize, Deserialize, Debug)]
 pub struct NewMessage {
+    /// Must be `user`; clients cannot speak as the model
+    #[serde(default = "default_user_role")]
     pub role: String,
     pub parts: Vec<MessagePart>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// One part of a message; exactly one field is set
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct MessagePart {
     #[serde(default)]
     pub text: Option<String>,
-    #[serde(default, rename = "inlineData")]
+    #[serde(default)]
     pub inline_data: Option<InlineData>,
+    #[serde(default)]
+    pub file_data: Option<FileData>,
+    /// Answers a function call, e.g. the result of a long-running tool or a tool
+    /// confirmation, resuming the agent waiting on it
+    #[serde(default)]
+    pub function_response: Option<FunctionResponse>,
 }
 
+/// Bytes sent with the message, base64-encoded
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InlineData {
@@ -66,66 +122,269 @@ pub struct InlineData {
     pub mime_type: String,
 }
 
-pub async fn run_sse(
-    State(controller): State<RuntimeController>,
-    Path((app_name, user_id, session_id)): Path<(String, String, String)>,
-    Json(req): Json<RunRequest>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
-    let span = tracing::info_span!("run_sse", session_id = %session_id, app_name = %app_name, user_id = %user_id);
+/// A file the message refers to by URI
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileData {
+    pub file_uri: String,
+    pub mime_type: String,
+}
 
-    async move {
-        // Validate session exists
-        controller
-            .config
-            .session_service
-            .get(adk_session::GetRequest {
-                app_name: app_name.clone(),
-                user_id: user_id.clone(),
-                session_id: session_id.clone(),
-                num_recent_events: None,
-                after: None,
-            })
-            .await
-            .map_err(|_| StatusCode::NOT_FOUND)?;
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FunctionResponse {
+    pub name: String,
+    #[serde(default)]
+    pub response: serde_json::Value,
+    #[serde(default)]
+    pub id: Option<String>,
+}
 
+impl TryFrom<MessagePart> for Part {
+    type Error = String;
+
+    fn try_from(part: MessagePart) -> Result<Self, Self::Error> {
+        match part {
+            MessagePart {
+                text: Some(text),
+                inline_data: None,
+                file_data: None,
+                function_response: None,
+            } => Ok(Part::Text { text }),
+            MessagePart {
+                text: None,
+                inline_data: Some(inline),
+                file_data: None,
+                function_response: None,
+            } => {
+                // Browsers commonly send URL-safe base64
+                let data = general_purpose::STANDARD
+                    .decode(&inline.data)
+                    .or_else(|_| general_purpose::URL_SAFE.decode(&inline.data))
+                    .map_err(|e| format!("invalid base64 in inlineData: {e}"))?;
+                Ok(Part::InlineData { mime_type: inline.mime_type, data })
+            }
+            MessagePart {
+                text: None,
+                inline_data: None,
+                file_data: Some(file),
+                function_response: None,
+            } => Ok(Part::FileData { mime_type: file.mime_type, file_uri: file.file_uri }),
+            MessagePart {
+                text: None,
+                inline_data: None,
+                file_data: None,
+                function_response: Some(response),
+            } => Ok(Part::FunctionResponse {
+                function_response: FunctionResponseData {
+                    name: response.name,
+                    response: response.response,
+                },
+                id: response.id,
+            }),
+            _ => Err(
+                "each part must set exactly one of text, inlineData, fileData and functionResponse"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+impl TryFrom<NewMessage> for Content {
+    type Error = String;
+
+    fn try_from(message: NewMessage) -> Result<Self, Self::Error> {
+        if message.role != "user" {
+            return Err(format!("message role must be \"user\", got {:?}", message.role));
+        }
+        let parts = message.parts.into_iter().map(Part::try_from).collect::<Result<Vec<_>, _>>()?;
+        if parts.is_empty() {
+            return Err("message has no parts".to_string());
+        }
+        Ok(Content { role: message.role, parts })
+    }
+}
+
+impl TryFrom<RunMessage> for Content {
+    type Error = String;
+
+    fn try_from(message: RunMessage) -> Result<Self, Self::Error> {
+        match message {
+            RunMessage::Text(text) => Ok(Content::new("user").with_text(text)),
+            RunMessage::Content(message) => message.try_into(),
+        }
+    }
+}
+
+fn bad_request(reason: String) -> StatusCode {
+    debug!(reason = %reason, "rejected run request");
+    StatusCode::BAD_REQUEST
+}
+
+/// The message and per-request run options of a run request
+fn run_input(
+    message: impl TryInto<Content, Error = String>,
+    streaming: bool,
+    state_delta: Option<serde_json::Value>,
+) -> Result<(Content, RunConfig, HashMap<String, serde_json::Value>), StatusCode> {
+    let content = message.try_into().map_err(bad_request)?;
+    let streaming_mode = if streaming { StreamingMode::SSE } else { StreamingMode::None };
+    let state_delta = match state_delta {
+        None | Some(serde_json::Value::Null) => HashMap::new(),
+        Some(serde_json::Value::Object(delta)) => delta.into_iter().collect(),
+        Some(_) => return Err(bad_request("stateDelta must be an object".to_string())),
+    };
+    Ok((content, RunConfig { streaming_mode }, state_delta))
+}
+
+impl RuntimeController {
+    /// Run the app's agent on `content`, streaming its events as SSE
+    async fn run(
+        &self,
+        app_name: String,
+        user_id: String,
+        session_id: String,
+        (content, run_config, state_delta): (
+            Content,
+            RunConfig,
+            HashMap<String, serde_json::Value>,
+        ),
+    ) -> Result<impl IntoResponse + use<>, StatusCode> {
         // Load agent
-        let agent = controller
+        let agent = self
             .config
             .agent_loader
             .load_agent(&app_name)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        // Create runner
+        // Create runner with the request's run config
         let runner = adk_runner::Runner::new(adk_runner::RunnerConfig {
             app_name: app_name.clone(),
             agent,
-            session_service: controller.config.session_service.clone(),
-            artifact_service: controller.config.artifact_service.clone(),
-            memory_service: None,
-            run_config: None,
+            session_service: self.config.session_service.clone(),
+            artifact_service: self.config.artifact_service.clone(),
+            memory_service: self.config.memory_for(&app_name, &user_id),
+            run_config: Some(run_config),
         })
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let runner = match &self.config.session_locks {
+            Some(locks) => runner.with_session_locks(locks.clone()),
+            None => runner,
+        };
+        let cancellation_token = CancellationToken::new();
+        let invocation_id = format!("inv-{}", uuid::Uuid::new_v4());
+
+        // Registered before the run starts, so it can be cancelled during its first
+        // model call
+        let (finished, finished_receiver) = watch::channel(());
+        let register = {
+            let running = self.running.clone();
+            let (app_name, user_id, session_id) =
+                (app_name.clone(), user_id.clone(), session_id.clone());
+            let token = cancellation_token.clone();
+            move |invocation_id: &str| {
+                running.lock().unwrap().insert(
+                    invocation_id.to_string(),
+                    RunningInvocation {
+                        app_name: app_name.clone(),
+                        user_id: user_id.clone(),
+                        session_id: session_id.clone(),
+                        cancellation_token: token.clone(),
+                        finished: finished_receiver.clone(),
+                    },
+                );
+            }
+        };
+        register(&invocation_id);
 
         // Run agent
-        let event_stream = runner
-            .run(user_id, session_id, adk_core::Content::new("user").with_text(&req.new_message))
+        let options = adk_runner::RunOptions {
+            state_delta,
+            invocation_id: Some(invocation_id.clone()),
+            cancellation_token: Some(cancellation_token.clone()),
+        };
+        let mut event_stream = match runner
+            .run_with_options(user_id.clone(), session_id.clone(), content, options)
             .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        {
+            Ok(event_stream) => event_stream,
+            Err(_) => {
+                self.running.lock().unwrap().remove(&invocation_id);
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        };
 
-        // Convert to SSE stream
-        let sse_stream = stream::unfold(event_stream, move |mut stream| async move {
+        // The run is driven by its own task, so that once it is cancelled it still
+        // records its cancellation event after the client has gone
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let running = self.running.clone();
+        let mut invocation_ids = vec![invocation_id.clone()];
+        tokio::spawn(async move {
             use futures::StreamExt;
-            match stream.next().await {
-                Some(Ok(event)) => {
-                    let json = serde_json::to_string(&event).ok()?;
-                    Some((Ok(Event::default().data(json)), stream))
+            while let Some(result) = event_stream.next().await {
+                let event = match result {
+                    Ok(event) => event,
+                    Err(e) => {
+                        debug!(session_id = %session_id, error = %e, "run failed");
+                        break;
+                    }
+                };
+                // A resumed invocation keeps its ID, and transfers start new ones
+                if !invocation_ids.contains(&event.invocation_id) {
+                    invocation_ids.push(event.invocation_id.clone());
+                    register(&event.invocation_id);
                 }
-                _ => None,
+                // A disconnected client cancels the run, which ends shortly
+                let _ = sender.send(event);
             }
+            drop(sender);
+
+            let mut running = running.lock().unwrap();
+            for invocation_id in &invocation_ids {
+                running.remove(invocation_id);
+            }
+            drop(finished);
         });
 
-        Ok(Sse::new(sse_stream).keep_alive(KeepAlive::default()))
+        // Convert to SSE stream, cancelling the run when the client disconnects
+        let disconnect_guard = cancellation_token.drop_guard();
+        let sse_stream =
+            stream::unfold((receiver, disconnect_guard), move |(mut receiver, guard)| async move {
+                let event = receiver.recv().await?;
+                let json = serde_json::to_string(&event).ok()?;
+                Some((Ok::<_, Infallible>(Event::default().data(json)), (receiver, guard)))
+            });
+
+        let sse = Sse::new(sse_stream).keep_alive(KeepAlive::default());
+        Ok(([(INVOCATION_ID_HEADER, invocation_id)], sse))
+    }
+}
+
+pub async fn run_sse(
+    State(controller): State<RuntimeController>,
+    Path((app_name, user_id, session_id)): Path<(String, String, String)>,
+    Json(req): Json<RunRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let span = tracing::info_span!("run_sse", session_id = %session_id, app_name = %app_name, user_id = %user_id);
+
+    async move {
+        let input = run_input(req.new_message, req.streaming, req.state_delta)?;
+
+        // Validate session exists
+        controller
+            .config
+            .session_service
+            .get(adk_session::GetRequest {
+                app_name: app_name.clone(),
+                user_id: user_id.clone(),
+                session_id: session_id.clone(),
+                num_recent_events: None,
+                after: None,
+            })
+            .await
+            .map_err(|_| StatusCode::NOT_FOUND)?;
+
+        controller.run(app_name, user_id, session_id, input).await
     }
     .instrument(span)
     .await
@@ -136,7 +395,7 @@ pub async fn run_sse(
 pub async fn run_sse_compat(
     State(controller): State<RuntimeController>,
     Json(req): Json<RunSseRequest>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+) -> Result<impl IntoResponse, StatusCode> {
     let app_name = req.app_name;
     let user_id = req.user_id;
     let session_id = req.session_id;
@@ -148,15 +407,7 @@ pub async fn run_sse_compat(
         "POST /run_sse request received"
     );
 
-    // Extract text from message parts
-    let message_text = req
-        .new_message
-        .parts
-        .iter()
-        .filter_map(|p| p.text.as_ref())
-        .cloned()
-        .collect::<Vec<_>>()
-        .join(" ");
+    let input = run_input(req.new_message, req.streaming, req.state_delta)?;
 
     // Validate session exists or create it
     let session_result = controller
@@ -186,45 +437,35 @@ pub async fn run_sse_compat(
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     }
 
-    // Load agent
-    let agent = controller
-        .config
-        .agent_loader
-        .load_agent(&app_name)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    // Create runner with streaming config from request
-    let streaming_mode =
-        if req.streaming { adk_core::StreamingMode::SSE } else { adk_core::StreamingMode::None };
-
-    let runner = adk_runner::Runner::new(adk_runner::RunnerConfig {
-        app_name,
-        agent,
-        session_service: controller.config.session_service.clone(),
-        artifact_service: controller.config.artifact_service.clone(),
-        memory_service: None,
-        run_config: Some(adk_core::RunConfig { streaming_mode }),
-    })
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    // Run agent
-    let event_stream = runner
-        .run(user_id, session_id, adk_core::Content::new("user").with_text(&message_text))
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    controller.run(app_name, user_id, session_id, input).await
+}
 
-    // Convert to SSE stream
-    let sse_stream = stream::unfold(event_stream, move |mut stream| async move {
-        use futures::StreamExt;
-        match stream.next().await {
-            Some(Ok(event)) => {
-                let json = serde_json::to_string(&event).ok()?;
-                Some((Ok(Event::default().data(json)), stream))
+/// POST /apps/{app_name}/users/{user_id}/sessions/{session_id}/invocations/{invocation_id}/cancel
+///
+/// Stops an in-flight invocation, responding once its cancellation event is recorded
+/// in the session.
+pub async fn cancel_invocation(
+    State(controller): State<RuntimeController>,
+    Path((app_name, user_id, session_id, invocation_id)): Path<(String, String, String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    let (cancellation_token, mut finished) = {
+        let running = controller.running.lock().unwrap();
+        match running.get(&invocation_id) {
+            Some(invocation)
+                if invocation.app_name == app_name
+                    && invocation.user_id == user_id
+                    && invocation.session_id == session_id =>
+            {
+                (invocation.cancellation_token.clone(), invocation.finished.clone())
             }
-            _ => None,
+            _ => return Err(StatusCode::NOT_FOUND),
         }
-    });
+    };
+
+    info!(invocation_id = %invocation_id, session_id = %session_id, "cancelling invocation");
+    cancellation_token.cancel();
+    // Resolves when the run's task drops the sender, after its last event
+    let _ = finished.changed().await;
 
-    Ok(Sse::new(sse_stream).keep_alive(KeepAlive::default()))
+    Ok(StatusCode::NO_CONTENT)
 }
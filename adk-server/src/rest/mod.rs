@@ -2,15 +2,17 @@ pub mod controllers;
 mod routes;
 
 pub use controllers::{
-    A2aController, AppsController, ArtifactsController, DebugController, RuntimeController,
-    SessionController,
+    A2aController, AppsController, ArtifactsController, DebugController, GraphController,
+    RuntimeController, SessionController,
 };
 
+use crate::auth::{AuthLayer, require_auth};
 use crate::{ServerConfig, web_ui};
 use axum::{
     Router,
     extract::DefaultBodyLimit,
     http::{HeaderValue, Method, header},
+    middleware,
     routing::{get, post},
 };
 use tower::ServiceBuilder;
@@ -25,7 +27,11 @@ use tower_http::{
 fn build_cors_layer(config: &ServerConfig) -> CorsLayer {
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::HeaderName::from_static(crate::auth::API_KEY_HEADER),
+        ]);
 
     if config.security.allowed_origins.is_empty() {
         // Development mode: allow all origins (with warning logged at startup)
@@ -50,9 +56,9 @@ pub fn create_app_with_a2a(config: ServerConfig, a2a_base_url: Option<&str>) ->
     let apps_controller = AppsController::new(config.clone());
     let artifacts_controller = ArtifactsController::new(config.clone());
     let debug_controller = DebugController::new(config.clone());
+    let graph_controller = GraphController::new(config.clone());
 
-    let api_router = Router::new()
-        .route("/health", get(health_check))
+    let mut api_router = Router::new()
         .route("/apps", get(controllers::apps::list_apps))
         .route("/list-apps", get(controllers::apps::list_apps_compat))
         .with_state(apps_controller)
@@ -76,6 +82,10 @@ pub fn create_app_with_a2a(config: ServerConfig, a2a_base_url: Option<&str>) ->
         .with_state(session_controller)
         .route("/run/{app_name}/{user_id}/{session_id}", post(controllers::runtime::run_sse))
         .route("/run_sse", post(controllers::runtime::run_sse_compat))
+        .route(
+            "/apps/{app_name}/users/{user_id}/sessions/{session_id}/invocations/{invocation_id}/cancel",
+            post(controllers::runtime::cancel_invocation),
+        )
         .with_state(runtime_controller)
         .route(
             "/sessions/{app_name}/{user_id}/{session_id}/artifacts",
@@ -104,7 +114,38 @@ pub fn create_app_with_a2a(config: ServerConfig, a2a_base_url: Option<&str>) ->
             "/apps/{app_name}/users/{user_id}/sessions/{session_id}/events/{event_id}",
             get(controllers::debug::get_event),
         )
-        .with_state(debug_controller);
+        .with_state(debug_controller)
+        // Graph time travel: checkpoint history, forks, state edits and diffs
+        .route(
+            "/apps/{app_name}/graph/threads/{thread_id}/checkpoints",
+            get(controllers::graph::list_checkpoints),
+        )
+        .route(
+            "/apps/{app_name}/graph/threads/{thread_id}/state",
+            post(controllers::graph::update_state),
+        )
+        .route(
+            "/apps/{app_name}/graph/threads/{thread_id}/replay",
+            post(controllers::graph::replay),
+        )
+        .route(
+            "/apps/{app_name}/graph/checkpoints/{checkpoint_id}",
+            get(controllers::graph::get_checkpoint),
+        )
+        .route(
+            "/apps/{app_name}/graph/checkpoints/{checkpoint_id}/fork",
+            post(controllers::graph::fork_checkpoint),
+        )
+        .route("/apps/{app_name}/graph/diff", get(controllers::graph::diff_checkpoints))
+        .with_state(graph_controller);
+    if let Some(auth) = &config.auth {
+        api_router = api_router.route_layer(middleware::from_fn_with_state(
+            AuthLayer::new(auth.clone(), config.security.max_body_size),
+            require_auth,
+        ));
+    }
+    // Left open for load balancers and uptime checks
+    let api_router = api_router.route("/health", get(health_check));
 
     let ui_router = Router::new()
         .route("/", get(web_ui::root_redirect))
@@ -118,10 +159,22 @@ pub fn create_app_with_a2a(config: ServerConfig, a2a_base_url: Option<&str>) ->
     // Add A2A routes if base URL is provided
     if let Some(base_url) = a2a_base_url {
         let a2a_controller = A2aController::new(config.clone(), base_url);
-        let a2a_router = Router::new()
-            .route("/.well-known/agent.json", get(controllers::a2a::get_agent_card))
+        let mut a2a_router = Router::new()
             .route("/a2a", post(controllers::a2a::handle_jsonrpc))
             .route("/a2a/stream", post(controllers::a2a::handle_jsonrpc_stream))
+            .with_state(a2a_controller.clone());
+        if let Some(auth) = &config.auth {
+            // A2A serves the root agent, so that is the app callers need access to
+            let app_name = config.agent_loader.root_agent().name().to_string();
+            a2a_router = a2a_router.route_layer(middleware::from_fn_with_state(
+                AuthLayer::new(auth.clone(), config.security.max_body_size)
+                    .with_default_app(app_name),
+                require_auth,
+            ));
+        }
+        // The agent card stays public so clients can discover how to authenticate
+        let a2a_router = a2a_router
+            .route("/.well-known/agent.json", get(controllers::a2a::get_agent_card))
             .with_state(a2a_controller);
         app = app.merge(a2a_router);
     }
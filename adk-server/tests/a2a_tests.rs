@@ -1,11 +1,17 @@
 use adk_core::{Agent, EventStream, InvocationContext, Result as AdkResult};
-use adk_server::{ServerConfig, create_app_with_a2a};
+use adk_memory::{InMemoryMemoryService, MemoryEntry, MemoryService};
+use adk_server::a2a::{PushNotifier, push};
+use adk_server::{AuthConfig, ServerConfig, SessionTaskStore, TaskStore, create_app_with_a2a};
 use adk_session::InMemorySessionService;
 use async_trait::async_trait;
 use axum::body::Body;
-use axum::http::{Request, StatusCode};
+use axum::http::{Request, Response, StatusCode};
 use http_body_util::BodyExt;
-use std::sync::Arc;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
 use tower::ServiceExt;
 
 // Simple test agent for A2A testing
@@ -265,7 +271,520 @@ async fn test_a2a_tasks_cancel() {
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
 
-    // Should return a canceled status
-    assert!(json["result"].is_object());
-    assert_eq!(json["result"]["status"]["state"], "Canceled");
+    // Unknown tasks can't be canceled
+    assert!(json["result"].is_null());
+    assert_eq!(json["error"]["code"], -32001); // Task not found
+}
+
+/// Sets its flag when dropped, i.e. when the agent's stream is dropped
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Answers "Thinking", then "Done" once the gate opens
+struct GatedAgent {
+    gate: Arc<Notify>,
+    stopped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Agent for GatedAgent {
+    fn name(&self) -> &str {
+        "gated_agent"
+    }
+
+    fn description(&self) -> &str {
+        "Waits for the test before finishing"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> AdkResult<EventStream> {
+        let invocation_id = ctx.invocation_id().to_string();
+        let gate = self.gate.clone();
+        let stopped = self.stopped.clone();
+        let stream = async_stream::stream! {
+            let _flag = DropFlag(stopped);
+            for (text, wait) in [("Thinking", true), ("Done", false)] {
+                let mut event = adk_core::Event::new(&invocation_id);
+                event.author = "gated_agent".to_string();
+                event.llm_response.content = Some(adk_core::Content::new("model").with_text(text));
+                yield Ok(event);
+                if wait {
+                    gate.notified().await;
+                }
+            }
+        };
+        Ok(Box::pin(stream))
+    }
+}
+
+fn gated_app() -> (axum::Router, Arc<Notify>, Arc<AtomicBool>) {
+    gated_app_with(|config| config)
+}
+
+fn gated_app_with(
+    configure: impl FnOnce(ServerConfig) -> ServerConfig,
+) -> (axum::Router, Arc<Notify>, Arc<AtomicBool>) {
+    let gate = Arc::new(Notify::new());
+    let stopped = Arc::new(AtomicBool::new(false));
+    let agent = Arc::new(GatedAgent { gate: gate.clone(), stopped: stopped.clone() });
+    let config = ServerConfig::new(
+        Arc::new(TestAgentLoader { agent }),
+        Arc::new(InMemorySessionService::new()),
+    );
+    (create_app_with_a2a(configure(config), Some("http://localhost:8080")), gate, stopped)
+}
+
+async fn post(app: &axum::Router, uri: &str, method: &str, params: Value) -> Response<Body> {
+    let body = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap()
+}
+
+async fn rpc(app: &axum::Router, method: &str, params: Value) -> Value {
+    let response = post(app, "/a2a", method, params).await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn user_message(task_id: Option<&str>) -> Value {
+    json!({
+        "message": {"role": "user", "messageId": "msg-1", "parts": [{"text": "Hi"}], "taskId": task_id},
+        "config": {"blocking": false}
+    })
+}
+
+/// Reads server-sent events as they arrive
+struct SseReader {
+    body: Body,
+    buffer: String,
+}
+
+impl SseReader {
+    fn new(response: Response<Body>) -> Self {
+        Self { body: response.into_body(), buffer: String::new() }
+    }
+
+    /// The next JSON-RPC message, or `None` once the stream is done
+    async fn next(&mut self) -> Option<Value> {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let block: String = self.buffer.drain(..end + 2).collect();
+                if block.contains("event: done") {
+                    return None;
+                }
+                let data = block.lines().find_map(|line| line.strip_prefix("data: "));
+                if let Some(data) = data {
+                    return Some(serde_json::from_str(data).unwrap());
+                }
+                continue;
+            }
+            let frame = tokio::time::timeout(Duration::from_secs(5), self.body.frame())
+                .await
+                .expect("timed out waiting for an event")?
+                .unwrap();
+            if let Ok(data) = frame.into_data() {
+                self.buffer.push_str(std::str::from_utf8(&data).unwrap());
+            }
+        }
+    }
+}
+
+/// Polls tasks/get until `ready` accepts the task
+async fn wait_for_task(app: &axum::Router, task_id: &str, ready: impl Fn(&Value) -> bool) -> Value {
+    for _ in 0..200 {
+        let task = rpc(app, "tasks/get", json!({"taskId": task_id})).await["result"].clone();
+        if ready(&task) {
+            return task;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("task {task_id} never became ready");
+}
+
+#[tokio::test]
+async fn test_a2a_stream_yields_updates_as_they_happen() {
+    let (app, gate, _) = gated_app();
+    let mut params = user_message(None);
+    params["config"] = Value::Null;
+    let mut events = SseReader::new(post(&app, "/a2a/stream", "message/stream", params).await);
+
+    // The first chunk arrives while the agent is still waiting at the gate
+    let mut seen = vec![];
+    while let Some(event) = events.next().await {
+        let is_chunk = event["result"]["artifact"]["parts"][0]["text"] == "Thinking";
+        seen.push(event);
+        if is_chunk {
+            break;
+        }
+    }
+    assert_eq!(seen[0]["result"]["status"]["state"], "Submitted");
+    assert_eq!(seen[1]["result"]["status"]["state"], "Working");
+    assert_eq!(seen.len(), 3);
+
+    gate.notify_one();
+    let mut rest = vec![];
+    while let Some(event) = events.next().await {
+        rest.push(event);
+    }
+    assert_eq!(rest[0]["result"]["artifact"]["parts"][0]["text"], "Done");
+    let last = &rest.last().unwrap()["result"];
+    assert_eq!(last["status"]["state"], "Completed");
+    assert_eq!(last["finalUpdate"], true);
+}
+
+#[tokio::test]
+async fn test_a2a_cancel_stops_running_task() {
+    let (app, _gate, stopped) = gated_app();
+
+    let task = rpc(&app, "message/send", user_message(Some("task-1"))).await["result"].clone();
+    assert_eq!(task["id"], "task-1");
+    wait_for_task(&app, "task-1", |task| task["artifacts"][0].is_object()).await;
+    assert!(!stopped.load(Ordering::SeqCst));
+
+    let canceled = rpc(&app, "tasks/cancel", json!({"taskId": "task-1"})).await;
+    assert_eq!(canceled["result"]["status"]["state"], "Canceled");
+    assert!(stopped.load(Ordering::SeqCst));
+
+    let task = rpc(&app, "tasks/get", json!({"taskId": "task-1"})).await["result"].clone();
+    assert_eq!(task["status"]["state"], "Canceled");
+    assert_eq!(task["artifacts"][0]["parts"], json!([{"text": "Thinking"}]));
+    assert_eq!(task["history"][0]["messageId"], "msg-1");
+
+    let again = rpc(&app, "tasks/cancel", json!({"taskId": "task-1"})).await;
+    assert_eq!(again["error"]["code"], -32002); // Task not cancelable
+}
+
+#[tokio::test]
+async fn test_a2a_resubscribe_to_running_task() {
+    let (app, gate, _) = gated_app();
+    rpc(&app, "message/send", user_message(Some("task-1"))).await;
+    wait_for_task(&app, "task-1", |task| task["artifacts"][0].is_object()).await;
+
+    let response =
+        post(&app, "/a2a/stream", "tasks/resubscribe", json!({"taskId": "task-1"})).await;
+    let mut events = SseReader::new(response);
+
+    // The task as it stands, then the rest of its updates
+    let current = events.next().await.unwrap();
+    assert_eq!(current["result"]["id"], "task-1");
+    assert_eq!(current["result"]["status"]["state"], "Working");
+
+    gate.notify_one();
+    let mut last = Value::Null;
+    while let Some(event) = events.next().await {
+        last = event;
+    }
+    assert_eq!(last["result"]["status"]["state"], "Completed");
+
+    // Finished tasks take no more messages
+    let reply = rpc(&app, "message/send", user_message(Some("task-1"))).await;
+    assert_eq!(reply["error"]["code"], -32602);
+}
+
+#[tokio::test]
+async fn test_a2a_tasks_saved_to_session_store() {
+    let session_service = Arc::new(InMemorySessionService::new());
+    let store = Arc::new(SessionTaskStore::new(session_service.clone(), "a2a"));
+    let agent = Arc::new(TestAgent::new("test_agent", "A test agent for A2A"));
+    let config = ServerConfig::new(Arc::new(TestAgentLoader { agent }), session_service)
+        .with_task_store(store.clone());
+    let app = create_app_with_a2a(config, Some("http://localhost:8080"));
+
+    let mut params = user_message(Some("task-1"));
+    params["config"]["blocking"] = json!(true);
+    let reply = rpc(&app, "message/send", params).await;
+    assert_eq!(reply["result"]["status"]["state"], "Completed");
+
+    let task = store.get("task-1").await.unwrap().unwrap();
+    assert_eq!(task.artifacts.unwrap().len(), 1);
+    assert_eq!(task.history.unwrap()[0].message_id, "msg-1");
+    assert_eq!(json!(task.status.state), "Completed");
+}
+
+/// Calls a long-running `approve_order` tool, then finishes once a message answers it
+struct ApprovalAgent;
+
+#[async_trait]
+impl Agent for ApprovalAgent {
+    fn name(&self) -> &str {
+        "approval_agent"
+    }
+
+    fn description(&self) -> &str {
+        "Needs an approval before finishing"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> AdkResult<EventStream> {
+        let approved = ctx
+            .user_content()
+            .parts
+            .iter()
+            .any(|part| matches!(part, adk_core::Part::FunctionResponse { .. }));
+        let mut event = adk_core::Event::new(ctx.invocation_id());
+        event.author = "approval_agent".to_string();
+        if approved {
+            event.llm_response.content = Some(adk_core::Content::new("model").with_text("Ordered"));
+        } else {
+            event.llm_response.content = Some(adk_core::Content {
+                role: "model".to_string(),
+                parts: vec![adk_core::Part::FunctionCall {
+                    name: "approve_order".to_string(),
+                    args: json!({"order": 42}),
+                    id: Some("call-1".to_string()),
+                }],
+            });
+            event.long_running_tool_ids = vec!["approve_order".to_string()];
+        }
+        Ok(Box::pin(futures::stream::iter([Ok(event)])))
+    }
+}
+
+#[tokio::test]
+async fn test_a2a_input_required_resumes_on_follow_up() {
+    let config = ServerConfig::new(
+        Arc::new(TestAgentLoader { agent: Arc::new(ApprovalAgent) }),
+        Arc::new(InMemorySessionService::new()),
+    );
+    let app = create_app_with_a2a(config, Some("http://localhost:8080"));
+    let send = |task_id: &str, part: Value| json!({"message": {"role": "user", "messageId": "msg-1", "parts": [part], "taskId": task_id}});
+
+    let paused = rpc(&app, "message/send", send("task-1", json!({"text": "Order it"}))).await;
+    let task = &paused["result"];
+    assert_eq!(task["status"]["state"], "InputRequired");
+    assert!(task["status"]["message"].as_str().unwrap().contains("approve_order"));
+    let call = &task["artifacts"][0]["parts"][0];
+    assert_eq!(call["data"]["function_call"]["id"], "call-1");
+    assert_eq!(call["metadata"]["long_running"], true);
+
+    let answer = json!({"data": {"function_response": {
+        "name": "approve_order", "response": {"approved": true}, "id": "call-1"
+    }}});
+    let resumed = rpc(&app, "message/send", send("task-1", answer)).await;
+    let task = &resumed["result"];
+    assert_eq!(task["status"]["state"], "Completed");
+    assert_eq!(task["contextId"], paused["result"]["contextId"]);
+    assert_eq!(task["history"].as_array().unwrap().len(), 2);
+
+    // A task waiting for input can be canceled
+    rpc(&app, "message/send", send("task-2", json!({"text": "Order it"}))).await;
+    let canceled = rpc(&app, "tasks/cancel", json!({"taskId": "task-2"})).await;
+    assert_eq!(canceled["result"]["status"]["state"], "Canceled");
+}
+
+/// A webhook that records what it receives, failing its first request
+#[derive(Clone, Default)]
+struct Receiver {
+    hits: Arc<Mutex<usize>>,
+    received: Arc<Mutex<Vec<(axum::http::HeaderMap, axum::body::Bytes)>>>,
+}
+
+impl Receiver {
+    async fn start(&self) -> String {
+        let receiver = self.clone();
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+                let receiver = receiver.clone();
+                async move {
+                    let mut hits = receiver.hits.lock().unwrap();
+                    *hits += 1;
+                    if *hits == 1 {
+                        return StatusCode::SERVICE_UNAVAILABLE;
+                    }
+                    receiver.received.lock().unwrap().push((headers, body));
+                    StatusCode::OK
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url
+    }
+
+    fn states(&self) -> Vec<Value> {
+        let received = self.received.lock().unwrap();
+        received
+            .iter()
+            .map(|(_, body)| {
+                serde_json::from_slice::<Value>(body).unwrap()["status"]["state"].clone()
+            })
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_a2a_push_notifications_delivered_to_webhook() {
+    let receiver = Receiver::default();
+    let url = receiver.start().await;
+    let notifier = PushNotifier::new()
+        .with_url_policy(|url| url.host_str() == Some("127.0.0.1"))
+        .with_signing_secret("webhook-secret")
+        .with_retry(3, Duration::from_millis(10));
+    let (app, gate, _) = gated_app_with(|config| config.with_push_notifications(notifier));
+
+    let request = Request::builder().uri("/.well-known/agent.json").body(Body::empty()).unwrap();
+    let body = app.clone().oneshot(request).await.unwrap().into_body().collect().await.unwrap();
+    let card: Value = serde_json::from_slice(&body.to_bytes()).unwrap();
+    assert_eq!(card["capabilities"]["pushNotifications"], true);
+
+    let mut params = user_message(Some("task-1"));
+    params["config"]["pushNotificationConfig"] = json!({"url": url, "token": "task-1-token"});
+    rpc(&app, "message/send", params).await;
+
+    let config = rpc(&app, "tasks/pushNotificationConfig/get", json!({"taskId": "task-1"})).await;
+    assert_eq!(config["result"]["pushNotificationConfig"]["url"], url.as_str());
+
+    gate.notify_one();
+    for _ in 0..200 {
+        if receiver.states().last() == Some(&json!("Completed")) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The rejected first delivery was retried, and every status arrived in order
+    assert_eq!(receiver.states(), vec!["Submitted", "Working", "Completed"]);
+    assert_eq!(*receiver.hits.lock().unwrap(), 4);
+    for (headers, body) in receiver.received.lock().unwrap().iter() {
+        let header = |name: &str| headers[name].to_str().unwrap().to_string();
+        assert_eq!(header(push::TOKEN_HEADER), "task-1-token");
+        assert!(push::verify_signature(
+            b"webhook-secret",
+            &header(push::TIMESTAMP_HEADER),
+            body,
+            &header(push::SIGNATURE_HEADER),
+        ));
+    }
+
+    // The webhook is forgotten once the final status has gone out
+    let mut config = json!(null);
+    for _ in 0..200 {
+        config = rpc(&app, "tasks/pushNotificationConfig/get", json!({"taskId": "task-1"})).await;
+        if config.get("error").is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(config.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_a2a_push_notification_config() {
+    let (app, _gate, _) =
+        gated_app_with(|config| config.with_push_notifications(PushNotifier::new()));
+    rpc(&app, "message/send", user_message(Some("task-1"))).await;
+
+    let config =
+        json!({"taskId": "task-1", "pushNotificationConfig": {"url": "https://example.com/hook"}});
+    let set = rpc(&app, "tasks/pushNotificationConfig/set", config.clone()).await;
+    assert_eq!(set["result"], config);
+    let get = rpc(&app, "tasks/pushNotificationConfig/get", json!({"taskId": "task-1"})).await;
+    assert_eq!(get["result"], config);
+
+    let unknown =
+        json!({"taskId": "task-2", "pushNotificationConfig": {"url": "https://example.com/hook"}});
+    let set = rpc(&app, "tasks/pushNotificationConfig/set", unknown).await;
+    assert_eq!(set["error"]["code"], -32001);
+
+    let invalid =
+        json!({"taskId": "task-1", "pushNotificationConfig": {"url": "file:///etc/passwd"}});
+    let set = rpc(&app, "tasks/pushNotificationConfig/set", invalid).await;
+    assert_eq!(set["error"]["code"], -32602);
+
+    // Without a notifier the server does not offer push notifications
+    let (app, _gate, _) = gated_app();
+    rpc(&app, "message/send", user_message(Some("task-1"))).await;
+    let set = rpc(&app, "tasks/pushNotificationConfig/set", config).await;
+    assert_eq!(set["error"]["code"], -32003);
+}
+
+/// Answers with what a memory search for "tea" finds
+struct MemoryAgent;
+
+#[async_trait]
+impl Agent for MemoryAgent {
+    fn name(&self) -> &str {
+        "memory_agent"
+    }
+
+    fn description(&self) -> &str {
+        "Reports its memories"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> AdkResult<EventStream> {
+        let memories = match ctx.memory() {
+            Some(memory) => memory.search("tea").await?,
+            None => Vec::new(),
+        };
+        let text: Vec<&str> =
+            memories.iter().filter_map(|entry| entry.content.parts.first()?.text()).collect();
+        let mut event = adk_core::Event::new(ctx.invocation_id());
+        event.author = "memory_agent".to_string();
+        event.llm_response.content =
+            Some(adk_core::Content::new("model").with_text(format!("[{}]", text.join(", "))));
+        event.llm_response.turn_complete = true;
+        Ok(Box::pin(futures::stream::iter([Ok(event)])))
+    }
+}
+
+#[tokio::test]
+async fn test_a2a_runs_see_only_the_callers_memories() {
+    let memory_service = Arc::new(InMemoryMemoryService::new());
+    let memory =
+        MemoryEntry::new("user", adk_core::Content::new("user").with_text("alice likes tea"));
+    memory_service.add("memory_agent", "alice", vec![memory]).await.unwrap();
+    let config = ServerConfig::new(
+        Arc::new(TestAgentLoader { agent: Arc::new(MemoryAgent) }),
+        Arc::new(InMemorySessionService::new()),
+    )
+    .with_memory_service(memory_service)
+    .with_auth(AuthConfig::new().with_api_key("alice-key", "alice").with_api_key("bob-key", "bob"));
+    let app = create_app_with_a2a(config, Some("http://localhost:8080"));
+
+    let answer = |key: &'static str| {
+        let app = app.clone();
+        async move {
+            let body = json!({
+                "jsonrpc": "2.0",
+                "method": "message/send",
+                "params": {"message": {"role": "user", "messageId": key, "parts": [{"text": "Hi"}]}},
+                "id": 1
+            });
+            let request = Request::builder()
+                .method("POST")
+                .uri("/a2a")
+                .header("Content-Type", "application/json")
+                .header("X-API-Key", key)
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let body = app.oneshot(request).await.unwrap().into_body().collect().await.unwrap();
+            let response: Value = serde_json::from_slice(&body.to_bytes()).unwrap();
+            response["result"]["artifacts"][0]["parts"][0]["text"].clone()
+        }
+    };
+
+    assert_eq!(answer("alice-key").await, "[alice likes tea]");
+    assert_eq!(answer("bob-key").await, "[]");
 }
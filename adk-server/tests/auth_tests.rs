@@ -0,0 +1,236 @@
+use adk_auth::sso::{ClaimsMapper, TokenClaims, TokenError, TokenValidator};
+use adk_auth::{AccessControl, AuditEvent, AuditSink, AuthError, Permission, Role};
+use adk_server::{AuthConfig, ServerConfig, create_app};
+use adk_session::InMemorySessionService;
+use adk_telemetry::{AdkSpanExporter, AdkSpanLayer};
+use async_trait::async_trait;
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+struct MockAgentLoader;
+
+#[async_trait]
+impl adk_core::AgentLoader for MockAgentLoader {
+    async fn load_agent(&self, _app_name: &str) -> adk_core::Result<Arc<dyn adk_core::Agent>> {
+        Err(adk_core::AdkError::Agent("not implemented".to_string()))
+    }
+
+    fn list_agents(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn root_agent(&self) -> Arc<dyn adk_core::Agent> {
+        panic!("MockAgentLoader has no root agent")
+    }
+}
+
+/// Accepts `token-<user>`, putting `carol` in the `writers` group
+struct MockValidator;
+
+#[async_trait]
+impl TokenValidator for MockValidator {
+    async fn validate(&self, token: &str) -> Result<TokenClaims, TokenError> {
+        let user = token.strip_prefix("token-").ok_or(TokenError::Expired)?;
+        let groups = if user == "carol" { vec!["writers".to_string()] } else { vec![] };
+        Ok(TokenClaims { sub: user.to_string(), groups, ..Default::default() })
+    }
+
+    fn issuer(&self) -> &str {
+        "https://issuer.example"
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingSink(Arc<Mutex<Vec<AuditEvent>>>);
+
+#[async_trait]
+impl AuditSink for RecordingSink {
+    async fn log(&self, event: AuditEvent) -> Result<(), AuthError> {
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+/// alice has an API key and the `editor` role; carol gets `writer` from her token's groups
+fn auth(sink: RecordingSink) -> AuthConfig {
+    AuthConfig::new()
+        .with_api_key("alice-key", "alice")
+        .with_token_validator(MockValidator)
+        .with_claims_mapper(ClaimsMapper::builder().map_group("writers", "writer").build())
+        .with_access_control(
+            AccessControl::builder()
+                .role(Role::new("editor").allow(Permission::AllAgents))
+                .role(Role::new("writer").allow(Permission::agent("blog")))
+                .assign("alice", "editor")
+                .build()
+                .unwrap(),
+        )
+        .with_audit_sink(sink)
+}
+
+fn app(auth: AuthConfig) -> Router {
+    let config =
+        ServerConfig::new(Arc::new(MockAgentLoader), Arc::new(InMemorySessionService::new()))
+            .with_auth(auth);
+    create_app(config)
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    credential: Option<&str>,
+    body: Option<Value>,
+) -> StatusCode {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(credential) = credential {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {credential}"));
+    }
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+        None => request.body(Body::empty()).unwrap(),
+    };
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    response.into_body().collect().await.unwrap();
+    status
+}
+
+#[tokio::test]
+async fn test_credentials_required() {
+    let app = app(AuthConfig::new().with_api_key("alice-key", "alice"));
+
+    let request =
+        Request::builder().uri("/api/apps/blog/users/alice/sessions").body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+    let uri = "/api/apps/blog/users/alice/sessions";
+    assert_eq!(send(&app, "GET", uri, Some("wrong-key"), None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(send(&app, "GET", uri, Some("alice-key"), None).await, StatusCode::OK);
+
+    let request =
+        Request::builder().uri(uri).header("x-api-key", "alice-key").body(Body::empty()).unwrap();
+    assert_eq!(app.clone().oneshot(request).await.unwrap().status(), StatusCode::OK);
+
+    assert_eq!(send(&app, "GET", "/api/health", None, None).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_caller_bound_to_user_id() {
+    let app = app(auth(RecordingSink::default()));
+
+    let own = "/api/apps/blog/users/alice/sessions";
+    let other = "/api/apps/blog/users/bob/sessions";
+    assert_eq!(send(&app, "GET", own, Some("alice-key"), None).await, StatusCode::OK);
+    assert_eq!(send(&app, "GET", other, Some("alice-key"), None).await, StatusCode::FORBIDDEN);
+    assert_eq!(
+        send(&app, "DELETE", "/api/sessions/blog/bob/s1", Some("alice-key"), None).await,
+        StatusCode::FORBIDDEN
+    );
+
+    let body = |user: &str| Some(json!({"appName": "blog", "userId": user, "sessionId": "s1"}));
+    assert_eq!(
+        send(&app, "POST", "/api/sessions", Some("alice-key"), body("bob")).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        send(&app, "POST", "/api/sessions", Some("alice-key"), body("alice")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        send(&app, "GET", "/api/sessions/blog/alice/s1", Some("alice-key"), None).await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn test_agent_permission_from_token_roles() {
+    let app = app(auth(RecordingSink::default()));
+
+    assert_eq!(
+        send(&app, "GET", "/api/apps/blog/users/carol/sessions", Some("token-carol"), None).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        send(&app, "GET", "/api/apps/wiki/users/carol/sessions", Some("token-carol"), None).await,
+        StatusCode::FORBIDDEN
+    );
+    // Authenticated, but without a role granting the agent
+    assert_eq!(
+        send(&app, "GET", "/api/apps/blog/users/dave/sessions", Some("token-dave"), None).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        send(&app, "GET", "/api/apps/blog/users/dave/sessions", Some("forged"), None).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_decisions_audited() {
+    let sink = RecordingSink::default();
+    let app = app(auth(sink.clone()));
+
+    send(&app, "GET", "/api/sessions/blog/carol/s1", Some("token-carol"), None).await;
+    send(&app, "GET", "/api/apps/wiki/users/carol/sessions", Some("token-carol"), None).await;
+    send(&app, "GET", "/api/apps/blog/users/bob/sessions", Some("token-carol"), None).await;
+
+    let events = sink.0.lock().unwrap().clone();
+    let summary: Vec<_> = events
+        .iter()
+        .map(|e| (json!(e.event_type), e.resource.as_str(), json!(e.outcome)))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (json!("agent_access"), "blog", json!("allowed")),
+            (json!("agent_access"), "wiki", json!("denied")),
+            (json!("permission_check"), "user:bob", json!("denied")),
+        ]
+    );
+    assert!(events.iter().all(|e| e.user == "carol"));
+    assert_eq!(events[0].session_id.as_deref(), Some("s1"));
+}
+
+/// A span from `user`'s session `session_id`, as the runner records it
+fn record_span(exporter: &Arc<AdkSpanExporter>, user: &str, session_id: &str, event_id: &str) {
+    let subscriber = tracing_subscriber::registry().with(AdkSpanLayer::new(exporter.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!(
+            "agent.execute",
+            "gcp.vertex.agent.session_id" = %session_id,
+            "gcp.vertex.agent.user_id" = %user,
+            "gcp.vertex.agent.event_id" = %event_id,
+        );
+    });
+}
+
+#[tokio::test]
+async fn test_traces_visible_only_to_session_owner() {
+    let exporter = Arc::new(AdkSpanExporter::new());
+    record_span(&exporter, "alice", "alice-session", "alice-event");
+    let auth = AuthConfig::new().with_api_key("alice-key", "alice").with_api_key("bob-key", "bob");
+    let config =
+        ServerConfig::new(Arc::new(MockAgentLoader), Arc::new(InMemorySessionService::new()))
+            .with_span_exporter(exporter)
+            .with_auth(auth);
+    let app = create_app(config);
+
+    let session = "/api/debug/trace/session/alice-session";
+    let event = "/api/debug/trace/alice-event";
+    assert_eq!(send(&app, "GET", session, Some("alice-key"), None).await, StatusCode::OK);
+    assert_eq!(send(&app, "GET", event, Some("alice-key"), None).await, StatusCode::OK);
+    assert_eq!(send(&app, "GET", session, Some("bob-key"), None).await, StatusCode::FORBIDDEN);
+    assert_eq!(send(&app, "GET", event, Some("bob-key"), None).await, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,166 @@
+use adk_graph::{
+    CompiledGraph, END, ExecutionConfig, GraphError, MemoryCheckpointer, NodeOutput, START, State,
+    StateGraph,
+};
+use adk_server::{ServerConfig, create_app};
+use adk_session::InMemorySessionService;
+use async_trait::async_trait;
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+struct MockAgentLoader;
+
+#[async_trait]
+impl adk_core::AgentLoader for MockAgentLoader {
+    async fn load_agent(&self, _app_name: &str) -> adk_core::Result<Arc<dyn adk_core::Agent>> {
+        Err(adk_core::AdkError::Agent("not implemented".to_string()))
+    }
+
+    fn list_agents(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn root_agent(&self) -> Arc<dyn adk_core::Agent> {
+        panic!("MockAgentLoader has no root agent")
+    }
+}
+
+/// draft -> publish, pausing before publish
+fn graph() -> Arc<CompiledGraph> {
+    let graph = StateGraph::with_channels(&["topic", "draft", "post"])
+        .add_node_fn("draft", |ctx| async move {
+            let topic = ctx.get("topic").and_then(|v| v.as_str()).unwrap_or_default();
+            Ok(NodeOutput::new().with_update("draft", json!(format!("post on {topic}"))))
+        })
+        .add_node_fn("publish", |ctx| async move {
+            let draft = ctx.get("draft").cloned().unwrap_or_default();
+            Ok(NodeOutput::new().with_update("post", draft))
+        })
+        .add_edge(START, "draft")
+        .add_edge("draft", "publish")
+        .add_edge("publish", END)
+        .compile()
+        .unwrap()
+        .with_checkpointer(MemoryCheckpointer::new())
+        .with_interrupt_before(&["publish"]);
+    Arc::new(graph)
+}
+
+/// Runs the graph on `thread_id` until it pauses, returning the paused checkpoint's ID
+async fn pause(graph: &CompiledGraph, thread_id: &str) -> String {
+    let input = State::from([("topic".to_string(), json!("tides"))]);
+    match graph.invoke(input, ExecutionConfig::new(thread_id)).await {
+        Err(GraphError::Interrupted(interrupted)) => interrupted.checkpoint_id,
+        other => panic!("expected an interrupt, got {other:?}"),
+    }
+}
+
+fn app(graph: Arc<CompiledGraph>) -> Router {
+    let config =
+        ServerConfig::new(Arc::new(MockAgentLoader), Arc::new(InMemorySessionService::new()))
+            .with_graph("blog", graph);
+    create_app(config)
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+        None => request.body(Body::empty()).unwrap(),
+    };
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_list_and_get_checkpoints() {
+    let graph = graph();
+    let paused = pause(&graph, "session-1").await;
+    let app = app(graph);
+
+    let (status, history) =
+        send(&app, "GET", "/api/apps/blog/graph/threads/session-1/checkpoints", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(history[0]["checkpointId"], json!(paused));
+    assert_eq!(history[0]["pendingNodes"], json!(["publish"]));
+    assert_eq!(history[0]["state"]["draft"], json!("post on tides"));
+    assert_eq!(history[0]["parentId"], history[1]["checkpointId"]);
+
+    let uri = format!("/api/apps/blog/graph/checkpoints/{paused}");
+    let (status, checkpoint) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(checkpoint["threadId"], json!("session-1"));
+
+    let (status, _) = send(&app, "GET", "/api/apps/blog/graph/checkpoints/missing", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) =
+        send(&app, "GET", "/api/apps/unknown/graph/threads/session-1/checkpoints", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_fork_edit_and_replay() {
+    let graph = graph();
+    let paused = pause(&graph, "session-1").await;
+    let app = app(graph.clone());
+
+    let uri = format!("/api/apps/blog/graph/checkpoints/{paused}/fork");
+    let (status, fork) = send(&app, "POST", &uri, Some(json!({"threadId": "session-2"}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fork["threadId"], json!("session-2"));
+    assert_eq!(fork["parentId"], json!(paused));
+
+    let edit = json!({"values": {"draft": "edited post"}});
+    let (status, edited) =
+        send(&app, "POST", "/api/apps/blog/graph/threads/session-2/state", Some(edit)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(edited["pendingNodes"], json!(["publish"]));
+
+    let replay = json!({"checkpointId": edited["checkpointId"]});
+    let (status, result) =
+        send(&app, "POST", "/api/apps/blog/graph/threads/session-2/replay", Some(replay)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(result["state"]["post"], json!("edited post"));
+    assert_eq!(result["interrupt"], Value::Null);
+
+    // The original session is untouched
+    let original = graph.get_state("session-1").await.unwrap().unwrap();
+    assert_eq!(original.get("post"), None);
+}
+
+#[tokio::test]
+async fn test_update_state_as_node_and_diff() {
+    let graph = graph();
+    let paused = pause(&graph, "session-1").await;
+    let app = app(graph);
+
+    let edit = json!({"values": {"post": "written by hand"}, "asNode": "publish"});
+    let (status, edited) =
+        send(&app, "POST", "/api/apps/blog/graph/threads/session-1/state", Some(edit)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(edited["pendingNodes"], json!([]));
+
+    let uri = format!(
+        "/api/apps/blog/graph/diff?from={paused}&to={}",
+        edited["checkpointId"].as_str().unwrap()
+    );
+    let (status, diff) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(diff["added"], json!({"post": "written by hand"}));
+    assert_eq!(diff["changed"], json!({}));
+
+    let edit = json!({"values": {}, "asNode": "missing"});
+    let (status, _) =
+        send(&app, "POST", "/api/apps/blog/graph/threads/session-1/state", Some(edit)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
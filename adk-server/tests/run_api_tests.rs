@@ -0,0 +1,435 @@
+use adk_core::{Agent, Content, EventStream, InvocationContext, Part, StreamingMode};
+use adk_memory::{InMemoryMemoryService, MemoryEntry, MemoryService};
+use adk_server::{ServerConfig, create_app};
+use adk_session::{CreateRequest, InMemorySessionService, SessionService};
+use async_trait::async_trait;
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use futures::StreamExt;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower::ServiceExt;
+
+/// What the agent saw of its invocation
+#[derive(Clone, Debug)]
+struct Seen {
+    content: Content,
+    streaming_mode: StreamingMode,
+    topic: Option<Value>,
+    /// What a memory search for "tea" found, if the agent had memory
+    memories: Option<Vec<String>>,
+}
+
+#[derive(Clone, Default)]
+struct RecordingAgent {
+    seen: Arc<Mutex<Option<Seen>>>,
+}
+
+#[async_trait]
+impl Agent for RecordingAgent {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn description(&self) -> &str {
+        "Records its invocation"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> adk_core::Result<EventStream> {
+        let memories = match ctx.memory() {
+            Some(memory) => Some(
+                memory
+                    .search("tea")
+                    .await?
+                    .iter()
+                    .filter_map(|entry| entry.content.parts.first()?.text().map(str::to_string))
+                    .collect(),
+            ),
+            None => None,
+        };
+        *self.seen.lock().unwrap() = Some(Seen {
+            content: ctx.user_content().clone(),
+            streaming_mode: ctx.run_config().streaming_mode,
+            topic: ctx.session().state().get("topic"),
+            memories,
+        });
+        let mut event = adk_core::Event::new(ctx.invocation_id());
+        event.author = "recorder".to_string();
+        event.llm_response.content = Some(Content::new("model").with_text("ok"));
+        Ok(Box::pin(futures::stream::iter([Ok(event)])))
+    }
+}
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Answers once, then hangs until its stream is dropped
+#[derive(Clone, Default)]
+struct HangingAgent {
+    dropped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Agent for HangingAgent {
+    fn name(&self) -> &str {
+        "hanging"
+    }
+
+    fn description(&self) -> &str {
+        "Never finishes"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, ctx: Arc<dyn InvocationContext>) -> adk_core::Result<EventStream> {
+        let flag = DropFlag(self.dropped.clone());
+        let mut event = adk_core::Event::new(ctx.invocation_id());
+        event.author = "hanging".to_string();
+        event.llm_response.content = Some(Content::new("model").with_text("Working on it"));
+        let events = futures::stream::iter([Ok(event)]).chain(futures::stream::pending()).map(
+            move |event| {
+                let _ = &flag;
+                event
+            },
+        );
+        Ok(Box::pin(events))
+    }
+}
+
+struct TestAgentLoader {
+    agent: Arc<dyn Agent>,
+}
+
+#[async_trait]
+impl adk_core::AgentLoader for TestAgentLoader {
+    async fn load_agent(&self, _app_name: &str) -> adk_core::Result<Arc<dyn Agent>> {
+        Ok(self.agent.clone())
+    }
+
+    fn list_agents(&self) -> Vec<String> {
+        vec![self.agent.name().to_string()]
+    }
+
+    fn root_agent(&self) -> Arc<dyn Agent> {
+        self.agent.clone()
+    }
+}
+
+async fn app() -> (Router, RecordingAgent) {
+    let agent = RecordingAgent::default();
+    let (app, _) = app_with(Arc::new(agent.clone())).await;
+    (app, agent)
+}
+
+/// Sessions `s1` for alice and `b1` for bob, and a memory of alice's
+async fn app_with(agent: Arc<dyn Agent>) -> (Router, Arc<InMemorySessionService>) {
+    let session_service = Arc::new(InMemorySessionService::new());
+    for (user_id, session_id) in [("alice", "s1"), ("bob", "b1")] {
+        session_service
+            .create(CreateRequest {
+                app_name: agent.name().to_string(),
+                user_id: user_id.to_string(),
+                session_id: Some(session_id.to_string()),
+                state: Default::default(),
+            })
+            .await
+            .unwrap();
+    }
+    let memory_service = Arc::new(InMemoryMemoryService::new());
+    let memory = MemoryEntry::new("user", Content::new("user").with_text("alice likes tea"));
+    memory_service.add(agent.name(), "alice", vec![memory]).await.unwrap();
+    let config = ServerConfig::new(Arc::new(TestAgentLoader { agent }), session_service.clone())
+        .with_memory_service(memory_service);
+    (create_app(config), session_service)
+}
+
+async fn send(app: &Router, uri: &str, body: Value) -> axum::response::Response {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap()
+}
+
+async fn post(app: &Router, uri: &str, body: Value) -> StatusCode {
+    let response = send(app, uri, body).await;
+    let status = response.status();
+    response.into_body().collect().await.unwrap();
+    status
+}
+
+/// The next event on an SSE response, or `None` once it ends
+async fn next_event(body: &mut Body) -> Option<Value> {
+    loop {
+        let frame = body.frame().await?.unwrap();
+        let Ok(data) = frame.into_data() else { continue };
+        let text = String::from_utf8(data.to_vec()).unwrap();
+        if let Some(json) = text.strip_prefix("data: ") {
+            return Some(serde_json::from_str(json.trim()).unwrap());
+        }
+    }
+}
+
+async fn recorded_events(
+    sessions: &InMemorySessionService,
+    app_name: &str,
+) -> Vec<adk_core::Event> {
+    let session = sessions
+        .get(adk_session::GetRequest {
+            app_name: app_name.to_string(),
+            user_id: "alice".to_string(),
+            session_id: "s1".to_string(),
+            num_recent_events: None,
+            after: None,
+        })
+        .await
+        .unwrap();
+    session.events().all()
+}
+
+#[tokio::test]
+async fn test_run_with_multimodal_content_and_options() {
+    let (app, agent) = app().await;
+
+    let body = json!({
+        "new_message": {
+            "role": "user",
+            "parts": [
+                {"text": "What is in these?"},
+                {"inlineData": {"mimeType": "image/png", "data": "iVBORw=="}},
+                {"fileData": {"mimeType": "application/pdf", "fileUri": "gs://bucket/doc.pdf"}}
+            ]
+        },
+        "streaming": false,
+        "state_delta": {"topic": "images"}
+    });
+    assert_eq!(post(&app, "/api/run/recorder/alice/s1", body).await, StatusCode::OK);
+
+    let seen = agent.seen.lock().unwrap().clone().unwrap();
+    assert_eq!(
+        seen.content.parts,
+        vec![
+            Part::Text { text: "What is in these?".to_string() },
+            Part::InlineData {
+                mime_type: "image/png".to_string(),
+                data: vec![0x89, b'P', b'N', b'G']
+            },
+            Part::FileData {
+                mime_type: "application/pdf".to_string(),
+                file_uri: "gs://bucket/doc.pdf".to_string()
+            },
+        ]
+    );
+    assert_eq!(seen.streaming_mode, StreamingMode::None);
+    assert_eq!(seen.topic, Some(json!("images")));
+    assert_eq!(seen.memories, Some(vec!["alice likes tea".to_string()]));
+
+    // Plain text messages still work, streaming by default
+    let body = json!({"new_message": "Hello"});
+    assert_eq!(post(&app, "/api/run/recorder/alice/s1", body).await, StatusCode::OK);
+    let seen = agent.seen.lock().unwrap().clone().unwrap();
+    assert_eq!(seen.content.role, "user");
+    assert_eq!(seen.content.parts, vec![Part::Text { text: "Hello".to_string() }]);
+    assert_eq!(seen.streaming_mode, StreamingMode::SSE);
+}
+
+#[tokio::test]
+async fn test_run_only_sees_own_memories() {
+    let (app, agent) = app().await;
+
+    let body = json!({"new_message": "What do I like?"});
+    assert_eq!(post(&app, "/api/run/recorder/bob/b1", body).await, StatusCode::OK);
+    let seen = agent.seen.lock().unwrap().clone().unwrap();
+    assert_eq!(seen.memories, Some(vec![]));
+}
+
+#[tokio::test]
+async fn test_run_sse_function_response() {
+    let (app, agent) = app().await;
+
+    let body = json!({
+        "appName": "recorder",
+        "userId": "alice",
+        "sessionId": "s2",
+        "newMessage": {"role": "user", "parts": [{"functionResponse": {
+            "name": "approve_order", "id": "call-1", "response": {"approved": true}
+        }}]},
+        "stateDelta": {"topic": "orders"}
+    });
+    assert_eq!(post(&app, "/api/run_sse", body).await, StatusCode::OK);
+
+    let seen = agent.seen.lock().unwrap().clone().unwrap();
+    assert_eq!(
+        seen.content.parts,
+        vec![Part::FunctionResponse {
+            function_response: adk_core::FunctionResponseData {
+                name: "approve_order".to_string(),
+                response: json!({"approved": true}),
+            },
+            id: Some("call-1".to_string()),
+        }]
+    );
+    assert_eq!(seen.topic, Some(json!("orders")));
+}
+
+#[tokio::test]
+async fn test_run_rejects_invalid_input() {
+    let (app, agent) = app().await;
+    let uri = "/api/run/recorder/alice/s1";
+    let message = |parts: Value| json!({"new_message": {"parts": parts}});
+
+    let bad_base64 = message(json!([{"inlineData": {"mimeType": "image/png", "data": "%%%"}}]));
+    assert_eq!(post(&app, uri, bad_base64).await, StatusCode::BAD_REQUEST);
+
+    let two_fields =
+        message(json!([{"text": "a", "fileData": {"mimeType": "a/b", "fileUri": "x"}}]));
+    assert_eq!(post(&app, uri, two_fields).await, StatusCode::BAD_REQUEST);
+
+    assert_eq!(post(&app, uri, message(json!([]))).await, StatusCode::BAD_REQUEST);
+
+    let model_role = json!({"new_message": {"role": "model", "parts": [{"text": "Sure"}]}});
+    assert_eq!(post(&app, uri, model_role).await, StatusCode::BAD_REQUEST);
+
+    let bad_delta = json!({"new_message": "Hi", "state_delta": ["topic"]});
+    assert_eq!(post(&app, uri, bad_delta).await, StatusCode::BAD_REQUEST);
+
+    assert!(agent.seen.lock().unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_cancel_in_flight_invocation() {
+    let agent = HangingAgent::default();
+    let (app, sessions) = app_with(Arc::new(agent.clone())).await;
+
+    let response = send(&app, "/api/run/hanging/alice/s1", json!({"new_message": "Hi"})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let invocation_id = response.headers()["x-invocation-id"].to_str().unwrap().to_string();
+    let mut body = response.into_body();
+    let first = next_event(&mut body).await.unwrap();
+    assert_eq!(first["invocation_id"], invocation_id.as_str());
+
+    // Only the invocation's own session can cancel it
+    let other =
+        format!("/api/apps/hanging/users/alice/sessions/s2/invocations/{invocation_id}/cancel");
+    assert_eq!(post(&app, &other, json!({})).await, StatusCode::NOT_FOUND);
+
+    let cancel =
+        format!("/api/apps/hanging/users/alice/sessions/s1/invocations/{invocation_id}/cancel");
+    assert_eq!(post(&app, &cancel, json!({})).await, StatusCode::NO_CONTENT);
+    assert!(agent.dropped.load(Ordering::SeqCst));
+
+    // The client sees the cancellation, then the stream ends
+    let cancelled = next_event(&mut body).await.unwrap();
+    assert_eq!(cancelled["invocation_id"], invocation_id.as_str());
+    assert_eq!(cancelled["error_code"], adk_runner::CANCELLED_ERROR_CODE);
+    assert!(next_event(&mut body).await.is_none());
+
+    let events = recorded_events(&sessions, "hanging").await;
+    let last = events.last().unwrap();
+    assert_eq!(last.invocation_id, invocation_id);
+    assert_eq!(last.llm_response.error_code.as_deref(), Some(adk_runner::CANCELLED_ERROR_CODE));
+
+    // It has stopped, so there is nothing left to cancel
+    assert_eq!(post(&app, &cancel, json!({})).await, StatusCode::NOT_FOUND);
+}
+
+/// Never produces an event, like an agent waiting on its first model call
+#[derive(Clone, Default)]
+struct SilentAgent {
+    dropped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Agent for SilentAgent {
+    fn name(&self) -> &str {
+        "silent"
+    }
+
+    fn description(&self) -> &str {
+        "Never answers"
+    }
+
+    fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+        &[]
+    }
+
+    async fn run(&self, _ctx: Arc<dyn InvocationContext>) -> adk_core::Result<EventStream> {
+        let flag = DropFlag(self.dropped.clone());
+        let events = futures::stream::pending().map(move |event| {
+            let _ = &flag;
+            event
+        });
+        Ok(Box::pin(events))
+    }
+}
+
+#[tokio::test]
+async fn test_cancel_before_first_event() {
+    let agent = SilentAgent::default();
+    let (app, sessions) = app_with(Arc::new(agent.clone())).await;
+
+    let response = send(&app, "/api/run/silent/alice/s1", json!({"new_message": "Hi"})).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let invocation_id = response.headers()["x-invocation-id"].to_str().unwrap().to_string();
+    let mut body = response.into_body();
+
+    let cancel =
+        format!("/api/apps/silent/users/alice/sessions/s1/invocations/{invocation_id}/cancel");
+    assert_eq!(post(&app, &cancel, json!({})).await, StatusCode::NO_CONTENT);
+    assert!(agent.dropped.load(Ordering::SeqCst));
+
+    // The cancellation is the only event the client sees
+    let cancelled = next_event(&mut body).await.unwrap();
+    assert_eq!(cancelled["invocation_id"], invocation_id.as_str());
+    assert_eq!(cancelled["error_code"], adk_runner::CANCELLED_ERROR_CODE);
+    assert!(next_event(&mut body).await.is_none());
+
+    let events = recorded_events(&sessions, "silent").await;
+    assert_eq!(events.last().unwrap().invocation_id, invocation_id);
+}
+
+#[tokio::test]
+async fn test_client_disconnect_cancels_invocation() {
+    let agent = HangingAgent::default();
+    let (app, sessions) = app_with(Arc::new(agent.clone())).await;
+
+    let response = send(&app, "/api/run/hanging/alice/s1", json!({"new_message": "Hi"})).await;
+    let mut body = response.into_body();
+    next_event(&mut body).await.unwrap();
+    drop(body);
+
+    let cancelled = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let events = recorded_events(&sessions, "hanging").await;
+            let last = events.last().unwrap();
+            if last.llm_response.error_code.is_some() {
+                return last.clone();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(
+        cancelled.llm_response.error_code.as_deref(),
+        Some(adk_runner::CANCELLED_ERROR_CODE)
+    );
+    assert!(agent.dropped.load(Ordering::SeqCst));
+}
@@ -0,0 +1,43 @@
+CREATE TABLE sessions (
+    app_name VARCHAR(128) NOT NULL,
+    user_id VARCHAR(128) NOT NULL,
+    session_id VARCHAR(128) NOT NULL,
+    state JSON NOT NULL,
+    created_at DATETIME(6) NOT NULL,
+    updated_at DATETIME(6) NOT NULL,
+    PRIMARY KEY (app_name, user_id, session_id),
+    INDEX idx_sessions_session_id (session_id)
+);
+
+-- Keyed by insertion order: streamed chunks of one response share an event id.
+CREATE TABLE events (
+    seq BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    id VARCHAR(128) NOT NULL,
+    app_name VARCHAR(128) NOT NULL,
+    user_id VARCHAR(128) NOT NULL,
+    session_id VARCHAR(128) NOT NULL,
+    invocation_id VARCHAR(128) NOT NULL,
+    branch TEXT NOT NULL,
+    author VARCHAR(255) NOT NULL,
+    timestamp DATETIME(6) NOT NULL,
+    llm_response JSON NOT NULL,
+    actions JSON NOT NULL,
+    long_running_tool_ids JSON NOT NULL,
+    INDEX idx_events_session (app_name, user_id, session_id, seq),
+    FOREIGN KEY (app_name, user_id, session_id)
+        REFERENCES sessions (app_name, user_id, session_id) ON DELETE CASCADE
+);
+
+CREATE TABLE app_states (
+    app_name VARCHAR(128) NOT NULL PRIMARY KEY,
+    state JSON NOT NULL,
+    updated_at DATETIME(6) NOT NULL
+);
+
+CREATE TABLE user_states (
+    app_name VARCHAR(128) NOT NULL,
+    user_id VARCHAR(128) NOT NULL,
+    state JSON NOT NULL,
+    updated_at DATETIME(6) NOT NULL,
+    PRIMARY KEY (app_name, user_id)
+);
@@ -0,0 +1,9 @@
+-- Optimistic concurrency token: the number of events appended to the session.
+ALTER TABLE sessions ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
+
+UPDATE sessions s SET version = (
+    SELECT COUNT(*) FROM events e
+    WHERE e.app_name = s.app_name
+      AND e.user_id = s.user_id
+      AND e.session_id = s.session_id
+);
@@ -0,0 +1,45 @@
+CREATE TABLE sessions (
+    app_name TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    state JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (app_name, user_id, session_id)
+);
+
+CREATE INDEX idx_sessions_session_id ON sessions (session_id);
+
+-- Keyed by insertion order: streamed chunks of one response share an event id.
+CREATE TABLE events (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    invocation_id TEXT NOT NULL,
+    branch TEXT NOT NULL,
+    author TEXT NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL,
+    llm_response JSONB NOT NULL,
+    actions JSONB NOT NULL,
+    long_running_tool_ids JSONB NOT NULL,
+    FOREIGN KEY (app_name, user_id, session_id)
+        REFERENCES sessions (app_name, user_id, session_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_events_session ON events (app_name, user_id, session_id, seq);
+
+CREATE TABLE app_states (
+    app_name TEXT PRIMARY KEY,
+    state JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE user_states (
+    app_name TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    state JSONB NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (app_name, user_id)
+);
@@ -0,0 +1,9 @@
+-- Optimistic concurrency token: the number of events appended to the session.
+ALTER TABLE sessions ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
+
+UPDATE sessions SET version = (
+    SELECT COUNT(*) FROM events
+    WHERE events.app_name = sessions.app_name
+      AND events.user_id = sessions.user_id
+      AND events.session_id = sessions.session_id
+);
@@ -0,0 +1,38 @@
+-- Key events by insertion order. Streamed chunks of one response share an event id,
+-- so the id alone cannot be part of the key. Older releases stored events without
+-- their app and user; fill those in from the owning session and drop orphans.
+CREATE TABLE events_new (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL,
+    app_name TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    invocation_id TEXT NOT NULL,
+    branch TEXT NOT NULL,
+    author TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    llm_response TEXT NOT NULL,
+    actions TEXT NOT NULL,
+    long_running_tool_ids TEXT NOT NULL,
+    FOREIGN KEY (app_name, user_id, session_id) REFERENCES sessions(app_name, user_id, session_id)
+        ON DELETE CASCADE
+);
+
+INSERT INTO events_new (id, app_name, user_id, session_id, invocation_id, branch, author,
+                        timestamp, llm_response, actions, long_running_tool_ids)
+SELECT e.id,
+       COALESCE(NULLIF(e.app_name, ''),
+                (SELECT s.app_name FROM sessions s WHERE s.session_id = e.session_id LIMIT 1), ''),
+       COALESCE(NULLIF(e.user_id, ''),
+                (SELECT s.user_id FROM sessions s WHERE s.session_id = e.session_id LIMIT 1), ''),
+       e.session_id, e.invocation_id, e.branch, e.author,
+       e.timestamp, e.llm_response, e.actions, e.long_running_tool_ids
+FROM events e
+WHERE EXISTS (SELECT 1 FROM sessions s WHERE s.session_id = e.session_id)
+ORDER BY e.timestamp;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+
+CREATE INDEX idx_events_session ON events (app_name, user_id, session_id, seq);
+CREATE INDEX idx_sessions_session_id ON sessions (session_id);
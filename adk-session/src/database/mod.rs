@@ -0,0 +1,226 @@
+//! SQL-backed session services.
+//!
+//! Each backend lives behind its own cargo feature:
+//!
+//! | Feature | Service | Database |
+//! |---------|---------|----------|
+//! | `database` | [`DatabaseSessionService`] | SQLite |
+//! | `postgres` | [`PostgresSessionService`] | PostgreSQL |
+//! | `mysql` | [`MySqlSessionService`] | MySQL 8 |
+//!
+//! All backends scope state the same way as [`InMemorySessionService`](crate::InMemorySessionService):
+//! `app:` keys are shared by every session of an app, `user:` keys by every session of a
+//! user, `temp:` keys are never persisted and the rest belongs to the session.
+//!
+//! Each session row counts its events in a `version` column. Appends lock the row, so
+//! `append_event_if_version` compares and bumps the version atomically.
+//!
+//! The schema is versioned. `migrate()` applies the migrations a database has not seen yet
+//! and records them in the `adk_session_migrations` table, so it is safe to call on every
+//! startup and from several replicas at once.
+
+#[cfg(feature = "mysql")]
+mod mysql;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "database")]
+mod sqlite;
+
+#[cfg(feature = "mysql")]
+pub use mysql::MySqlSessionService;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresSessionService;
+#[cfg(feature = "database")]
+pub use sqlite::DatabaseSessionService;
+
+use crate::{Event, Events, KEY_PREFIX_APP, KEY_PREFIX_TEMP, KEY_PREFIX_USER, Session, State};
+use adk_core::{AdkError, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) type StateMap = HashMap<String, Value>;
+
+/// Table recording which schema migrations a database has applied.
+pub(crate) const MIGRATIONS_TABLE: &str = "adk_session_migrations";
+
+/// One versioned schema change.
+pub(crate) struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Migrations in `migrations` whose version is not in `applied`, in order.
+pub(crate) fn pending<'a>(
+    migrations: &'a [Migration],
+    applied: &'a [i64],
+) -> impl Iterator<Item = &'a Migration> {
+    migrations.iter().filter(move |m| !applied.contains(&m.version))
+}
+
+/// Maps a driver error to a session error, prefixed with what was being attempted.
+pub(crate) fn db_error(action: &'static str) -> impl Fn(sqlx::Error) -> AdkError {
+    move |e| AdkError::Session(format!("{} failed: {}", action, e))
+}
+
+pub(crate) fn serialize_error(e: serde_json::Error) -> AdkError {
+    AdkError::Session(format!("serialize failed: {}", e))
+}
+
+/// Splits a state delta into its app, user and session scopes, stripping the scope
+/// prefixes and dropping `temp:` keys.
+pub(crate) fn extract_state_deltas(delta: &StateMap) -> (StateMap, StateMap, StateMap) {
+    let mut app_delta = StateMap::new();
+    let mut user_delta = StateMap::new();
+    let mut session_delta = StateMap::new();
+
+    for (key, value) in delta {
+        if let Some(clean_key) = key.strip_prefix(KEY_PREFIX_APP) {
+            app_delta.insert(clean_key.to_string(), value.clone());
+        } else if let Some(clean_key) = key.strip_prefix(KEY_PREFIX_USER) {
+            user_delta.insert(clean_key.to_string(), value.clone());
+        } else if !key.starts_with(KEY_PREFIX_TEMP) {
+            session_delta.insert(key.clone(), value.clone());
+        }
+    }
+
+    (app_delta, user_delta, session_delta)
+}
+
+/// Combines the three scopes into the state a session sees, restoring the prefixes.
+pub(crate) fn merge_states(app: &StateMap, user: &StateMap, session: &StateMap) -> StateMap {
+    let mut merged = session.clone();
+    for (k, v) in app {
+        merged.insert(format!("{}{}", KEY_PREFIX_APP, k), v.clone());
+    }
+    for (k, v) in user {
+        merged.insert(format!("{}{}", KEY_PREFIX_USER, k), v.clone());
+    }
+    merged
+}
+
+/// Event columns as JSON values, in the order `llm_response`, `actions`,
+/// `long_running_tool_ids`.
+pub(crate) fn event_json(event: &Event) -> Result<(Value, Value, Value)> {
+    Ok((
+        serde_json::to_value(&event.llm_response).map_err(serialize_error)?,
+        serde_json::to_value(&event.actions).map_err(serialize_error)?,
+        serde_json::to_value(&event.long_running_tool_ids).map_err(serialize_error)?,
+    ))
+}
+
+/// Rebuilds a stored event. Returns `None` if the stored JSON no longer matches the
+/// event types, so one bad row does not make the whole session unreadable.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn event_from_columns(
+    id: String,
+    invocation_id: String,
+    branch: String,
+    author: String,
+    timestamp: DateTime<Utc>,
+    llm_response: Value,
+    actions: Value,
+    long_running_tool_ids: Value,
+) -> Option<Event> {
+    Some(Event {
+        id,
+        timestamp,
+        invocation_id_camel: invocation_id.clone(),
+        invocation_id,
+        branch,
+        author,
+        llm_request: None,
+        llm_response: serde_json::from_value(llm_response).ok()?,
+        actions: serde_json::from_value(actions).ok()?,
+        long_running_tool_ids: serde_json::from_value(long_running_tool_ids).ok()?,
+        gcp_llm_request: None,
+        gcp_llm_response: None,
+        usage_report: None,
+    })
+}
+
+/// Applies the `num_recent_events` and `after` filters of a get request.
+pub(crate) fn filter_events(
+    mut events: Vec<Event>,
+    num_recent_events: Option<usize>,
+    after: Option<DateTime<Utc>>,
+) -> Vec<Event> {
+    if let Some(num) = num_recent_events {
+        let start = events.len().saturating_sub(num);
+        events = events.split_off(start);
+    }
+    if let Some(after) = after {
+        events.retain(|e| e.timestamp >= after);
+    }
+    events
+}
+
+/// Session snapshot returned by the SQL backends.
+pub(crate) struct DatabaseSession {
+    pub app_name: String,
+    pub user_id: String,
+    pub session_id: String,
+    pub state: StateMap,
+    pub events: Vec<Event>,
+    pub updated_at: DateTime<Utc>,
+    pub version: u64,
+}
+
+impl Session for DatabaseSession {
+    fn id(&self) -> &str {
+        &self.session_id
+    }
+
+    fn app_name(&self) -> &str {
+        &self.app_name
+    }
+
+    fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    fn state(&self) -> &dyn State {
+        self
+    }
+
+    fn events(&self) -> &dyn Events {
+        self
+    }
+
+    fn last_update_time(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+impl State for DatabaseSession {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.state.get(key).cloned()
+    }
+
+    fn set(&mut self, key: String, value: Value) {
+        self.state.insert(key, value);
+    }
+
+    fn all(&self) -> HashMap<String, Value> {
+        self.state.clone()
+    }
+}
+
+impl Events for DatabaseSession {
+    fn all(&self) -> Vec<Event> {
+        self.events.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    fn at(&self, index: usize) -> Option<&Event> {
+        self.events.get(index)
+    }
+}
@@ -0,0 +1,400 @@
+use super::{
+    DatabaseSession, MIGRATIONS_TABLE, Migration, StateMap, db_error, event_from_columns,
+    event_json, extract_state_deltas, filter_events, merge_states, pending, serialize_error,
+};
+use crate::service::{SessionKey, check_version};
+use crate::{
+    CreateRequest, DeleteRequest, Event, GetRequest, KEY_PREFIX_TEMP, ListRequest, Session,
+    SessionService,
+};
+use adk_core::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{Row, SqliteConnection, sqlite::SqlitePool};
+use uuid::Uuid;
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial",
+        sql: include_str!("../../migrations/sqlite/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "event sequence",
+        sql: include_str!("../../migrations/sqlite/0002_event_sequence.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "session version",
+        sql: include_str!("../../migrations/sqlite/0003_session_version.sql"),
+    },
+];
+
+/// SQLite-backed [`SessionService`].
+pub struct DatabaseSessionService {
+    pool: SqlitePool,
+}
+
+impl DatabaseSessionService {
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = SqlitePool::connect(database_url).await.map_err(|e| {
+            adk_core::AdkError::Session(format!("database connection failed: {}", e))
+        })?;
+        Ok(Self { pool })
+    }
+
+    /// Apply schema migrations this database has not seen yet.
+    pub async fn migrate(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error("migration"))?;
+
+        sqlx::query(&format!(
+            "CREATE TABLE IF NOT EXISTS {MIGRATIONS_TABLE} (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            )"
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error("migration"))?;
+
+        let applied: Vec<i64> =
+            sqlx::query_scalar(&format!("SELECT version FROM {MIGRATIONS_TABLE}"))
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(db_error("migration"))?;
+
+        for migration in pending(MIGRATIONS, &applied) {
+            sqlx::raw_sql(migration.sql).execute(&mut *tx).await.map_err(db_error("migration"))?;
+            sqlx::query(&format!(
+                "INSERT INTO {MIGRATIONS_TABLE} (version, description, applied_at) VALUES (?, ?, ?)"
+            ))
+            .bind(migration.version)
+            .bind(migration.description)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error("migration"))?;
+        }
+
+        tx.commit().await.map_err(db_error("migration"))
+    }
+
+    async fn app_state(conn: &mut SqliteConnection, app_name: &str) -> Result<StateMap> {
+        Ok(sqlx::query("SELECT state FROM app_states WHERE app_name = ?")
+            .bind(app_name)
+            .fetch_optional(conn)
+            .await
+            .map_err(db_error("query"))?
+            .map(|row| serde_json::from_str(row.get("state")).unwrap_or_default())
+            .unwrap_or_default())
+    }
+
+    async fn user_state(
+        conn: &mut SqliteConnection,
+        app_name: &str,
+        user_id: &str,
+    ) -> Result<StateMap> {
+        Ok(sqlx::query("SELECT state FROM user_states WHERE app_name = ? AND user_id = ?")
+            .bind(app_name)
+            .bind(user_id)
+            .fetch_optional(conn)
+            .await
+            .map_err(db_error("query"))?
+            .map(|row| serde_json::from_str(row.get("state")).unwrap_or_default())
+            .unwrap_or_default())
+    }
+
+    /// Merge `delta` into the stored app state and return the result.
+    async fn update_app_state(
+        conn: &mut SqliteConnection,
+        app_name: &str,
+        delta: StateMap,
+        now: DateTime<Utc>,
+    ) -> Result<StateMap> {
+        let mut state = Self::app_state(conn, app_name).await?;
+        if delta.is_empty() {
+            return Ok(state);
+        }
+        state.extend(delta);
+
+        sqlx::query(
+            "INSERT OR REPLACE INTO app_states (app_name, state, updated_at) VALUES (?, ?, ?)",
+        )
+        .bind(app_name)
+        .bind(serde_json::to_string(&state).map_err(serialize_error)?)
+        .bind(now.to_rfc3339())
+        .execute(conn)
+        .await
+        .map_err(db_error("insert"))?;
+        Ok(state)
+    }
+
+    /// Merge `delta` into the stored user state and return the result.
+    async fn update_user_state(
+        conn: &mut SqliteConnection,
+        app_name: &str,
+        user_id: &str,
+        delta: StateMap,
+        now: DateTime<Utc>,
+    ) -> Result<StateMap> {
+        let mut state = Self::user_state(conn, app_name, user_id).await?;
+        if delta.is_empty() {
+            return Ok(state);
+        }
+        state.extend(delta);
+
+        sqlx::query("INSERT OR REPLACE INTO user_states (app_name, user_id, state, updated_at) VALUES (?, ?, ?, ?)")
+            .bind(app_name)
+            .bind(user_id)
+            .bind(serde_json::to_string(&state).map_err(serialize_error)?)
+            .bind(now.to_rfc3339())
+            .execute(conn)
+            .await
+            .map_err(db_error("insert"))?;
+        Ok(state)
+    }
+
+    /// Appends `event`, checking the session version first when `expected` is set.
+    async fn append(
+        &self,
+        session_id: &str,
+        key: Option<&SessionKey>,
+        mut event: Event,
+        expected: Option<u64>,
+    ) -> Result<u64> {
+        event.actions.state_delta.retain(|k, _| !k.starts_with(KEY_PREFIX_TEMP));
+        let (llm_response, actions, tool_ids) = event_json(&event)?;
+        let (app_delta, user_delta, session_delta) =
+            extract_state_deltas(&event.actions.state_delta);
+
+        let mut tx = self.pool.begin().await.map_err(db_error("transaction"))?;
+
+        // Bumping the version first takes SQLite's write lock, so concurrent appends to
+        // one session apply in order and the version check cannot race
+        let lookup = match key {
+            Some(key) => sqlx::query("UPDATE sessions SET version = version + 1 WHERE app_name = ? AND user_id = ? AND session_id = ? RETURNING app_name, user_id, state, version")
+                .bind(&key.app_name)
+                .bind(&key.user_id),
+            None => sqlx::query("UPDATE sessions SET version = version + 1 WHERE rowid = (SELECT rowid FROM sessions WHERE session_id = ? LIMIT 1) RETURNING app_name, user_id, state, version"),
+        };
+        let row = lookup
+            .bind(session_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(db_error("update"))?
+            .ok_or_else(|| adk_core::AdkError::Session("session not found".into()))?;
+        let version = row.get::<i64, _>("version") as u64;
+        check_version(session_id, expected, version - 1)?;
+        let app_name: String = row.get("app_name");
+        let user_id: String = row.get("user_id");
+
+        sqlx::query("INSERT INTO events (id, app_name, user_id, session_id, invocation_id, branch, author, timestamp, llm_response, actions, long_running_tool_ids) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")
+            .bind(&event.id)
+            .bind(&app_name)
+            .bind(&user_id)
+            .bind(session_id)
+            .bind(&event.invocation_id)
+            .bind(&event.branch)
+            .bind(&event.author)
+            .bind(event.timestamp.to_rfc3339())
+            .bind(llm_response.to_string())
+            .bind(actions.to_string())
+            .bind(tool_ids.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error("insert"))?;
+
+        let mut session_state: StateMap =
+            serde_json::from_str(row.get("state")).unwrap_or_default();
+        session_state.extend(session_delta);
+
+        sqlx::query("UPDATE sessions SET state = ?, updated_at = ? WHERE app_name = ? AND user_id = ? AND session_id = ?")
+            .bind(serde_json::to_string(&session_state).map_err(serialize_error)?)
+            .bind(event.timestamp.to_rfc3339())
+            .bind(&app_name)
+            .bind(&user_id)
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error("update"))?;
+
+        Self::update_app_state(&mut tx, &app_name, app_delta, event.timestamp).await?;
+        Self::update_user_state(&mut tx, &app_name, &user_id, user_delta, event.timestamp).await?;
+
+        tx.commit().await.map_err(db_error("commit"))?;
+        Ok(version)
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+#[async_trait]
+impl SessionService for DatabaseSessionService {
+    async fn create(&self, req: CreateRequest) -> Result<Box<dyn Session>> {
+        let session_id = req.session_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let now = Utc::now();
+
+        let (app_delta, user_delta, session_state) = extract_state_deltas(&req.state);
+
+        let mut tx = self.pool.begin().await.map_err(db_error("transaction"))?;
+
+        let app_state = Self::update_app_state(&mut tx, &req.app_name, app_delta, now).await?;
+        let user_state =
+            Self::update_user_state(&mut tx, &req.app_name, &req.user_id, user_delta, now).await?;
+
+        sqlx::query("INSERT INTO sessions (app_name, user_id, session_id, state, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&session_id)
+            .bind(serde_json::to_string(&session_state).map_err(serialize_error)?)
+            .bind(now.to_rfc3339())
+            .bind(now.to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error("insert"))?;
+
+        tx.commit().await.map_err(db_error("commit"))?;
+
+        Ok(Box::new(DatabaseSession {
+            app_name: req.app_name,
+            user_id: req.user_id,
+            session_id,
+            state: merge_states(&app_state, &user_state, &session_state),
+            events: Vec::new(),
+            updated_at: now,
+            version: 0,
+        }))
+    }
+
+    async fn get(&self, req: GetRequest) -> Result<Box<dyn Session>> {
+        let mut conn = self.pool.acquire().await.map_err(db_error("connection"))?;
+
+        let row = sqlx::query("SELECT state, updated_at, version FROM sessions WHERE app_name = ? AND user_id = ? AND session_id = ?")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&req.session_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(db_error("query"))?
+            .ok_or_else(|| adk_core::AdkError::Session("session not found".into()))?;
+
+        let session_state: StateMap = serde_json::from_str(row.get("state"))
+            .map_err(|e| adk_core::AdkError::Session(format!("deserialize failed: {}", e)))?;
+        let updated_at: String = row.get("updated_at");
+        let updated_at = parse_timestamp(&updated_at)
+            .ok_or_else(|| adk_core::AdkError::Session("parse date failed".into()))?;
+        let version: i64 = row.get("version");
+
+        let app_state = Self::app_state(&mut conn, &req.app_name).await?;
+        let user_state = Self::user_state(&mut conn, &req.app_name, &req.user_id).await?;
+
+        let events: Vec<Event> = sqlx::query("SELECT * FROM events WHERE app_name = ? AND user_id = ? AND session_id = ? ORDER BY seq")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&req.session_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(db_error("query"))?
+            .into_iter()
+            .filter_map(|row| {
+                let json = |column: &str| serde_json::from_str::<Value>(row.get(column)).ok();
+                let timestamp: String = row.get("timestamp");
+                event_from_columns(
+                    row.get("id"),
+                    row.get("invocation_id"),
+                    row.get("branch"),
+                    row.get("author"),
+                    parse_timestamp(&timestamp)?,
+                    json("llm_response")?,
+                    json("actions")?,
+                    json("long_running_tool_ids")?,
+                )
+            })
+            .collect();
+
+        Ok(Box::new(DatabaseSession {
+            app_name: req.app_name,
+            user_id: req.user_id,
+            session_id: req.session_id,
+            state: merge_states(&app_state, &user_state, &session_state),
+            events: filter_events(events, req.num_recent_events, req.after),
+            updated_at,
+            version: version as u64,
+        }))
+    }
+
+    async fn list(&self, req: ListRequest) -> Result<Vec<Box<dyn Session>>> {
+        let mut conn = self.pool.acquire().await.map_err(db_error("connection"))?;
+
+        let rows = sqlx::query(
+            "SELECT session_id, state, updated_at, version FROM sessions WHERE app_name = ? AND user_id = ?",
+        )
+        .bind(&req.app_name)
+        .bind(&req.user_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(db_error("query"))?;
+
+        let app_state = Self::app_state(&mut conn, &req.app_name).await?;
+        let user_state = Self::user_state(&mut conn, &req.app_name, &req.user_id).await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            let session_state: StateMap =
+                serde_json::from_str(row.get("state")).unwrap_or_default();
+            let updated_at: String = row.get("updated_at");
+
+            sessions.push(Box::new(DatabaseSession {
+                app_name: req.app_name.clone(),
+                user_id: req.user_id.clone(),
+                session_id: row.get("session_id"),
+                state: merge_states(&app_state, &user_state, &session_state),
+                events: Vec::new(),
+                updated_at: parse_timestamp(&updated_at).unwrap_or_else(Utc::now),
+                version: row.get::<i64, _>("version") as u64,
+            }) as Box<dyn Session>);
+        }
+
+        Ok(sessions)
+    }
+
+    async fn delete(&self, req: DeleteRequest) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error("transaction"))?;
+
+        sqlx::query("DELETE FROM events WHERE app_name = ? AND user_id = ? AND session_id = ?")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&req.session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error("delete"))?;
+
+        sqlx::query("DELETE FROM sessions WHERE app_name = ? AND user_id = ? AND session_id = ?")
+            .bind(&req.app_name)
+            .bind(&req.user_id)
+            .bind(&req.session_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error("delete"))?;
+
+        tx.commit().await.map_err(db_error("commit"))
+    }
+
+    async fn append_event(&self, session_id: &str, event: Event) -> Result<()> {
+        self.append(session_id, None, event, None).await.map(|_| ())
+    }
+
+    async fn append_event_if_version(
+        &self,
+        key: &SessionKey,
+        event: Event,
+        expected_version: u64,
+    ) -> Result<u64> {
+        self.append(&key.session_id, Some(key), event, Some(expected_version)).await
+    }
+}
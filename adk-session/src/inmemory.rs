@@ -1,3 +1,4 @@
+use crate::service::{SessionKey, check_version};
 use crate::{
     CreateRequest, DeleteRequest, Event, Events, GetRequest, KEY_PREFIX_APP, KEY_PREFIX_TEMP,
     KEY_PREFIX_USER, ListRequest, Session, SessionService, State,
@@ -18,6 +19,7 @@ struct SessionData {
     events: Vec<Event>,
     state: StateMap,
     updated_at: DateTime<Utc>,
+    version: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -76,6 +78,62 @@ impl InMemorySessionService {
         }
         merged
     }
+
+    /// Appends `event`, checking the session version first when `expected` is set.
+    ///
+    /// With a `key` the session is looked up by its app, user and ID; without one, by
+    /// its ID alone.
+    fn append(
+        &self,
+        session_id: &str,
+        key: Option<&SessionKey>,
+        mut event: Event,
+        expected: Option<u64>,
+    ) -> Result<u64> {
+        event.actions.state_delta.retain(|k, _| !k.starts_with(KEY_PREFIX_TEMP));
+
+        let (app_name, user_id, app_delta, user_delta, version) = {
+            let mut sessions = self.sessions.write().unwrap();
+            let data = match key {
+                Some(key) => sessions.get_mut(
+                    &SessionId {
+                        app_name: key.app_name.clone(),
+                        user_id: key.user_id.clone(),
+                        session_id: key.session_id.clone(),
+                    }
+                    .key(),
+                ),
+                None => sessions.values_mut().find(|d| d.id.session_id == session_id),
+            }
+            .ok_or_else(|| adk_core::AdkError::Session("session not found".into()))?;
+            check_version(session_id, expected, data.version)?;
+
+            data.events.push(event.clone());
+            data.updated_at = event.timestamp;
+            data.version += 1;
+
+            let (app_delta, user_delta, session_delta) =
+                Self::extract_state_deltas(&event.actions.state_delta);
+            data.state.extend(session_delta);
+
+            (data.id.app_name.clone(), data.id.user_id.clone(), app_delta, user_delta, data.version)
+        };
+
+        if !app_delta.is_empty() {
+            let mut app_state_lock = self.app_state.write().unwrap();
+            let app_state = app_state_lock.entry(app_name.clone()).or_default();
+            app_state.extend(app_delta);
+        }
+
+        if !user_delta.is_empty() {
+            let mut user_state_lock = self.user_state.write().unwrap();
+            let user_map = user_state_lock.entry(app_name).or_default();
+            let user_state = user_map.entry(user_id).or_default();
+            user_state.extend(user_delta);
+        }
+
+        Ok(version)
+    }
 }
 
 impl Default for InMemorySessionService {
@@ -117,6 +175,7 @@ impl SessionService for InMemorySessionService {
             events: Vec::new(),
             state: merged_state.clone(),
             updated_at: Utc::now(),
+            version: 0,
         };
 
         let mut sessions = self.sessions.write().unwrap();
@@ -128,6 +187,7 @@ impl SessionService for InMemorySessionService {
             state: merged_state,
             events: Vec::new(),
             updated_at: Utc::now(),
+            version: 0,
         }))
     }
 
@@ -171,6 +231,7 @@ impl SessionService for InMemorySessionService {
             state: merged_state,
             events,
             updated_at: data.updated_at,
+            version: data.version,
         }))
     }
 
@@ -185,6 +246,7 @@ impl SessionService for InMemorySessionService {
                     state: data.state.clone(),
                     events: data.events.clone(),
                     updated_at: data.updated_at,
+                    version: data.version,
                 }) as Box<dyn Session>);
             }
         }
@@ -201,46 +263,17 @@ impl SessionService for InMemorySessionService {
         Ok(())
     }
 
-    async fn append_event(&self, session_id: &str, mut event: Event) -> Result<()> {
-        event.actions.state_delta.retain(|k, _| !k.starts_with(KEY_PREFIX_TEMP));
-
-        let (app_name, user_id, app_delta, user_delta, _session_delta) = {
-            let mut sessions = self.sessions.write().unwrap();
-            let data = sessions
-                .values_mut()
-                .find(|d| d.id.session_id == session_id)
-                .ok_or_else(|| adk_core::AdkError::Session("session not found".into()))?;
-
-            data.events.push(event.clone());
-            data.updated_at = event.timestamp;
-
-            let (app_delta, user_delta, session_delta) =
-                Self::extract_state_deltas(&event.actions.state_delta);
-            data.state.extend(session_delta.clone());
-
-            (
-                data.id.app_name.clone(),
-                data.id.user_id.clone(),
-                app_delta,
-                user_delta,
-                session_delta,
-            )
-        };
-
-        if !app_delta.is_empty() {
-            let mut app_state_lock = self.app_state.write().unwrap();
-            let app_state = app_state_lock.entry(app_name.clone()).or_default();
-            app_state.extend(app_delta);
-        }
-
-        if !user_delta.is_empty() {
-            let mut user_state_lock = self.user_state.write().unwrap();
-            let user_map = user_state_lock.entry(app_name).or_default();
-            let user_state = user_map.entry(user_id).or_default();
-            user_state.extend(user_delta);
-        }
+    async fn append_event(&self, session_id: &str, event: Event) -> Result<()> {
+        self.append(session_id, None, event, None).map(|_| ())
+    }
 
-        Ok(())
+    async fn append_event_if_version(
+        &self,
+        key: &SessionKey,
+        event: Event,
+        expected_version: u64,
+    ) -> Result<u64> {
+        self.append(&key.session_id, Some(key), event, Some(expected_version))
     }
 }
 
@@ -249,6 +282,7 @@ struct InMemorySession {
     state: StateMap,
     events: Vec<Event>,
     updated_at: DateTime<Utc>,
+    version: u64,
 }
 
 impl Session for InMemorySession {
@@ -275,6 +309,10 @@ impl Session for InMemorySession {
     fn last_update_time(&self) -> DateTime<Utc> {
         self.updated_at
     }
+
+    fn version(&self) -> u64 {
+        self.version
+    }
 }
 
 impl State for InMemorySession {
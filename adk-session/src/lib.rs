@@ -7,6 +7,8 @@
 //! This crate provides session and state management:
 //!
 //! - [`InMemorySessionService`] - Simple in-memory session storage
+//! - `DatabaseSessionService`, `PostgresSessionService`, `MySqlSessionService` - SQL
+//!   storage for SQLite, PostgreSQL and MySQL (`database`, `postgres` and `mysql` features)
 //! - [`Session`] - Conversation session with state and events
 //! - [`State`] - Key-value state with typed prefixes
 //! - [`SessionService`] - Trait for custom session backends
@@ -38,14 +40,20 @@ pub mod service;
 pub mod session;
 pub mod state;
 
-#[cfg(feature = "database")]
+#[cfg(any(feature = "database", feature = "postgres", feature = "mysql"))]
 pub mod database;
 
 pub use event::{Event, EventActions, Events};
 pub use inmemory::InMemorySessionService;
-pub use service::{CreateRequest, DeleteRequest, GetRequest, ListRequest, SessionService};
+pub use service::{
+    CreateRequest, DeleteRequest, GetRequest, ListRequest, SessionKey, SessionService,
+};
 pub use session::{KEY_PREFIX_APP, KEY_PREFIX_TEMP, KEY_PREFIX_USER, Session};
 pub use state::{ReadonlyState, State};
 
 #[cfg(feature = "database")]
 pub use database::DatabaseSessionService;
+#[cfg(feature = "mysql")]
+pub use database::MySqlSessionService;
+#[cfg(feature = "postgres")]
+pub use database::PostgresSessionService;
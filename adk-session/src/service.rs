@@ -35,6 +35,25 @@ pub struct DeleteRequest {
     pub session_id: String,
 }
 
+/// Identifies one session. Clients choose session IDs, so an ID is only unique within
+/// its app and user.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    pub app_name: String,
+    pub user_id: String,
+    pub session_id: String,
+}
+
+impl SessionKey {
+    pub fn new(
+        app_name: impl Into<String>,
+        user_id: impl Into<String>,
+        session_id: impl Into<String>,
+    ) -> Self {
+        Self { app_name: app_name.into(), user_id: user_id.into(), session_id: session_id.into() }
+    }
+}
+
 #[async_trait]
 pub trait SessionService: Send + Sync {
     async fn create(&self, req: CreateRequest) -> Result<Box<dyn Session>>;
@@ -42,4 +61,33 @@ pub trait SessionService: Send + Sync {
     async fn list(&self, req: ListRequest) -> Result<Vec<Box<dyn Session>>>;
     async fn delete(&self, req: DeleteRequest) -> Result<()>;
     async fn append_event(&self, session_id: &str, event: Event) -> Result<()>;
+
+    /// Appends `event` to the session identified by `key` only if it is still at
+    /// `expected_version`, the [`Session::version`] the caller last read, and returns
+    /// the new version.
+    ///
+    /// Fails with [`AdkError::StaleSession`](adk_core::AdkError::StaleSession) when
+    /// another writer appended in between. The default implementation does not track
+    /// versions and appends unconditionally.
+    async fn append_event_if_version(
+        &self,
+        key: &SessionKey,
+        event: Event,
+        expected_version: u64,
+    ) -> Result<u64> {
+        self.append_event(&key.session_id, event).await?;
+        Ok(expected_version + 1)
+    }
+}
+
+/// Rejects a conditional append whose `expected` version no longer matches `actual`.
+pub(crate) fn check_version(session_id: &str, expected: Option<u64>, actual: u64) -> Result<()> {
+    match expected {
+        Some(expected) if expected != actual => Err(adk_core::AdkError::StaleSession {
+            session_id: session_id.to_string(),
+            expected,
+            actual,
+        }),
+        _ => Ok(()),
+    }
 }
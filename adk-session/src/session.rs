@@ -8,6 +8,13 @@ pub trait Session: Send + Sync {
     fn state(&self) -> &dyn State;
     fn events(&self) -> &dyn Events;
     fn last_update_time(&self) -> DateTime<Utc>;
+
+    /// Number of events appended to the session, used as the concurrency token for
+    /// [`SessionService::append_event_if_version`](crate::SessionService::append_event_if_version).
+    /// Unlike [`events`](Self::events) it ignores the filters of the get request.
+    fn version(&self) -> u64 {
+        self.events().len() as u64
+    }
 }
 
 pub const KEY_PREFIX_APP: &str = "app:";
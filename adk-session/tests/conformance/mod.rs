@@ -0,0 +1,366 @@
+//! Behaviour every `SessionService` backend must share.
+//!
+//! Each check takes a service and works under a fresh app name, so the checks can run
+//! concurrently against one shared database. `conformance_suite!` expands to one test
+//! per check for a backend; its argument is an async expression evaluating to
+//! `Option<service>`, where `None` skips the backend.
+
+use adk_core::{Content, Event};
+use adk_session::{
+    CreateRequest, DeleteRequest, GetRequest, ListRequest, Session, SessionKey, SessionService,
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Reads a database URL for an external backend, or explains how to enable it.
+#[allow(dead_code)]
+pub fn database_url(var: &str) -> Option<String> {
+    let url = std::env::var(var).ok().filter(|url| !url.is_empty());
+    if url.is_none() {
+        eprintln!("skipping: {var} is not set (see adk-session/scripts/test-databases.sh)");
+    }
+    url
+}
+
+macro_rules! conformance_suite {
+    ($service:expr) => {
+        conformance_suite!(@tests $service;
+            create_and_get,
+            get_missing_session_fails,
+            state_is_scoped_by_prefix,
+            append_event_persists_events_and_state,
+            append_to_missing_session_fails,
+            streamed_chunks_sharing_an_id_are_kept,
+            get_filters_recent_and_after,
+            list_returns_only_the_users_sessions,
+            delete_removes_session,
+            version_counts_appends,
+            stale_version_is_rejected,
+            conditional_append_is_scoped_to_the_user,
+        );
+    };
+    (@tests $service:expr; $($check:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $check() {
+                if let Some(service) = $service.await {
+                    crate::conformance::$check(&service).await;
+                }
+            }
+        )*
+    };
+}
+
+fn unique_app() -> String {
+    format!("app-{}", uuid::Uuid::new_v4())
+}
+
+async fn create(
+    service: &dyn SessionService,
+    app_name: &str,
+    user_id: &str,
+    session_id: Option<&str>,
+    state: HashMap<String, serde_json::Value>,
+) -> Box<dyn Session> {
+    service
+        .create(CreateRequest {
+            app_name: app_name.to_string(),
+            user_id: user_id.to_string(),
+            session_id: session_id.map(String::from),
+            state,
+        })
+        .await
+        .unwrap()
+}
+
+async fn get(
+    service: &dyn SessionService,
+    app_name: &str,
+    user_id: &str,
+    session_id: &str,
+) -> adk_core::Result<Box<dyn Session>> {
+    service
+        .get(GetRequest {
+            app_name: app_name.to_string(),
+            user_id: user_id.to_string(),
+            session_id: session_id.to_string(),
+            num_recent_events: None,
+            after: None,
+        })
+        .await
+}
+
+fn text_event(id: &str, author: &str, text: &str) -> Event {
+    let mut event = Event::with_id(id, "inv-1");
+    event.author = author.to_string();
+    event.llm_response.content = Some(Content::new("model").with_text(text));
+    event
+}
+
+fn event_texts(session: &dyn Session) -> Vec<String> {
+    session
+        .events()
+        .all()
+        .iter()
+        .filter_map(|e| e.llm_response.content.as_ref()?.parts.first()?.text().map(String::from))
+        .collect()
+}
+
+pub async fn create_and_get(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", Some("session1"), HashMap::new()).await;
+    assert_eq!(session.id(), "session1");
+    assert_eq!(session.app_name(), app);
+    assert_eq!(session.user_id(), "user1");
+
+    let fetched = get(service, &app, "user1", "session1").await.unwrap();
+    assert_eq!(fetched.id(), "session1");
+    assert!(fetched.events().is_empty());
+
+    let generated = create(service, &app, "user1", None, HashMap::new()).await;
+    assert!(!generated.id().is_empty());
+    assert_ne!(generated.id(), "session1");
+    get(service, &app, "user1", generated.id()).await.unwrap();
+}
+
+pub async fn get_missing_session_fails(service: &dyn SessionService) {
+    let app = unique_app();
+    create(service, &app, "user1", Some("session1"), HashMap::new()).await;
+
+    assert!(get(service, &app, "user1", "missing").await.is_err());
+    assert!(get(service, &app, "user2", "session1").await.is_err());
+}
+
+pub async fn state_is_scoped_by_prefix(service: &dyn SessionService) {
+    let app = unique_app();
+    let state = HashMap::from([
+        ("app:model".to_string(), json!("fast")),
+        ("user:theme".to_string(), json!("dark")),
+        ("step".to_string(), json!(1)),
+        ("temp:scratch".to_string(), json!("gone")),
+    ]);
+    let first = create(service, &app, "user1", Some("first"), state).await;
+    assert_eq!(first.state().get("app:model"), Some(json!("fast")));
+    assert_eq!(first.state().get("user:theme"), Some(json!("dark")));
+    assert_eq!(first.state().get("step"), Some(json!(1)));
+    assert_eq!(first.state().get("temp:scratch"), None);
+
+    // Another session of the same user shares app and user state only
+    let second = create(service, &app, "user1", Some("second"), HashMap::new()).await;
+    assert_eq!(second.state().get("app:model"), Some(json!("fast")));
+    assert_eq!(second.state().get("user:theme"), Some(json!("dark")));
+    assert_eq!(second.state().get("step"), None);
+
+    // Another user shares app state only
+    let other = create(service, &app, "user2", Some("other"), HashMap::new()).await;
+    assert_eq!(other.state().get("app:model"), Some(json!("fast")));
+    assert_eq!(other.state().get("user:theme"), None);
+
+    let fetched = get(service, &app, "user1", "first").await.unwrap();
+    assert_eq!(fetched.state().get("step"), Some(json!(1)));
+    assert_eq!(fetched.state().get("temp:scratch"), None);
+}
+
+pub async fn append_event_persists_events_and_state(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", None, HashMap::new()).await;
+    let session_id = session.id().to_string();
+    create(service, &app, "user1", Some("sibling"), HashMap::new()).await;
+
+    service.append_event(&session_id, text_event("e1", "user", "hello")).await.unwrap();
+    let mut answer = text_event("e2", "agent", "hi there");
+    answer.actions.state_delta = HashMap::from([
+        ("app:visits".to_string(), json!(1)),
+        ("user:name".to_string(), json!("Ada")),
+        ("topic".to_string(), json!("greeting")),
+        ("temp:draft".to_string(), json!("discard")),
+    ]);
+    service.append_event(&session_id, answer).await.unwrap();
+
+    let fetched = get(service, &app, "user1", &session_id).await.unwrap();
+    let events = fetched.events().all();
+    assert_eq!(events.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), ["e1", "e2"]);
+    assert_eq!(events[1].author, "agent");
+    assert_eq!(event_texts(fetched.as_ref()), ["hello", "hi there"]);
+    assert!(!events[1].actions.state_delta.contains_key("temp:draft"));
+
+    assert_eq!(fetched.state().get("app:visits"), Some(json!(1)));
+    assert_eq!(fetched.state().get("user:name"), Some(json!("Ada")));
+    assert_eq!(fetched.state().get("topic"), Some(json!("greeting")));
+    assert_eq!(fetched.state().get("temp:draft"), None);
+
+    let sibling = get(service, &app, "user1", "sibling").await.unwrap();
+    assert_eq!(sibling.state().get("user:name"), Some(json!("Ada")));
+    assert_eq!(sibling.state().get("topic"), None);
+}
+
+pub async fn append_to_missing_session_fails(service: &dyn SessionService) {
+    let missing = format!("missing-{}", uuid::Uuid::new_v4());
+    assert!(service.append_event(&missing, text_event("e1", "user", "hello")).await.is_err());
+}
+
+pub async fn streamed_chunks_sharing_an_id_are_kept(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", None, HashMap::new()).await;
+
+    for chunk in ["Hel", "lo", "Hello"] {
+        service.append_event(session.id(), text_event("stream-1", "agent", chunk)).await.unwrap();
+    }
+
+    let fetched = get(service, &app, "user1", session.id()).await.unwrap();
+    assert_eq!(event_texts(fetched.as_ref()), ["Hel", "lo", "Hello"]);
+}
+
+pub async fn get_filters_recent_and_after(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", None, HashMap::new()).await;
+
+    let now = Utc::now();
+    for (i, minutes_ago) in [3, 2, 1].into_iter().enumerate() {
+        let mut event = text_event(&format!("e{i}"), "user", &format!("message {i}"));
+        event.timestamp = now - Duration::minutes(minutes_ago);
+        service.append_event(session.id(), event).await.unwrap();
+    }
+
+    let request = |num_recent_events, after| GetRequest {
+        app_name: app.clone(),
+        user_id: "user1".to_string(),
+        session_id: session.id().to_string(),
+        num_recent_events,
+        after,
+    };
+
+    let recent = service.get(request(Some(2), None)).await.unwrap();
+    assert_eq!(event_texts(recent.as_ref()), ["message 1", "message 2"]);
+
+    let after = now - Duration::seconds(150);
+    let later = service.get(request(None, Some(after))).await.unwrap();
+    assert_eq!(event_texts(later.as_ref()), ["message 1", "message 2"]);
+}
+
+pub async fn list_returns_only_the_users_sessions(service: &dyn SessionService) {
+    let app = unique_app();
+    create(service, &app, "user1", Some("a"), HashMap::new()).await;
+    create(service, &app, "user1", Some("b"), HashMap::new()).await;
+    create(service, &app, "user2", Some("c"), HashMap::new()).await;
+    create(service, &unique_app(), "user1", Some("d"), HashMap::new()).await;
+
+    let sessions = service
+        .list(ListRequest { app_name: app.clone(), user_id: "user1".to_string() })
+        .await
+        .unwrap();
+    let mut ids: Vec<_> = sessions.iter().map(|s| s.id().to_string()).collect();
+    ids.sort();
+    assert_eq!(ids, ["a", "b"]);
+}
+
+pub async fn delete_removes_session(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", None, HashMap::new()).await;
+    service.append_event(session.id(), text_event("e1", "user", "hello")).await.unwrap();
+    create(service, &app, "user1", Some("kept"), HashMap::new()).await;
+
+    service
+        .delete(DeleteRequest {
+            app_name: app.clone(),
+            user_id: "user1".to_string(),
+            session_id: session.id().to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert!(get(service, &app, "user1", session.id()).await.is_err());
+    get(service, &app, "user1", "kept").await.unwrap();
+}
+
+pub async fn version_counts_appends(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", None, HashMap::new()).await;
+    assert_eq!(session.version(), 0);
+
+    service.append_event(session.id(), text_event("e1", "user", "hello")).await.unwrap();
+    let version = service
+        .append_event_if_version(
+            &SessionKey::new(&app, "user1", session.id()),
+            text_event("e2", "agent", "hi"),
+            1,
+        )
+        .await
+        .unwrap();
+    assert_eq!(version, 2);
+
+    // The version counts every event, not just the ones a filtered get returns
+    let fetched = service
+        .get(GetRequest {
+            app_name: app.clone(),
+            user_id: "user1".to_string(),
+            session_id: session.id().to_string(),
+            num_recent_events: Some(1),
+            after: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(fetched.version(), 2);
+
+    let listed =
+        service.list(ListRequest { app_name: app, user_id: "user1".to_string() }).await.unwrap();
+    assert_eq!(listed[0].version(), 2);
+}
+
+pub async fn stale_version_is_rejected(service: &dyn SessionService) {
+    let app = unique_app();
+    let session = create(service, &app, "user1", None, HashMap::new()).await;
+    let read_version = session.version();
+
+    // Another writer appends after our read
+    service.append_event(session.id(), text_event("e1", "user", "first")).await.unwrap();
+
+    let mut stale = text_event("e2", "agent", "second");
+    stale.actions.state_delta = HashMap::from([("topic".to_string(), json!("lost"))]);
+    let key = SessionKey::new(&app, "user1", session.id());
+    let err = service.append_event_if_version(&key, stale, read_version).await.unwrap_err();
+    match err {
+        adk_core::AdkError::StaleSession { session_id, expected, actual } => {
+            assert_eq!(session_id, session.id());
+            assert_eq!(expected, 0);
+            assert_eq!(actual, 1);
+        }
+        other => panic!("expected StaleSession, got {other}"),
+    }
+
+    // The rejected write left no trace
+    let fetched = get(service, &app, "user1", session.id()).await.unwrap();
+    assert_eq!(event_texts(fetched.as_ref()), ["first"]);
+    assert_eq!(fetched.state().get("topic"), None);
+    assert_eq!(fetched.version(), 1);
+}
+
+pub async fn conditional_append_is_scoped_to_the_user(service: &dyn SessionService) {
+    let app = unique_app();
+    create(service, &app, "user1", Some("shared"), HashMap::new()).await;
+    create(service, &app, "user2", Some("shared"), HashMap::new()).await;
+
+    let mut event = text_event("e1", "agent", "for user2");
+    event.actions.state_delta = HashMap::from([("owner".to_string(), json!("user2"))]);
+    let version = service
+        .append_event_if_version(&SessionKey::new(&app, "user2", "shared"), event, 0)
+        .await
+        .unwrap();
+    assert_eq!(version, 1);
+
+    let theirs = get(service, &app, "user2", "shared").await.unwrap();
+    assert_eq!(event_texts(theirs.as_ref()), ["for user2"]);
+    assert_eq!(theirs.state().get("owner"), Some(json!("user2")));
+    assert_eq!(theirs.version(), 1);
+
+    let mine = get(service, &app, "user1", "shared").await.unwrap();
+    assert!(event_texts(mine.as_ref()).is_empty());
+    assert_eq!(mine.state().get("owner"), None);
+    assert_eq!(mine.version(), 0);
+
+    let missing = SessionKey::new(&app, "user3", "shared");
+    assert!(
+        service.append_event_if_version(&missing, text_event("e2", "agent", "x"), 0).await.is_err()
+    );
+}
@@ -0,0 +1,49 @@
+//! Runs the shared conformance checks against every session backend.
+//!
+//! SQLite runs in memory. PostgreSQL and MySQL run when `ADK_TEST_POSTGRES_URL` and
+//! `ADK_TEST_MYSQL_URL` point at a database; `scripts/test-databases.sh` starts both in
+//! containers and runs this suite.
+
+#[macro_use]
+mod conformance;
+
+mod inmemory {
+    use adk_session::InMemorySessionService;
+
+    conformance_suite!(async { Some(InMemorySessionService::new()) });
+}
+
+#[cfg(feature = "database")]
+mod sqlite {
+    use adk_session::DatabaseSessionService;
+
+    conformance_suite!(async {
+        let service = DatabaseSessionService::new(":memory:").await.unwrap();
+        service.migrate().await.unwrap();
+        Some(service)
+    });
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use adk_session::PostgresSessionService;
+
+    conformance_suite!(async {
+        let url = crate::conformance::database_url("ADK_TEST_POSTGRES_URL")?;
+        let service = PostgresSessionService::new(&url).await.unwrap();
+        service.migrate().await.unwrap();
+        Some(service)
+    });
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use adk_session::MySqlSessionService;
+
+    conformance_suite!(async {
+        let url = crate::conformance::database_url("ADK_TEST_MYSQL_URL")?;
+        let service = MySqlSessionService::new(&url).await.unwrap();
+        service.migrate().await.unwrap();
+        Some(service)
+    });
+}
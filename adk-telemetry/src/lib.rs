@@ -7,6 +7,7 @@
 //! - OpenTelemetry integration for distributed tracing
 //! - OTLP export for observability backends (Jaeger, Datadog, etc.)
 //! - Automatic context propagation
+//! - Token usage and cost metrics
 //!
 //! ## Usage
 //!
@@ -27,6 +28,7 @@
 //! ```
 
 pub mod init;
+pub mod metrics;
 pub mod span_exporter;
 pub mod spans;
 
@@ -42,6 +44,9 @@ pub use span_exporter::*;
 // Re-export init functions
 pub use init::{init_telemetry, init_with_adk_exporter, init_with_otlp, shutdown_telemetry};
 
+// Re-export metric helpers
+pub use metrics::{TokenCounts, record_llm_usage};
+
 // Re-export metrics
 pub use opentelemetry::global;
 pub use opentelemetry::metrics::{Meter, MeterProvider};
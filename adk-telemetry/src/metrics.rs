@@ -0,0 +1,73 @@
+//! Metric helpers for common ADK measurements
+//!
+//! Instruments are created from the global meter provider, which
+//! [`init_with_otlp`](crate::init_with_otlp) configures. Without a provider the
+//! calls are no-ops.
+
+use opentelemetry::KeyValue;
+use opentelemetry::global;
+
+/// Name of the meter ADK instruments are registered under
+pub const METER_NAME: &str = "adk";
+
+/// Token counts for one model response or part of one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenCounts {
+    pub prompt: u64,
+    pub completion: u64,
+    pub cached: u64,
+    pub reasoning: u64,
+}
+
+/// Record LLM token usage and cost
+///
+/// Tokens go to the `adk.llm.tokens` counter with a `token.type` attribute of
+/// `prompt`, `completion`, `cached` or `reasoning`; cost goes to `adk.llm.cost`.
+///
+/// # Arguments
+/// * `agent_name` - Name of the agent that called the model
+/// * `model_name` - Name of the model that answered
+/// * `tokens` - Token counts to add
+/// * `cost` - Cost of those tokens, if the model is priced
+///
+/// # Example
+/// ```
+/// use adk_telemetry::{TokenCounts, record_llm_usage};
+/// let tokens = TokenCounts { prompt: 120, completion: 30, ..Default::default() };
+/// record_llm_usage("my-agent", "gemini-2.0-flash", tokens, Some(0.0001));
+/// ```
+pub fn record_llm_usage(
+    agent_name: &str,
+    model_name: &str,
+    tokens: TokenCounts,
+    cost: Option<f64>,
+) {
+    let meter = global::meter(METER_NAME);
+    let attributes = [
+        KeyValue::new("agent.name", agent_name.to_string()),
+        KeyValue::new("model.name", model_name.to_string()),
+    ];
+
+    let counter =
+        meter.u64_counter("adk.llm.tokens").with_description("Tokens consumed by LLM calls").init();
+    for (kind, count) in [
+        ("prompt", tokens.prompt),
+        ("completion", tokens.completion),
+        ("cached", tokens.cached),
+        ("reasoning", tokens.reasoning),
+    ] {
+        if count > 0 {
+            let mut attrs = attributes.to_vec();
+            attrs.push(KeyValue::new("token.type", kind));
+            counter.add(count, &attrs);
+        }
+    }
+
+    if let Some(cost) = cost {
+        meter
+            .f64_counter("adk.llm.cost")
+            .with_description("Cost of LLM calls, in the price table's currency")
+            .init()
+            .add(cost, &attributes);
+    }
+}
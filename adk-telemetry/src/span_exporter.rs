@@ -114,6 +114,7 @@ where
             if let Some(parent_fields) = parent.extensions().get::<SpanFields>() {
                 let context_keys = [
                     "gcp.vertex.agent.session_id",
+                    "gcp.vertex.agent.user_id",
                     "gcp.vertex.agent.invocation_id",
                     "gcp.vertex.agent.event_id",
                 ];
@@ -29,7 +29,7 @@
 
 use adk_core::{
     Agent, Artifacts, CallbackContext, Content, Event, InvocationContext, Memory, Part,
-    ReadonlyContext, Result, RunConfig, Session, State, Tool, ToolContext,
+    ReadonlyContext, Result, RunConfig, Session, State, Tool, ToolContext, ToolExecutionPolicy,
 };
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -49,7 +49,10 @@ pub struct AgentToolConfig {
     /// When true, the sub-agent can access parent's artifacts.
     pub forward_artifacts: bool,
 
-    /// Optional timeout for sub-agent execution.
+    /// Optional timeout for sub-agent execution, enforced by [`AgentTool`] itself.
+    #[deprecated(
+        note = "use `AgentTool::with_execution_policy` with `ToolExecutionPolicy::with_timeout`"
+    )]
     pub timeout: Option<Duration>,
 
     /// Custom input schema for the tool.
@@ -60,6 +63,7 @@ pub struct AgentToolConfig {
     pub output_schema: Option<Value>,
 }
 
+#[allow(deprecated)]
 impl Default for AgentToolConfig {
     fn default() -> Self {
         Self {
@@ -80,17 +84,18 @@ impl Default for AgentToolConfig {
 pub struct AgentTool {
     agent: Arc<dyn Agent>,
     config: AgentToolConfig,
+    execution_policy: Option<ToolExecutionPolicy>,
 }
 
 impl AgentTool {
     /// Create a new AgentTool wrapping the given agent.
     pub fn new(agent: Arc<dyn Agent>) -> Self {
-        Self { agent, config: AgentToolConfig::default() }
+        Self { agent, config: AgentToolConfig::default(), execution_policy: None }
     }
 
     /// Create a new AgentTool with custom configuration.
     pub fn with_config(agent: Arc<dyn Agent>, config: AgentToolConfig) -> Self {
-        Self { agent, config }
+        Self { agent, config, execution_policy: None }
     }
 
     /// Set whether to skip summarization.
@@ -106,8 +111,17 @@ impl AgentTool {
     }
 
     /// Set timeout for sub-agent execution.
+    ///
+    /// Shorthand for an execution policy with only a timeout; replaces any policy
+    /// set with [`AgentTool::with_execution_policy`].
     pub fn timeout(mut self, timeout: Duration) -> Self {
-        self.config.timeout = Some(timeout);
+        self.execution_policy = Some(ToolExecutionPolicy::new().with_timeout(timeout));
+        self
+    }
+
+    /// Set the timeout and retry policy the calling agent applies to this tool.
+    pub fn with_execution_policy(mut self, policy: ToolExecutionPolicy) -> Self {
+        self.execution_policy = Some(policy);
         self
     }
 
@@ -224,6 +238,10 @@ impl Tool for AgentTool {
         false
     }
 
+    fn execution_policy(&self) -> Option<ToolExecutionPolicy> {
+        self.execution_policy.clone()
+    }
+
     #[adk_telemetry::instrument(
         skip(self, ctx, args),
         fields(
@@ -232,6 +250,7 @@ impl Tool for AgentTool {
             function_call.id = %ctx.function_call_id()
         )
     )]
+    #[allow(deprecated)]
     async fn execute(&self, ctx: Arc<dyn ToolContext>, args: Value) -> Result<Value> {
         adk_telemetry::debug!("Executing agent tool: {}", self.agent.name());
 
@@ -276,7 +295,8 @@ impl Tool for AgentTool {
             Ok((events, state_delta, artifact_delta))
         };
 
-        // Apply timeout if configured
+        // The deprecated config timeout is applied here, so callers that execute the
+        // tool directly keep it
         let result = if let Some(timeout_duration) = self.config.timeout {
             match tokio::time::timeout(timeout_duration, execution).await {
                 Ok(r) => r,
@@ -514,6 +534,87 @@ mod tests {
         }
     }
 
+    /// Never yields an event.
+    struct HangingAgent;
+
+    #[async_trait]
+    impl Agent for HangingAgent {
+        fn name(&self) -> &str {
+            "hanging"
+        }
+
+        fn description(&self) -> &str {
+            "never answers"
+        }
+
+        fn sub_agents(&self) -> &[Arc<dyn Agent>] {
+            &[]
+        }
+
+        async fn run(&self, _ctx: Arc<dyn InvocationContext>) -> Result<adk_core::EventStream> {
+            Ok(Box::pin(futures::stream::pending::<Result<Event>>()))
+        }
+    }
+
+    struct MockToolContext {
+        content: Content,
+        actions: std::sync::Mutex<adk_core::EventActions>,
+    }
+
+    impl MockToolContext {
+        fn new() -> Self {
+            Self { content: Content::new("user"), actions: Default::default() }
+        }
+    }
+
+    #[async_trait]
+    impl ReadonlyContext for MockToolContext {
+        fn invocation_id(&self) -> &str {
+            "inv"
+        }
+        fn agent_name(&self) -> &str {
+            "parent"
+        }
+        fn user_id(&self) -> &str {
+            "user"
+        }
+        fn app_name(&self) -> &str {
+            "app"
+        }
+        fn session_id(&self) -> &str {
+            "session"
+        }
+        fn branch(&self) -> &str {
+            ""
+        }
+        fn user_content(&self) -> &Content {
+            &self.content
+        }
+    }
+
+    #[async_trait]
+    impl CallbackContext for MockToolContext {
+        fn artifacts(&self) -> Option<Arc<dyn Artifacts>> {
+            None
+        }
+    }
+
+    #[async_trait]
+    impl ToolContext for MockToolContext {
+        fn function_call_id(&self) -> &str {
+            "call"
+        }
+        fn actions(&self) -> adk_core::EventActions {
+            self.actions.lock().unwrap().clone()
+        }
+        fn set_actions(&self, actions: adk_core::EventActions) {
+            *self.actions.lock().unwrap() = actions;
+        }
+        async fn search_memory(&self, _query: &str) -> Result<Vec<adk_core::MemoryEntry>> {
+            Ok(vec![])
+        }
+    }
+
     #[test]
     fn test_agent_tool_creation() {
         let agent = Arc::new(MockAgent {
@@ -538,7 +639,25 @@ mod tests {
 
         assert!(tool.config.skip_summarization);
         assert!(!tool.config.forward_artifacts);
-        assert_eq!(tool.config.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(
+            tool.execution_policy().and_then(|policy| policy.timeout),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_config_timeout_is_enforced_by_execute() {
+        let config =
+            AgentToolConfig { timeout: Some(Duration::from_millis(20)), ..Default::default() };
+        let tool = AgentTool::with_config(Arc::new(HangingAgent), config);
+        assert!(tool.execution_policy().is_none());
+
+        let result = tool.execute(Arc::new(MockToolContext::new()), json!({})).await.unwrap();
+        assert_eq!(result["error"], "Agent execution timed out");
+
+        let tool = tool.with_execution_policy(ToolExecutionPolicy::new().with_max_retries(2));
+        assert_eq!(tool.execution_policy().unwrap().max_retries, 2);
     }
 
     #[test]
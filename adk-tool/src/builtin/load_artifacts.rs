@@ -1,4 +1,4 @@
-use adk_core::{AdkError, Result, Tool, ToolContext};
+use adk_core::{AdkError, Content, LlmRequest, Part, Result, Tool, ToolContext};
 use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use serde_json::{Value, json};
@@ -7,6 +7,8 @@ use std::sync::Arc;
 pub struct LoadArtifactsTool {
     name: String,
     description: String,
+    prefer_references: bool,
+    max_inline_bytes: Option<usize>,
 }
 
 impl LoadArtifactsTool {
@@ -14,8 +16,97 @@ impl LoadArtifactsTool {
         Self {
             name: "load_artifacts".to_string(),
             description: "Loads artifacts by name and returns their content. Accepts an array of artifact names.".to_string(),
+            prefer_references: false,
+            max_inline_bytes: None,
         }
     }
+
+    /// Return a `file_data` URI instead of the content for artifacts whose backend can
+    /// reference them (see [`adk_core::Artifacts::reference`]), without loading them.
+    ///
+    /// The next model request carries each referenced file as a `Part::FileData`, so
+    /// the model reads the file rather than its URI.
+    pub fn prefer_references(mut self) -> Self {
+        self.prefer_references = true;
+        self
+    }
+
+    /// Report artifacts larger than `bytes` by size only instead of inlining them.
+    ///
+    /// Backends that know an artifact's size (see [`adk_core::Artifacts::size`]) skip
+    /// reading such artifacts entirely.
+    pub fn with_max_inline_bytes(mut self, bytes: usize) -> Self {
+        self.max_inline_bytes = Some(bytes);
+        self
+    }
+
+    async fn load(&self, artifacts: &dyn adk_core::Artifacts, name: &str) -> Result<Value> {
+        if self.prefer_references {
+            if let Some(Part::FileData { mime_type, file_uri }) = artifacts.reference(name).await? {
+                return Ok(json!({
+                    "type": "file_data",
+                    "mime_type": mime_type,
+                    "file_uri": file_uri,
+                }));
+            }
+        }
+
+        if let Some(max) = self.max_inline_bytes {
+            if let Some(size) = artifacts.size(name).await? {
+                if size > max as u64 {
+                    return Ok(too_large(size));
+                }
+            }
+        }
+
+        let content = match artifacts.load(name).await? {
+            Part::Text { text } if self.exceeds_inline_limit(text.len()) => {
+                too_large(text.len() as u64)
+            }
+            Part::Text { text } => json!({
+                "type": "text",
+                "text": text,
+            }),
+            Part::InlineData { data, .. } if self.exceeds_inline_limit(data.len()) => {
+                too_large(data.len() as u64)
+            }
+            Part::InlineData { mime_type, data } => {
+                // Base64 encode binary data for JSON transport
+                let encoded = STANDARD.encode(&data);
+                json!({
+                    "type": "inline_data",
+                    "mime_type": mime_type,
+                    "data_base64": encoded,
+                    "size_bytes": data.len(),
+                })
+            }
+            Part::FileData { mime_type, file_uri } => json!({
+                "type": "file_data",
+                "mime_type": mime_type,
+                "file_uri": file_uri,
+            }),
+            _ => json!({ "type": "unknown" }),
+        };
+        Ok(content)
+    }
+
+    fn exceeds_inline_limit(&self, size: usize) -> bool {
+        self.max_inline_bytes.is_some_and(|max| size > max)
+    }
+}
+
+fn too_large(size: u64) -> Value {
+    json!({
+        "type": "too_large",
+        "size_bytes": size,
+        "omitted": "Artifact is too large to inline",
+    })
+}
+
+/// Whether `content` holds only function responses, as tool results do.
+fn is_function_response(content: &Content) -> bool {
+    !content.parts.is_empty()
+        && content.parts.iter().all(|part| matches!(part, Part::FunctionResponse { .. }))
 }
 
 impl Default for LoadArtifactsTool {
@@ -38,6 +129,45 @@ impl Tool for LoadArtifactsTool {
         false
     }
 
+    /// When the latest tool results answer a call to this tool, attach the files it
+    /// referenced so the model can read them.
+    fn process_llm_request(&self, request: &mut LlmRequest) {
+        // Calls from one model turn may be answered in several contents
+        let start = request
+            .contents
+            .iter()
+            .rposition(|content| !is_function_response(content))
+            .map_or(0, |position| position + 1);
+        let mut parts = Vec::new();
+        for part in request.contents[start..].iter().flat_map(|content| &content.parts) {
+            let Part::FunctionResponse { function_response, .. } = part else {
+                continue;
+            };
+            if function_response.name != self.name {
+                continue;
+            }
+            let artifacts = function_response.response["artifacts"].as_array();
+            for artifact in artifacts.into_iter().flatten() {
+                let content = &artifact["content"];
+                if let (Some("file_data"), Some(mime_type), Some(file_uri)) = (
+                    content["type"].as_str(),
+                    content["mime_type"].as_str(),
+                    content["file_uri"].as_str(),
+                ) {
+                    let name = artifact["name"].as_str().unwrap_or_default();
+                    parts.push(Part::Text { text: format!("Artifact {name} is:") });
+                    parts.push(Part::FileData {
+                        mime_type: mime_type.to_string(),
+                        file_uri: file_uri.to_string(),
+                    });
+                }
+            }
+        }
+        if !parts.is_empty() {
+            request.contents.push(Content { role: "user".to_string(), parts });
+        }
+    }
+
     fn parameters_schema(&self) -> Option<Value> {
         Some(json!({
             "type": "object",
@@ -70,26 +200,8 @@ impl Tool for LoadArtifactsTool {
                 .as_str()
                 .ok_or_else(|| AdkError::Tool("artifact name must be a string".to_string()))?;
 
-            match artifact_service.load(name).await {
-                Ok(part) => {
-                    let content = match part {
-                        adk_core::Part::Text { text } => json!({
-                            "type": "text",
-                            "text": text,
-                        }),
-                        adk_core::Part::InlineData { mime_type, data } => {
-                            // Base64 encode binary data for JSON transport
-                            let encoded = STANDARD.encode(&data);
-                            json!({
-                                "type": "inline_data",
-                                "mime_type": mime_type,
-                                "data_base64": encoded,
-                                "size_bytes": data.len(),
-                            })
-                        }
-                        _ => json!({ "type": "unknown" }),
-                    };
-
+            match self.load(artifact_service.as_ref(), name).await {
+                Ok(content) => {
                     results.push(json!({
                         "name": name,
                         "content": content,
@@ -0,0 +1,97 @@
+use adk_core::{AdkError, Content, MemoryEntry, Result, Tool, ToolContext};
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Lets the model search, save and forget long-term memories about the user.
+///
+/// Saved memories are authored by the calling agent. Forgetting takes the id of a
+/// memory found by a search, so the model looks a memory up before deleting it.
+#[derive(Default)]
+pub struct ManageMemoryTool;
+
+impl ManageMemoryTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn string_arg<'a>(args: &'a Value, name: &str) -> Result<&'a str> {
+    args[name]
+        .as_str()
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| AdkError::Tool(format!("{name} must be a non-empty string")))
+}
+
+#[async_trait]
+impl Tool for ManageMemoryTool {
+    fn name(&self) -> &str {
+        "manage_memory"
+    }
+
+    fn description(&self) -> &str {
+        "Searches, saves or forgets long-term memories about the user.\n\
+         Use action \"save\" with a fact when the user asks you to remember something, \
+         \"search\" with a query to recall memories, and \"forget\" with the id of a memory \
+         found by a search when the user asks you to forget it."
+    }
+
+    fn parameters_schema(&self) -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["search", "save", "forget"],
+                    "description": "What to do"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Words to search memories for (search)"
+                },
+                "fact": {
+                    "type": "string",
+                    "description": "A short standalone statement to remember (save)"
+                },
+                "memory_id": {
+                    "type": "string",
+                    "description": "Id of the memory to forget, as returned by search (forget)"
+                }
+            },
+            "required": ["action"]
+        }))
+    }
+
+    async fn execute(&self, ctx: Arc<dyn ToolContext>, args: Value) -> Result<Value> {
+        match args["action"].as_str() {
+            Some("search") => {
+                let memories = ctx.search_memory(string_arg(&args, "query")?).await?;
+                let memories: Vec<Value> = memories
+                    .iter()
+                    .map(|memory| {
+                        let text: String =
+                            memory.content.parts.iter().filter_map(|part| part.text()).collect();
+                        json!({ "memory_id": memory.id, "author": memory.author, "text": text })
+                    })
+                    .collect();
+                Ok(json!({ "memories": memories }))
+            }
+            Some("save") => {
+                let fact = string_arg(&args, "fact")?;
+                let id = ctx
+                    .save_memory(MemoryEntry::new(
+                        ctx.agent_name(),
+                        Content::new("model").with_text(fact),
+                    ))
+                    .await?;
+                Ok(json!({ "status": "saved", "memory_id": id }))
+            }
+            Some("forget") => {
+                let id = string_arg(&args, "memory_id")?;
+                let status = if ctx.forget_memory(id).await? { "forgotten" } else { "not_found" };
+                Ok(json!({ "status": status, "memory_id": id }))
+            }
+            _ => Err(AdkError::Tool("action must be one of search, save or forget".to_string())),
+        }
+    }
+}
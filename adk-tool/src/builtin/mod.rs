@@ -1,7 +1,9 @@
 mod exit_loop;
 mod google_search;
 mod load_artifacts;
+mod manage_memory;
 
 pub use exit_loop::ExitLoopTool;
 pub use google_search::GoogleSearchTool;
 pub use load_artifacts::LoadArtifactsTool;
+pub use manage_memory::ManageMemoryTool;
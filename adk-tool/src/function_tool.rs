@@ -1,4 +1,4 @@
-use adk_core::{Result, Tool, ToolContext};
+use adk_core::{Result, Tool, ToolContext, ToolExecutionPolicy};
 use async_trait::async_trait;
 use schemars::{JsonSchema, schema::RootSchema};
 use serde::Serialize;
@@ -18,8 +18,10 @@ pub struct FunctionTool {
     description: String,
     handler: AsyncHandler,
     long_running: bool,
+    requires_confirmation: bool,
     parameters_schema: Option<Value>,
     response_schema: Option<Value>,
+    execution_policy: Option<ToolExecutionPolicy>,
 }
 
 impl FunctionTool {
@@ -33,8 +35,10 @@ impl FunctionTool {
             description: description.into(),
             handler: Box::new(move |ctx, args| Box::pin(handler(ctx, args))),
             long_running: false,
+            requires_confirmation: false,
             parameters_schema: None,
             response_schema: None,
+            execution_policy: None,
         }
     }
 
@@ -43,6 +47,18 @@ impl FunctionTool {
         self
     }
 
+    /// Require human approval before each call of this tool is executed.
+    pub fn with_requires_confirmation(mut self, requires_confirmation: bool) -> Self {
+        self.requires_confirmation = requires_confirmation;
+        self
+    }
+
+    /// Set the timeout and retry policy agents apply when executing this tool.
+    pub fn with_execution_policy(mut self, policy: ToolExecutionPolicy) -> Self {
+        self.execution_policy = Some(policy);
+        self
+    }
+
     pub fn with_parameters_schema<T>(mut self) -> Self
     where
         T: JsonSchema + Serialize,
@@ -99,6 +115,10 @@ impl Tool for FunctionTool {
         self.long_running
     }
 
+    fn requires_confirmation(&self) -> bool {
+        self.requires_confirmation
+    }
+
     fn parameters_schema(&self) -> Option<Value> {
         self.parameters_schema.clone()
     }
@@ -107,6 +127,10 @@ impl Tool for FunctionTool {
         self.response_schema.clone()
     }
 
+    fn execution_policy(&self) -> Option<ToolExecutionPolicy> {
+        self.execution_policy.clone()
+    }
+
     #[adk_telemetry::instrument(
         skip(self, ctx, args),
         fields(
@@ -13,6 +13,7 @@
 //! - [`BasicToolset`] - Group multiple tools together
 //! - [`ExitLoopTool`] - Control flow for loop agents
 //! - [`LoadArtifactsTool`] - Inject binary artifacts into context
+//! - [`ManageMemoryTool`] - Let the model search, save and forget user memories
 //!
 //! ## Quick Start
 //!
@@ -64,7 +65,7 @@ pub mod toolset;
 
 pub use adk_core::{Tool, ToolContext, Toolset};
 pub use agent_tool::{AgentTool, AgentToolConfig};
-pub use builtin::{ExitLoopTool, GoogleSearchTool, LoadArtifactsTool};
+pub use builtin::{ExitLoopTool, GoogleSearchTool, LoadArtifactsTool, ManageMemoryTool};
 pub use function_tool::FunctionTool;
 pub use mcp::McpToolset;
 pub use toolset::{BasicToolset, string_predicate};
@@ -1,19 +1,76 @@
 use adk_core::{
-    CallbackContext, Content, EventActions, MemoryEntry, ReadonlyContext, Result, Tool, ToolContext,
+    AdkError, Artifacts, CallbackContext, Content, EventActions, FunctionResponseData, LlmRequest,
+    MemoryEntry, Part, ReadonlyContext, Result, Tool, ToolContext,
 };
-use adk_tool::{ExitLoopTool, GoogleSearchTool};
+use adk_tool::{ExitLoopTool, GoogleSearchTool, LoadArtifactsTool, ManageMemoryTool};
 use async_trait::async_trait;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 struct MockToolContext {
     actions: Mutex<EventActions>,
     content: Content,
+    memories: Mutex<Vec<MemoryEntry>>,
+    artifacts: Option<Arc<dyn Artifacts>>,
 }
 
 impl MockToolContext {
     fn new() -> Self {
-        Self { actions: Mutex::new(EventActions::default()), content: Content::new("user") }
+        Self {
+            actions: Mutex::new(EventActions::default()),
+            content: Content::new("user"),
+            memories: Mutex::new(Vec::new()),
+            artifacts: None,
+        }
+    }
+
+    fn with_artifacts(artifacts: MockArtifacts) -> Self {
+        Self { artifacts: Some(Arc::new(artifacts)), ..Self::new() }
+    }
+}
+
+/// Artifacts whose names starting with `remote/` can be referenced by URI. Records
+/// the names it loads, and reports sizes when `sizes_known` is set.
+#[derive(Default)]
+struct MockArtifacts {
+    parts: HashMap<String, Part>,
+    sizes_known: bool,
+    loaded: Mutex<Vec<String>>,
+}
+
+impl MockArtifacts {
+    fn get(&self, name: &str) -> Result<&Part> {
+        self.parts.get(name).ok_or_else(|| AdkError::Artifact("not found".into()))
+    }
+}
+
+#[async_trait]
+impl Artifacts for MockArtifacts {
+    async fn save(&self, _name: &str, _data: &Part) -> Result<i64> {
+        unimplemented!()
+    }
+    async fn load(&self, name: &str) -> Result<Part> {
+        self.loaded.lock().unwrap().push(name.to_string());
+        self.get(name).cloned()
+    }
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.parts.keys().cloned().collect())
+    }
+    async fn reference(&self, name: &str) -> Result<Option<Part>> {
+        let part = self.get(name)?;
+        Ok(name.starts_with("remote/").then(|| Part::FileData {
+            mime_type: part.mime_type().unwrap_or("text/plain").to_string(),
+            file_uri: format!("https://store.example/{name}"),
+        }))
+    }
+    async fn size(&self, name: &str) -> Result<Option<u64>> {
+        let size = match self.get(name)? {
+            Part::Text { text } => text.len(),
+            Part::InlineData { data, .. } => data.len(),
+            _ => return Ok(None),
+        };
+        Ok(self.sizes_known.then_some(size as u64))
     }
 }
 
@@ -45,7 +102,7 @@ impl ReadonlyContext for MockToolContext {
 #[async_trait]
 impl CallbackContext for MockToolContext {
     fn artifacts(&self) -> Option<Arc<dyn adk_core::Artifacts>> {
-        None
+        self.artifacts.clone()
     }
 }
 
@@ -60,8 +117,26 @@ impl ToolContext for MockToolContext {
     fn set_actions(&self, actions: EventActions) {
         *self.actions.lock().unwrap() = actions;
     }
-    async fn search_memory(&self, _query: &str) -> Result<Vec<MemoryEntry>> {
-        Ok(vec![])
+    async fn search_memory(&self, query: &str) -> Result<Vec<MemoryEntry>> {
+        let memories = self.memories.lock().unwrap();
+        Ok(memories
+            .iter()
+            .filter(|m| m.content.parts.iter().any(|p| p.text().is_some_and(|t| t.contains(query))))
+            .cloned()
+            .collect())
+    }
+    async fn save_memory(&self, mut entry: MemoryEntry) -> Result<String> {
+        let mut memories = self.memories.lock().unwrap();
+        let id = format!("mem-{}", memories.len() + 1);
+        entry.id = Some(id.clone());
+        memories.push(entry);
+        Ok(id)
+    }
+    async fn forget_memory(&self, id: &str) -> Result<bool> {
+        let mut memories = self.memories.lock().unwrap();
+        let before = memories.len();
+        memories.retain(|m| m.id.as_deref() != Some(id));
+        Ok(memories.len() < before)
     }
 }
 
@@ -96,3 +171,180 @@ async fn test_google_search_not_executable() {
     let result = tool.execute(ctx, json!({})).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_manage_memory_save_search_forget() {
+    let tool = ManageMemoryTool::new();
+    assert_eq!(tool.name(), "manage_memory");
+    let mock = Arc::new(MockToolContext::new());
+    let ctx = mock.clone() as Arc<dyn ToolContext>;
+
+    let saved = tool
+        .execute(ctx.clone(), json!({"action": "save", "fact": "User is allergic to peanuts"}))
+        .await
+        .unwrap();
+    assert_eq!(saved, json!({"status": "saved", "memory_id": "mem-1"}));
+    assert_eq!(mock.memories.lock().unwrap()[0].author, "test-agent");
+
+    let found =
+        tool.execute(ctx.clone(), json!({"action": "search", "query": "peanuts"})).await.unwrap();
+    assert_eq!(
+        found,
+        json!({"memories": [{
+            "memory_id": "mem-1",
+            "author": "test-agent",
+            "text": "User is allergic to peanuts"
+        }]})
+    );
+
+    let forgotten =
+        tool.execute(ctx.clone(), json!({"action": "forget", "memory_id": "mem-1"})).await.unwrap();
+    assert_eq!(forgotten["status"], "forgotten");
+    let again =
+        tool.execute(ctx.clone(), json!({"action": "forget", "memory_id": "mem-1"})).await.unwrap();
+    assert_eq!(again["status"], "not_found");
+}
+
+#[tokio::test]
+async fn test_manage_memory_rejects_bad_arguments() {
+    let tool = ManageMemoryTool::new();
+    let ctx = Arc::new(MockToolContext::new()) as Arc<dyn ToolContext>;
+
+    assert!(tool.execute(ctx.clone(), json!({"action": "save"})).await.is_err());
+    assert!(tool.execute(ctx.clone(), json!({"action": "save", "fact": "  "})).await.is_err());
+    assert!(tool.execute(ctx.clone(), json!({"action": "remember"})).await.is_err());
+}
+
+fn artifact_fixtures() -> MockArtifacts {
+    let image = |size| Part::InlineData { mime_type: "image/png".to_string(), data: vec![1; size] };
+    let parts = [
+        ("notes.txt", Part::Text { text: "hello".to_string() }),
+        ("small.png", image(3)),
+        ("large.png", image(64)),
+        ("remote/video.mp4", Part::InlineData { mime_type: "video/mp4".into(), data: vec![0; 8] }),
+    ];
+    MockArtifacts {
+        parts: parts.into_iter().map(|(name, part)| (name.to_string(), part)).collect(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_load_artifacts_inlines_content() {
+    let ctx =
+        Arc::new(MockToolContext::with_artifacts(artifact_fixtures())) as Arc<dyn ToolContext>;
+    let args =
+        json!({ "artifact_names": ["notes.txt", "small.png", "remote/video.mp4", "missing"] });
+    let result = LoadArtifactsTool::new().execute(ctx, args).await.unwrap();
+
+    let artifacts = result["artifacts"].as_array().unwrap();
+    assert_eq!(artifacts[0]["content"], json!({ "type": "text", "text": "hello" }));
+    assert_eq!(artifacts[1]["content"]["data_base64"], "AQEB");
+    assert_eq!(artifacts[2]["content"]["type"], "inline_data");
+    assert_eq!(artifacts[3]["error"], "Artifact not found");
+}
+
+#[tokio::test]
+async fn test_load_artifacts_references_and_size_limit() {
+    let ctx =
+        Arc::new(MockToolContext::with_artifacts(artifact_fixtures())) as Arc<dyn ToolContext>;
+    let tool = LoadArtifactsTool::new().prefer_references().with_max_inline_bytes(16);
+    let args = json!({ "artifact_names": ["small.png", "large.png", "remote/video.mp4"] });
+    let result = tool.execute(ctx, args).await.unwrap();
+
+    let artifacts = result["artifacts"].as_array().unwrap();
+    assert_eq!(artifacts[0]["content"]["data_base64"], "AQEB");
+    assert_eq!(artifacts[1]["content"]["size_bytes"], 64);
+    assert!(artifacts[1]["content"].get("data_base64").is_none());
+    assert_eq!(
+        artifacts[2]["content"],
+        json!({
+            "type": "file_data",
+            "mime_type": "video/mp4",
+            "file_uri": "https://store.example/remote/video.mp4",
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_load_artifacts_skips_reading_artifacts_known_to_be_too_large() {
+    let artifacts = Arc::new(MockArtifacts { sizes_known: true, ..artifact_fixtures() });
+    let ctx =
+        Arc::new(MockToolContext { artifacts: Some(artifacts.clone()), ..MockToolContext::new() })
+            as Arc<dyn ToolContext>;
+    let tool = LoadArtifactsTool::new().with_max_inline_bytes(16);
+    let args = json!({ "artifact_names": ["small.png", "large.png"] });
+    let result = tool.execute(ctx, args).await.unwrap();
+
+    let artifacts_json = result["artifacts"].as_array().unwrap();
+    assert_eq!(artifacts_json[0]["content"]["data_base64"], "AQEB");
+    assert_eq!(artifacts_json[1]["content"]["size_bytes"], 64);
+    assert_eq!(*artifacts.loaded.lock().unwrap(), ["small.png"]);
+}
+
+#[tokio::test]
+async fn test_load_artifacts_attaches_referenced_files_to_next_request() {
+    let ctx =
+        Arc::new(MockToolContext::with_artifacts(artifact_fixtures())) as Arc<dyn ToolContext>;
+    let tool = LoadArtifactsTool::new().prefer_references();
+    let args = json!({ "artifact_names": ["small.png", "remote/video.mp4"] });
+    let result = tool.execute(ctx, args).await.unwrap();
+
+    let response = Content {
+        role: "function".to_string(),
+        parts: vec![Part::FunctionResponse {
+            function_response: FunctionResponseData {
+                name: "load_artifacts".to_string(),
+                response: result,
+            },
+            id: Some("call-1".to_string()),
+        }],
+    };
+    let mut request = LlmRequest::new("model", vec![response]);
+    tool.process_llm_request(&mut request);
+
+    assert_eq!(request.contents.len(), 2);
+    assert_eq!(
+        request.contents[1].parts,
+        [
+            Part::Text { text: "Artifact remote/video.mp4 is:".to_string() },
+            Part::FileData {
+                mime_type: "video/mp4".to_string(),
+                file_uri: "https://store.example/remote/video.mp4".to_string(),
+            },
+        ]
+    );
+
+    // Files are only attached right after the tool's response
+    request.contents.push(Content::new("model").with_text("It is a video"));
+    tool.process_llm_request(&mut request);
+    assert_eq!(request.contents.len(), 3);
+}
+
+#[tokio::test]
+async fn test_load_artifacts_attaches_files_answered_before_other_tool_results() {
+    let ctx =
+        Arc::new(MockToolContext::with_artifacts(artifact_fixtures())) as Arc<dyn ToolContext>;
+    let tool = LoadArtifactsTool::new().prefer_references();
+    let args = json!({ "artifact_names": ["remote/video.mp4"] });
+    let result = tool.execute(ctx, args).await.unwrap();
+
+    let response = |name: &str, response, id: &str| Content {
+        role: "function".to_string(),
+        parts: vec![Part::FunctionResponse {
+            function_response: FunctionResponseData { name: name.to_string(), response },
+            id: Some(id.to_string()),
+        }],
+    };
+    let mut request = LlmRequest::new(
+        "model",
+        vec![
+            response("load_artifacts", result, "call-1"),
+            response("get_weather", json!({ "forecast": "sunny" }), "call-2"),
+        ],
+    );
+    tool.process_llm_request(&mut request);
+
+    assert_eq!(request.contents.len(), 3);
+    assert!(matches!(request.contents[2].parts[1], Part::FileData { .. }));
+}
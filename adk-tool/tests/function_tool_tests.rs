@@ -142,6 +142,30 @@ async fn test_function_tool_long_running() {
     assert!(tool.is_long_running());
 }
 
+#[tokio::test]
+async fn test_function_tool_execution_policy() {
+    let tool =
+        FunctionTool::new("fetch", "Fetches a URL", |_ctx, _args| async move { Ok(json!("ok")) });
+    assert!(tool.execution_policy().is_none());
+
+    let policy = adk_core::ToolExecutionPolicy::new()
+        .with_timeout(std::time::Duration::from_secs(5))
+        .with_max_retries(2);
+    let tool = tool.with_execution_policy(policy.clone());
+    assert_eq!(tool.execution_policy(), Some(policy));
+}
+
+#[tokio::test]
+async fn test_function_tool_requires_confirmation() {
+    let tool = FunctionTool::new("delete_file", "Deletes a file", |_ctx, _args| async move {
+        Ok(json!({"deleted": true}))
+    });
+    assert!(!tool.requires_confirmation());
+
+    let tool = tool.with_requires_confirmation(true);
+    assert!(tool.requires_confirmation());
+}
+
 #[tokio::test]
 async fn test_function_tool_long_running_enhanced_description() {
     // Test with description
@@ -10,9 +10,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // From docs: MemoryEntry creation
     let entry = MemoryEntry {
+        id: String::new(),
         content: Content::new("user").with_text("I prefer dark mode"),
         author: "user".to_string(),
         timestamp: Utc::now(),
+        expires_at: None,
     };
     assert_eq!(entry.author, "user");
     println!("✓ MemoryEntry creation works");
@@ -23,14 +25,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // From docs: Store memories from a session
     let entries = vec![
         MemoryEntry {
+            id: String::new(),
             content: Content::new("user").with_text("I like Rust programming"),
             author: "user".to_string(),
             timestamp: Utc::now(),
+            expires_at: None,
         },
         MemoryEntry {
+            id: String::new(),
             content: Content::new("assistant").with_text("Rust is great for systems programming"),
             author: "assistant".to_string(),
             timestamp: Utc::now(),
+            expires_at: None,
         },
     ];
 
@@ -42,6 +48,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         query: "Rust".to_string(),
         user_id: "user-123".to_string(),
         app_name: "my_app".to_string(),
+        ..Default::default()
     };
 
     let response = memory.search(request).await?;
@@ -50,14 +57,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // From docs: Memory isolation by user
     let entries_a = vec![MemoryEntry {
+        id: String::new(),
         content: Content::new("user").with_text("User A topic"),
         author: "user".to_string(),
         timestamp: Utc::now(),
+        expires_at: None,
     }];
     let entries_b = vec![MemoryEntry {
+        id: String::new(),
         content: Content::new("user").with_text("User B topic"),
         author: "user".to_string(),
         timestamp: Utc::now(),
+        expires_at: None,
     }];
 
     memory.add_session("app", "user-a", "sess-1", entries_a).await?;
@@ -68,6 +79,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         query: "topic".to_string(),
         user_id: "user-a".to_string(),
         app_name: "app".to_string(),
+        ..Default::default()
     };
     let response = memory.search(request).await?;
     assert_eq!(response.memories.len(), 1);
@@ -82,6 +94,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         query: "Rust".to_string(),
         user_id: "user-123".to_string(),
         app_name: "different_app".to_string(), // Different app
+        ..Default::default()
     };
     let response = memory.search(request).await?;
     assert!(response.memories.is_empty());
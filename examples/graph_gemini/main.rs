@@ -26,9 +26,10 @@ use std::sync::Arc;
 async fn call_gemini(model: &Arc<GeminiModel>, prompt: &str) -> Result<String, GraphError> {
     let request = LlmRequest::new(model.name(), vec![Content::new("user").with_text(prompt)]);
 
-    let mut stream = model.generate_content(request, false).await.map_err(|e| {
-        GraphError::NodeExecutionFailed { node: "gemini".to_string(), message: e.to_string() }
-    })?;
+    let mut stream = model
+        .generate_content(request, false)
+        .await
+        .map_err(|e| GraphError::node_failed_with_source("gemini", e))?;
 
     let mut result = String::new();
     while let Some(response_result) = stream.next().await {
@@ -26,9 +26,10 @@ async fn call_llm(
 ) -> Result<String, GraphError> {
     let request = LlmRequest::new(model_name, vec![Content::new("user").with_text(prompt)]);
 
-    let mut stream = model.generate_content(request, false).await.map_err(|e| {
-        GraphError::NodeExecutionFailed { node: "llm".to_string(), message: e.to_string() }
-    })?;
+    let mut stream = model
+        .generate_content(request, false)
+        .await
+        .map_err(|e| GraphError::node_failed_with_source("llm", e))?;
 
     let mut result = String::new();
     while let Some(response_result) = stream.next().await {
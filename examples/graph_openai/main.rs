@@ -22,9 +22,10 @@ use std::sync::Arc;
 async fn call_llm(client: &Arc<OpenAIClient>, prompt: &str) -> Result<String, GraphError> {
     let request = LlmRequest::new(client.name(), vec![Content::new("user").with_text(prompt)]);
 
-    let mut stream = client.generate_content(request, false).await.map_err(|e| {
-        GraphError::NodeExecutionFailed { node: "llm".to_string(), message: e.to_string() }
-    })?;
+    let mut stream = client
+        .generate_content(request, false)
+        .await
+        .map_err(|e| GraphError::node_failed_with_source("llm", e))?;
 
     let mut result = String::new();
     while let Some(response_result) = stream.next().await {